@@ -143,6 +143,7 @@ fn demonstrate_table_parsing(font: &Font) -> Result<(), Box<dyn std::error::Erro
         for (i, record) in cmap.encoding_records.iter().enumerate().take(5) {
             let format_str = match cmap.subtables.get(i) {
                 Some(CmapSubtable::Format0(_)) => "0",
+                Some(CmapSubtable::Format2(_)) => "2",
                 Some(CmapSubtable::Format4(_)) => "4",
                 Some(CmapSubtable::Format6(_)) => "6",
                 Some(CmapSubtable::Format12(_)) => "12",
@@ -105,7 +105,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         if let Ok(family) = font.family_name() {
             println!("  Family name: {}", family);
         }
-        if let Ok(full_name) = font.font_name() {
+        if let Ok(full_name) = font.full_name() {
             println!("  Full name: {}", full_name);
         }
         println!();
@@ -19,40 +19,36 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Font: {}", Path::new(font_path).display());
     println!();
 
+    let summary = font.summary()?;
+
     // Basic information
     println!("Basic Information:");
-    println!("  SFNT Version: {:#x}", font.sfnt_version);
-    println!("  Number of tables: {}", font.num_tables);
+    println!("  SFNT Version: {:#x}", summary.sfnt_version);
+    println!("  Number of tables: {}", summary.num_tables);
     println!();
 
     // Font names
-    if let Ok(name) = font.font_name() {
+    if let Ok(name) = font.full_name() {
         println!("Font Name: {}", name);
     }
-    if let Ok(family) = font.family_name() {
-        println!("Family Name: {}", family);
+    println!("Family Name: {}", summary.family_name);
+    println!("Subfamily Name: {}", summary.subfamily_name);
+    if let Ok(postscript) = font.postscript_name() {
+        println!("PostScript Name: {}", postscript);
     }
     println!();
 
     // Font metrics
-    if let Ok(units_per_em) = font.units_per_em() {
-        println!("  Units per EM: {}", units_per_em);
-    }
-    if let Ok(num_glyphs) = font.num_glyphs() {
-        println!("  Number of glyphs: {}", num_glyphs);
-    }
-    if let Ok(is_bold) = font.is_bold() {
-        println!("  Bold: {}", is_bold);
-    }
-    if let Ok(is_italic) = font.is_italic() {
-        println!("  Italic: {}", is_italic);
-    }
+    println!("  Units per EM: {}", summary.units_per_em);
+    println!("  Number of glyphs: {}", summary.num_glyphs);
+    println!("  Weight class: {}", summary.weight_class);
+    println!("  Bold: {}", summary.is_bold);
+    println!("  Italic: {}", summary.is_italic);
     println!();
 
     // Tables
     println!("Tables:");
-    let tables = font.list_tables();
-    for table in &tables {
+    for table in &summary.tables {
         println!("  {}", table);
     }
 
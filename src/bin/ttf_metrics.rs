@@ -28,13 +28,12 @@ fn main() -> Result<()> {
     println!();
 
     // SFNT header
+    let summary = font.summary()?;
     println!("SFNT Header:");
-    println!("  SFNT Version: 0x{:08X}", font.sfnt_version);
-    println!("  Number of Tables: {}", font.num_tables);
-    println!("  Tables: {}", font.table_records.iter()
-        .map(|r| String::from_utf8_lossy(&r.table_tag).to_string())
-        .collect::<Vec<_>>()
-        .join(", "));
+    println!("  SFNT Version: 0x{:08X}", summary.sfnt_version);
+    println!("  Number of Tables: {}", summary.num_tables);
+    println!("  Tables: {}", summary.tables.join(", "));
+    println!("  Family: {} {}", summary.family_name, summary.subfamily_name);
     println!();
 
     // HEAD table
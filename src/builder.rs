@@ -0,0 +1,129 @@
+//! Font construction API for authoring fonts from parsed or hand-built tables.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use ttf_rs::{Font, FontBuilder};
+//!
+//! let source = Font::load("input.ttf")?;
+//! let mut builder = FontBuilder::new();
+//! builder
+//!     .table(&source.head_table()?)?
+//!     .table(&source.maxp_table()?)?
+//!     .table(&source.hhea_table()?)?
+//!     .table(&source.hmtx_table()?)?
+//!     .table(&source.cmap_table()?)?
+//!     .table(&source.name_table()?)?
+//!     .table(&source.os2_table()?)?
+//!     .table(&source.post_table()?)?
+//!     .glyf(&source.glyf_table()?);
+//!
+//! let font = builder.build()?;
+//! font.save("output.ttf")?;
+//! # Ok::<(), ttf_rs::TtfError>(())
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::error::{Result, TtfError};
+use crate::font::Font;
+use crate::stream::{FontWriter, calculate_checksum};
+use crate::tables::glyf::GlyfTable;
+use crate::tables::loca::LocaTable;
+use crate::tables::TtfTableWrite;
+
+/// Tables every font built with [`FontBuilder`] must have
+const REQUIRED_TABLES: [&[u8; 4]; 10] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"glyf", b"loca",
+];
+
+/// Font builder for authoring a font from its tables.
+///
+/// Tables are serialized as they are added and assembled by `build()`,
+/// which lays out the table directory, checksums and head checksum
+/// adjustment.
+pub struct FontBuilder {
+    sfnt_version: u32,
+    tables: BTreeMap<[u8; 4], Vec<u8>>,
+    /// head.indexToLocFormat of the loca written by `glyf()`
+    index_to_loc_format: Option<i16>,
+}
+
+impl Default for FontBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FontBuilder {
+    /// Create a builder for a font with TrueType outlines
+    pub fn new() -> Self {
+        Self {
+            sfnt_version: 0x00010000,
+            tables: BTreeMap::new(),
+            index_to_loc_format: None,
+        }
+    }
+
+    /// Add a table, replacing any table with the same tag.
+    ///
+    /// For glyf use [`FontBuilder::glyf`], which writes the matching loca.
+    pub fn table<T: TtfTableWrite>(&mut self, table: &T) -> Result<&mut Self> {
+        let mut writer = FontWriter::new();
+        table.write(&mut writer)?;
+        Ok(self.raw_table(T::table_tag(), writer.into_inner()))
+    }
+
+    /// Add an already serialized table, replacing any table with the same tag
+    pub fn raw_table(&mut self, tag: &[u8; 4], data: Vec<u8>) -> &mut Self {
+        self.tables.insert(*tag, data);
+        self
+    }
+
+    /// Add the glyph outlines together with their loca table.
+    ///
+    /// The short loca format is used when the glyph data is small enough,
+    /// and head.indexToLocFormat is set to match when the font is built.
+    pub fn glyf(&mut self, glyf: &GlyfTable) -> &mut Self {
+        let (data, offsets) = glyf.to_bytes();
+        let is_long = offsets.last().is_some_and(|&end| end / 2 > u16::MAX as u32);
+
+        self.index_to_loc_format = Some(is_long as i16);
+        self.tables.insert(*b"loca", LocaTable::Long(offsets).to_bytes(is_long));
+        self.tables.insert(*b"glyf", data);
+        self
+    }
+
+    /// Assemble the font.
+    ///
+    /// # Returns
+    ///
+    /// Returns the built font, or `TtfError::MissingTable` if a required
+    /// table (cmap, head, hhea, hmtx, maxp, name, OS/2, post, glyf and loca)
+    /// hasn't been added.
+    pub fn build(&self) -> Result<Font> {
+        if let Some(tag) = REQUIRED_TABLES.iter().find(|tag| !self.tables.contains_key(**tag)) {
+            return Err(TtfError::MissingTable(String::from_utf8_lossy(*tag).to_string()));
+        }
+
+        let mut tables = self.tables.clone();
+        let head = tables.get_mut(b"head").filter(|head| head.len() >= 54).ok_or_else(|| {
+            TtfError::ParseError("head table is too short".to_string())
+        })?;
+        // The adjustment is computed over the font with the field zeroed
+        head[8..12].fill(0);
+        if let Some(format) = self.index_to_loc_format {
+            head[50..52].copy_from_slice(&format.to_be_bytes());
+        }
+
+        let mut font = Font::from_tables(self.sfnt_version, tables.into_iter().collect());
+        let adjustment = 0xB1B0AFBAu32.wrapping_sub(calculate_checksum(&font.data));
+        let offset = font
+            .get_table_record(b"head")
+            .map(|record| record.offset as usize + 8)
+            .ok_or_else(|| TtfError::MissingTable("head".to_string()))?;
+        font.data[offset..offset + 4].copy_from_slice(&adjustment.to_be_bytes());
+
+        Ok(font)
+    }
+}
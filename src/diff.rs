@@ -0,0 +1,129 @@
+use std::fmt;
+
+use crate::font::Font;
+use crate::stream::calculate_checksum;
+
+/// Differences between two fonts, as reported by [`Font::diff`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FontDiff {
+    /// Tables present only in the first font
+    pub only_in_self: Vec<String>,
+    /// Tables present only in the second font
+    pub only_in_other: Vec<String>,
+    /// Tables present in both fonts whose contents differ
+    pub changed_tables: Vec<TableDiff>,
+    /// maxp.num_glyphs as (self, other), if it differs
+    pub num_glyphs: Option<(u16, u16)>,
+    /// head.units_per_em as (self, other), if it differs
+    pub units_per_em: Option<(u16, u16)>,
+}
+
+/// A table present in both fonts with a different length or checksum
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDiff {
+    pub tag: String,
+    pub self_length: u32,
+    pub other_length: u32,
+    pub self_checksum: u32,
+    pub other_checksum: u32,
+}
+
+impl Font {
+    /// Compare this font's tables and key metrics with another font.
+    ///
+    /// Checksums are computed from the table data rather than taken from the
+    /// table directory, and the head checksumAdjustment field is ignored since
+    /// it depends on the whole file.
+    pub fn diff(&self, other: &Font) -> FontDiff {
+        let mut diff = FontDiff::default();
+
+        for record in &self.table_records {
+            if !other.has_table(&record.table_tag) {
+                diff.only_in_self.push(record.tag_to_string());
+            }
+        }
+        for record in &other.table_records {
+            if !self.has_table(&record.table_tag) {
+                diff.only_in_other.push(record.tag_to_string());
+            }
+        }
+
+        for record in &self.table_records {
+            let Some(ours) = table_summary(self, &record.table_tag) else {
+                continue;
+            };
+            let Some(theirs) = table_summary(other, &record.table_tag) else {
+                continue;
+            };
+            if ours != theirs {
+                diff.changed_tables.push(TableDiff {
+                    tag: record.tag_to_string(),
+                    self_length: ours.0,
+                    other_length: theirs.0,
+                    self_checksum: ours.1,
+                    other_checksum: theirs.1,
+                });
+            }
+        }
+
+        if let (Ok(ours), Ok(theirs)) = (self.maxp_table(), other.maxp_table()) {
+            diff.num_glyphs = (ours.num_glyphs != theirs.num_glyphs)
+                .then_some((ours.num_glyphs, theirs.num_glyphs));
+        }
+        if let (Ok(ours), Ok(theirs)) = (self.head_table(), other.head_table()) {
+            diff.units_per_em = (ours.units_per_em != theirs.units_per_em)
+                .then_some((ours.units_per_em, theirs.units_per_em));
+        }
+
+        diff
+    }
+}
+
+/// Length and checksum of a table, with the head checksumAdjustment zeroed
+fn table_summary(font: &Font, tag: &[u8; 4]) -> Option<(u32, u32)> {
+    let mut data = font.get_table_data(tag)?;
+    if tag == b"head" && data.len() >= 12 {
+        data[8..12].fill(0);
+    }
+    Some((data.len() as u32, calculate_checksum(&data)))
+}
+
+impl FontDiff {
+    /// Whether the two fonts compared equal
+    pub fn is_empty(&self) -> bool {
+        self.only_in_self.is_empty()
+            && self.only_in_other.is_empty()
+            && self.changed_tables.is_empty()
+            && self.num_glyphs.is_none()
+            && self.units_per_em.is_none()
+    }
+}
+
+impl fmt::Display for FontDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "Fonts are identical");
+        }
+
+        for tag in &self.only_in_self {
+            writeln!(f, "- {}: only in first font", tag)?;
+        }
+        for tag in &self.only_in_other {
+            writeln!(f, "+ {}: only in second font", tag)?;
+        }
+        for table in &self.changed_tables {
+            writeln!(
+                f,
+                "~ {}: length {} -> {}, checksum {:#010x} -> {:#010x}",
+                table.tag, table.self_length, table.other_length, table.self_checksum, table.other_checksum
+            )?;
+        }
+        if let Some((ours, theirs)) = self.num_glyphs {
+            writeln!(f, "~ num_glyphs: {} -> {}", ours, theirs)?;
+        }
+        if let Some((ours, theirs)) = self.units_per_em {
+            writeln!(f, "~ units_per_em: {} -> {}", ours, theirs)?;
+        }
+        Ok(())
+    }
+}
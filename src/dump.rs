@@ -0,0 +1,234 @@
+use crate::error::{Result, TtfError};
+use crate::font::Font;
+use crate::stream::FontReader;
+
+/// Encoding of a fixed-layout table field
+#[derive(Clone, Copy)]
+enum Field {
+    U16,
+    I16,
+    U32,
+    /// u32 shown in hex, for checksums and magic numbers
+    Hex32,
+    Fixed,
+    LongDateTime,
+    Tag,
+    Bytes(usize),
+}
+
+impl Field {
+    fn size(self) -> usize {
+        match self {
+            Field::U16 | Field::I16 => 2,
+            Field::U32 | Field::Hex32 | Field::Fixed | Field::Tag => 4,
+            Field::LongDateTime => 8,
+            Field::Bytes(len) => len,
+        }
+    }
+
+    fn read(self, reader: &mut FontReader) -> Result<String> {
+        Ok(match self {
+            Field::U16 => reader.read_u16()?.to_string(),
+            Field::I16 => reader.read_i16()?.to_string(),
+            Field::U32 => reader.read_u32()?.to_string(),
+            Field::Hex32 => format!("{:#010x}", reader.read_u32()?),
+            Field::Fixed => reader.read_fixed()?.to_string(),
+            Field::LongDateTime => reader.read_long_datetime()?.to_string(),
+            Field::Tag => format!("{:?}", String::from_utf8_lossy(&reader.read_tag()?)),
+            Field::Bytes(len) => hex_bytes(&reader.read_bytes(len)?),
+        })
+    }
+}
+
+const HEAD_FIELDS: &[(&str, Field)] = &[
+    ("table_version", Field::Fixed),
+    ("font_revision", Field::Fixed),
+    ("checksum_adjustment", Field::Hex32),
+    ("magic_number", Field::Hex32),
+    ("flags", Field::U16),
+    ("units_per_em", Field::U16),
+    ("created", Field::LongDateTime),
+    ("modified", Field::LongDateTime),
+    ("x_min", Field::I16),
+    ("y_min", Field::I16),
+    ("x_max", Field::I16),
+    ("y_max", Field::I16),
+    ("mac_style", Field::U16),
+    ("lowest_rec_ppem", Field::U16),
+    ("font_direction_hint", Field::I16),
+    ("index_to_loc_format", Field::I16),
+    ("glyph_data_format", Field::I16),
+];
+
+const HHEA_FIELDS: &[(&str, Field)] = &[
+    ("table_version", Field::Fixed),
+    ("ascent", Field::I16),
+    ("descent", Field::I16),
+    ("line_gap", Field::I16),
+    ("advance_width_max", Field::U16),
+    ("min_left_side_bearing", Field::I16),
+    ("min_right_side_bearing", Field::I16),
+    ("x_max_extent", Field::I16),
+    ("caret_slope_rise", Field::I16),
+    ("caret_slope_run", Field::I16),
+    ("caret_offset", Field::I16),
+    ("reserved0", Field::I16),
+    ("reserved1", Field::I16),
+    ("reserved2", Field::I16),
+    ("reserved3", Field::I16),
+    ("metric_data_format", Field::I16),
+    ("number_of_h_metrics", Field::U16),
+];
+
+const VHEA_FIELDS: &[(&str, Field)] = &[
+    ("table_version", Field::Fixed),
+    ("ascent", Field::I16),
+    ("descent", Field::I16),
+    ("line_gap", Field::I16),
+    ("advance_height_max", Field::U16),
+    ("min_top_side_bearing", Field::I16),
+    ("min_bottom_side_bearing", Field::I16),
+    ("y_max_extent", Field::I16),
+    ("caret_slope_rise", Field::I16),
+    ("caret_slope_run", Field::I16),
+    ("caret_offset", Field::I16),
+    ("reserved0", Field::I16),
+    ("reserved1", Field::I16),
+    ("reserved2", Field::I16),
+    ("reserved3", Field::I16),
+    ("metric_data_format", Field::I16),
+    ("number_of_v_metrics", Field::U16),
+];
+
+const MAXP_FIELDS: &[(&str, Field)] = &[
+    ("version", Field::Fixed),
+    ("num_glyphs", Field::U16),
+    ("max_points", Field::U16),
+    ("max_contours", Field::U16),
+    ("max_composite_points", Field::U16),
+    ("max_composite_contours", Field::U16),
+    ("max_zones", Field::U16),
+    ("max_twilight_points", Field::U16),
+    ("max_storage", Field::U16),
+    ("max_function_defs", Field::U16),
+    ("max_instruction_defs", Field::U16),
+    ("max_stack_elements", Field::U16),
+    ("max_size_of_instructions", Field::U16),
+    ("max_component_elements", Field::U16),
+    ("max_component_depth", Field::U16),
+];
+
+const POST_FIELDS: &[(&str, Field)] = &[
+    ("format", Field::Fixed),
+    ("italic_angle", Field::Fixed),
+    ("underline_position", Field::I16),
+    ("underline_thickness", Field::I16),
+    ("is_fixed_pitch", Field::U32),
+    ("min_mem_type42", Field::U32),
+    ("max_mem_type42", Field::U32),
+    ("min_mem_type1", Field::U32),
+    ("max_mem_type1", Field::U32),
+];
+
+const OS2_FIELDS: &[(&str, Field)] = &[
+    ("version", Field::U16),
+    ("x_avg_char_width", Field::I16),
+    ("us_weight_class", Field::U16),
+    ("us_width_class", Field::U16),
+    ("fs_type", Field::U16),
+    ("y_subscript_x_size", Field::I16),
+    ("y_subscript_y_size", Field::I16),
+    ("y_subscript_x_offset", Field::I16),
+    ("y_subscript_y_offset", Field::I16),
+    ("y_superscript_x_size", Field::I16),
+    ("y_superscript_y_size", Field::I16),
+    ("y_superscript_x_offset", Field::I16),
+    ("y_superscript_y_offset", Field::I16),
+    ("y_strikeout_size", Field::I16),
+    ("y_strikeout_position", Field::I16),
+    ("s_family_class", Field::I16),
+    ("panose", Field::Bytes(10)),
+    ("ul_unicode_range1", Field::Hex32),
+    ("ul_unicode_range2", Field::Hex32),
+    ("ul_unicode_range3", Field::Hex32),
+    ("ul_unicode_range4", Field::Hex32),
+    ("ach_vend_id", Field::Tag),
+    ("fs_selection", Field::U16),
+    ("us_first_char_index", Field::U16),
+    ("us_last_char_index", Field::U16),
+    ("s_typo_ascender", Field::I16),
+    ("s_typo_descender", Field::I16),
+    ("s_typo_line_gap", Field::I16),
+    ("us_win_ascent", Field::U16),
+    ("us_win_descent", Field::U16),
+    ("ul_code_page_range1", Field::Hex32),
+    ("ul_code_page_range2", Field::Hex32),
+    ("sx_height", Field::I16),
+    ("s_cap_height", Field::I16),
+    ("us_default_char", Field::U16),
+    ("us_break_char", Field::U16),
+    ("us_max_context", Field::U16),
+    ("us_lower_optical_point_size", Field::U16),
+    ("us_upper_optical_point_size", Field::U16),
+];
+
+/// Field layout of the tables with a fixed header
+fn table_fields(tag: &[u8; 4]) -> Option<&'static [(&'static str, Field)]> {
+    match tag {
+        b"head" => Some(HEAD_FIELDS),
+        b"hhea" => Some(HHEA_FIELDS),
+        b"vhea" => Some(VHEA_FIELDS),
+        b"maxp" => Some(MAXP_FIELDS),
+        b"post" => Some(POST_FIELDS),
+        b"OS/2" => Some(OS2_FIELDS),
+        _ => None,
+    }
+}
+
+impl Font {
+    /// Describe a table's bytes for debugging.
+    ///
+    /// Tables with a fixed header (head, hhea, vhea, maxp, post and OS/2)
+    /// list one field per line with its value and offset within the table,
+    /// e.g. `units_per_em: 1000 @ offset 18`; fields past the end of a short
+    /// table, such as those of a newer OS/2 version, are left out. Any bytes
+    /// after the header, and the whole of other tables, follow as a hex
+    /// dump with offsets.
+    ///
+    /// # Returns
+    ///
+    /// Returns the dump, or `TtfError::MissingTable` if the font has no such
+    /// table.
+    pub fn dump_table(&self, tag: &[u8; 4]) -> Result<String> {
+        let data = self
+            .get_table_data(tag)
+            .ok_or_else(|| TtfError::MissingTable(String::from_utf8_lossy(tag).to_string()))?;
+
+        let mut dump = format!("{} ({} bytes)\n", String::from_utf8_lossy(tag), data.len());
+        let mut reader = FontReader::from_slice(&data);
+        for &(name, field) in table_fields(tag).unwrap_or_default() {
+            let offset = reader.position();
+            if offset + field.size() > data.len() {
+                break;
+            }
+            let value = field.read(&mut reader)?;
+            dump.push_str(&format!("{}: {} @ offset {}\n", name, value, offset));
+        }
+
+        let start = reader.position();
+        if start < data.len() {
+            if start > 0 {
+                dump.push_str(&format!("remaining {} bytes:\n", data.len() - start));
+            }
+            for (row, chunk) in data[start..].chunks(16).enumerate() {
+                dump.push_str(&format!("{:08x}: {}\n", start + row * 16, hex_bytes(chunk)));
+            }
+        }
+
+        Ok(dump)
+    }
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<_>>().join(" ")
+}
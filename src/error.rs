@@ -28,15 +28,27 @@ pub enum TtfError {
     #[error("Invalid number of glyphs: {0}")]
     InvalidNumGlyphs(u16),
 
+    #[error("Unexpected EOF at offset {offset} {context}")]
+    UnexpectedEof { offset: usize, context: &'static str },
+
     #[error("IO error: {0}")]
     Io(#[from] io::Error),
 
     #[error("Parse error: {0}")]
     ParseError(String),
 
+    #[error("{table} at offset {offset:#x}: {message}")]
+    TableParseError { table: String, offset: u64, message: String },
+
     #[error("Invalid encoding: {0}")]
     InvalidEncoding(String),
 
     #[error("Invalid offset in loca table: {0}")]
     InvalidLocaOffset(u32),
+
+    #[error("Parse limit exceeded: {0}")]
+    LimitExceeded(String),
+
+    #[error("Tables not preserved by serialization: {}", .0.join(", "))]
+    RoundTripMismatch(Vec<String>),
 }
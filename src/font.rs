@@ -1,21 +1,44 @@
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::Path;
 
 use crate::error::{Result, TtfError};
+use crate::font_data::FontData;
 use crate::stream::{FontReader, FontWriter};
 use crate::stream::calculate_checksum;
-use crate::tables::{TableRecord, TtfTable};
+use crate::tables::{TableRecord, TtfTable, TtfTableWrite};
 use crate::tables::head::HeadTable;
 use crate::tables::maxp::MaxpTable;
 use crate::tables::cmap::CmapTable;
-use crate::tables::name::NameTable;
+use crate::tables::name::{NameRecord, NameTable};
 use crate::tables::hhea::HheaTable;
-use crate::tables::hmtx::HmtxTable;
-use crate::tables::glyf::GlyfTable;
+use crate::tables::hmtx::{HmtxTable, LongHorMetric};
+use crate::tables::vhea::VheaTable;
+use crate::tables::vmtx::VmtxTable;
+use crate::tables::glyf::{BoundingBox, GlyfTable, Glyph, GlyphData, PathCommand, Point};
 use crate::tables::loca::LocaTable;
 use crate::tables::post::PostTable;
-use crate::tables::os2::Os2Table;
+use crate::tables::os2::{EmbeddingPermission, Os2Table};
+use crate::tables::cvt::CvtTable;
+use crate::tables::fpgm::FpgmTable;
+use crate::tables::prep::PrepTable;
+use crate::tables::fvar::FvarTable;
+use crate::tables::gvar::GvarTable;
+use crate::tables::avar::AvarTable;
+use crate::tables::colr::{ColrTable, ColorLayer};
+use crate::tables::cpal::CpalTable;
+use crate::tables::sbix::{SbixTable, SbixGlyph};
+use crate::tables::cbdt::{CbdtTable, CblcTable, ColorBitmap};
+use crate::tables::ebdt::{EbdtTable, MonoBitmap};
+use crate::tables::kern::KernTable;
+use crate::tables::gpos::GposTable;
+use crate::tables::gsub::GsubTable;
+use crate::tables::svg::SvgTable;
+use crate::tables::vdmx::VdmxTable;
+use crate::tables::ltsh::LtshTable;
+use crate::tables::meta::MetaTable;
+use crate::tables::cff::{CffOutline, CffTable};
 
 /// Main Font structure representing a TrueType font.
 ///
@@ -50,13 +73,95 @@ pub struct Font {
     /// Table directory records containing metadata for each table
     pub table_records: Vec<TableRecord>,
     /// Raw font data bytes
-    pub data: Vec<u8>,
+    pub data: FontData,
+}
+
+/// Bounds checked while loading a font from untrusted data, see
+/// `Font::from_data_limited`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Largest length a table directory record may declare, in bytes
+    pub max_table_length: u32,
+    /// Largest glyph count maxp may declare. Whatever the limit, the count
+    /// must also fit in the font data at two bytes per glyph
+    pub max_glyphs: u16,
+}
+
+impl Default for ParseLimits {
+    /// Limits generous enough for any real font: 256 MiB per table, and no
+    /// more glyphs than the font data can hold. CJK fonts use the whole
+    /// 16-bit glyph range, so no lower count is imposed
+    fn default() -> Self {
+        ParseLimits {
+            max_table_length: 256 * 1024 * 1024,
+            max_glyphs: u16::MAX,
+        }
+    }
+}
+
+/// Vertical line metrics in font units
+///
+/// `descent` is negative below the baseline, as in hhea.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineMetrics {
+    pub ascent: i16,
+    pub descent: i16,
+    pub line_gap: i16,
+}
+
+impl LineMetrics {
+    /// Distance between consecutive baselines
+    pub fn line_height(&self) -> i32 {
+        self.ascent as i32 - self.descent as i32 + self.line_gap as i32
+    }
+}
+
+/// Ink box of a glyph relative to its origin, as returned by
+/// `Font::glyph_extents`
+///
+/// `x_bearing` is the left side bearing from hmtx and `y_bearing` the top
+/// of the outline above the baseline; `width` and `height` extend right and
+/// down from that corner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GlyphExtents {
+    /// Extents in whole font units
+    FontUnits { x_bearing: i32, y_bearing: i32, width: i32, height: i32 },
+    /// Extents scaled to pixels at a given size
+    Pixels { x_bearing: f32, y_bearing: f32, width: f32, height: f32 },
+}
+
+/// Overview of a font's header, naming and style information
+///
+/// Built by `Font::summary`. Fields whose table is missing or unreadable
+/// hold a default: zero counts, "Unknown" names and a regular (400) weight.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontSummary {
+    pub sfnt_version: u32,
+    pub num_tables: u16,
+    pub num_glyphs: u16,
+    pub units_per_em: u16,
+    pub family_name: String,
+    pub subfamily_name: String,
+    pub is_bold: bool,
+    pub is_italic: bool,
+    pub weight_class: u16,
+    /// Table tags in directory order
+    pub tables: Vec<String>,
 }
 
 impl Font {
     const SFNT_TRUETYPE: u32 = 0x00010000;
     const SFNT_OPENTYPE: u32 = 0x4F54544F; // 'OTTO'
 
+    /// Letter frequencies (per 1000) used by OS/2 versions 0-2 to weight
+    /// xAvgCharWidth
+    const AVG_CHAR_WEIGHTS: [(char, u32); 27] = [
+        ('a', 64), ('b', 14), ('c', 27), ('d', 35), ('e', 100), ('f', 20), ('g', 14),
+        ('h', 42), ('i', 63), ('j', 3), ('k', 6), ('l', 35), ('m', 20), ('n', 56),
+        ('o', 56), ('p', 17), ('q', 4), ('r', 49), ('s', 56), ('t', 71), ('u', 31),
+        ('v', 10), ('w', 18), ('x', 3), ('y', 18), ('z', 2), (' ', 166),
+    ];
+
     /// Load a font from a file path.
     ///
     /// Reads the entire font file into memory and parses the SFNT structure.
@@ -113,7 +218,47 @@ impl Font {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn from_data(data: Vec<u8>) -> Result<Self> {
-        let mut reader = FontReader::new(data);
+        Self::parse(FontData::Owned(data), &ParseLimits::default())
+    }
+
+    /// Load a font from raw bytes, rejecting it if it exceeds `limits`.
+    ///
+    /// Table parsers size their buffers from counts and lengths in the
+    /// font, so fonts from untrusted sources can be loaded with tighter
+    /// limits than the defaults used by `from_data`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Font` instance on success, `TtfError::LimitExceeded` if a
+    /// table record or the maxp glyph count is over its limit, or
+    /// `TtfError::TableParseError` if a table record points past the end of
+    /// the data.
+    pub fn from_data_limited(data: Vec<u8>, limits: ParseLimits) -> Result<Self> {
+        Self::parse(FontData::Owned(data), &limits)
+    }
+
+    /// Load a font by memory-mapping the file instead of reading it.
+    ///
+    /// Tables are parsed from the mapped bytes on demand, so only the
+    /// tables that are accessed get paged in, which suits large (e.g. CJK)
+    /// fonts used read-only. The font otherwise behaves like one from
+    /// `load`; modifying its data copies it into memory first.
+    ///
+    /// The file must not be modified while the font is in use.
+    #[cfg(feature = "mmap")]
+    pub fn load_mmap<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the map is read-only; as documented, the caller must not
+        // modify the file while it is mapped
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+
+        Self::parse(FontData::Mapped(std::sync::Arc::new(mmap)), &ParseLimits::default())
+    }
+
+    /// Parse the SFNT header and table directory, keeping `data` as the
+    /// font's bytes
+    fn parse(data: FontData, limits: &ParseLimits) -> Result<Self> {
+        let mut reader = FontReader::from_slice(&data[..data.len().min(12)]);
 
         // Read SFNT header
         let sfnt_version = reader.read_u32()?;
@@ -131,14 +276,31 @@ impl Font {
         let range_shift = reader.read_u16()?;
 
         // Read table records
+        let directory_end = data.len().min(12 + num_tables as usize * 16);
+        let mut reader = FontReader::from_slice(&data[12..directory_end]);
         let mut table_records = Vec::with_capacity(num_tables as usize);
         for _ in 0..num_tables {
-            table_records.push(TableRecord::from_reader(&mut reader)?);
+            let record = TableRecord::from_reader(&mut reader)?;
+            if record.length > limits.max_table_length {
+                return Err(TtfError::LimitExceeded(format!(
+                    "table {} is {} bytes, over the {} byte limit",
+                    record.tag_to_string(), record.length, limits.max_table_length
+                )));
+            }
+            if record.offset as u64 + record.length as u64 > data.len() as u64 {
+                return Err(TtfError::TableParseError {
+                    table: record.tag_to_string(),
+                    offset: record.offset as u64,
+                    message: format!(
+                        "{} byte table extends past the end of the {} byte font",
+                        record.length, data.len()
+                    ),
+                });
+            }
+            table_records.push(record);
         }
 
-        let data = reader.into_inner();
-
-        Ok(Font {
+        let font = Font {
             sfnt_version,
             num_tables,
             search_range,
@@ -146,7 +308,31 @@ impl Font {
             range_shift,
             table_records,
             data,
-        })
+        };
+
+        // Read numGlyphs directly so a truncated maxp is left to maxp_table
+        if let Some(record) = font.get_table_record(b"maxp") {
+            let start = record.offset as usize + 4;
+            if let Some(bytes) = font.data.get(start..start + 2) {
+                let num_glyphs = u16::from_be_bytes([bytes[0], bytes[1]]);
+                if num_glyphs > limits.max_glyphs {
+                    return Err(TtfError::LimitExceeded(format!(
+                        "maxp declares {} glyphs, over the limit of {}",
+                        num_glyphs, limits.max_glyphs
+                    )));
+                }
+                // Every glyph takes at least two bytes of the font (its
+                // hmtx side bearing and loca entry, or its CFF charstring)
+                if num_glyphs as usize * 2 > font.data.len() {
+                    return Err(TtfError::LimitExceeded(format!(
+                        "maxp declares {} glyphs, more than the {} byte font can hold",
+                        num_glyphs, font.data.len()
+                    )));
+                }
+            }
+        }
+
+        Ok(font)
     }
 
     /// Get a table record by tag.
@@ -166,6 +352,59 @@ impl Font {
             .find(|r| r.table_tag == *tag)
     }
 
+    /// Check whether the font has a table, e.g. `b"OS/2"` or `b"CVT "`
+    pub fn has_table(&self, tag: &[u8; 4]) -> bool {
+        self.get_table_record(tag).is_some()
+    }
+
+    /// Check whether the font has TrueType outlines (a glyf table)
+    pub fn has_glyf(&self) -> bool {
+        self.has_table(b"glyf")
+    }
+
+    /// Check whether the font has a "CFF " or "CFF2" table, whatever its
+    /// sfnt version; see also `is_cff`
+    pub fn has_cff(&self) -> bool {
+        self.has_table(b"CFF ") || self.has_table(b"CFF2")
+    }
+
+    /// Check whether the font has color layers (a COLR table)
+    pub fn has_colr(&self) -> bool {
+        self.has_table(b"COLR")
+    }
+
+    /// Check whether the font has OpenType positioning (a GPOS table)
+    pub fn has_gpos(&self) -> bool {
+        self.has_table(b"GPOS")
+    }
+
+    /// Check whether the font has legacy kerning (a kern table)
+    pub fn has_kern(&self) -> bool {
+        self.has_table(b"kern")
+    }
+
+    /// Check whether the font has any glyph outlines.
+    ///
+    /// CFF fonts always count as having outlines. For TrueType fonts this is
+    /// false when there is no glyf table or when every loca entry is empty,
+    /// as in bitmap-only fonts that keep an empty glyf for compatibility.
+    ///
+    /// # Returns
+    ///
+    /// Returns whether any glyph has outline data, or an error if the loca
+    /// table can't be read.
+    pub fn has_outlines(&self) -> Result<bool> {
+        if self.is_cff() {
+            return Ok(true);
+        }
+        if !self.has_glyf() {
+            return Ok(false);
+        }
+
+        let offsets = self.loca_table()?.offsets();
+        Ok(offsets.windows(2).any(|pair| pair[1] > pair[0]))
+    }
+
     /// Get raw table data
     pub fn get_table_data(&self, tag: &[u8; 4]) -> Option<Vec<u8>> {
         let record = self.get_table_record(tag)?;
@@ -179,6 +418,56 @@ impl Font {
         Some(self.data[start..end].to_vec())
     }
 
+    /// Iterate over the tables in directory order, borrowing their data.
+    ///
+    /// Tables whose record points outside the font data are skipped, as
+    /// `get_table_data` returns `None` for them.
+    pub fn tables(&self) -> impl Iterator<Item = (&[u8; 4], &[u8])> {
+        self.table_records.iter().filter_map(|record| {
+            let start = record.offset as usize;
+            let end = start.checked_add(record.length as usize)?;
+            Some((&record.table_tag, self.data.get(start..end)?))
+        })
+    }
+
+    /// Parse any table type that declares its tag through
+    /// `TtfTable::table_tag`, e.g. `font.parse_table::<HeadTable>()`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed table, or an error if the type has no tag or the
+    /// table is missing or invalid.
+    pub fn parse_table<T: TtfTable>(&self) -> Result<T> {
+        let tag = T::table_tag().ok_or_else(|| {
+            TtfError::ParseError(format!("{} does not declare a table tag", std::any::type_name::<T>()))
+        })?;
+        let record = self
+            .get_table_record(tag)
+            .ok_or_else(|| TtfError::MissingTable(String::from_utf8_lossy(tag).to_string()))?;
+
+        let start = record.offset as usize;
+        let end = start + record.length as usize;
+        let data = self.data.get(start..end).ok_or(TtfError::InvalidOffset(end as u64))?;
+        let mut reader = FontReader::from_slice(data);
+
+        T::from_reader(&mut reader, record.length)
+    }
+
+    /// Serialize a table and store it under its tag, replacing the existing
+    /// table or adding a new one.
+    ///
+    /// The font is rebuilt as by `FontModifier::commit`, so the table
+    /// directory and checksums stay consistent.
+    pub fn set_table<T: TtfTableWrite>(&mut self, table: &T) -> Result<()> {
+        let mut writer = FontWriter::new();
+        table.write(&mut writer)?;
+
+        let mut modifier = self.clone().modify();
+        modifier.add_table(T::table_tag(), writer.into_inner());
+        *self = modifier.commit()?;
+        Ok(())
+    }
+
     /// Get the head table (font header).
     ///
     /// The head table contains global font information including
@@ -188,14 +477,7 @@ impl Font {
     ///
     /// Returns the parsed `HeadTable` or an error if the table is missing or invalid.
     pub fn head_table(&self) -> Result<HeadTable> {
-        let record = self
-            .get_table_record(b"head")
-            .ok_or_else(|| TtfError::MissingTable("head".to_string()))?;
-
-        let start = record.offset as usize;
-        let mut reader = FontReader::from_slice(&self.data[start..start + record.length as usize]);
-
-        HeadTable::from_reader(&mut reader, record.length)
+        self.parse_table()
     }
 
     /// Get the maxp table (maximum profile).
@@ -207,14 +489,7 @@ impl Font {
     ///
     /// Returns the parsed `MaxpTable` or an error if the table is missing or invalid.
     pub fn maxp_table(&self) -> Result<MaxpTable> {
-        let record = self
-            .get_table_record(b"maxp")
-            .ok_or_else(|| TtfError::MissingTable("maxp".to_string()))?;
-
-        let start = record.offset as usize;
-        let mut reader = FontReader::from_slice(&self.data[start..start + record.length as usize]);
-
-        MaxpTable::from_reader(&mut reader, record.length)
+        self.parse_table()
     }
 
     /// Get the cmap table (character to glyph mapping).
@@ -245,14 +520,7 @@ impl Font {
     ///
     /// Returns the parsed `NameTable` or an error if the table is missing or invalid.
     pub fn name_table(&self) -> Result<NameTable> {
-        let record = self
-            .get_table_record(b"name")
-            .ok_or_else(|| TtfError::MissingTable("name".to_string()))?;
-
-        let start = record.offset as usize;
-        let mut reader = FontReader::from_slice(&self.data[start..start + record.length as usize]);
-
-        NameTable::from_reader(&mut reader, record.length)
+        self.parse_table()
     }
 
     /// Get the hhea table (horizontal header).
@@ -264,14 +532,7 @@ impl Font {
     ///
     /// Returns the parsed `HheaTable` or an error if the table is missing or invalid.
     pub fn hhea_table(&self) -> Result<HheaTable> {
-        let record = self
-            .get_table_record(b"hhea")
-            .ok_or_else(|| TtfError::MissingTable("hhea".to_string()))?;
-
-        let start = record.offset as usize;
-        let mut reader = FontReader::from_slice(&self.data[start..start + record.length as usize]);
-
-        HheaTable::from_reader(&mut reader, record.length)
+        self.parse_table()
     }
 
     /// Get the hmtx table (horizontal metrics).
@@ -297,6 +558,42 @@ impl Font {
         HmtxTable::from_reader(&mut reader, record.length, maxp.num_glyphs, hhea.number_of_h_metrics)
     }
 
+    /// Get the vhea table (vertical header).
+    ///
+    /// The vhea table contains the vertical layout metrics shared by all
+    /// glyphs, including the number of long metrics in vmtx.
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `VheaTable` or an error if the table is missing or invalid.
+    pub fn vhea_table(&self) -> Result<VheaTable> {
+        self.parse_table()
+    }
+
+    /// Get the vmtx table (vertical metrics).
+    ///
+    /// The vmtx table contains the advance height and top side bearing of
+    /// each glyph, for vertical text layout.
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `VmtxTable` or an error if the table is missing or invalid.
+    pub fn vmtx_table(&self) -> Result<VmtxTable> {
+        let record = self
+            .get_table_record(b"vmtx")
+            .ok_or_else(|| TtfError::MissingTable("vmtx".to_string()))?;
+
+        let vhea = self.vhea_table()?;
+        let maxp = self.maxp_table()?;
+
+        let start = record.offset as usize;
+        let end = start + record.length as usize;
+        let data = self.data.get(start..end).ok_or(TtfError::InvalidOffset(end as u64))?;
+        let mut reader = FontReader::from_slice(data);
+
+        VmtxTable::from_reader(&mut reader, record.length, maxp.num_glyphs, vhea.number_of_v_metrics)
+    }
+
     /// Get the loca table (glyph location).
     ///
     /// The loca table contains information about the location of each glyph
@@ -306,12 +603,16 @@ impl Font {
     ///
     /// Returns the parsed `LocaTable` or an error if the table is missing or invalid.
     pub fn loca_table(&self) -> Result<LocaTable> {
+        self.loca_table_for(self.maxp_table()?.num_glyphs)
+    }
+
+    /// Parse the loca offsets of the first `num_glyphs` glyphs
+    fn loca_table_for(&self, num_glyphs: u16) -> Result<LocaTable> {
         let record = self
             .get_table_record(b"loca")
             .ok_or_else(|| TtfError::MissingTable("loca".to_string()))?;
 
         let head = self.head_table()?;
-        let maxp = self.maxp_table()?;
 
         let start = record.offset as usize;
         let end = start + record.length as usize;
@@ -320,7 +621,7 @@ impl Font {
         LocaTable::from_reader(
             &mut reader,
             record.length,
-            maxp.num_glyphs as usize + 1,
+            num_glyphs as usize,
             head.is_long_loca_format(),
         )
     }
@@ -333,18 +634,98 @@ impl Font {
     ///
     /// Returns the parsed `GlyfTable` or an error if the table is missing or invalid.
     pub fn glyf_table(&self) -> Result<GlyfTable> {
+        self.glyf_table_for(self.maxp_table()?.num_glyphs)
+    }
+
+    /// Parse the outlines of the first `num_glyphs` glyphs
+    fn glyf_table_for(&self, num_glyphs: u16) -> Result<GlyfTable> {
         let record = self
             .get_table_record(b"glyf")
             .ok_or_else(|| TtfError::MissingTable("glyf".to_string()))?;
 
-        let loca = self.loca_table()?;
-        let maxp = self.maxp_table()?;
+        let loca = self.loca_table_for(num_glyphs)?;
 
         let start = record.offset as usize;
         let end = start + record.length as usize;
         let mut reader = FontReader::from_slice(&self.data[start..end]);
 
-        GlyfTable::from_reader(&mut reader, record.length, &loca, maxp.num_glyphs)
+        GlyfTable::from_reader(&mut reader, record.length, &loca, num_glyphs)
+    }
+
+    /// Get a glyph from the glyf table.
+    ///
+    /// Unlike `GlyfTable::get_glyph`, an out-of-range ID is an error.
+    ///
+    /// # Returns
+    ///
+    /// Returns the glyph, or `TtfError::InvalidGlyphIndex` if `glyph_id` is
+    /// not below the font's glyph count.
+    pub fn glyph(&self, glyph_id: u16) -> Result<Glyph> {
+        if glyph_id >= self.num_glyphs()? {
+            return Err(TtfError::InvalidGlyphIndex(glyph_id));
+        }
+
+        self.glyf_table()?
+            .get_glyph(glyph_id as usize)
+            .cloned()
+            .ok_or(TtfError::InvalidGlyphIndex(glyph_id))
+    }
+
+    /// Compute the bounding box of a glyph from its outline points.
+    ///
+    /// Composite glyphs are resolved first, so the box covers every
+    /// transformed component rather than the bbox stored in the glyph header.
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` for glyphs without an outline, or
+    /// `TtfError::InvalidGlyphIndex` if `glyph_id` is out of range.
+    pub fn glyph_bounding_box(&self, glyph_id: u16) -> Result<Option<BoundingBox>> {
+        if glyph_id >= self.num_glyphs()? {
+            return Err(TtfError::InvalidGlyphIndex(glyph_id));
+        }
+
+        let resolved = self.glyf_table()?.resolve_composite(glyph_id as usize)?;
+        Ok(resolved.and_then(|glyph| glyph.calculate_bounding_box()))
+    }
+
+    /// Get the extents of a glyph's outline: `GlyphExtents::FontUnits`
+    /// without `px_size`, or `GlyphExtents::Pixels` scaled to that size.
+    ///
+    /// The box comes from the outline with composites resolved (see
+    /// `glyph_bounding_box`); glyphs without an outline have zero size.
+    /// Font-unit extents are rounded to whole units.
+    ///
+    /// # Returns
+    ///
+    /// Returns the extents, or `TtfError::InvalidGlyphIndex` if `glyph_id`
+    /// is out of range.
+    pub fn glyph_extents(&self, glyph_id: u16, px_size: Option<f32>) -> Result<GlyphExtents> {
+        let bbox = self.glyph_bounding_box(glyph_id)?;
+        let lsb = self.hmtx_table()?.get_lsb(glyph_id) as f32;
+
+        let (y_bearing, width, height) = match bbox {
+            Some(bbox) => (bbox.y_max, bbox.x_max - bbox.x_min, bbox.y_max - bbox.y_min),
+            None => (0.0, 0.0, 0.0),
+        };
+
+        Ok(match px_size {
+            Some(px_size) => {
+                let scale = px_size / self.head_table()?.units_per_em as f32;
+                GlyphExtents::Pixels {
+                    x_bearing: lsb * scale,
+                    y_bearing: y_bearing * scale,
+                    width: width * scale,
+                    height: height * scale,
+                }
+            }
+            None => GlyphExtents::FontUnits {
+                x_bearing: lsb as i32,
+                y_bearing: y_bearing.round() as i32,
+                width: width.round() as i32,
+                height: height.round() as i32,
+            },
+        })
     }
 
     /// Get the post table (PostScript information).
@@ -356,14 +737,7 @@ impl Font {
     ///
     /// Returns the parsed `PostTable` or an error if the table is missing or invalid.
     pub fn post_table(&self) -> Result<PostTable> {
-        let record = self
-            .get_table_record(b"post")
-            .ok_or_else(|| TtfError::MissingTable("post".to_string()))?;
-
-        let start = record.offset as usize;
-        let mut reader = FontReader::from_slice(&self.data[start..start + record.length as usize]);
-
-        PostTable::from_reader(&mut reader, record.length)
+        self.parse_table()
     }
 
     /// Get the OS/2 table (OS/2 and Windows metrics).
@@ -375,117 +749,874 @@ impl Font {
     ///
     /// Returns the parsed `Os2Table` or an error if the table is missing or invalid.
     pub fn os2_table(&self) -> Result<Os2Table> {
-        let record = self
-            .get_table_record(b"OS/2")
-            .ok_or_else(|| TtfError::MissingTable("OS/2".to_string()))?;
+        self.parse_table()
+    }
 
-        let start = record.offset as usize;
-        let mut reader = FontReader::from_slice(&self.data[start..start + record.length as usize]);
+    /// Get the cvt table (control value table).
+    ///
+    /// The cvt table holds the FWORD values referenced by TrueType hinting
+    /// instructions.
+    ///
+    /// # Returns
+    ///
+    /// Returns the control values or an error if the table is missing or invalid.
+    pub fn cvt_table(&self) -> Result<Vec<i16>> {
+        Ok(self.parse_table::<CvtTable>()?.values)
+    }
 
-        Os2Table::from_reader(&mut reader, record.length)
+    /// Get the raw bytecode of the fpgm table (font program).
+    ///
+    /// The font program is executed once, before any glyph is hinted.
+    ///
+    /// # Returns
+    ///
+    /// Returns the instruction bytes or an error if the table is missing or invalid.
+    pub fn fpgm_bytecode(&self) -> Result<Vec<u8>> {
+        Ok(self.parse_table::<FpgmTable>()?.instructions)
     }
 
-    /// Get glyph index for a character
-    pub fn char_to_glyph(&self, c: char) -> Result<u32> {
-        let cmap = self.cmap_table()?;
-        cmap.map_char(c).ok_or_else(|| {
-            TtfError::ParseError(format!("No glyph found for character: {}", c))
-        })
+    /// Get the raw bytecode of the prep table (control value program).
+    ///
+    /// The control value program is executed whenever the point size or
+    /// transformation changes.
+    ///
+    /// # Returns
+    ///
+    /// Returns the instruction bytes or an error if the table is missing or invalid.
+    pub fn prep_bytecode(&self) -> Result<Vec<u8>> {
+        Ok(self.parse_table::<PrepTable>()?.instructions)
     }
 
-    /// Get font name
-    pub fn font_name(&self) -> Result<String> {
-        let name = self.name_table()?;
-        if let Some(record) = name.get_font_name() {
-            Ok(format!("{:?}", record))
-        } else {
-            Ok("Unknown".to_string())
-        }
+    /// Get the fvar table (font variations).
+    ///
+    /// The fvar table lists the variation axes of a variable font and its
+    /// named instances.
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `FvarTable` or an error if the table is missing or invalid.
+    pub fn fvar_table(&self) -> Result<FvarTable> {
+        self.parse_table()
     }
 
-    /// Get font family name
-    pub fn family_name(&self) -> Result<String> {
-        let name = self.name_table()?;
-        if let Some(record) = name.get_full_name() {
-            Ok(format!("{:?}", record))
-        } else {
-            Ok("Unknown".to_string())
-        }
+    /// Check if the font carries a digital signature (has a DSIG table)
+    pub fn has_digital_signature(&self) -> bool {
+        self.has_table(b"DSIG")
     }
 
-    /// Check if font is bold
-    pub fn is_bold(&self) -> Result<bool> {
-        let os2 = self.os2_table()?;
-        Ok(os2.is_bold())
+    /// Check if the font has PostScript (CFF or CFF2) outlines.
+    ///
+    /// True for 'OTTO' fonts with a "CFF " or "CFF2" table; such fonts have
+    /// no glyf or loca table.
+    pub fn is_cff(&self) -> bool {
+        self.sfnt_version == Self::SFNT_OPENTYPE && self.has_cff()
     }
 
-    /// Check if font is italic
-    pub fn is_italic(&self) -> Result<bool> {
-        let os2 = self.os2_table()?;
-        Ok(os2.is_italic())
+    /// Get the raw bytes of the CFF table, or of the CFF2 table if there is
+    /// no CFF table.
+    ///
+    /// The CFF outlines are not parsed; the data is passed through as-is.
+    ///
+    /// # Returns
+    ///
+    /// Returns `TtfError::MissingTable` if the font has neither table.
+    pub fn cff_data(&self) -> Result<Vec<u8>> {
+        self.get_table_data(b"CFF ")
+            .or_else(|| self.get_table_data(b"CFF2"))
+            .ok_or_else(|| TtfError::MissingTable("CFF ".to_string()))
     }
 
-    /// Get number of glyphs
-    pub fn num_glyphs(&self) -> Result<u16> {
-        let maxp = self.maxp_table()?;
-        Ok(maxp.num_glyphs)
+    /// Get the CFF table (PostScript outlines of an OpenType font).
+    ///
+    /// Only name-keyed CFF fonts are supported; CFF2 tables are not parsed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `CffTable` or an error if the table is missing or invalid.
+    pub fn cff_table(&self) -> Result<CffTable> {
+        self.parse_table()
     }
 
-    /// Get units per em
-    pub fn units_per_em(&self) -> Result<u16> {
-        let head = self.head_table()?;
-        Ok(head.units_per_em)
+    /// Get the outline of a glyph from the CFF table
+    pub fn cff_outline(&self, glyph_id: u16) -> Result<CffOutline> {
+        self.cff_table()?.outline(glyph_id)
     }
 
-    /// List all tables
-    pub fn list_tables(&self) -> Vec<String> {
-        self.table_records
-            .iter()
-            .map(|r| r.tag_to_string())
-            .collect()
+    /// Check if this is a variable font (has an fvar table)
+    pub fn is_variable(&self) -> Result<bool> {
+        Ok(self.has_table(b"fvar"))
     }
 
-    /// Save the font to a file.
+    /// Get the gvar table (glyph variations).
     ///
-    /// Serializes the font data and writes it to the specified path.
+    /// The gvar table holds the point deltas that move glyph outlines
+    /// across the design space of a variable font.
     ///
-    /// # Arguments
+    /// # Returns
     ///
-    /// * `path` - Output path for the font file
+    /// Returns the parsed `GvarTable` or an error if the table is missing or invalid.
+    pub fn gvar_table(&self) -> Result<GvarTable> {
+        self.parse_table()
+    }
+
+    /// Get the avar table (axis variations).
     ///
-    /// # Examples
+    /// The avar table remaps normalized axis coordinates before they are
+    /// used to interpolate glyph variations.
     ///
-    /// ```no_run
-    /// use ttf_rs::Font;
+    /// # Returns
     ///
-    /// let font = Font::load("input.ttf")?;
-    /// font.save("output.ttf")?;
-    /// # Ok::<(), Box<dyn std::error::Error>>(())
-    /// ```
-    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        let data = self.to_bytes()?;
-        let mut file = File::create(path)?;
-        file.write_all(&data)?;
-        Ok(())
+    /// Returns the parsed `AvarTable` or an error if the table is missing or invalid.
+    pub fn avar_table(&self) -> Result<AvarTable> {
+        self.parse_table()
     }
 
-    /// Convert font to raw bytes
-    pub fn to_bytes(&self) -> Result<Vec<u8>> {
-        let mut writer = FontWriter::new();
+    /// Get the COLR table (color glyph layers).
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `ColrTable` or an error if the table is missing or invalid.
+    pub fn colr_table(&self) -> Result<ColrTable> {
+        self.parse_table()
+    }
 
-        // Calculate table data and offsets
-        let header_size = 12 + (self.table_records.len() * 16);
-        let mut current_offset = header_size as u32;
+    /// Get the CPAL table (color palettes).
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `CpalTable` or an error if the table is missing or invalid.
+    pub fn cpal_table(&self) -> Result<CpalTable> {
+        self.parse_table()
+    }
 
-        // Round up offset to 4-byte boundary
-        if current_offset % 4 != 0 {
-            current_offset = (current_offset + 3) & !3;
+    /// Get the color layers of a glyph.
+    ///
+    /// Returns `None` if the glyph has no color layers (or the font has no
+    /// COLR table), in which case the glyph is drawn as a plain outline.
+    pub fn color_layers(&self, glyph_id: u16) -> Result<Option<Vec<ColorLayer>>> {
+        if !self.has_colr() {
+            return Ok(None);
         }
 
-        // Collect table data
-        let mut table_data: Vec<([u8; 4], Vec<u8>)> = Vec::new();
+        let colr = self.colr_table()?;
+        Ok(colr.layers(glyph_id).map(|layers| layers.to_vec()))
+    }
 
-        for record in &self.table_records {
+    /// Get the colors of a CPAL palette as RGBA values.
+    pub fn palette(&self, index: usize) -> Result<Vec<[u8; 4]>> {
+        let cpal = self.cpal_table()?;
+        let palette = cpal
+            .palettes
+            .get(index)
+            .ok_or_else(|| TtfError::ParseError(format!("Palette index {} out of range", index)))?;
+
+        Ok(palette.colors.iter().map(|color| color.to_rgba()).collect())
+    }
+
+    /// Get the sbix table (embedded bitmap graphics).
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `SbixTable` or an error if the table is missing or invalid.
+    pub fn sbix_table(&self) -> Result<SbixTable> {
+        let record = self
+            .get_table_record(b"sbix")
+            .ok_or_else(|| TtfError::MissingTable("sbix".to_string()))?;
+
+        let maxp = self.maxp_table()?;
+
+        let start = record.offset as usize;
+        let end = start + record.length as usize;
+        let data = self.data.get(start..end).ok_or(TtfError::InvalidOffset(end as u64))?;
+        let mut reader = FontReader::from_slice(data);
+
+        SbixTable::from_reader(&mut reader, record.length, maxp.num_glyphs)
+    }
+
+    /// Get the embedded image of a glyph from the sbix strike best suited
+    /// to `ppem`.
+    ///
+    /// Returns `None` if the font has no sbix table or the strike holds no
+    /// image for the glyph.
+    pub fn sbix_glyph(&self, glyph_id: u16, ppem: u16) -> Result<Option<SbixGlyph>> {
+        if !self.has_table(b"sbix") {
+            return Ok(None);
+        }
+
+        let sbix = self.sbix_table()?;
+        Ok(sbix
+            .best_strike(ppem)
+            .and_then(|strike| strike.glyph(glyph_id))
+            .cloned())
+    }
+
+    /// Get the CBLC table (color bitmap locations).
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `CblcTable` or an error if the table is missing or invalid.
+    pub fn cblc_table(&self) -> Result<CblcTable> {
+        self.parse_table()
+    }
+
+    /// Get the CBDT table (color bitmap data).
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `CbdtTable` or an error if the table is missing or invalid.
+    pub fn cbdt_table(&self) -> Result<CbdtTable> {
+        self.parse_table()
+    }
+
+    /// Get the color bitmap of a glyph from the CBLC/CBDT strike best suited
+    /// to `ppem`.
+    ///
+    /// Returns `None` if the font has no CBLC table or the strike holds no
+    /// bitmap for the glyph.
+    pub fn color_bitmap(&self, glyph_id: u16, ppem: u16) -> Result<Option<ColorBitmap>> {
+        if !self.has_table(b"CBLC") {
+            return Ok(None);
+        }
+
+        let cblc = self.cblc_table()?;
+        let location = cblc
+            .best_size(ppem)
+            .and_then(|size| size.glyph_location(glyph_id));
+
+        match location {
+            Some((image_format, offset, length)) => {
+                let cbdt = self.cbdt_table()?;
+                Ok(Some(cbdt.bitmap(image_format, offset, length)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Get the EBLC table (embedded bitmap locations).
+    ///
+    /// EBLC shares its layout with CBLC, so it is parsed into a `CblcTable`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `CblcTable` or an error if the table is missing or invalid.
+    pub fn eblc_table(&self) -> Result<CblcTable> {
+        let record = self
+            .get_table_record(b"EBLC")
+            .ok_or_else(|| TtfError::MissingTable("EBLC".to_string()))?;
+
+        let start = record.offset as usize;
+        let end = start + record.length as usize;
+        let data = self.data.get(start..end).ok_or(TtfError::InvalidOffset(end as u64))?;
+        let mut reader = FontReader::from_slice(data);
+
+        CblcTable::from_reader(&mut reader, record.length)
+    }
+
+    /// Get the EBDT table (embedded bitmap data).
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `EbdtTable` or an error if the table is missing or invalid.
+    pub fn ebdt_table(&self) -> Result<EbdtTable> {
+        self.parse_table()
+    }
+
+    /// Get the embedded bitmap of a glyph from the EBLC/EBDT strike best
+    /// suited to `ppem`.
+    ///
+    /// Returns `None` if the font has no EBLC table or the strike holds no
+    /// bitmap for the glyph.
+    pub fn embedded_bitmap(&self, glyph_id: u16, ppem: u16) -> Result<Option<MonoBitmap>> {
+        if !self.has_table(b"EBLC") {
+            return Ok(None);
+        }
+
+        let eblc = self.eblc_table()?;
+        let Some(size) = eblc.best_size(ppem) else {
+            return Ok(None);
+        };
+
+        match size.glyph_subtable(glyph_id) {
+            Some((subtable, offset, length)) => {
+                let ebdt = self.ebdt_table()?;
+                Ok(Some(ebdt.bitmap(
+                    subtable.image_format,
+                    offset,
+                    length,
+                    size.bit_depth,
+                    subtable.metrics.as_ref(),
+                )?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Get the SVG table (OpenType-SVG glyph documents).
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `SvgTable` or an error if the table is missing or invalid.
+    pub fn svg_table(&self) -> Result<SvgTable> {
+        self.parse_table()
+    }
+
+    /// Get the text of the SVG document that renders a glyph.
+    ///
+    /// Returns `None` if the font has no SVG table or no document covers the
+    /// glyph. Gzip-compressed documents are decompressed, which requires the
+    /// `compression` feature.
+    pub fn svg_document(&self, glyph_id: u16) -> Result<Option<String>> {
+        if !self.has_table(b"SVG ") {
+            return Ok(None);
+        }
+
+        let svg = self.svg_table()?;
+        svg.document(glyph_id).map(|document| document.text()).transpose()
+    }
+
+    /// Get the outline of a glyph at a position in the design space.
+    ///
+    /// `coords` are user-space axis values in fvar axis order (e.g. `[650.0]`
+    /// for a weight of 650); missing values default to the axis default.
+    /// Coordinates are normalized through fvar and remapped by avar when the
+    /// font has one. The gvar deltas of every tuple are scaled, summed and
+    /// applied to the default outline, and the bounding box is recomputed.
+    /// Composite and empty glyphs are returned unchanged.
+    pub fn glyph_outline_at(&self, glyph_id: u16, coords: &[f32]) -> Result<Glyph> {
+        let mut glyph = self.glyph(glyph_id)?;
+
+        let simple = match &mut glyph.data {
+            GlyphData::Simple(simple) => simple,
+            _ => return Ok(glyph),
+        };
+
+        let mut normalized = self.fvar_table()?.normalize(coords);
+        if self.has_table(b"avar") {
+            let avar = self.avar_table()?;
+            for (axis, value) in normalized.iter_mut().enumerate() {
+                *value = avar.map_coords(axis, *value);
+            }
+        }
+        let gvar = self.gvar_table()?;
+        let num_points = simple.x_coordinates.len();
+
+        // Deltas also cover the four phantom points after the outline
+        let mut total = vec![(0.0f32, 0.0f32); num_points];
+        for variation in gvar.glyph_variations(glyph_id, num_points + 4)? {
+            let scalar = variation.scalar(&normalized);
+            if scalar == 0.0 {
+                continue;
+            }
+
+            for (sum, (dx, dy)) in total.iter_mut().zip(variation.point_deltas(simple)) {
+                sum.0 += dx * scalar;
+                sum.1 += dy * scalar;
+            }
+        }
+
+        for (i, (dx, dy)) in total.into_iter().enumerate() {
+            simple.x_coordinates[i] = (simple.x_coordinates[i] as f32 + dx).round() as i16;
+            simple.y_coordinates[i] = (simple.y_coordinates[i] as f32 + dy).round() as i16;
+        }
+
+        if num_points > 0 {
+            glyph.x_min = *simple.x_coordinates.iter().min().unwrap();
+            glyph.x_max = *simple.x_coordinates.iter().max().unwrap();
+            glyph.y_min = *simple.y_coordinates.iter().min().unwrap();
+            glyph.y_max = *simple.y_coordinates.iter().max().unwrap();
+        }
+
+        Ok(glyph)
+    }
+
+    /// Get the outline of a glyph as drawing commands in font units.
+    ///
+    /// Composite glyphs are resolved first and empty glyphs give no commands.
+    /// See [`SimpleGlyph::path`] for how contours are converted.
+    pub fn glyph_path(&self, glyph_id: u16) -> Result<Vec<PathCommand>> {
+        let glyf = self.glyf_table()?;
+        if glyph_id >= self.num_glyphs()? {
+            return Err(TtfError::InvalidGlyphIndex(glyph_id));
+        }
+
+        match glyf.resolve_composite(glyph_id as usize)? {
+            Some(Glyph { data: GlyphData::Simple(simple), .. }) => Ok(simple.path()),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Get the outline of a glyph as SVG path data (a `d` attribute).
+    ///
+    /// The commands of [`Font::glyph_path`] are written as `M`, `L`, `Q` and
+    /// `Z`, so empty glyphs give an empty string. Coordinates are in font
+    /// units; with `flip_y` the y values are negated so the glyph is upright
+    /// in SVG's y-down coordinate system, with the baseline at y = 0.
+    pub fn glyph_svg_path(&self, glyph_id: u16, flip_y: bool) -> Result<String> {
+        // Adding 0.0 turns a flipped -0 into 0
+        let coord = |point: Point| format!("{} {}", point.x, if flip_y { -point.y + 0.0 } else { point.y });
+
+        let commands: Vec<String> = self
+            .glyph_path(glyph_id)?
+            .into_iter()
+            .map(|command| match command {
+                PathCommand::MoveTo(point) => format!("M{}", coord(point)),
+                PathCommand::LineTo(point) => format!("L{}", coord(point)),
+                PathCommand::QuadTo(ctrl, point) => format!("Q{} {}", coord(ctrl), coord(point)),
+                PathCommand::Close => "Z".to_string(),
+            })
+            .collect();
+
+        Ok(commands.join(" "))
+    }
+
+    /// Get the kern table (legacy kerning pairs).
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `KernTable` or an error if the table is missing or invalid.
+    pub fn kern_table(&self) -> Result<KernTable> {
+        self.parse_table()
+    }
+
+    /// Get the GPOS table (glyph positioning).
+    ///
+    /// Only pair adjustment (kerning) lookups are parsed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `GposTable` or an error if the table is missing or invalid.
+    pub fn gpos_table(&self) -> Result<GposTable> {
+        self.parse_table()
+    }
+
+    /// Get the GSUB table (glyph substitution).
+    ///
+    /// Only the ligature lookups of the default script are parsed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `GsubTable` or an error if the table is missing or invalid.
+    pub fn gsub_table(&self) -> Result<GsubTable> {
+        self.parse_table()
+    }
+
+    /// Get the VDMX table (vertical device metrics).
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `VdmxTable` or an error if the table is missing or invalid.
+    pub fn vdmx_table(&self) -> Result<VdmxTable> {
+        self.parse_table()
+    }
+
+    /// Get the LTSH table (linear threshold).
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `LtshTable` or an error if the table is missing or invalid.
+    pub fn ltsh_table(&self) -> Result<LtshTable> {
+        self.parse_table()
+    }
+
+    /// Get the meta table (font metadata).
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `MetaTable` or an error if the table is missing or invalid.
+    pub fn meta_table(&self) -> Result<MetaTable> {
+        self.parse_table()
+    }
+
+    /// Get the text entries of the meta table, keyed by tag (e.g. "dlng").
+    ///
+    /// Entries whose data is not UTF-8 text are skipped. Fonts without a
+    /// meta table give an empty map.
+    pub fn meta_tags(&self) -> Result<HashMap<String, String>> {
+        if !self.has_table(b"meta") {
+            return Ok(HashMap::new());
+        }
+
+        let meta = self.meta_table()?;
+        Ok(meta
+            .data_maps
+            .iter()
+            .filter_map(|map| {
+                let text = std::str::from_utf8(&map.data).ok()?;
+                Some((map.tag_to_string(), text.to_string()))
+            })
+            .collect())
+    }
+
+    /// Get the total advance width of a string in font units.
+    ///
+    /// Each character is mapped through cmap, falling back to glyph 0 when
+    /// unmapped, and the hmtx advances are summed. With `apply_kerning` the
+    /// adjustments between consecutive glyphs are added too, taken from the
+    /// GPOS pair adjustment lookups when the font has a GPOS table and from
+    /// the legacy kern table otherwise (fonts with neither are measured
+    /// unkerned).
+    pub fn advance_for_text(&self, text: &str, apply_kerning: bool) -> Result<i32> {
+        let cmap = self.cmap_table()?;
+        let hmtx = self.hmtx_table()?;
+        let gpos = if apply_kerning && self.has_gpos() {
+            Some(self.gpos_table()?)
+        } else {
+            None
+        };
+        let kern = if apply_kerning && gpos.is_none() && self.has_kern() {
+            Some(self.kern_table()?)
+        } else {
+            None
+        };
+
+        let mut total = 0i32;
+        let mut previous: Option<u16> = None;
+        for c in text.chars() {
+            let glyph_id = cmap.map_char(c).unwrap_or(0) as u16;
+            total += hmtx.get_advance_width(glyph_id) as i32;
+
+            if let Some(left) = previous {
+                if let Some(gpos) = &gpos {
+                    total += gpos.pair_adjustment(left, glyph_id).unwrap_or(0) as i32;
+                } else if let Some(kern) = &kern {
+                    total += kern.get_kerning(left, glyph_id) as i32;
+                }
+            }
+            previous = Some(glyph_id);
+        }
+
+        Ok(total)
+    }
+
+    /// Get the total advance height of a string in font units, for
+    /// vertical layout.
+    ///
+    /// Each character is mapped through cmap, falling back to glyph 0 when
+    /// unmapped, and the vmtx advance heights are summed. Fonts without
+    /// vertical metrics advance every glyph by units_per_em.
+    pub fn vertical_advance_for_text(&self, text: &str) -> Result<i32> {
+        let cmap = self.cmap_table()?;
+        let vmtx = if self.has_table(b"vmtx") && self.has_table(b"vhea") {
+            Some(self.vmtx_table()?)
+        } else {
+            None
+        };
+        let units_per_em = self.head_table()?.units_per_em;
+
+        let mut total = 0i32;
+        for c in text.chars() {
+            let glyph_id = cmap.map_char(c).unwrap_or(0) as u16;
+            total += match &vmtx {
+                Some(vmtx) => vmtx.get_advance_height(glyph_id) as i32,
+                None => units_per_em as i32,
+            };
+        }
+
+        Ok(total)
+    }
+
+    /// Get glyph index for a character
+    pub fn char_to_glyph(&self, c: char) -> Result<u32> {
+        let cmap = self.cmap_table()?;
+        cmap.map_char(c).ok_or_else(|| {
+            TtfError::ParseError(format!("No glyph found for character: {}", c))
+        })
+    }
+
+    /// Get glyph index for a character using a specific cmap encoding
+    /// (e.g. platform 3, encoding 0 for symbol fonts) instead of the
+    /// preferred subtable
+    pub fn char_to_glyph_with(&self, c: char, platform_id: u16, encoding_id: u16) -> Result<u32> {
+        let cmap = self.cmap_table()?;
+        if cmap.subtable_for(platform_id, encoding_id).is_none() {
+            return Err(TtfError::ParseError(format!(
+                "No cmap subtable for platform {} encoding {}", platform_id, encoding_id
+            )));
+        }
+
+        cmap.map_char_with(c, platform_id, encoding_id).ok_or_else(|| {
+            TtfError::ParseError(format!("No glyph found for character: {}", c))
+        })
+    }
+
+    /// Map each character of `text` to a glyph through the preferred cmap
+    /// subtable, without any shaping.
+    ///
+    /// # Returns
+    ///
+    /// Returns the glyph IDs together with, for each glyph, the index of the
+    /// character it came from (counted in chars, not bytes). Unmapped
+    /// characters give .notdef (glyph 0) rather than an error.
+    pub fn glyph_ids_for_text(&self, text: &str) -> Result<(Vec<u16>, Vec<usize>)> {
+        let cmap = self.cmap_table()?;
+        Ok(text
+            .chars()
+            .enumerate()
+            .map(|(index, c)| (cmap.map_char(c).unwrap_or(0) as u16, index))
+            .unzip())
+    }
+
+    /// Check whether every character of `text` maps to a glyph other than
+    /// .notdef (glyph 0). Empty text is covered.
+    pub fn covers(&self, text: &str) -> Result<bool> {
+        let cmap = self.cmap_table()?;
+        Ok(text.chars().all(|c| cmap.map_char(c).is_some_and(|glyph_id| glyph_id != 0)))
+    }
+
+    /// Get the fraction of the characters of `text` that map to a glyph
+    /// other than .notdef, from 0.0 to 1.0. Empty text gives 1.0.
+    pub fn coverage_ratio(&self, text: &str) -> Result<f32> {
+        let cmap = self.cmap_table()?;
+        let (covered, total) = text.chars().fold((0usize, 0usize), |(covered, total), c| {
+            let mapped = cmap.map_char(c).is_some_and(|glyph_id| glyph_id != 0);
+            (covered + mapped as usize, total + 1)
+        });
+
+        if total == 0 {
+            return Ok(1.0);
+        }
+        Ok(covered as f32 / total as f32)
+    }
+
+    /// Get font name (the family name)
+    pub fn font_name(&self) -> Result<String> {
+        self.family_name()
+    }
+
+    /// Get font family name (name ID 1)
+    pub fn family_name(&self) -> Result<String> {
+        self.name_string(NameRecord::FONT_FAMILY_NAME)
+    }
+
+    /// Get font subfamily name (name ID 2), e.g. "Bold Italic"
+    pub fn subfamily_name(&self) -> Result<String> {
+        self.name_string(NameRecord::FONT_SUBFAMILY_NAME)
+    }
+
+    /// Get full font name (name ID 4)
+    pub fn full_name(&self) -> Result<String> {
+        self.name_string(NameRecord::FULL_FONT_NAME)
+    }
+
+    /// Get PostScript name (name ID 6)
+    pub fn postscript_name(&self) -> Result<String> {
+        self.name_string(NameRecord::POSTSCRIPT_NAME)
+    }
+
+    /// Decoded name string by platform preference, or "Unknown" if absent
+    fn name_string(&self, name_id: u16) -> Result<String> {
+        let name = self.name_table()?;
+        Ok(name.get_string(name_id).unwrap_or_else(|| "Unknown".to_string()))
+    }
+
+    /// Check if font is bold
+    pub fn is_bold(&self) -> Result<bool> {
+        let os2 = self.os2_table()?;
+        Ok(os2.is_bold())
+    }
+
+    /// Check if font is italic
+    pub fn is_italic(&self) -> Result<bool> {
+        let os2 = self.os2_table()?;
+        Ok(os2.is_italic())
+    }
+
+    /// Find a glyph by its PostScript name in the post table
+    ///
+    /// Returns `None` if no glyph has the name, including when the post
+    /// table (format 3) stores no names.
+    pub fn glyph_for_name(&self, name: &str) -> Result<Option<u16>> {
+        let post = self.post_table()?;
+        Ok(post.glyph_index(name))
+    }
+
+    /// Get the PostScript name of a glyph from the post table
+    ///
+    /// Returns `None` if the post table doesn't name the glyph.
+    pub fn name_for_glyph(&self, glyph_id: u16) -> Result<Option<String>> {
+        let post = self.post_table()?;
+        Ok(post.glyph_name(glyph_id).map(str::to_string))
+    }
+
+    /// Get the embedding permission from OS/2 fsType
+    pub fn embedding_permission(&self) -> Result<EmbeddingPermission> {
+        let os2 = self.os2_table()?;
+        Ok(os2.embedding_permission())
+    }
+
+    /// Get number of glyphs
+    pub fn num_glyphs(&self) -> Result<u16> {
+        let maxp = self.maxp_table()?;
+        Ok(maxp.num_glyphs)
+    }
+
+    /// Get the number of glyphs the loca table has room for.
+    ///
+    /// This counts the loca entries (one per glyph plus the end offset)
+    /// rather than trusting maxp.num_glyphs, which can disagree after
+    /// subsetting with preserved glyph IDs or manual edits.
+    ///
+    /// # Returns
+    ///
+    /// Returns the glyph count, or an error if the head or loca table is missing.
+    pub fn actual_glyph_count(&self) -> Result<usize> {
+        let record = self
+            .get_table_record(b"loca")
+            .ok_or_else(|| TtfError::MissingTable("loca".to_string()))?;
+        let entry_size = if self.head_table()?.is_long_loca_format() { 4 } else { 2 };
+        Ok((record.length as usize / entry_size).saturating_sub(1))
+    }
+
+    /// Get units per em
+    pub fn units_per_em(&self) -> Result<u16> {
+        let head = self.head_table()?;
+        Ok(head.units_per_em)
+    }
+
+    /// Get the vertical line metrics.
+    ///
+    /// With `prefer_typo`, the OS/2 sTypo values are used when the font sets
+    /// USE_TYPO_METRICS (fsSelection bit 7). Otherwise hhea is used, falling
+    /// back to the OS/2 sTypo values and then usWin values (with no line
+    /// gap) when a table is missing or its ascent and descent are both zero.
+    pub fn line_metrics(&self, prefer_typo: bool) -> Result<LineMetrics> {
+        let os2 = if self.has_table(b"OS/2") {
+            Some(self.os2_table()?)
+        } else {
+            None
+        };
+        let typo = os2.as_ref().map(|os2| LineMetrics {
+            ascent: os2.s_typo_ascender,
+            descent: os2.s_typo_descender,
+            line_gap: os2.s_typo_line_gap,
+        });
+
+        if prefer_typo
+            && let (Some(os2), Some(typo)) = (&os2, typo)
+            && os2.fs_selection & Os2Table::USE_TYPO_METRICS != 0
+        {
+            return Ok(typo);
+        }
+
+        if self.has_table(b"hhea") {
+            let hhea = self.hhea_table()?;
+            if hhea.ascent != 0 || hhea.descent != 0 {
+                return Ok(LineMetrics {
+                    ascent: hhea.ascent,
+                    descent: hhea.descent,
+                    line_gap: hhea.line_gap,
+                });
+            }
+        }
+
+        match (os2, typo) {
+            (_, Some(typo)) if typo.ascent != 0 || typo.descent != 0 => Ok(typo),
+            (Some(os2), _) => Ok(LineMetrics {
+                ascent: os2.us_win_ascent.min(i16::MAX as u16) as i16,
+                descent: -(os2.us_win_descent.min(i16::MAX as u16) as i16),
+                line_gap: 0,
+            }),
+            (None, _) => Err(TtfError::MissingTable("hhea".to_string())),
+        }
+    }
+
+    /// Compute a value for OS/2 xAvgCharWidth from the current advances.
+    ///
+    /// When the font maps all lowercase latin letters and space, their
+    /// advances are averaged with the frequency weights of the OpenType
+    /// spec; otherwise all non-zero advances are averaged. Write the result
+    /// back with `FontModifier::set_avg_char_width`.
+    pub fn recompute_avg_char_width(&self) -> Result<i16> {
+        let hmtx = self.hmtx_table()?;
+        let cmap = self.cmap_table()?;
+
+        let weighted: Option<Vec<(u32, u32)>> = Self::AVG_CHAR_WEIGHTS
+            .iter()
+            .map(|&(c, weight)| {
+                cmap.map_char(c)
+                    .map(|glyph_id| (hmtx.get_advance_width(glyph_id as u16) as u32, weight))
+            })
+            .collect();
+
+        let (total, count) = match weighted {
+            Some(advances) => (advances.iter().map(|(advance, weight)| advance * weight).sum::<u32>(), 1000),
+            None => (0..self.num_glyphs()?)
+                .map(|glyph_id| hmtx.get_advance_width(glyph_id) as u32)
+                .filter(|&advance| advance != 0)
+                .fold((0, 0), |(total, count), advance| (total + advance, count + 1)),
+        };
+
+        if count == 0 {
+            return Ok(0);
+        }
+        Ok(((total + count / 2) / count).min(i16::MAX as u32) as i16)
+    }
+
+    /// List all tables
+    pub fn list_tables(&self) -> Vec<String> {
+        self.table_records
+            .iter()
+            .map(|r| r.tag_to_string())
+            .collect()
+    }
+
+    /// Collect the header, naming and style information shown by the
+    /// command line tools.
+    ///
+    /// Missing tables don't fail the summary; see `FontSummary` for the
+    /// defaults used. Bold and italic come from OS/2, or from the head
+    /// macStyle bits when there is no OS/2 table.
+    pub fn summary(&self) -> Result<FontSummary> {
+        let os2 = self.os2_table().ok();
+        let mac_style = self.head_table().map(|head| head.mac_style).unwrap_or(0);
+        let name = |name: Result<String>| name.unwrap_or_else(|_| "Unknown".to_string());
+
+        Ok(FontSummary {
+            sfnt_version: self.sfnt_version,
+            num_tables: self.table_records.len() as u16,
+            num_glyphs: self.num_glyphs().unwrap_or(0),
+            units_per_em: self.units_per_em().unwrap_or(0),
+            family_name: name(self.family_name()),
+            subfamily_name: name(self.subfamily_name()),
+            is_bold: os2.as_ref().map_or(mac_style & 0x01 != 0, |os2| os2.is_bold()),
+            is_italic: os2.as_ref().map_or(mac_style & 0x02 != 0, |os2| os2.is_italic()),
+            weight_class: os2.as_ref().map_or(400, |os2| os2.us_weight_class),
+            tables: self.list_tables(),
+        })
+    }
+
+    /// Save the font to a file.
+    ///
+    /// Serializes the font data and writes it to the specified path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Output path for the font file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ttf_rs::Font;
+    ///
+    /// let font = Font::load("input.ttf")?;
+    /// font.save("output.ttf")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let data = self.to_bytes()?;
+        let mut file = File::create(path)?;
+        file.write_all(&data)?;
+        Ok(())
+    }
+
+    /// Convert font to raw bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut writer = FontWriter::new();
+
+        // Collect table data
+        let mut table_data: Vec<([u8; 4], Vec<u8>)> = Vec::new();
+
+        for record in &self.table_records {
             // Get raw table data
             if let Some(data) = self.get_table_data(&record.table_tag) {
                 table_data.push((record.table_tag, data));
@@ -499,31 +1630,25 @@ impl Font {
         writer.write_u16(self.entry_selector);
         writer.write_u16(self.range_shift);
 
-        // Write table directory (placeholder offsets for now)
-        let mut table_dir_offset = writer.position();
+        // Write table directory, remembering where each record starts so
+        // its checksum and offset can be patched once the data is written
+        let mut dir_positions = Vec::with_capacity(table_data.len());
 
-        for record in &self.table_records {
-            writer.write_tag(&record.table_tag);
+        for (tag, data) in &table_data {
+            dir_positions.push(writer.position());
+            writer.write_tag(tag);
             writer.write_u32(0); // checksum placeholder
             writer.write_u32(0); // offset placeholder
-            writer.write_u32(record.length);
+            writer.write_u32(data.len() as u32);
         }
 
-        // Write table data and update directory
-        let mut data_start = writer.position();
-
         // Pad to 4-byte boundary
-        while data_start % 4 != 0 {
-            writer.write_u8(0);
-            data_start = writer.position();
-        }
-
-        let mut dir_positions: Vec<(usize, u32, u32)> = Vec::new();
+        writer.write_padding(4);
 
-        for (tag, data) in &table_data {
+        // Write table data and update directory
+        for ((_, data), dir_pos) in table_data.iter().zip(dir_positions) {
             let checksum = calculate_checksum(data);
             let offset = writer.position() as u32;
-            let length = data.len() as u32;
 
             // Write table data
             writer.write_bytes(data);
@@ -531,41 +1656,117 @@ impl Font {
             // Pad to 4-byte boundary
             writer.write_padding(4);
 
-            // Store position in directory to update
-            dir_positions.push((data_start, offset, checksum));
-            data_start += 16;
+            writer.overwrite_u32_at(dir_pos + 4, checksum)?;
+            writer.overwrite_u32_at(dir_pos + 8, offset)?;
+        }
+
+        Ok(writer.into_inner())
+    }
+
+    /// Check that the font survives serialization.
+    ///
+    /// Serializes the font with `to_bytes`, reloads it and compares every
+    /// table with the original. The head checksumAdjustment field is ignored,
+    /// since it depends on the whole file.
+    ///
+    /// # Returns
+    ///
+    /// Returns `TtfError::RoundTripMismatch` listing every table that is
+    /// missing, added or changed in the reloaded font.
+    pub fn verify_roundtrip(&self) -> Result<()> {
+        let reloaded = Font::from_data(self.to_bytes()?)?;
+
+        let normalized = |font: &Font, tag: &[u8; 4]| {
+            font.get_table_data(tag).map(|mut data| {
+                if tag == b"head" && data.len() >= 12 {
+                    data[8..12].fill(0);
+                }
+                data
+            })
+        };
+
+        let mut mismatched: Vec<String> = Vec::new();
+        for record in self.table_records.iter().chain(&reloaded.table_records) {
+            let tag = record.tag_to_string();
+            if mismatched.contains(&tag) {
+                continue;
+            }
+
+            let original = normalized(self, &record.table_tag);
+            if original.is_none() || original != normalized(&reloaded, &record.table_tag) {
+                mismatched.push(tag);
+            }
         }
 
-        // Update directory with actual offsets and checksums
-        let header_bytes = writer.as_slice();
-        let mut header_update = Vec::from(&header_bytes[12..12 + self.table_records.len() * 16]);
+        if mismatched.is_empty() {
+            Ok(())
+        } else {
+            Err(TtfError::RoundTripMismatch(mismatched))
+        }
+    }
 
-        let mut idx = 0;
-        for (_, offset, checksum) in dir_positions {
-            // Update checksum at position 12 + idx*16 + 4
-            let checksum_pos = idx * 16 + 4;
-            header_update[checksum_pos..checksum_pos + 4]
-                .copy_from_slice(&checksum.to_be_bytes());
+    /// Build a font from a complete set of tables.
+    ///
+    /// Tables are sorted by tag and laid out on 4-byte boundaries with fresh
+    /// checksums, and the binary search header fields are recomputed.
+    pub(crate) fn from_tables(sfnt_version: u32, mut tables: Vec<([u8; 4], Vec<u8>)>) -> Self {
+        tables.sort_by_key(|(tag, _)| *tag);
 
-            // Update offset at position 12 + idx*16 + 8
-            let offset_pos = idx * 16 + 8;
-            header_update[offset_pos..offset_pos + 4]
-                .copy_from_slice(&offset.to_be_bytes());
+        let num_tables = tables.len() as u16;
+        let (search_range, entry_selector, range_shift) = Self::calculate_search_range(num_tables);
 
-            idx += 1;
+        let mut writer = FontWriter::new();
+        writer.write_u32(sfnt_version);
+        writer.write_u16(num_tables);
+        writer.write_u16(search_range);
+        writer.write_u16(entry_selector);
+        writer.write_u16(range_shift);
+
+        // Table data starts right after the directory, which is always 4-byte aligned
+        let mut offset = 12 + tables.len() * 16;
+        let mut table_records = Vec::with_capacity(tables.len());
+        for (tag, data) in &tables {
+            let record = TableRecord {
+                table_tag: *tag,
+                checksum: calculate_checksum(data),
+                offset: offset as u32,
+                length: data.len() as u32,
+            };
+            record.write(&mut writer);
+            table_records.push(record);
+            offset += (data.len() + 3) & !3;
         }
 
-        // Reconstruct final data
-        let mut final_data = FontWriter::new();
-        final_data.write_bytes(&header_bytes[..12]); // SFNT header
-        final_data.write_bytes(&header_update);      // Updated table directory
-        final_data.write_bytes(&header_bytes[12 + self.table_records.len() * 16..]); // Table data
+        for (_, data) in &tables {
+            writer.write_bytes(data);
+            writer.write_padding(4);
+        }
 
-        Ok(final_data.into_inner())
+        Font {
+            sfnt_version,
+            num_tables,
+            search_range,
+            entry_selector,
+            range_shift,
+            table_records,
+            data: writer.into_inner().into(),
+        }
+    }
+
+    /// Recompute the table directory's binary search fields (search_range,
+    /// entry_selector and range_shift) from `num_tables`.
+    ///
+    /// Fonts are loaded with whatever values they store; this replaces them
+    /// with the spec values, which `to_bytes` then writes.
+    pub fn normalize_header(&mut self) {
+        let (search_range, entry_selector, range_shift) = Self::calculate_search_range(self.num_tables);
+        self.search_range = search_range;
+        self.entry_selector = entry_selector;
+        self.range_shift = range_shift;
     }
 
     /// Calculate search range for given number of tables
-    fn calculate_search_range(num_tables: u16) -> (u16, u16, u16) {
+    pub(crate) fn calculate_search_range(num_tables: u16) -> (u16, u16, u16) {
         let mut max_power = 1u16;
         let mut entry_selector = 0u16;
 
@@ -584,22 +1785,290 @@ impl Font {
         (search_range, entry_selector, range_shift)
     }
 
-    /// Create a new font with modified data
+    /// Create a new font with one table's data replaced.
+    ///
+    /// Data that does not fit in the table's current space is appended to
+    /// the font data and the table record is pointed at it; `to_bytes` lays
+    /// every table out afresh, so the abandoned bytes are not written.
+    ///
+    /// # Returns
+    ///
+    /// Returns `TtfError::MissingTable` if the font has no table with the
+    /// given tag.
     pub fn with_table_data(&self, tag: &[u8; 4], data: Vec<u8>) -> Result<Self> {
+        if !self.has_table(tag) {
+            return Err(TtfError::MissingTable(String::from_utf8_lossy(tag).to_string()));
+        }
+
         let mut font = self.clone();
+        font.replace_table_data(tag, data);
+        Ok(font)
+    }
 
-        // Find and replace table data
-        if let Some(record) = font.table_records.iter().position(|r| r.table_tag == *tag) {
-            font.table_records[record].length = data.len() as u32;
+    /// Replace the data of an existing table, in place if it fits in the
+    /// old table's space and appended to the font data otherwise
+    pub(crate) fn replace_table_data(&mut self, tag: &[u8; 4], data: Vec<u8>) {
+        let Some(record) = self.table_records.iter_mut().find(|r| r.table_tag == *tag) else {
+            return;
+        };
 
-            // Update font data (simplified - in practice you'd want more sophisticated handling)
-            let offset = font.table_records[record].offset as usize;
-            if offset + data.len() <= font.data.len() {
-                font.data[offset..offset + data.len()].copy_from_slice(&data);
+        let offset = record.offset as usize;
+        if data.len() <= record.length as usize && offset + data.len() <= self.data.len() {
+            self.data[offset..offset + data.len()].copy_from_slice(&data);
+        } else {
+            let mut bytes = self.data.to_vec();
+            bytes.resize(bytes.len().next_multiple_of(4), 0);
+            record.offset = bytes.len() as u32;
+            bytes.extend_from_slice(&data);
+            self.data = bytes.into();
+        }
+        record.length = data.len() as u32;
+        record.checksum = calculate_checksum(&data);
+    }
+
+    /// Replace every composite glyph with its flattened simple-glyph outline.
+    ///
+    /// Rewrites the glyf and loca tables, recomputes the head bounding box
+    /// over the new outlines and updates maxp so that the point and contour
+    /// limits cover them and the composite limits are zero. The loca format is switched to long if the data outgrows
+    /// the short format.
+    pub fn decompose_composites(&self) -> Result<Font> {
+        let glyf = self.glyf_table()?;
+        let mut head = self.head_table()?;
+        let mut maxp = self.maxp_table()?;
+
+        let glyphs = glyf.iter_resolved().collect::<Result<Vec<_>>>()?;
+
+        let mut max_points = 0u16;
+        let mut max_contours = 0u16;
+        let mut bbox: Option<(i16, i16, i16, i16)> = None;
+        for glyph in &glyphs {
+            if let GlyphData::Simple(simple) = &glyph.data {
+                max_points = max_points.max(simple.x_coordinates.len() as u16);
+                max_contours = max_contours.max(simple.end_pts_of_contours.len() as u16);
+                bbox = Some(match bbox {
+                    Some((x_min, y_min, x_max, y_max)) => (
+                        x_min.min(glyph.x_min),
+                        y_min.min(glyph.y_min),
+                        x_max.max(glyph.x_max),
+                        y_max.max(glyph.y_max),
+                    ),
+                    None => (glyph.x_min, glyph.y_min, glyph.x_max, glyph.y_max),
+                });
             }
         }
 
-        Ok(font)
+        // Flattened outlines can reach past the composites' header bounds
+        if let Some((x_min, y_min, x_max, y_max)) = bbox {
+            head.x_min = x_min;
+            head.y_min = y_min;
+            head.x_max = x_max;
+            head.y_max = y_max;
+        }
+
+        let (glyf_data, offsets) = GlyfTable { glyphs }.to_bytes();
+
+        if head.is_short_loca_format() && offsets.last().is_some_and(|&end| end / 2 > u16::MAX as u32) {
+            head.index_to_loc_format = 1;
+        }
+        let loca_data = LocaTable::Long(offsets).to_bytes(head.is_long_loca_format());
+
+        if maxp.is_version_1_0() {
+            maxp.max_points = Some(max_points);
+            maxp.max_contours = Some(max_contours);
+            maxp.max_composite_points = Some(0);
+            maxp.max_composite_contours = Some(0);
+            maxp.max_component_elements = Some(0);
+            maxp.max_component_depth = Some(0);
+        }
+
+        let mut head_data = FontWriter::new();
+        head.write(&mut head_data)?;
+        let mut maxp_data = FontWriter::new();
+        maxp.write(&mut maxp_data)?;
+
+        let mut modifier = self.clone().modify();
+        modifier
+            .add_table(b"glyf", glyf_data)
+            .add_table(b"loca", loca_data)
+            .add_table(b"head", head_data.into_inner())
+            .add_table(b"maxp", maxp_data.into_inner());
+        modifier.commit()
+    }
+
+    /// Remove all TrueType hinting from the font.
+    ///
+    /// Drops the fpgm, prep and cvt tables, clears the instructions of every
+    /// simple glyph and rewrites glyf and loca. In a version 1.0 maxp the
+    /// hinting limits are reset (maxZones to 1, the others to 0).
+    pub fn strip_hinting(&self) -> Result<Font> {
+        let mut glyf = self.glyf_table()?;
+        let head = self.head_table()?;
+        let mut maxp = self.maxp_table()?;
+
+        for glyph in &mut glyf.glyphs {
+            if let GlyphData::Simple(simple) = &mut glyph.data {
+                simple.instruction_length = 0;
+                simple.instructions.clear();
+            }
+        }
+
+        let (glyf_data, offsets) = glyf.to_bytes();
+        let loca_data = LocaTable::Long(offsets).to_bytes(head.is_long_loca_format());
+
+        if maxp.is_version_1_0() {
+            maxp.max_zones = Some(1);
+            maxp.max_twilight_points = Some(0);
+            maxp.max_storage = Some(0);
+            maxp.max_function_defs = Some(0);
+            maxp.max_instruction_defs = Some(0);
+            maxp.max_stack_elements = Some(0);
+            maxp.max_size_of_instructions = Some(0);
+        }
+
+        let mut maxp_data = FontWriter::new();
+        maxp.write(&mut maxp_data)?;
+
+        let mut modifier = self.clone().modify();
+        modifier
+            .remove_table(b"fpgm")
+            .remove_table(b"prep")
+            .remove_table(b"cvt ")
+            .add_table(b"glyf", glyf_data)
+            .add_table(b"loca", loca_data)
+            .add_table(b"maxp", maxp_data.into_inner());
+        modifier.commit()
+    }
+
+    /// Replace the outline of a single glyph.
+    ///
+    /// Rewrites glyf and loca with `new_glyph` at `glyph_id`, sets the
+    /// glyph's hmtx left side bearing to its new x_min and recomputes the
+    /// maxp statistics. With `update_advance` set to `Some(width)` the
+    /// glyph's advance width becomes `width`, splitting it off the shared
+    /// trailing advance (and raising hhea's number_of_h_metrics) if needed;
+    /// with `None` the advance width is kept. The loca format is switched
+    /// to long if the data outgrows the short format.
+    ///
+    /// # Returns
+    ///
+    /// Returns the new `Font`, or `TtfError::InvalidGlyphIndex` if the font
+    /// has no glyph `glyph_id`.
+    pub fn replace_glyph(&self, glyph_id: u16, new_glyph: Glyph, update_advance: Option<u16>) -> Result<Font> {
+        let mut glyf = self.glyf_table()?;
+        let mut head = self.head_table()?;
+        let mut hmtx = self.hmtx_table()?;
+        let mut hhea = None;
+
+        let lsb = new_glyph.x_min;
+        *glyf
+            .get_glyph_mut(glyph_id as usize)
+            .ok_or(TtfError::InvalidGlyphIndex(glyph_id))? = new_glyph;
+
+        let (glyf_data, offsets) = glyf.to_bytes();
+        if head.is_short_loca_format() && offsets.last().is_some_and(|&end| end / 2 > u16::MAX as u32) {
+            head.index_to_loc_format = 1;
+        }
+        let loca_data = LocaTable::Long(offsets).to_bytes(head.is_long_loca_format());
+
+        let num_h_metrics = hmtx.h_metrics.len();
+        if let Some(metric) = hmtx.h_metrics.get_mut(glyph_id as usize) {
+            metric.lsb = lsb;
+        } else if let Some(bearing) = hmtx.left_side_bearings.get_mut(glyph_id as usize - num_h_metrics) {
+            *bearing = lsb;
+        }
+
+        if let Some(advance_width) = update_advance {
+            if glyph_id as usize + 1 >= hmtx.h_metrics.len() && !hmtx.left_side_bearings.is_empty() {
+                // Glyphs past number_of_h_metrics share the last advance, so
+                // give every glyph up to the one after this its own metric
+                // before changing it
+                let shared = hmtx.get_advance_width(glyph_id);
+                let split = (glyph_id as usize + 2 - hmtx.h_metrics.len()).min(hmtx.left_side_bearings.len());
+                hmtx.h_metrics.extend(hmtx.left_side_bearings.drain(..split).map(|lsb| LongHorMetric {
+                    advance_width: shared,
+                    lsb,
+                }));
+                let mut table = self.hhea_table()?;
+                table.number_of_h_metrics = hmtx.h_metrics.len() as u16;
+                hhea = Some(table);
+            }
+            if let Some(metric) = hmtx.h_metrics.get_mut(glyph_id as usize) {
+                metric.advance_width = advance_width;
+            }
+        }
+
+        let mut head_data = FontWriter::new();
+        head.write(&mut head_data)?;
+        let mut hmtx_data = FontWriter::new();
+        hmtx.write(&mut hmtx_data)?;
+
+        let mut modifier = self.clone().modify();
+        modifier
+            .add_table(b"glyf", glyf_data)
+            .add_table(b"loca", loca_data)
+            .add_table(b"head", head_data.into_inner())
+            .add_table(b"hmtx", hmtx_data.into_inner());
+        if let Some(hhea) = hhea {
+            let mut hhea_data = FontWriter::new();
+            hhea.write(&mut hhea_data)?;
+            modifier.add_table(b"hhea", hhea_data.into_inner());
+        }
+        modifier.commit()?.recompute_maxp()
+    }
+
+    /// Recompute the maxp glyph statistics from the glyf table.
+    ///
+    /// Sets num_glyphs to the number of glyphs loca has entries for (see
+    /// `actual_glyph_count`) and, for version 1.0 tables, updates
+    /// max_points, max_contours, max_composite_points,
+    /// max_composite_contours, max_component_elements and
+    /// max_component_depth over those glyphs. Composite glyphs are counted
+    /// by their flattened outline. The hinting limits are left as-is.
+    pub fn recompute_maxp(&self) -> Result<Font> {
+        let mut maxp = self.maxp_table()?;
+        maxp.num_glyphs = u16::try_from(self.actual_glyph_count()?).unwrap_or(u16::MAX);
+        let glyf = self.glyf_table_for(maxp.num_glyphs)?;
+
+        if maxp.is_version_1_0() {
+            let (mut max_points, mut max_contours) = (0, 0);
+            let (mut max_composite_points, mut max_composite_contours) = (0, 0);
+            let (mut max_component_elements, mut max_component_depth) = (0, 0);
+
+            for (glyph_index, glyph) in glyf.glyphs.iter().enumerate() {
+                match &glyph.data {
+                    GlyphData::Simple(simple) => {
+                        max_points = max_points.max(simple.x_coordinates.len() as u16);
+                        max_contours = max_contours.max(simple.end_pts_of_contours.len() as u16);
+                    }
+                    GlyphData::Composite(composite) => {
+                        let resolved = glyf.resolve_composite(glyph_index)?;
+                        if let Some(GlyphData::Simple(simple)) = resolved.as_ref().map(|g| &g.data) {
+                            max_composite_points = max_composite_points.max(simple.x_coordinates.len() as u16);
+                            max_composite_contours =
+                                max_composite_contours.max(simple.end_pts_of_contours.len() as u16);
+                        }
+                        max_component_elements = max_component_elements.max(composite.components.len() as u16);
+                        max_component_depth = max_component_depth.max(glyf.component_depth(glyph_index)?);
+                    }
+                    GlyphData::Empty => {}
+                }
+            }
+
+            maxp.max_points = Some(max_points);
+            maxp.max_contours = Some(max_contours);
+            maxp.max_composite_points = Some(max_composite_points);
+            maxp.max_composite_contours = Some(max_composite_contours);
+            maxp.max_component_elements = Some(max_component_elements);
+            maxp.max_component_depth = Some(max_component_depth);
+        }
+
+        let mut maxp_data = FontWriter::new();
+        maxp.write(&mut maxp_data)?;
+
+        let mut modifier = self.clone().modify();
+        modifier.add_table(b"maxp", maxp_data.into_inner());
+        modifier.commit()
     }
 }
 
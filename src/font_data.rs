@@ -0,0 +1,73 @@
+// Backing storage for the bytes of a font file
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+#[cfg(feature = "mmap")]
+use std::sync::Arc;
+
+/// The raw bytes of a font: either owned, or a read-only memory map of the
+/// font file (with the `mmap` feature).
+///
+/// Derefs to `[u8]`. Writing through a mapped font first copies it into
+/// memory, so the file itself is never modified.
+#[derive(Clone)]
+pub enum FontData {
+    Owned(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(Arc<memmap2::Mmap>),
+}
+
+impl FontData {
+    /// Whether the bytes are read from a memory-mapped file
+    pub fn is_mapped(&self) -> bool {
+        !matches!(self, FontData::Owned(_))
+    }
+
+    /// Take the bytes as a vector, copying them if mapped
+    pub fn into_vec(self) -> Vec<u8> {
+        match self {
+            FontData::Owned(data) => data,
+            #[cfg(feature = "mmap")]
+            FontData::Mapped(mmap) => mmap.to_vec(),
+        }
+    }
+}
+
+impl Deref for FontData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            FontData::Owned(data) => data,
+            #[cfg(feature = "mmap")]
+            FontData::Mapped(mmap) => mmap,
+        }
+    }
+}
+
+impl DerefMut for FontData {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        #[cfg(feature = "mmap")]
+        if let FontData::Mapped(mmap) = self {
+            *self = FontData::Owned(mmap.to_vec());
+        }
+
+        match self {
+            FontData::Owned(data) => data,
+            #[cfg(feature = "mmap")]
+            FontData::Mapped(_) => unreachable!("mapped data was copied above"),
+        }
+    }
+}
+
+impl From<Vec<u8>> for FontData {
+    fn from(data: Vec<u8>) -> Self {
+        FontData::Owned(data)
+    }
+}
+
+impl fmt::Debug for FontData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
@@ -2,37 +2,69 @@
 
 mod error;
 mod font;
+mod font_data;
 mod tables;
 mod stream;
 mod modifier;
+mod builder;
 mod subset;
 mod validation;
+mod diff;
+mod dump;
+mod overlap;
 mod woff;
 mod rasterizer;
 mod cached;
 
 pub use error::{TtfError, Result};
-pub use font::Font;
+pub use font::{Font, FontSummary, GlyphExtents, LineMetrics, ParseLimits};
+pub use font_data::FontData;
 pub use modifier::FontModifier;
+pub use builder::FontBuilder;
 pub use subset::FontSubset;
-pub use validation::{ValidationReport, ValidationError, ValidationWarning};
+pub use validation::{
+    ValidationReport, ValidationError, ValidationErrorType, ValidationWarning, ValidationWarningType,
+};
+pub use diff::{FontDiff, TableDiff};
 pub use rasterizer::{Rasterizer, RasterizedGlyph};
 pub use cached::CachedFont;
-pub use stream::{FontReader, FontWriter, calculate_checksum};
+pub use stream::{ChecksumWriter, FontReader, FontWriter, calculate_checksum};
 pub use tables::{
     TableRecord,
     TtfTable,
     TtfTableWrite,
     head::HeadTable,
     maxp::MaxpTable,
-    cmap::{CmapTable, CmapSubtable, Format4, Format6, Format12, Format13, Format14},
+    cmap::{CmapTable, CmapSubtable, Format2, SubHeader, Format4, Format6, Format12, Format13, Format14},
     name::NameTable,
     hhea::HheaTable,
-    hmtx::HmtxTable,
-    glyf::{GlyfTable, GlyphData, Point, BoundingBox, SimpleGlyph, CompositeGlyph, Transform},
+    hmtx::{HmtxTable, LongHorMetric},
+    vhea::VheaTable,
+    vmtx::{VmtxTable, LongVerMetric},
+    glyf::{GlyfTable, Glyph, GlyphData, GlyphComponent, Contour, ContourPoint, PathCommand, Point, BoundingBox, SimpleGlyph, CompositeGlyph, Transform},
     loca::LocaTable,
-    post::PostTable,
-    os2::Os2Table,
+    post::{PostTable, GlyphNames},
+    os2::{Os2Table, EmbeddingPermission},
+    cvt::CvtTable,
+    fpgm::FpgmTable,
+    prep::PrepTable,
+    fvar::{FvarTable, VariationAxis, NamedInstance},
+    gvar::{GvarTable, TupleVariation},
+    avar::{AvarTable, SegmentMap, AxisValueMap},
+    colr::{ColrTable, BaseGlyphRecord, ColorLayer},
+    cpal::{CpalTable, Palette, ColorRecord},
+    sbix::{SbixTable, Strike, SbixGlyph},
+    kern::{KernTable, KernSubtable, KernFormat0, KernPair},
+    gpos::{GposTable, PairPos},
+    gsub::{GsubTable, LigatureSubst, Ligature},
+    layout::{Coverage, ClassDef},
+    svg::{SvgTable, SvgDocument},
+    vdmx::{VdmxTable, RatioRange, VdmxGroup, VdmxRecord},
+    ltsh::LtshTable,
+    meta::{MetaTable, MetaDataMap},
+    cff::{CffTable, CffOutline, OutlineCommand},
+    cbdt::{CbdtTable, CblcTable, BitmapSize, IndexSubtable, BigGlyphMetrics, ColorBitmap, ColorBitmapFormat},
+    ebdt::{EbdtTable, MonoBitmap},
 };
 
 #[cfg(test)]
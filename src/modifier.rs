@@ -21,31 +21,30 @@
 //! # Ok::<(), ttf_rs::TtfError>(())
 //! ```
 
-use crate::error::Result;
+use crate::error::{Result, TtfError};
 use crate::font::Font;
-use crate::stream::FontWriter;
-use crate::tables::name::NameTable;
+use crate::stream::{FontReader, FontWriter};
+use crate::tables::name::{NameRecord, NameTable};
 use crate::tables::head::HeadTable;
 use crate::tables::hhea::HheaTable;
 use crate::tables::os2::Os2Table;
 use crate::tables::hmtx::HmtxTable;
-use crate::tables::TtfTableWrite;
-use std::collections::HashMap;
+use crate::tables::glyf::{Glyph, GlyphData};
+use crate::tables::loca::LocaTable;
+use crate::tables::{TtfTable, TtfTableWrite};
+use std::collections::{HashMap, HashSet};
+use std::time::SystemTime;
 
 /// Font modifier for updating font properties.
 ///
 /// Provides a builder-style API for modifying font metadata, metrics,
 /// and other properties. Changes are tracked internally and applied
 /// when `commit()` is called.
-///
-/// # Note
-///
-/// Full round-trip serialization with proper offset recalculation is
-/// currently in development. Some modifications may not persist correctly
-/// when saving and reloading the font.
 pub struct FontModifier {
     font: Font,
     modified_tables: HashMap<[u8; 4], Vec<u8>>,
+    removed_tables: HashSet<[u8; 4]>,
+    retain_digital_signature: bool,
 }
 
 impl FontModifier {
@@ -53,6 +52,8 @@ impl FontModifier {
         Self {
             font,
             modified_tables: HashMap::new(),
+            removed_tables: HashSet::new(),
+            retain_digital_signature: false,
         }
     }
 
@@ -74,7 +75,7 @@ impl FontModifier {
     /// # Ok::<(), ttf_rs::TtfError>(())
     /// ```
     pub fn set_font_name(&mut self, name: &str) -> Result<&mut Self> {
-        let mut name_table = self.font.name_table()?;
+        let mut name_table = self.table(b"name", Font::name_table)?;
 
         let platform_id = 3u16;
         let encoding_id = 1u16;
@@ -94,7 +95,7 @@ impl FontModifier {
     ///
     /// * `name` - The new full font name
     pub fn set_full_font_name(&mut self, name: &str) -> Result<&mut Self> {
-        let mut name_table = self.font.name_table()?;
+        let mut name_table = self.table(b"name", Font::name_table)?;
 
         let platform_id = 3u16;
         let encoding_id = 1u16;
@@ -127,7 +128,7 @@ impl FontModifier {
     pub fn set_version(&mut self, major: u16, minor: u16) -> Result<&mut Self> {
         let version_string = format!("Version {}.{}", major, minor);
 
-        let mut name_table = self.font.name_table()?;
+        let mut name_table = self.table(b"name", Font::name_table)?;
 
         let platform_id = 3u16;
         let encoding_id = 1u16;
@@ -138,7 +139,7 @@ impl FontModifier {
         self.serialize_name_table(name_table)?;
 
         // Also update the font revision in head table
-        let mut head_table = self.font.head_table()?;
+        let mut head_table = self.table(b"head", Font::head_table)?;
         head_table.font_revision = (major as f32) + (minor as f32) / 100.0;
         self.serialize_head_table(head_table)?;
 
@@ -153,7 +154,7 @@ impl FontModifier {
     ///
     /// * `copyright` - The copyright notice text
     pub fn set_copyright(&mut self, copyright: &str) -> Result<&mut Self> {
-        let mut name_table = self.font.name_table()?;
+        let mut name_table = self.table(b"name", Font::name_table)?;
 
         let platform_id = 3u16;
         let encoding_id = 1u16;
@@ -173,7 +174,7 @@ impl FontModifier {
     ///
     /// * `trademark` - The trademark notice text
     pub fn set_trademark(&mut self, trademark: &str) -> Result<&mut Self> {
-        let mut name_table = self.font.name_table()?;
+        let mut name_table = self.table(b"name", Font::name_table)?;
 
         let platform_id = 3u16;
         let encoding_id = 1u16;
@@ -194,23 +195,130 @@ impl FontModifier {
     /// * `major` - Major version number
     /// * `minor` - Minor version number (0-99)
     pub fn set_font_revision(&mut self, major: u16, minor: u16) -> Result<&mut Self> {
-        let mut head_table = self.font.head_table()?;
+        let mut head_table = self.table(b"head", Font::head_table)?;
         head_table.font_revision = (major as f32) + (minor as f32) / 100.0;
         self.serialize_head_table(head_table)?;
         Ok(self)
     }
 
+    /// Set the creation time in the head table
+    pub fn set_created(&mut self, time: SystemTime) -> Result<&mut Self> {
+        let mut head_table = self.table(b"head", Font::head_table)?;
+        head_table.created = HeadTable::long_datetime(time);
+        self.serialize_head_table(head_table)?;
+        Ok(self)
+    }
+
+    /// Set the modification time in the head table
+    pub fn set_modified(&mut self, time: SystemTime) -> Result<&mut Self> {
+        let mut head_table = self.table(b"head", Font::head_table)?;
+        head_table.modified = HeadTable::long_datetime(time);
+        self.serialize_head_table(head_table)?;
+        Ok(self)
+    }
+
     /// Modify embedding permissions in OS/2 table
     pub fn set_embedding_type(&mut self, embedding_type: u16) -> Result<&mut Self> {
-        let mut os2_table = self.font.os2_table()?;
+        let mut os2_table = self.table(b"OS/2", Font::os2_table)?;
         os2_table.fs_type = embedding_type;
         self.serialize_os2_table(os2_table)?;
         Ok(self)
     }
 
+    /// Set xAvgCharWidth in the OS/2 table, e.g. to the value from
+    /// `Font::recompute_avg_char_width` after subsetting
+    pub fn set_avg_char_width(&mut self, width: i16) -> Result<&mut Self> {
+        let mut os2_table = self.table(b"OS/2", Font::os2_table)?;
+        os2_table.x_avg_char_width = width;
+        self.serialize_os2_table(os2_table)?;
+        Ok(self)
+    }
+
+    /// Set usWeightClass in the OS/2 table (e.g. 400 regular, 700 bold).
+    ///
+    /// The bold flags follow the weight: fsSelection's BOLD bit (5) and
+    /// head.macStyle's bold bit (0) are set for weights of 700 and above and
+    /// cleared otherwise, and fsSelection's REGULAR bit (6) is kept set only
+    /// for fonts that are neither bold nor italic.
+    pub fn set_weight_class(&mut self, weight: u16) -> Result<&mut Self> {
+        let bold = weight >= 700;
+
+        let mut os2_table = self.table(b"OS/2", Font::os2_table)?;
+        os2_table.us_weight_class = weight;
+        if bold {
+            os2_table.fs_selection = (os2_table.fs_selection | 0x20) & !0x40;
+        } else {
+            os2_table.fs_selection &= !0x20;
+            if os2_table.fs_selection & 0x01 == 0 {
+                os2_table.fs_selection |= 0x40;
+            }
+        }
+        self.serialize_os2_table(os2_table)?;
+
+        let mut head_table = self.table(b"head", Font::head_table)?;
+        if bold {
+            head_table.mac_style |= 0x01;
+        } else {
+            head_table.mac_style &= !0x01;
+        }
+        self.serialize_head_table(head_table)?;
+        Ok(self)
+    }
+
+    /// Mark the font as italic or upright.
+    ///
+    /// Sets or clears fsSelection's ITALIC bit (0) in OS/2 and head.macStyle's
+    /// italic bit (1); fsSelection's REGULAR bit (6) is cleared for italics and
+    /// set again for upright fonts that aren't bold. `angle_degrees` becomes
+    /// post.italicAngle (negative for a forward slant). Without an angle an
+    /// italic font keeps its current angle and an upright one gets 0.
+    pub fn set_italic(&mut self, italic: bool, angle_degrees: Option<f32>) -> Result<&mut Self> {
+        let mut post = self
+            .raw_table(b"post")
+            .filter(|data| data.len() >= 8)
+            .ok_or_else(|| TtfError::MissingTable("post".to_string()))?;
+
+        let mut os2_table = self.table(b"OS/2", Font::os2_table)?;
+        if italic {
+            os2_table.fs_selection = (os2_table.fs_selection | 0x01) & !0x40;
+        } else {
+            os2_table.fs_selection &= !0x01;
+            if os2_table.fs_selection & 0x20 == 0 {
+                os2_table.fs_selection |= 0x40;
+            }
+        }
+        self.serialize_os2_table(os2_table)?;
+
+        let mut head_table = self.table(b"head", Font::head_table)?;
+        if italic {
+            head_table.mac_style |= 0x02;
+        } else {
+            head_table.mac_style &= !0x02;
+        }
+        self.serialize_head_table(head_table)?;
+
+        // italicAngle is a 16.16 fixed value after the version
+        if let Some(angle) = angle_degrees.or((!italic).then_some(0.0)) {
+            let mut writer = FontWriter::new();
+            writer.write_fixed(angle);
+            post[4..8].copy_from_slice(&writer.into_inner());
+            self.add_table(b"post", post);
+        }
+        Ok(self)
+    }
+
+    /// Set usWidthClass in the OS/2 table (1 ultra-condensed to 9
+    /// ultra-expanded, 5 normal)
+    pub fn set_width_class(&mut self, width: u16) -> Result<&mut Self> {
+        let mut os2_table = self.table(b"OS/2", Font::os2_table)?;
+        os2_table.us_width_class = width;
+        self.serialize_os2_table(os2_table)?;
+        Ok(self)
+    }
+
     /// Set font name in multiple languages
     pub fn set_localized_font_name(&mut self, name: &str, language_id: u16) -> Result<&mut Self> {
-        let mut name_table = self.font.name_table()?;
+        let mut name_table = self.table(b"name", Font::name_table)?;
 
         let platform_id = 3u16;
         let encoding_id = 1u16;
@@ -224,12 +332,12 @@ impl FontModifier {
     /// Update font metrics in head and hhea tables
     pub fn set_font_metrics(&mut self, units_per_em: u16, ascender: i16, descender: i16, line_gap: i16) -> Result<&mut Self> {
         // Update head table
-        let mut head_table = self.font.head_table()?;
+        let mut head_table = self.table(b"head", Font::head_table)?;
         head_table.units_per_em = units_per_em;
         self.serialize_head_table(head_table)?;
 
         // Update hhea table (field names are ascent/descent, not ascender/descender)
-        let mut hhea_table = self.font.hhea_table()?;
+        let mut hhea_table = self.table(b"hhea", Font::hhea_table)?;
         hhea_table.ascent = ascender;
         hhea_table.descent = descender;
         hhea_table.line_gap = line_gap;
@@ -240,8 +348,8 @@ impl FontModifier {
 
     /// Modify glyph advance widths
     pub fn set_glyph_advance(&mut self, glyph_index: usize, advance_width: u16) -> Result<&mut Self> {
-        let mut hmtx_table = self.font.hmtx_table()?;
-        let hhea_table = self.font.hhea_table()?;
+        let mut hmtx_table = self.hmtx_table()?;
+        let hhea_table = self.table(b"hhea", Font::hhea_table)?;
 
         if glyph_index < hmtx_table.h_metrics.len() {
             if glyph_index < hhea_table.number_of_h_metrics as usize {
@@ -253,6 +361,194 @@ impl FontModifier {
         Ok(self)
     }
 
+    /// Rescale the font to a new units-per-em.
+    ///
+    /// Glyph coordinates and bounding boxes, composite component offsets,
+    /// horizontal metrics, the font-wide metrics in head, hhea and OS/2, the
+    /// post underline metrics and the cvt values are multiplied by
+    /// `new_upem / old_upem` and rounded to whole units. Kerning and GPOS
+    /// values are left as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ttf_rs::Font;
+    /// let font = Font::load("font.ttf")?;
+    /// let mut modifier = font.modify();
+    /// modifier.rescale_to_upem(2048)?;
+    /// # Ok::<(), ttf_rs::TtfError>(())
+    /// ```
+    pub fn rescale_to_upem(&mut self, new_upem: u16) -> Result<&mut Self> {
+        let mut head = self.table(b"head", Font::head_table)?;
+        if head.units_per_em == 0 || new_upem == 0 {
+            return Err(TtfError::ParseError("units_per_em must be non-zero".to_string()));
+        }
+
+        let factor = new_upem as f32 / head.units_per_em as f32;
+        let scale = |value: i16| (value as f32 * factor).round() as i16;
+        let scale_u = |value: u16| (value as f32 * factor).round() as u16;
+
+        head.units_per_em = new_upem;
+        head.x_min = scale(head.x_min);
+        head.y_min = scale(head.y_min);
+        head.x_max = scale(head.x_max);
+        head.y_max = scale(head.y_max);
+
+        if self.font.has_glyf() {
+            let mut glyf = self.font.glyf_table()?;
+            for glyph in &mut glyf.glyphs {
+                rescale_glyph(glyph, factor);
+            }
+
+            let (glyf_data, offsets) = glyf.to_bytes();
+            if head.is_short_loca_format() && offsets.last().is_some_and(|&end| end / 2 > u16::MAX as u32) {
+                head.index_to_loc_format = 1;
+            }
+            let loca_data = LocaTable::Long(offsets).to_bytes(head.is_long_loca_format());
+            self.add_table(b"glyf", glyf_data).add_table(b"loca", loca_data);
+        }
+        self.serialize_head_table(head)?;
+
+        let mut hhea = self.table(b"hhea", Font::hhea_table)?;
+        hhea.ascent = scale(hhea.ascent);
+        hhea.descent = scale(hhea.descent);
+        hhea.line_gap = scale(hhea.line_gap);
+        hhea.advance_width_max = scale_u(hhea.advance_width_max);
+        hhea.min_left_side_bearing = scale(hhea.min_left_side_bearing);
+        hhea.min_right_side_bearing = scale(hhea.min_right_side_bearing);
+        hhea.x_max_extent = scale(hhea.x_max_extent);
+        hhea.caret_offset = scale(hhea.caret_offset);
+        self.serialize_hhea_table(hhea)?;
+
+        let mut hmtx = self.hmtx_table()?;
+        for metric in &mut hmtx.h_metrics {
+            metric.advance_width = scale_u(metric.advance_width);
+            metric.lsb = scale(metric.lsb);
+        }
+        for lsb in &mut hmtx.left_side_bearings {
+            *lsb = scale(*lsb);
+        }
+        self.serialize_hmtx_table(hmtx)?;
+
+        if self.font.has_table(b"OS/2") {
+            let mut os2 = self.table(b"OS/2", Font::os2_table)?;
+            for value in [
+                &mut os2.x_avg_char_width,
+                &mut os2.y_subscript_x_size,
+                &mut os2.y_subscript_y_size,
+                &mut os2.y_subscript_x_offset,
+                &mut os2.y_subscript_y_offset,
+                &mut os2.y_superscript_x_size,
+                &mut os2.y_superscript_y_size,
+                &mut os2.y_superscript_x_offset,
+                &mut os2.y_superscript_y_offset,
+                &mut os2.y_strikeout_size,
+                &mut os2.y_strikeout_position,
+                &mut os2.s_typo_ascender,
+                &mut os2.s_typo_descender,
+                &mut os2.s_typo_line_gap,
+                &mut os2.sx_height,
+                &mut os2.s_cap_height,
+            ] {
+                *value = scale(*value);
+            }
+            os2.us_win_ascent = scale_u(os2.us_win_ascent);
+            os2.us_win_descent = scale_u(os2.us_win_descent);
+            self.serialize_os2_table(os2)?;
+        }
+
+        // underlinePosition and underlineThickness follow the version and italic angle
+        if let Some(mut post) = self.raw_table(b"post").filter(|data| data.len() >= 12) {
+            for offset in [8, 10] {
+                let value = i16::from_be_bytes([post[offset], post[offset + 1]]);
+                post[offset..offset + 2].copy_from_slice(&scale(value).to_be_bytes());
+            }
+            self.add_table(b"post", post);
+        }
+
+        if let Some(cvt) = self.raw_table(b"cvt ") {
+            let scaled: Vec<u8> = cvt
+                .chunks_exact(2)
+                .flat_map(|value| scale(i16::from_be_bytes([value[0], value[1]])).to_be_bytes())
+                .collect();
+            self.add_table(b"cvt ", scaled);
+        }
+
+        Ok(self)
+    }
+
+    /// Remove a table from the font.
+    ///
+    /// The table record and its data are dropped when `commit()` is called,
+    /// e.g. to strip a stale `DSIG` or legacy `kern` table.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - 4-byte table identifier
+    pub fn remove_table(&mut self, tag: &[u8; 4]) -> &mut Self {
+        self.modified_tables.remove(tag);
+        self.removed_tables.insert(*tag);
+        self
+    }
+
+    /// Add a raw table to the font, replacing any existing table with the same tag.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - 4-byte table identifier
+    /// * `data` - Raw table bytes
+    pub fn add_table(&mut self, tag: &[u8; 4], data: Vec<u8>) -> &mut Self {
+        self.removed_tables.remove(tag);
+        self.modified_tables.insert(*tag, data);
+        self
+    }
+
+    /// Keep the font's DSIG table on commit.
+    ///
+    /// A digital signature no longer matches once the font is modified, so
+    /// `commit()` drops the DSIG table by default. A DSIG table supplied
+    /// through `add_table` is always kept.
+    pub fn retain_digital_signature(&mut self) -> &mut Self {
+        self.retain_digital_signature = true;
+        self
+    }
+
+    /// Parse a table including the changes already made through this
+    /// modifier, so successive edits to the same table accumulate
+    fn table<T: TtfTable>(&self, tag: &[u8; 4], original: impl FnOnce(&Font) -> Result<T>) -> Result<T> {
+        match self.modified_tables.get(tag) {
+            Some(data) => T::from_reader(&mut FontReader::from_slice(data), data.len() as u32),
+            None => original(&self.font),
+        }
+    }
+
+    /// Raw table data including the changes already made through this modifier
+    fn raw_table(&self, tag: &[u8; 4]) -> Option<Vec<u8>> {
+        match self.modified_tables.get(tag) {
+            Some(data) => Some(data.clone()),
+            None if self.removed_tables.contains(tag) => None,
+            None => self.font.get_table_data(tag),
+        }
+    }
+
+    /// Parse the hmtx table including the changes already made through this
+    /// modifier
+    fn hmtx_table(&self) -> Result<HmtxTable> {
+        match self.modified_tables.get(b"hmtx") {
+            Some(data) => {
+                let hhea = self.table(b"hhea", Font::hhea_table)?;
+                let num_glyphs = self.font.num_glyphs()?;
+                HmtxTable::from_reader(
+                    &mut FontReader::from_slice(data),
+                    data.len() as u32,
+                    num_glyphs,
+                    hhea.number_of_h_metrics,
+                )
+            }
+            None => self.font.hmtx_table(),
+        }
+    }
+
     /// Serialize modified name table
     fn serialize_name_table(&mut self, table: NameTable) -> Result<()> {
         let mut writer = FontWriter::new();
@@ -293,38 +589,152 @@ impl FontModifier {
         Ok(())
     }
 
-    /// Commit all modifications and return the modified font
+    /// Commit all modifications and return the modified font.
+    ///
+    /// The font is rebuilt from scratch so modified tables may grow or shrink,
+    /// and the table directory and binary search fields are recalculated.
     pub fn commit(mut self) -> Result<Font> {
-        // Apply all modified tables to the font
-        for (tag, data) in &self.modified_tables {
-            // Find the table record and update it
-            if let Some(record) = self.font.table_records.iter_mut().find(|r| r.table_tag == *tag) {
-                record.length = data.len() as u32;
-                // Offset will be recalculated during save
+        if !self.retain_digital_signature && !self.modified_tables.contains_key(b"DSIG") {
+            self.removed_tables.insert(*b"DSIG");
+        }
+
+        let mut tables: Vec<([u8; 4], Vec<u8>)> = Vec::new();
+
+        for record in &self.font.table_records {
+            if self.removed_tables.contains(&record.table_tag) {
+                continue;
             }
+
+            let data = match self.modified_tables.remove(&record.table_tag) {
+                Some(data) => data,
+                None => self
+                    .font
+                    .get_table_data(&record.table_tag)
+                    .ok_or(TtfError::InvalidOffset(record.offset as u64))?,
+            };
+            tables.push((record.table_tag, data));
         }
 
-        // Create new data with modified tables
-        let mut new_data = self.font.data.clone();
+        // Whatever is left was not in the original font
+        tables.extend(self.modified_tables.drain());
 
-        // Update the data with modified tables (simplified approach)
-        for (tag, data) in &self.modified_tables {
-            if let Some(record) = self.font.get_table_record(tag) {
-                let offset = record.offset as usize;
-                // Make sure we have enough space
-                if offset + data.len() <= new_data.len() {
-                    new_data[offset..offset + data.len()].copy_from_slice(data);
+        Ok(Font::from_tables(self.font.sfnt_version, tables))
+    }
+}
+
+/// Scale a glyph's outline and bounding box for a new units-per-em
+///
+/// Composite components keep their scale matrices, since the glyphs they
+/// refer to are scaled themselves; only their x/y offsets change.
+fn rescale_glyph(glyph: &mut Glyph, factor: f32) {
+    let scale = |value: i16| (value as f32 * factor).round() as i16;
+
+    match &mut glyph.data {
+        GlyphData::Simple(simple) => {
+            for coordinate in simple.x_coordinates.iter_mut().chain(&mut simple.y_coordinates) {
+                *coordinate = scale(*coordinate);
+            }
+        }
+        GlyphData::Composite(composite) => {
+            for component in &mut composite.components {
+                // ARGS_ARE_XY_VALUES; otherwise the arguments are point numbers
+                if component.flags & 0x2 != 0 {
+                    component.arg1 = scale(component.arg1);
+                    component.arg2 = scale(component.arg2);
+                    component.transform.dx = component.arg1 as f32;
+                    component.transform.dy = component.arg2 as f32;
                 }
             }
         }
-
-        self.font.data = new_data;
-        Ok(self.font)
+        GlyphData::Empty => return,
     }
+
+    glyph.x_min = scale(glyph.x_min);
+    glyph.y_min = scale(glyph.y_min);
+    glyph.x_max = scale(glyph.x_max);
+    glyph.y_max = scale(glyph.y_max);
 }
 
 impl Font {
     pub fn modify(self) -> FontModifier {
         FontModifier::new(self)
     }
+
+    /// Rename the font family, keeping its name records consistent.
+    ///
+    /// Updates the family (ID 1), typographic family (ID 16, if present),
+    /// full name (ID 4), PostScript name (ID 6) and unique ID (ID 3) in every
+    /// Windows and Macintosh record. Existing names that start with the old
+    /// family keep their style suffix (e.g. "Old Sans Bold" becomes
+    /// "New Sans Bold"); the PostScript name is the family with spaces and
+    /// other disallowed characters removed.
+    pub fn rename(&self, new_family: &str) -> Result<Font> {
+        let mut name_table = self.name_table()?;
+
+        let name = |name_id: u16| name_table.get_name(name_id).map(|(_, value)| value);
+        let old_family = name(NameRecord::TYPOGRAPHIC_FAMILY_NAME)
+            .or_else(|| name(NameRecord::FONT_FAMILY_NAME))
+            .unwrap_or_default();
+        let old_postscript = name(NameRecord::POSTSCRIPT_NAME).unwrap_or_default();
+        let subfamily = name(NameRecord::FONT_SUBFAMILY_NAME).unwrap_or_else(|| "Regular".to_string());
+
+        let old_ps_family = postscript_sanitize(&old_family);
+        let new_ps_family = postscript_sanitize(new_family);
+        let new_postscript = match old_postscript.strip_prefix(old_ps_family.as_str()) {
+            Some(style) if !old_ps_family.is_empty() => format!("{}{}", new_ps_family, style),
+            _ => format!("{}-{}", new_ps_family, postscript_sanitize(&subfamily)),
+        };
+
+        let records: Vec<NameRecord> = name_table
+            .name_records
+            .iter()
+            .filter(|r| matches!((r.platform_id, r.encoding_id), (3, 1) | (1, 0)))
+            .cloned()
+            .collect();
+
+        for record in records {
+            let old_value = name_table.get_record_string(&record).unwrap_or_default();
+            let renamed = old_value
+                .strip_prefix(old_family.as_str())
+                .filter(|_| !old_family.is_empty())
+                .map(|style| format!("{}{}", new_family, style));
+
+            let value = match record.name_id {
+                NameRecord::FONT_FAMILY_NAME | NameRecord::TYPOGRAPHIC_FAMILY_NAME => {
+                    renamed.unwrap_or_else(|| new_family.to_string())
+                }
+                NameRecord::FULL_FONT_NAME => renamed.unwrap_or_else(|| {
+                    if subfamily == "Regular" {
+                        new_family.to_string()
+                    } else {
+                        format!("{} {}", new_family, subfamily)
+                    }
+                }),
+                NameRecord::POSTSCRIPT_NAME => new_postscript.clone(),
+                NameRecord::UNIQUE_FONT_ID if !old_postscript.is_empty() && old_value.contains(&old_postscript) => {
+                    old_value.replace(&old_postscript, &new_postscript)
+                }
+                NameRecord::UNIQUE_FONT_ID => new_postscript.clone(),
+                _ => continue,
+            };
+
+            name_table.set_name(&value, record.platform_id, record.encoding_id, record.language_id, record.name_id);
+        }
+
+        let mut writer = FontWriter::new();
+        name_table.write(&mut writer)?;
+
+        let mut modifier = self.clone().modify();
+        modifier.add_table(b"name", writer.into_inner());
+        modifier.commit()
+    }
+}
+
+/// Reduce a name to the characters allowed in PostScript names (printable
+/// ASCII without spaces or `[](){}<>/%`), at most 63 of them
+fn postscript_sanitize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_graphic() && !"[](){}<>/%".contains(*c))
+        .take(63)
+        .collect()
 }
@@ -0,0 +1,311 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::error::Result;
+use crate::tables::glyf::{ContourPoint, Glyph, GlyphData, PathCommand, Point};
+
+/// Largest distance, in font units, between a curve and the lines
+/// replacing it
+const FLATNESS: f64 = 0.25;
+
+/// Distance from an edge midpoint at which its two sides are sampled
+const SIDE_OFFSET: f64 = 0.01;
+
+/// Grid that split points are snapped to, so that edges meeting at an
+/// intersection share the vertex exactly
+const VERTEX_GRID: f64 = 1024.0;
+
+type Vertex = (f64, f64);
+
+/// A vertex snapped to the vertex grid
+type VertexKey = (i64, i64);
+
+impl Glyph {
+    /// Replace the contours of a simple glyph with the outline of their
+    /// union, so that no two contours overlap.
+    ///
+    /// The outline is flattened to lines (curves to within a quarter of a
+    /// font unit) and the edges that separate filled from unfilled area
+    /// under the non-zero rule are kept, joined into clockwise outer
+    /// contours and counter-clockwise holes. Points are rounded to whole
+    /// font units.
+    ///
+    /// Glyphs with no overlapping contours are left unchanged, curves
+    /// included. Composite glyphs are left unchanged too; decompose them
+    /// first. Hinting instructions are dropped whenever the outline changes.
+    pub fn remove_overlaps(&mut self) -> Result<()> {
+        let GlyphData::Simple(simple) = &mut self.data else {
+            return Ok(());
+        };
+
+        let polygons = flatten(&simple.path());
+        let edges: Vec<(Vertex, Vertex)> = polygons
+            .iter()
+            .flat_map(|polygon| {
+                polygon
+                    .iter()
+                    .zip(polygon.iter().cycle().skip(1))
+                    .map(|(&a, &b)| (a, b))
+            })
+            .filter(|(a, b)| a != b)
+            .collect();
+
+        let (pieces, split) = split_edges(&edges);
+        let mut kept = Vec::new();
+        for &(start, end) in &pieces {
+            let (a, b) = (key_vertex(start), key_vertex(end));
+            let length = ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+            if length == 0.0 {
+                continue;
+            }
+
+            // Sample just left and right of the midpoint
+            let normal = (-(b.1 - a.1) / length * SIDE_OFFSET, (b.0 - a.0) / length * SIDE_OFFSET);
+            let mid = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+            let left = winding(&edges, (mid.0 + normal.0, mid.1 + normal.1)) != 0;
+            let right = winding(&edges, (mid.0 - normal.0, mid.1 - normal.1)) != 0;
+
+            // Keep the filled side on the right, as in a clockwise contour
+            match (left, right) {
+                (false, true) => kept.push((start, end)),
+                (true, false) => kept.push((end, start)),
+                _ => {}
+            }
+        }
+
+        if !split && kept.len() == edges.len() {
+            return Ok(());
+        }
+
+        let contours: Vec<Vec<ContourPoint>> = link_edges(&kept)
+            .into_iter()
+            .map(|contour| round_contour(&contour))
+            .filter(|contour| contour.len() >= 3)
+            .collect();
+
+        simple.set_contours(&contours);
+        simple.instructions.clear();
+        simple.instruction_length = 0;
+        self.number_of_contours = contours.len() as i16;
+
+        match self.calculate_bounding_box() {
+            Some(bbox) if !contours.is_empty() => {
+                self.x_min = bbox.x_min.round() as i16;
+                self.y_min = bbox.y_min.round() as i16;
+                self.x_max = bbox.x_max.round() as i16;
+                self.y_max = bbox.y_max.round() as i16;
+            }
+            _ => *self = Glyph::empty(),
+        }
+        Ok(())
+    }
+}
+
+/// Turn path commands into closed polygons, approximating curves with lines
+fn flatten(path: &[PathCommand]) -> Vec<Vec<Vertex>> {
+    let vertex = |point: Point| (point.x as f64, point.y as f64);
+    let mut polygons = Vec::new();
+    let mut current: Vec<Vertex> = Vec::new();
+
+    for command in path {
+        match *command {
+            PathCommand::MoveTo(point) => current = vec![vertex(point)],
+            PathCommand::LineTo(point) => current.push(vertex(point)),
+            PathCommand::QuadTo(ctrl, point) => {
+                let p0 = *current.last().unwrap_or(&vertex(ctrl));
+                let (p1, p2) = (vertex(ctrl), vertex(point));
+
+                // A chord strays from the curve by at most |p0 - 2p1 + p2| / (4n²)
+                let bend = (p0.0 - 2.0 * p1.0 + p2.0).hypot(p0.1 - 2.0 * p1.1 + p2.1);
+                let steps = ((bend / (4.0 * FLATNESS)).sqrt().ceil() as usize).clamp(1, 64);
+                for i in 1..=steps {
+                    let t = i as f64 / steps as f64;
+                    let mt = 1.0 - t;
+                    current.push((
+                        mt * mt * p0.0 + 2.0 * mt * t * p1.0 + t * t * p2.0,
+                        mt * mt * p0.1 + 2.0 * mt * t * p1.1 + t * t * p2.1,
+                    ));
+                }
+            }
+            PathCommand::Close => {
+                if current.last() == current.first() {
+                    current.pop();
+                }
+                if current.len() >= 3 {
+                    polygons.push(std::mem::take(&mut current));
+                }
+                current.clear();
+            }
+        }
+    }
+
+    polygons
+}
+
+/// Key of a vertex snapped to the vertex grid
+fn vertex_key(point: Vertex) -> VertexKey {
+    ((point.0 * VERTEX_GRID).round() as i64, (point.1 * VERTEX_GRID).round() as i64)
+}
+
+/// Vertex at a grid key
+fn key_vertex(key: VertexKey) -> Vertex {
+    (key.0 as f64 / VERTEX_GRID, key.1 as f64 / VERTEX_GRID)
+}
+
+fn cross(a: Vertex, b: Vertex) -> f64 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+/// Split every edge where another edge crosses or touches it.
+///
+/// Returns the distinct pieces, as snapped end points, and whether any edge
+/// was split.
+fn split_edges(edges: &[(Vertex, Vertex)]) -> (Vec<(VertexKey, VertexKey)>, bool) {
+    const EPSILON: f64 = 1e-9;
+
+    // (edge parameter, point) of the split points of every edge
+    let mut splits: Vec<Vec<(f64, Vertex)>> = vec![Vec::new(); edges.len()];
+    for i in 0..edges.len() {
+        let (a, b) = edges[i];
+        let r = (b.0 - a.0, b.1 - a.1);
+        for j in i + 1..edges.len() {
+            let (c, d) = edges[j];
+            let s = (d.0 - c.0, d.1 - c.1);
+            let ac = (c.0 - a.0, c.1 - a.1);
+            let denom = cross(r, s);
+
+            if denom.abs() > EPSILON {
+                let t = cross(ac, s) / denom;
+                let u = cross(ac, r) / denom;
+                if (-EPSILON..=1.0 + EPSILON).contains(&t) && (-EPSILON..=1.0 + EPSILON).contains(&u) {
+                    let point = (a.0 + t * r.0, a.1 + t * r.1);
+                    splits[i].push((t, point));
+                    splits[j].push((u, point));
+                }
+            } else if cross(ac, r).abs() <= EPSILON * r.0.hypot(r.1) {
+                // Collinear edges split each other at their end points
+                let along = |origin: Vertex, direction: Vertex, point: Vertex| {
+                    ((point.0 - origin.0) * direction.0 + (point.1 - origin.1) * direction.1)
+                        / (direction.0 * direction.0 + direction.1 * direction.1)
+                };
+                for point in [c, d] {
+                    splits[i].push((along(a, r, point), point));
+                }
+                for point in [a, b] {
+                    splits[j].push((along(c, s, point), point));
+                }
+            }
+        }
+    }
+
+    let mut pieces = Vec::new();
+    let mut seen = HashSet::new();
+    let mut split = false;
+    for (&(a, b), points) in edges.iter().zip(&mut splits) {
+        points.retain(|&(t, _)| t > EPSILON && t < 1.0 - EPSILON);
+        points.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+        let mut keys = vec![vertex_key(a)];
+        keys.extend(points.iter().map(|&(_, point)| vertex_key(point)));
+        keys.push(vertex_key(b));
+        keys.dedup();
+        split |= keys.len() > 2;
+
+        for window in keys.windows(2) {
+            let (start, end) = (window[0], window[1]);
+            // Shared pieces of collinear edges are kept once
+            if seen.insert((start.min(end), start.max(end))) {
+                pieces.push((start, end));
+            } else {
+                split = true;
+            }
+        }
+    }
+
+    (pieces, split)
+}
+
+/// Winding number of the edges around `point`, counting a ray cast towards +x
+fn winding(edges: &[(Vertex, Vertex)], point: Vertex) -> i32 {
+    let mut winding = 0;
+    for &(a, b) in edges {
+        let side = cross((b.0 - a.0, b.1 - a.1), (point.0 - a.0, point.1 - a.1));
+        if a.1 <= point.1 && b.1 > point.1 && side > 0.0 {
+            winding += 1;
+        } else if b.1 <= point.1 && a.1 > point.1 && side < 0.0 {
+            winding -= 1;
+        }
+    }
+    winding
+}
+
+/// Join directed edges into closed loops of vertices
+fn link_edges(edges: &[(VertexKey, VertexKey)]) -> Vec<Vec<VertexKey>> {
+    let mut outgoing: HashMap<VertexKey, Vec<usize>> = HashMap::new();
+    for (index, &(start, _)) in edges.iter().enumerate() {
+        outgoing.entry(start).or_default().push(index);
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut loops = Vec::new();
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+
+        let mut contour = Vec::new();
+        let mut index = first;
+        loop {
+            used[index] = true;
+            let (start, end) = edges[index];
+            contour.push(start);
+
+            let next = outgoing
+                .get(&end)
+                .and_then(|candidates| candidates.iter().copied().find(|&next| !used[next]));
+            match next {
+                Some(next) => index = next,
+                None => break,
+            }
+        }
+        loops.push(contour);
+    }
+
+    loops
+}
+
+/// Round a loop to whole font units, dropping repeated and collinear points
+fn round_contour(contour: &[VertexKey]) -> Vec<ContourPoint> {
+    let mut points: Vec<(i64, i64)> = contour
+        .iter()
+        .map(|&key| {
+            let (x, y) = key_vertex(key);
+            (x.round() as i64, y.round() as i64)
+        })
+        .collect();
+
+    let mut changed = true;
+    while changed && points.len() >= 3 {
+        changed = false;
+        let mut i = 0;
+        while i < points.len() && points.len() >= 3 {
+            let n = points.len();
+            let (prev, point, next) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+            let turn = (point.0 - prev.0) * (next.1 - point.1) - (point.1 - prev.1) * (next.0 - point.0);
+            if point == prev || turn == 0 {
+                points.remove(i);
+                changed = true;
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    points
+        .into_iter()
+        .map(|(x, y)| ContourPoint {
+            x: x as i16,
+            y: y as i16,
+            on_curve: true,
+        })
+        .collect()
+}
@@ -1,12 +1,20 @@
 use crate::error::Result;
 use crate::font::Font;
-use crate::tables::glyf::{Glyph, GlyphData, SimpleGlyph};
+use crate::tables::glyf::{GlyfTable, Glyph, GlyphData, PathCommand, Point, SimpleGlyph};
+use crate::tables::hmtx::HmtxTable;
 use std::collections::HashMap;
 
 /// Rasterizer for converting TTF outlines to bitmaps
 pub struct Rasterizer {
     font: Font,
-    cache: HashMap<u32, RasterizedGlyph>,
+    /// Rendered bitmaps keyed by glyph ID and pixel size
+    cache: HashMap<(u32, u32), RasterizedGlyph>,
+    /// glyf table, parsed on first use
+    glyf: Option<GlyfTable>,
+    glyf_parses: usize,
+    outlines: HashMap<u32, Glyph>,
+    outline_parses: usize,
+    snap_to_pixel: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -19,72 +27,189 @@ pub struct RasterizedGlyph {
     pub left_side_bearing: i16,
 }
 
+impl RasterizedGlyph {
+    /// Encode the bitmap as a binary (P5) PGM image
+    pub fn to_pgm(&self) -> Vec<u8> {
+        let mut image = format!("P5\n{} {}\n255\n", self.width, self.height).into_bytes();
+        image.extend_from_slice(&self.bitmap);
+        image
+    }
+
+    /// Encode the bitmap as an 8-bit grayscale PNG image
+    ///
+    /// The image data is stored uncompressed. Empty bitmaps can't be
+    /// represented and return an error.
+    #[cfg(feature = "png")]
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        if self.width == 0 || self.height == 0 {
+            return Err(crate::error::TtfError::ParseError(
+                "Cannot encode an empty bitmap as PNG".to_string(),
+            ));
+        }
+        if self.bitmap.len() != self.width * self.height {
+            return Err(crate::error::TtfError::InvalidSize {
+                expected: (self.width * self.height) as u64,
+                actual: self.bitmap.len() as u64,
+            });
+        }
+
+        Ok(png::encode_grayscale(&self.bitmap, self.width, self.height))
+    }
+}
+
 impl Rasterizer {
     pub fn new(font: Font) -> Self {
         Self {
             font,
             cache: HashMap::new(),
+            glyf: None,
+            glyf_parses: 0,
+            outlines: HashMap::new(),
+            outline_parses: 0,
+            snap_to_pixel: false,
+        }
+    }
+
+    /// Round outline x-coordinates to whole pixels after scaling.
+    ///
+    /// Vertical stems then start and end on pixel boundaries instead of
+    /// being smeared across two columns, which sharpens small text. This is
+    /// a heuristic: the font's TrueType hinting instructions are not run.
+    /// Changing the setting clears the rasterization cache.
+    pub fn set_snap_to_pixel(&mut self, snap: bool) {
+        if self.snap_to_pixel != snap {
+            self.snap_to_pixel = snap;
+            self.cache.clear();
+        }
+    }
+
+    /// Whether x-coordinates are snapped to whole pixels
+    pub fn snap_to_pixel(&self) -> bool {
+        self.snap_to_pixel
+    }
+
+    /// The font's glyf table, parsed once and kept for later lookups
+    fn glyf(&mut self) -> Result<&GlyfTable> {
+        let glyf = match self.glyf.take() {
+            Some(glyf) => glyf,
+            None => {
+                let glyf = self.font.glyf_table()?;
+                self.glyf_parses += 1;
+                glyf
+            }
+        };
+
+        Ok(self.glyf.insert(glyf))
+    }
+
+    /// Get the parsed outline of a glyph, parsing it on first use
+    pub fn glyph_outline(&mut self, glyph_id: u32) -> Result<&Glyph> {
+        if !self.outlines.contains_key(&glyph_id) {
+            let glyph = self.glyf()?.get_glyph(glyph_id as usize)
+                .ok_or(crate::error::TtfError::InvalidGlyphIndex(glyph_id as u16))?
+                .clone();
+            self.outline_parses += 1;
+            self.outlines.insert(glyph_id, glyph);
         }
+
+        Ok(&self.outlines[&glyph_id])
+    }
+
+    /// Number of outlines parsed so far (test hook for the outline cache)
+    #[cfg(feature = "test-hooks")]
+    #[doc(hidden)]
+    pub fn outline_parse_count(&self) -> usize {
+        self.outline_parses
+    }
+
+    /// Number of times the glyf table was parsed (test hook for the outline cache)
+    #[cfg(feature = "test-hooks")]
+    #[doc(hidden)]
+    pub fn glyf_parse_count(&self) -> usize {
+        self.glyf_parses
     }
 
     /// Rasterize a glyph at a specific size
     pub fn rasterize_glyph(&mut self, glyph_id: u32, size: u32) -> Result<RasterizedGlyph> {
         // Check cache first
-        if let Some(cached) = self.cache.get(&glyph_id) {
+        if let Some(cached) = self.cache.get(&(glyph_id, size)) {
             return Ok(cached.clone());
         }
 
-        // Get the glyf table
-        let glyf_table = self.font.glyf_table()?;
+        // Get the glyph outline
+        let glyph = self.resolved_outline(glyph_id)?;
         let hmtx_table = self.font.hmtx_table()?;
         let head_table = self.font.head_table()?;
-        let units_per_em = head_table.units_per_em as f32;
 
-        // Get the glyph
-        let glyph = glyf_table.get_glyph(glyph_id as usize)
-            .ok_or_else(|| crate::error::TtfError::InvalidGlyphIndex(glyph_id as u16))?;
+        // Calculate scale factor
+        let scale = size as f32 / head_table.units_per_em as f32;
+        let rasterized = Self::render(glyph_id, &glyph, &hmtx_table, scale, self.snap_to_pixel)?;
+
+        // Cache the result
+        self.cache.insert((glyph_id, size), rasterized.clone());
+
+        Ok(rasterized)
+    }
+
+    /// Get a glyph's outline with any composite flattened into a simple glyph
+    fn resolved_outline(&mut self, glyph_id: u32) -> Result<Glyph> {
+        let glyph = self.glyph_outline(glyph_id)?.clone();
+        if glyph.is_composite()
+            && let Some(resolved) = self.glyf()?.resolve_composite(glyph_id as usize)?
+        {
+            return Ok(resolved);
+        }
+
+        Ok(glyph)
+    }
 
+    /// Rasterize a glyph outline at the given scale (pixels per font unit).
+    /// Composite glyphs must already be resolved
+    fn render(glyph_id: u32, glyph: &Glyph, hmtx_table: &HmtxTable, scale: f32, snap: bool) -> Result<RasterizedGlyph> {
         // Get metrics
         let advance_width = hmtx_table.get_advance_width(glyph_id as u16);
         let lsb = hmtx_table.get_lsb(glyph_id as u16);
 
-        // Calculate scale factor
-        let scale = size as f32 / units_per_em;
-
         // Create bitmap
         let (bitmap, width, height) = if let GlyphData::Simple(simple) = &glyph.data {
-            self.rasterize_simple(simple, scale, &glyph)?
+            Self::rasterize_simple(simple, scale, snap)?
         } else {
-            // For composite or empty glyphs, create empty bitmap
+            // Empty glyphs have no bitmap
             (vec![0u8; 0], 0, 0)
         };
 
-        let rasterized = RasterizedGlyph {
+        Ok(RasterizedGlyph {
             glyph_id,
             bitmap,
             width,
             height,
             advance_width,
             left_side_bearing: lsb,
-        };
-
-        // Cache the result
-        self.cache.insert(glyph_id, rasterized.clone());
-
-        Ok(rasterized)
+        })
     }
 
     /// Rasterize a simple glyph
-    fn rasterize_simple(&self, glyph: &SimpleGlyph, scale: f32, bounds: &Glyph) -> Result<(Vec<u8>, usize, usize)> {
+    ///
+    /// The bitmap covers the extents of the outline points rather than the
+    /// glyph header's bounding box, which may be stale.
+    fn rasterize_simple(glyph: &SimpleGlyph, scale: f32, snap: bool) -> Result<(Vec<u8>, usize, usize)> {
         if glyph.end_pts_of_contours.is_empty() || glyph.x_coordinates.is_empty() {
             return Ok((vec![0u8; 0], 0, 0));
         }
 
+        let scale_x = |x: i16| snap_x(x as f32 * scale, snap);
+
         // Calculate bounding box in pixels
-        let x_min = (bounds.x_min as f32 * scale).floor() as i32;
-        let y_min = (bounds.y_min as f32 * scale).floor() as i32;
-        let x_max = (bounds.x_max as f32 * scale).ceil() as i32;
-        let y_max = (bounds.y_max as f32 * scale).ceil() as i32;
+        let (Some(&x_min), Some(&x_max)) = (glyph.x_coordinates.iter().min(), glyph.x_coordinates.iter().max()) else {
+            return Ok((vec![0u8; 0], 0, 0));
+        };
+        let (Some(&y_min), Some(&y_max)) = (glyph.y_coordinates.iter().min(), glyph.y_coordinates.iter().max()) else {
+            return Ok((vec![0u8; 0], 0, 0));
+        };
+        let x_min = scale_x(x_min).floor() as i32;
+        let y_min = (y_min as f32 * scale).floor() as i32;
+        let x_max = scale_x(x_max).ceil() as i32;
+        let y_max = (y_max as f32 * scale).ceil() as i32;
 
         let width = (x_max - x_min).max(1) as usize;
         let height = (y_max - y_min).max(1) as usize;
@@ -115,13 +240,13 @@ impl Rasterizer {
                 let y2 = glyph.y_coordinates[next_idx];
 
                 // Convert to pixel coordinates
-                let px1 = ((x1 as f32 * scale) - x_min as f32).round() as i32;
+                let px1 = (scale_x(x1) - x_min as f32).round() as i32;
                 let py1 = ((y1 as f32 * scale) - y_min as f32).round() as i32;
-                let px2 = ((x2 as f32 * scale) - x_min as f32).round() as i32;
+                let px2 = (scale_x(x2) - x_min as f32).round() as i32;
                 let py2 = ((y2 as f32 * scale) - y_min as f32).round() as i32;
 
                 // Draw line
-                self.draw_line(&mut bitmap, width, height, px1, py1, px2, py2);
+                Self::draw_line(&mut bitmap, width, height, px1, py1, px2, py2);
 
                 point_idx += 1;
             }
@@ -131,72 +256,201 @@ impl Rasterizer {
     }
 
     /// Draw a line on the bitmap using Bresenham's algorithm
-    fn draw_line(&self, bitmap: &mut [u8], width: usize, height: usize, x0: i32, y0: i32, x1: i32, y1: i32) {
-        let mut x0 = x0;
-        let mut y0 = y0;
-        let mut x1 = x1;
-        let mut y1 = y1;
-
+    fn draw_line(bitmap: &mut [u8], width: usize, height: usize, x0: i32, y0: i32, x1: i32, y1: i32) {
         // Ensure coordinates are within bounds
-        x0 = x0.max(0).min(width as i32 - 1);
-        y0 = y0.max(0).min(height as i32 - 1);
-        x1 = x1.max(0).min(width as i32 - 1);
-        y1 = y1.max(0).min(height as i32 - 1);
+        let mut x0 = x0.clamp(0, width as i32 - 1);
+        let mut y0 = y0.clamp(0, height as i32 - 1);
+        let x1 = x1.clamp(0, width as i32 - 1);
+        let y1 = y1.clamp(0, height as i32 - 1);
 
         let dx = (x1 - x0).abs();
-        let dy = (y1 - y0).abs();
+        let dy = -(y1 - y0).abs();
         let sx = if x0 < x1 { 1 } else { -1 };
         let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
 
-        if dx > dy {
-            let mut err = dx - 2 * dy;
-            while x0 != x1 {
-                // Set pixel if in bounds
-                let idx = (y0 as usize * width + x0 as usize);
-                if idx < bitmap.len() {
-                    bitmap[idx] = 255;
-                }
+        loop {
+            bitmap[y0 as usize * width + x0 as usize] = 255;
 
-                if err > 0 {
-                    y0 += sy;
-                    err -= 2 * dx;
-                }
-                if err < 0 {
-                    err += 2 * dy;
-                }
+            if x0 == x1 && y0 == y1 {
+                break;
+            }
+
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
                 x0 += sx;
             }
+            if e2 <= dx {
+                err += dx;
+                y0 += sy;
+            }
+        }
+    }
+
+    /// Rasterize a glyph as a signed distance field
+    ///
+    /// Each byte encodes the distance from the pixel centre to the nearest
+    /// outline edge, positive inside: 128 lies on the edge, and `spread`
+    /// pixels inside or outside map to 255 and 0. The bitmap is padded by
+    /// `spread` pixels on every side and its rows run top to bottom.
+    pub fn rasterize_sdf(&mut self, glyph_id: u32, px_size: f32, spread: f32) -> Result<RasterizedGlyph> {
+        let glyph = self.resolved_outline(glyph_id)?;
+
+        let hmtx_table = self.font.hmtx_table()?;
+        let head_table = self.font.head_table()?;
+        let scale = px_size / head_table.units_per_em as f32;
+        let spread = spread.max(f32::EPSILON);
+
+        let snap = self.snap_to_pixel;
+        let segments: Vec<Segment> = match &glyph.data {
+            GlyphData::Simple(simple) => flatten_outline(simple, |point| {
+                Point::new(snap_x(point.x * scale, snap), point.y * scale)
+            }),
+            _ => Vec::new(),
+        };
+
+        let (bitmap, width, height) = if segments.is_empty() {
+            (vec![0u8; 0], 0, 0)
         } else {
-            let mut err = dy - 2 * dx;
-            while y0 != y1 {
-                // Set pixel if in bounds
-                let idx = (y0 as usize * width + x0 as usize);
-                if idx < bitmap.len() {
-                    bitmap[idx] = 255;
-                }
+            let pad = spread.ceil();
+            let points = segments.iter().flat_map(|&(a, b)| [a, b]);
+            let (mut x_min, mut y_max) = (f32::MAX, f32::MIN);
+            let (mut x_max, mut y_min) = (f32::MIN, f32::MAX);
+            for point in points {
+                x_min = x_min.min(point.x);
+                x_max = x_max.max(point.x);
+                y_min = y_min.min(point.y);
+                y_max = y_max.max(point.y);
+            }
 
-                if err > 0 {
-                    x0 += sx;
-                    err -= 2 * dy;
-                }
-                if err < 0 {
-                    err += 2 * dx;
+            let left = x_min.floor() - pad;
+            let top = y_max.ceil() + pad;
+            let width = (x_max.ceil() + pad - left) as usize;
+            let height = (top - (y_min.floor() - pad)) as usize;
+
+            let mut bitmap = vec![0u8; width * height];
+            for row in 0..height {
+                for col in 0..width {
+                    let point = Point::new(left + col as f32 + 0.5, top - row as f32 - 0.5);
+                    let distance = signed_distance(&segments, point);
+                    let value = 128.0 + distance / spread * 128.0;
+                    bitmap[row * width + col] = value.round().clamp(0.0, 255.0) as u8;
                 }
-                y0 += sy;
             }
-        }
 
-        // Set final pixel
-        let idx = (y1 as usize * width + x1 as usize);
-        if idx < bitmap.len() {
-            bitmap[idx] = 255;
-        }
+            (bitmap, width, height)
+        };
+
+        Ok(RasterizedGlyph {
+            glyph_id,
+            bitmap,
+            width,
+            height,
+            advance_width: hmtx_table.get_advance_width(glyph_id as u16),
+            left_side_bearing: hmtx_table.get_lsb(glyph_id as u16),
+        })
     }
 
     /// Clear the rasterization cache
+    ///
+    /// Parsed outlines don't depend on the size and are kept; use
+    /// `clear_outline_cache` to drop them as well.
     pub fn clear_cache(&mut self) {
         self.cache.clear();
     }
+
+    /// Clear the parsed outline cache
+    pub fn clear_outline_cache(&mut self) {
+        self.outlines.clear();
+    }
+}
+
+/// A line segment of a flattened outline
+type Segment = (Point, Point);
+
+/// Number of line segments each quadratic curve is flattened into
+const CURVE_STEPS: usize = 8;
+
+/// Round a scaled x-coordinate to a pixel boundary when snapping
+fn snap_x(x: f32, snap: bool) -> f32 {
+    if snap { x.round() } else { x }
+}
+
+/// Flatten the outline of a glyph into line segments, mapping each point
+/// from font units with `to_pixels` before flattening
+fn flatten_outline(glyph: &SimpleGlyph, to_pixels: impl Fn(Point) -> Point) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut start = Point::new(0.0, 0.0);
+    let mut current = start;
+
+    for command in glyph.path() {
+        match command {
+            PathCommand::MoveTo(point) => {
+                start = to_pixels(point);
+                current = start;
+            }
+            PathCommand::LineTo(point) => {
+                let point = to_pixels(point);
+                segments.push((current, point));
+                current = point;
+            }
+            PathCommand::QuadTo(ctrl, point) => {
+                let point = to_pixels(point);
+                flatten_quad(current, to_pixels(ctrl), point, &mut segments);
+                current = point;
+            }
+            PathCommand::Close => {
+                if current != start {
+                    segments.push((current, start));
+                }
+                current = start;
+            }
+        }
+    }
+
+    segments
+}
+
+/// Approximate a quadratic curve with line segments
+fn flatten_quad(p0: Point, p1: Point, p2: Point, segments: &mut Vec<Segment>) {
+    let mut previous = p0;
+    for i in 1..=CURVE_STEPS {
+        // de Casteljau: interpolate along both control legs, then between them
+        let t = i as f32 / CURVE_STEPS as f32;
+        let point = p0.lerp(&p1, t).lerp(&p1.lerp(&p2, t), t);
+        segments.push((previous, point));
+        previous = point;
+    }
+}
+
+/// Distance from `point` to the nearest segment, positive inside the
+/// outline (non-zero winding)
+fn signed_distance(segments: &[Segment], point: Point) -> f32 {
+    let mut nearest = f32::MAX;
+    let mut winding = 0;
+
+    for &(start, end) in segments {
+        let edge = end - start;
+        let to_point = point - start;
+        let length_sq = edge.x * edge.x + edge.y * edge.y;
+        let t = if length_sq > 0.0 {
+            ((to_point.x * edge.x + to_point.y * edge.y) / length_sq).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        nearest = nearest.min(start.lerp(&end, t).distance(&point));
+
+        // Crossings of a ray cast towards +x
+        let cross = edge.x * to_point.y - edge.y * to_point.x;
+        if start.y <= point.y && end.y > point.y && cross > 0.0 {
+            winding += 1;
+        } else if end.y <= point.y && start.y > point.y && cross < 0.0 {
+            winding -= 1;
+        }
+    }
+
+    if winding != 0 { nearest } else { -nearest }
 }
 
 impl Font {
@@ -204,4 +458,104 @@ impl Font {
     pub fn rasterizer(&self) -> Rasterizer {
         Rasterizer::new(self.clone())
     }
+
+    /// Rasterize several glyphs at a pixel size, returning them in the
+    /// order of `glyph_ids`.
+    ///
+    /// The glyf, hmtx and head tables are parsed once and shared. With the
+    /// `rayon` feature the glyphs are filled in parallel, each task cloning
+    /// (or, for composites, flattening) only its own outline.
+    pub fn rasterize_glyphs(&self, glyph_ids: &[u32], px_size: f32) -> Result<Vec<RasterizedGlyph>> {
+        let glyf_table = self.glyf_table()?;
+        let hmtx_table = self.hmtx_table()?;
+        let scale = px_size / self.head_table()?.units_per_em as f32;
+
+        let rasterize = |&glyph_id: &u32| {
+            let glyph = glyf_table.resolve_composite(glyph_id as usize)?
+                .ok_or(crate::error::TtfError::InvalidGlyphIndex(glyph_id as u16))?;
+            Rasterizer::render(glyph_id, &glyph, &hmtx_table, scale, false)
+        };
+
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            glyph_ids.par_iter().map(rasterize).collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            glyph_ids.iter().map(rasterize).collect()
+        }
+    }
+}
+
+#[cfg(feature = "png")]
+mod png {
+    const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+    /// Encode 8-bit grayscale pixels (rows top to bottom)
+    pub(super) fn encode_grayscale(pixels: &[u8], width: usize, height: usize) -> Vec<u8> {
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend_from_slice(&(width as u32).to_be_bytes());
+        ihdr.extend_from_slice(&(height as u32).to_be_bytes());
+        ihdr.extend_from_slice(&[8, 0, 0, 0, 0]); // bit depth, grayscale, deflate, no filter, no interlace
+
+        // Every scanline starts with filter type 0 (none)
+        let mut scanlines = Vec::with_capacity((width + 1) * height);
+        for row in pixels.chunks_exact(width) {
+            scanlines.push(0);
+            scanlines.extend_from_slice(row);
+        }
+
+        let mut image = SIGNATURE.to_vec();
+        write_chunk(&mut image, b"IHDR", &ihdr);
+        write_chunk(&mut image, b"IDAT", &zlib_stored(&scanlines));
+        write_chunk(&mut image, b"IEND", &[]);
+        image
+    }
+
+    fn write_chunk(out: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = out.len();
+        out.extend_from_slice(chunk_type);
+        out.extend_from_slice(data);
+        let crc = crc32(&out[start..]);
+        out.extend_from_slice(&crc.to_be_bytes());
+    }
+
+    /// Wrap data in a zlib stream of uncompressed deflate blocks
+    fn zlib_stored(data: &[u8]) -> Vec<u8> {
+        let mut out = vec![0x78, 0x01];
+        let mut blocks = data.chunks(0xFFFF).peekable();
+        if blocks.peek().is_none() {
+            out.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+        }
+        while let Some(block) = blocks.next() {
+            out.push(if blocks.peek().is_none() { 1 } else { 0 });
+            out.extend_from_slice(&(block.len() as u16).to_le_bytes());
+            out.extend_from_slice(&(!(block.len() as u16)).to_le_bytes());
+            out.extend_from_slice(block);
+        }
+        out.extend_from_slice(&adler32(data).to_be_bytes());
+        out
+    }
+
+    fn adler32(data: &[u8]) -> u32 {
+        let (mut a, mut b) = (1u32, 0u32);
+        for &byte in data {
+            a = (a + byte as u32) % 65521;
+            b = (b + a) % 65521;
+        }
+        (b << 16) | a
+    }
+
+    fn crc32(data: &[u8]) -> u32 {
+        let mut crc = 0xFFFF_FFFFu32;
+        for &byte in data {
+            crc ^= byte as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            }
+        }
+        !crc
+    }
 }
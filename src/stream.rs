@@ -1,5 +1,7 @@
 use std::io;
 
+use crate::error::{Result, TtfError};
+
 /// Helper struct for reading TTF data with proper endianness handling
 pub struct FontReader {
     data: Vec<u8>,
@@ -22,18 +24,15 @@ impl FontReader {
         self.pos
     }
 
-    pub fn set_position(&mut self, pos: usize) -> Result<(), io::Error> {
+    pub fn set_position(&mut self, pos: usize) -> Result<()> {
         if pos > self.data.len() {
-            return Err(io::Error::new(
-                io::ErrorKind::UnexpectedEof,
-                "Position out of bounds",
-            ));
+            return Err(TtfError::UnexpectedEof { offset: pos, context: "seeking" });
         }
         self.pos = pos;
         Ok(())
     }
 
-    pub fn skip(&mut self, bytes: usize) -> Result<(), io::Error> {
+    pub fn skip(&mut self, bytes: usize) -> Result<()> {
         self.set_position(self.pos + bytes)
     }
 
@@ -49,122 +48,132 @@ impl FontReader {
         self.data
     }
 
-    pub fn read_u8(&mut self) -> Result<u8, io::Error> {
-        if self.pos + 1 > self.data.len() {
-            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Not enough bytes"));
-        }
-        let val = self.data[self.pos];
-        self.pos += 1;
-        Ok(val)
+    pub fn read_u8(&mut self) -> Result<u8> {
+        self.read_array::<1>("reading u8").map(u8::from_be_bytes)
     }
 
-    pub fn read_i8(&mut self) -> Result<i8, io::Error> {
+    pub fn read_i8(&mut self) -> Result<i8> {
         self.read_u8().map(|b| b as i8)
     }
 
-    pub fn read_u16(&mut self) -> Result<u16, io::Error> {
-        if self.pos + 2 > self.data.len() {
-            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Not enough bytes"));
-        }
-        let val = u16::from_be_bytes([self.data[self.pos], self.data[self.pos + 1]]);
-        self.pos += 2;
-        Ok(val)
+    pub fn read_u16(&mut self) -> Result<u16> {
+        self.read_array::<2>("reading u16").map(u16::from_be_bytes)
     }
 
-    pub fn read_i16(&mut self) -> Result<i16, io::Error> {
+    pub fn read_i16(&mut self) -> Result<i16> {
         self.read_u16().map(|b| b as i16)
     }
 
-    pub fn read_u24(&mut self) -> Result<u32, io::Error> {
-        if self.pos + 3 > self.data.len() {
-            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Not enough bytes"));
-        }
-        let val = u32::from_be_bytes([
-            0,
-            self.data[self.pos],
-            self.data[self.pos + 1],
-            self.data[self.pos + 2],
-        ]);
-        self.pos += 3;
-        Ok(val)
-    }
-
-    pub fn read_u32(&mut self) -> Result<u32, io::Error> {
-        if self.pos + 4 > self.data.len() {
-            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Not enough bytes"));
-        }
-        let val = u32::from_be_bytes([
-            self.data[self.pos],
-            self.data[self.pos + 1],
-            self.data[self.pos + 2],
-            self.data[self.pos + 3],
-        ]);
-        self.pos += 4;
-        Ok(val)
+    pub fn read_u24(&mut self) -> Result<u32> {
+        let [b0, b1, b2] = self.read_array::<3>("reading u24")?;
+        Ok(u32::from_be_bytes([0, b0, b1, b2]))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32> {
+        self.read_array::<4>("reading u32").map(u32::from_be_bytes)
     }
 
-    pub fn read_i32(&mut self) -> Result<i32, io::Error> {
+    pub fn read_i32(&mut self) -> Result<i32> {
         self.read_u32().map(|b| b as i32)
     }
 
-    pub fn read_u64(&mut self) -> Result<u64, io::Error> {
-        if self.pos + 8 > self.data.len() {
-            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Not enough bytes"));
-        }
-        let val = u64::from_be_bytes([
-            self.data[self.pos],
-            self.data[self.pos + 1],
-            self.data[self.pos + 2],
-            self.data[self.pos + 3],
-            self.data[self.pos + 4],
-            self.data[self.pos + 5],
-            self.data[self.pos + 6],
-            self.data[self.pos + 7],
-        ]);
-        self.pos += 8;
-        Ok(val)
-    }
-
-    pub fn read_i64(&mut self) -> Result<i64, io::Error> {
+    /// Read a u8 at the current position without advancing
+    pub fn peek_u8(&self) -> Result<u8> {
+        self.peek_array::<1>("reading u8").map(u8::from_be_bytes)
+    }
+
+    /// Read a u16 at the current position without advancing
+    pub fn peek_u16(&self) -> Result<u16> {
+        self.peek_array::<2>("reading u16").map(u16::from_be_bytes)
+    }
+
+    /// Read a u32 at the current position without advancing
+    pub fn peek_u32(&self) -> Result<u32> {
+        self.peek_array::<4>("reading u32").map(u32::from_be_bytes)
+    }
+
+    fn peek_array<const N: usize>(&self, context: &'static str) -> Result<[u8; N]> {
+        self.data
+            .get(self.pos..self.pos + N)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(TtfError::UnexpectedEof { offset: self.pos, context })
+    }
+
+    fn read_array<const N: usize>(&mut self, context: &'static str) -> Result<[u8; N]> {
+        let bytes = self.peek_array::<N>(context)?;
+        self.pos += N;
+        Ok(bytes)
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64> {
+        self.read_array::<8>("reading u64").map(u64::from_be_bytes)
+    }
+
+    pub fn read_i64(&mut self) -> Result<i64> {
         self.read_u64().map(|b| b as i64)
     }
 
-    pub fn read_fixed(&mut self) -> Result<f32, io::Error> {
+    pub fn read_fixed(&mut self) -> Result<f32> {
         self.read_i32().map(|i| i as f32 / 65536.0)
     }
 
-    pub fn read_f2dot14(&mut self) -> Result<f32, io::Error> {
+    pub fn read_f2dot14(&mut self) -> Result<f32> {
         self.read_i16().map(|i| i as f32 / 16384.0)
     }
 
-    pub fn read_long_datetime(&mut self) -> Result<u64, io::Error> {
+    pub fn read_long_datetime(&mut self) -> Result<u64> {
         // Long datetime is a 64-bit integer representing seconds since 1904
         self.read_u64()
     }
 
-    pub fn read_tag(&mut self) -> Result<[u8; 4], io::Error> {
-        if self.pos + 4 > self.data.len() {
-            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Not enough bytes"));
-        }
-        let mut tag = [0u8; 4];
-        tag.copy_from_slice(&self.data[self.pos..self.pos + 4]);
-        self.pos += 4;
-        Ok(tag)
+    pub fn read_tag(&mut self) -> Result<[u8; 4]> {
+        self.read_array::<4>("reading tag")
     }
 
-    pub fn read_bytes(&mut self, len: usize) -> Result<Vec<u8>, io::Error> {
-        if self.pos + len > self.data.len() {
-            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Not enough bytes"));
-        }
-        let bytes = self.data[self.pos..self.pos + len].to_vec();
-        self.pos += len;
+    pub fn read_bytes(&mut self, len: usize) -> Result<Vec<u8>> {
+        self.take(len, 1, "reading bytes").map(<[u8]>::to_vec)
+    }
+
+    /// Read a Pascal string: a length byte followed by that many Mac Roman
+    /// characters
+    pub fn read_pascal_string(&mut self) -> Result<String> {
+        let len = self.read_u8()? as usize;
+        let bytes = self.take(len, 1, "reading Pascal string")?;
+        Ok(crate::tables::name::decode_mac_roman(bytes))
+    }
+
+    /// Read `count` big-endian u16 values, bounds-checked once up front
+    pub fn read_u16_array(&mut self, count: usize) -> Result<Vec<u16>> {
+        let bytes = self.take(count, 2, "reading u16 array")?;
+        Ok(bytes.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect())
+    }
+
+    /// Read `count` big-endian i16 values, bounds-checked once up front
+    pub fn read_i16_array(&mut self, count: usize) -> Result<Vec<i16>> {
+        let bytes = self.take(count, 2, "reading i16 array")?;
+        Ok(bytes.chunks_exact(2).map(|c| i16::from_be_bytes([c[0], c[1]])).collect())
+    }
+
+    /// Read `count` big-endian u32 values, bounds-checked once up front
+    pub fn read_u32_array(&mut self, count: usize) -> Result<Vec<u32>> {
+        let bytes = self.take(count, 4, "reading u32 array")?;
+        Ok(bytes.chunks_exact(4).map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]])).collect())
+    }
+
+    fn take(&mut self, count: usize, size: usize, context: &'static str) -> Result<&[u8]> {
+        let end = count
+            .checked_mul(size)
+            .and_then(|len| self.pos.checked_add(len))
+            .filter(|&end| end <= self.data.len())
+            .ok_or(TtfError::UnexpectedEof { offset: self.pos, context })?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
         Ok(bytes)
     }
 
-    pub fn read_string(&mut self, len: usize) -> Result<String, io::Error> {
+    pub fn read_string(&mut self, len: usize) -> Result<String> {
         let bytes = self.read_bytes(len)?;
-        String::from_utf8(bytes)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+        String::from_utf8(bytes).map_err(|e| TtfError::InvalidEncoding(e.to_string()))
     }
 }
 
@@ -232,13 +241,17 @@ impl FontWriter {
         self.data.extend_from_slice(&val.to_be_bytes());
     }
 
+    /// Write a 16.16 fixed-point value, rounding to the nearest quantum and
+    /// saturating at the representable range
     pub fn write_fixed(&mut self, val: f32) {
-        let fixed = (val * 65536.0) as i32;
+        let fixed = (val as f64 * 65536.0).round().clamp(i32::MIN as f64, i32::MAX as f64) as i32;
         self.write_i32(fixed);
     }
 
+    /// Write a 2.14 fixed-point value, rounding to the nearest quantum and
+    /// saturating at the representable range (-2.0 to just under 2.0)
     pub fn write_f2dot14(&mut self, val: f32) {
-        let f2dot14 = (val * 16384.0) as i16;
+        let f2dot14 = (val * 16384.0).round().clamp(i16::MIN as f32, i16::MAX as f32) as i16;
         self.write_i16(f2dot14);
     }
 
@@ -260,6 +273,24 @@ impl FontWriter {
         self.data.extend(vec![0u8; padding]);
     }
 
+    /// Overwrite a big-endian u32 at an already-written position
+    pub fn overwrite_u32_at(&mut self, pos: usize, val: u32) -> Result<()> {
+        self.overwrite_bytes_at(pos, &val.to_be_bytes())
+    }
+
+    /// Overwrite bytes at an already-written position.
+    ///
+    /// Fails without writing anything if the bytes would extend past the
+    /// end of the data written so far.
+    pub fn overwrite_bytes_at(&mut self, pos: usize, bytes: &[u8]) -> Result<()> {
+        let target = pos
+            .checked_add(bytes.len())
+            .and_then(|end| self.data.get_mut(pos..end))
+            .ok_or(TtfError::InvalidOffset(pos as u64))?;
+        target.copy_from_slice(bytes);
+        Ok(())
+    }
+
     pub fn pad_to(&mut self, target_len: usize) {
         while self.data.len() < target_len {
             self.data.push(0);
@@ -273,6 +304,65 @@ impl Default for FontWriter {
     }
 }
 
+/// Running TTF checksum over bytes written incrementally
+///
+/// Produces the same value as `calculate_checksum` on the concatenation of
+/// everything written, including zero padding of a trailing partial word,
+/// without keeping the bytes around.
+#[derive(Debug, Clone, Default)]
+pub struct ChecksumWriter {
+    sum: u32,
+    word: u32,
+    len: usize,
+}
+
+impl ChecksumWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of bytes written so far
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.word = (self.word << 8) | byte as u32;
+            self.len += 1;
+            if self.len.is_multiple_of(4) {
+                self.sum = self.sum.wrapping_add(self.word);
+                self.word = 0;
+            }
+        }
+    }
+
+    /// Checksum of the bytes written so far, as if zero-padded to 4 bytes
+    pub fn checksum(&self) -> u32 {
+        let pending = self.len % 4;
+        if pending == 0 {
+            self.sum
+        } else {
+            self.sum.wrapping_add(self.word << (8 * (4 - pending)))
+        }
+    }
+}
+
+impl io::Write for ChecksumWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_bytes(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 /// Calculate TTF checksum
 pub fn calculate_checksum(data: &[u8]) -> u32 {
     let mut sum: u32 = 0;
@@ -1,15 +1,24 @@
 use crate::error::{Result, TtfError};
 use crate::font::Font;
 use crate::stream::FontWriter;
-use crate::tables::glyf::GlyphData;
-use crate::tables::cmap::{CmapSubtable, Format4};
-use std::collections::{HashMap, HashSet};
+use crate::tables::glyf::{GlyfTable, Glyph, GlyphData};
+use crate::tables::loca::LocaTable;
+use crate::tables::cmap::CmapTable;
+use crate::tables::TtfTableWrite;
+use std::collections::{BTreeSet, HashSet};
+use std::ops::RangeInclusive;
 
 /// FontSubset allows creating a subset of a font with only specified glyphs
 pub struct FontSubset {
     font: Font,
     glyph_ids: HashSet<u32>,
+    /// Characters passed to `with_text` that the font has no glyph for
+    unmapped_chars: BTreeSet<char>,
     retain_tables: HashSet<[u8; 4]>,
+    retain_digital_signature: bool,
+    include_notdef: bool,
+    preserve_glyph_ids: bool,
+    ligature_closure: bool,
 }
 
 impl FontSubset {
@@ -18,7 +27,12 @@ impl FontSubset {
         Self {
             font,
             glyph_ids: HashSet::new(),
+            unmapped_chars: BTreeSet::new(),
             retain_tables: HashSet::new(),
+            retain_digital_signature: false,
+            include_notdef: true,
+            preserve_glyph_ids: false,
+            ligature_closure: false,
         }
     }
 
@@ -39,6 +53,123 @@ impl FontSubset {
         Ok(self)
     }
 
+    /// Add every glyph needed to render `text`.
+    ///
+    /// Collects the unique characters of the text, maps them to glyphs and
+    /// pulls in the components referenced by any composite glyphs. Control
+    /// characters such as line breaks are skipped, as are characters the
+    /// font has no glyph for; those are listed by `unmapped_chars()`.
+    ///
+    /// With `ligature_closure(true)`, the ligatures the text's glyphs can
+    /// form through the font's default GSUB ligature features are added as
+    /// well. Other substitutions, such as contextual alternates, are not
+    /// followed and must be passed to `with_glyphs()` if they are needed.
+    pub fn with_text(&mut self, text: &str) -> Result<&mut Self> {
+        let cmap = self.font.cmap_table()?;
+        for c in text.chars().filter(|c| !c.is_control()) {
+            match cmap.map_char(c) {
+                Some(glyph_id) => {
+                    self.glyph_ids.insert(glyph_id);
+                }
+                None => {
+                    self.unmapped_chars.insert(c);
+                }
+            }
+        }
+
+        if self.ligature_closure {
+            self.add_ligatures()?;
+        }
+        self.add_composite_components()?;
+        Ok(self)
+    }
+
+    /// Characters skipped by `with_text()` because the font has no glyph
+    /// for them, in ascending order
+    pub fn unmapped_chars(&self) -> Vec<char> {
+        self.unmapped_chars.iter().copied().collect()
+    }
+
+    /// Add every glyph mapped from a codepoint in any of `ranges`, e.g.
+    /// `0x0400..=0x04FF` for Cyrillic, plus the components of composites.
+    ///
+    /// Codepoints are looked up in the font's preferred cmap subtable.
+    pub fn with_unicode_ranges(&mut self, ranges: &[RangeInclusive<u32>]) -> Result<&mut Self> {
+        let cmap = self.font.cmap_table()?;
+        if let Some(subtable) = cmap.get_best_subtable() {
+            for (code, glyph_id) in subtable.mappings() {
+                if ranges.iter().any(|range| range.contains(&code)) {
+                    self.glyph_ids.insert(glyph_id);
+                }
+            }
+        }
+
+        self.add_composite_components()?;
+        Ok(self)
+    }
+
+    /// Get the glyph IDs currently selected for the subset, in ascending order
+    pub fn glyph_ids(&self) -> Vec<u32> {
+        let mut ids: Vec<u32> = self.glyph_ids.iter().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Add every ligature whose input glyphs are all selected, repeating
+    /// until no more are formed since a ligature can feed another
+    fn add_ligatures(&mut self) -> Result<()> {
+        if !self.font.has_table(b"GSUB") {
+            return Ok(());
+        }
+        let gsub = self.font.gsub_table()?;
+
+        loop {
+            let mut formed = Vec::new();
+            for subtable in gsub.ligature_lookups.iter().flatten() {
+                for &glyph_id in &self.glyph_ids {
+                    let ligatures = u16::try_from(glyph_id)
+                        .ok()
+                        .and_then(|first| subtable.coverage.index(first))
+                        .and_then(|index| subtable.ligature_sets.get(index));
+                    for ligature in ligatures.into_iter().flatten() {
+                        let ligature_id = ligature.ligature_glyph as u32;
+                        if !self.glyph_ids.contains(&ligature_id)
+                            && ligature.components.iter().all(|&c| self.glyph_ids.contains(&(c as u32)))
+                        {
+                            formed.push(ligature_id);
+                        }
+                    }
+                }
+            }
+
+            if formed.is_empty() {
+                return Ok(());
+            }
+            self.glyph_ids.extend(formed);
+        }
+    }
+
+    /// Add the components of every selected composite glyph, recursively
+    fn add_composite_components(&mut self) -> Result<()> {
+        let glyf_table = self.font.glyf_table()?;
+        let mut pending: Vec<u32> = self.glyph_ids.iter().copied().collect();
+
+        while let Some(glyph_id) = pending.pop() {
+            if let Some(GlyphData::Composite(composite)) =
+                glyf_table.get_glyph(glyph_id as usize).map(|g| &g.data)
+            {
+                for component in &composite.components {
+                    let component_id = component.glyph_index as u32;
+                    if self.glyph_ids.insert(component_id) {
+                        pending.push(component_id);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Retain specific tables (all tables are retained by default)
     pub fn retain_tables(&mut self, tables: &[[u8; 4]]) -> &mut Self {
         for &tag in tables {
@@ -47,6 +178,37 @@ impl FontSubset {
         self
     }
 
+    /// Keep the font's DSIG table in the subset (it is dropped by default,
+    /// since subsetting invalidates the signature)
+    pub fn retain_digital_signature(&mut self) -> &mut Self {
+        self.retain_digital_signature = true;
+        self
+    }
+
+    /// Whether to always keep .notdef as glyph 0 (enabled by default).
+    ///
+    /// When disabled, glyph 0 is only kept if it was selected explicitly.
+    pub fn include_notdef(&mut self, include: bool) -> &mut Self {
+        self.include_notdef = include;
+        self
+    }
+
+    /// Whether `with_text()` also adds the ligatures its glyphs form through
+    /// GSUB (disabled by default). Set it before calling `with_text()`.
+    pub fn ligature_closure(&mut self, enabled: bool) -> &mut Self {
+        self.ligature_closure = enabled;
+        self
+    }
+
+    /// Whether to keep every glyph at its original ID (disabled by default).
+    ///
+    /// When enabled, num_glyphs is unchanged and dropped glyphs are written
+    /// as empty glyphs instead of the remaining glyphs being renumbered.
+    pub fn preserve_glyph_ids(&mut self, preserve: bool) -> &mut Self {
+        self.preserve_glyph_ids = preserve;
+        self
+    }
+
     /// Build the subset font
     pub fn build(&self) -> Result<Font> {
         if self.glyph_ids.is_empty() {
@@ -55,29 +217,47 @@ impl FontSubset {
 
         let mut subset_font = self.font.clone();
 
-        // Create glyph ID mapping (old -> new)
-        let mut glyph_map: HashMap<u32, u32> = HashMap::new();
+        // Old glyph IDs in new ID order; .notdef goes first so the
+        // remaining glyphs shift up
         let mut sorted_glyphs: Vec<u32> = self.glyph_ids.iter().cloned().collect();
         sorted_glyphs.sort();
-
-        for (new_id, old_id) in sorted_glyphs.iter().enumerate() {
-            glyph_map.insert(*old_id, new_id as u32);
-        }
-
-        // Always include glyph 0 (.notdef)
-        if !self.glyph_ids.contains(&0) {
-            glyph_map.insert(0, 0);
+        if self.include_notdef && sorted_glyphs.first() != Some(&0) {
             sorted_glyphs.insert(0, 0);
         }
 
+        // (old, new) glyph ID pairs of the retained glyphs, sorted by both
+        // IDs. Every table is written by walking this one list so the output
+        // is reproducible
+        let glyph_order: Vec<(u32, u32)> = if self.preserve_glyph_ids {
+            sorted_glyphs.iter().map(|&old_id| (old_id, old_id)).collect()
+        } else {
+            sorted_glyphs
+                .iter()
+                .enumerate()
+                .map(|(new_id, &old_id)| (old_id, new_id as u32))
+                .collect()
+        };
+        let num_glyphs = if self.preserve_glyph_ids {
+            self.font.num_glyphs()? as usize
+        } else {
+            glyph_order.len()
+        };
+
         // Create subset of tables
-        self.subset_glyf_table(&mut subset_font, &glyph_map)?;
-        self.subset_loca_table(&mut subset_font, &glyph_map)?;
-        self.subset_hmtx_table(&mut subset_font, &glyph_map)?;
-        self.subset_cmap_table(&mut subset_font, &glyph_map)?;
+        let loca_offsets = self.subset_glyf_table(&mut subset_font, &glyph_order, num_glyphs)?;
+        self.subset_loca_table(&mut subset_font, &loca_offsets)?;
+        self.subset_hmtx_table(&mut subset_font, &glyph_order, num_glyphs)?;
+        self.subset_cmap_table(&mut subset_font, &glyph_order)?;
 
         // Update maxp table
-        self.update_maxp_table(&mut subset_font)?;
+        self.update_maxp_table(&mut subset_font, num_glyphs as u16)?;
+
+        // The signature covered the original tables
+        if !self.retain_digital_signature && subset_font.has_digital_signature() {
+            let mut modifier = subset_font.modify();
+            modifier.remove_table(b"DSIG");
+            subset_font = modifier.commit()?;
+        }
 
         // Remove unused tables if specified
         if !self.retain_tables.is_empty() {
@@ -87,403 +267,118 @@ impl FontSubset {
         Ok(subset_font)
     }
 
-    /// Subset the glyf table
-    fn subset_glyf_table(&self, font: &mut Font, glyph_map: &HashMap<u32, u32>) -> Result<()> {
+    /// Subset the glyf table, returning the loca offsets of the new glyph data
+    ///
+    /// New glyph IDs without a retained glyph are written as empty glyphs.
+    fn subset_glyf_table(&self, font: &mut Font, glyph_order: &[(u32, u32)], num_glyphs: usize) -> Result<Vec<u32>> {
         let glyf_table = self.font.glyf_table()?;
-        let mut new_glyphs = Vec::new();
-
-        for (old_id, new_id) in glyph_map {
-            if let Some(glyph) = glyf_table.get_glyph(*old_id as usize) {
-                new_glyphs.push((*new_id as usize, glyph.clone()));
-            }
-        }
-
-        // Sort by new glyph ID
-        new_glyphs.sort_by_key(|(id, _)| *id);
-
-        // Create new glyf data
-        let mut writer = FontWriter::new();
-
-        for (_, glyph) in &new_glyphs {
-            match &glyph.data {
-                GlyphData::Simple(simple) => {
-                    writer.write_i16(glyph.number_of_contours);
-                    writer.write_i16(glyph.x_min);
-                    writer.write_i16(glyph.y_min);
-                    writer.write_i16(glyph.x_max);
-                    writer.write_i16(glyph.y_max);
-
-                    for &end_pt in &simple.end_pts_of_contours {
-                        writer.write_u16(end_pt);
-                    }
-
-                    writer.write_u16(simple.instruction_length);
-                    for &instr in &simple.instructions {
-                        writer.write_u8(instr);
-                    }
-
-                    for &flag in &simple.flags {
-                        writer.write_u8(flag);
-                    }
-
-                    for &x in &simple.x_coordinates {
-                        writer.write_i16(x);
-                    }
-
-                    for &y in &simple.y_coordinates {
-                        writer.write_i16(y);
-                    }
-                }
-                GlyphData::Composite(composite) => {
-                    writer.write_i16(glyph.number_of_contours);
-                    writer.write_i16(glyph.x_min);
-                    writer.write_i16(glyph.y_min);
-                    writer.write_i16(glyph.x_max);
-                    writer.write_i16(glyph.y_max);
-
-                    for component in &composite.components {
-                        writer.write_u16(component.flags);
-                        let new_glyph_id = glyph_map.get(&(component.glyph_index as u32))
-                            .copied()
-                            .unwrap_or(component.glyph_index as u32);
-                        writer.write_u16(new_glyph_id as u16);
-                        writer.write_i16(component.arg1);
-                        writer.write_i16(component.arg2);
+        let mut new_glyphs = Vec::with_capacity(num_glyphs);
+
+        for &(old_id, new_id) in glyph_order {
+            let mut glyph = glyf_table
+                .get_glyph(old_id as usize)
+                .cloned()
+                .ok_or(TtfError::InvalidGlyphIndex(old_id as u16))?;
+            if let GlyphData::Composite(composite) = &mut glyph.data {
+                for component in &mut composite.components {
+                    if let Some(new_glyph_id) = new_glyph_id(glyph_order, component.glyph_index as u32) {
+                        component.glyph_index = new_glyph_id as u16;
                     }
                 }
-                GlyphData::Empty => {
-                    writer.write_i16(0);
-                    writer.write_i16(0);
-                    writer.write_i16(0);
-                    writer.write_i16(0);
-                    writer.write_i16(0);
-                }
             }
+            new_glyphs.resize(new_id as usize, Glyph::empty());
+            new_glyphs.push(glyph);
         }
+        new_glyphs.resize(num_glyphs, Glyph::empty());
 
-        // Update font data (simplified)
-        if let Some(record) = font.get_table_record(b"glyf") {
-            let data = writer.into_inner();
-            let offset = record.offset as usize;
-            if offset + data.len() <= font.data.len() {
-                font.data[offset..offset + data.len()].copy_from_slice(&data);
-                if let Some(record) = font.table_records.iter_mut().find(|r| r.table_tag == *b"glyf") {
-                    record.length = data.len() as u32;
-                }
-            }
-        }
+        let subset_glyf = GlyfTable { glyphs: new_glyphs };
+        let (data, offsets) = subset_glyf.to_bytes();
 
-        Ok(())
+        font.replace_table_data(b"glyf", data);
+
+        Ok(offsets)
     }
 
     /// Subset the loca table
-    fn subset_loca_table(&self, font: &mut Font, glyph_map: &HashMap<u32, u32>) -> Result<()> {
+    fn subset_loca_table(&self, font: &mut Font, offsets: &[u32]) -> Result<()> {
         let head = self.font.head_table()?;
-        let num_glyphs = glyph_map.len();
-
-        let mut offsets = Vec::new();
-        let mut current_offset = 0u32;
-
-        for i in 0..num_glyphs {
-            offsets.push(current_offset);
-
-            // Get glyph size (simplified - in practice you'd calculate this from glyf data)
-            if let Some(glyph) = self.font.glyf_table()?.get_glyph(i) {
-                let size = match &glyph.data {
-                    GlyphData::Simple(simple) => {
-                        10 + (simple.end_pts_of_contours.len() * 2) as u32 +
-                        simple.instruction_length as u32 +
-                        simple.flags.len() as u32 +
-                        (simple.x_coordinates.len() * 2) as u32 +
-                        (simple.y_coordinates.len() * 2) as u32
-                    }
-                    GlyphData::Composite(composite) => {
-                        10 + (composite.components.len() * 8) as u32
-                    }
-                    GlyphData::Empty => 10,
-                };
-                current_offset += size;
-            }
-        }
 
-        let mut writer = FontWriter::new();
-        if head.is_long_loca_format() {
-            for &offset in &offsets {
-                writer.write_u32(offset);
-            }
-        } else {
-            for &offset in &offsets {
-                writer.write_u16((offset / 2) as u16);
-            }
-        }
+        let data = LocaTable::Long(offsets.to_vec()).to_bytes(head.is_long_loca_format());
 
-        // Update font data (simplified)
-        if let Some(record) = font.get_table_record(b"loca") {
-            let data = writer.into_inner();
-            let offset = record.offset as usize;
-            if offset + data.len() <= font.data.len() {
-                font.data[offset..offset + data.len()].copy_from_slice(&data);
-                if let Some(record) = font.table_records.iter_mut().find(|r| r.table_tag == *b"loca") {
-                    record.length = data.len() as u32;
-                }
-            }
-        }
+        font.replace_table_data(b"loca", data);
 
         Ok(())
     }
 
     /// Subset the hmtx table
-    fn subset_hmtx_table(&self, font: &mut Font, glyph_map: &HashMap<u32, u32>) -> Result<()> {
+    ///
+    /// New glyph IDs without a retained glyph keep the metrics of the
+    /// original glyph with that ID.
+    fn subset_hmtx_table(&self, font: &mut Font, glyph_order: &[(u32, u32)], num_glyphs: usize) -> Result<()> {
         let hmtx = self.font.hmtx_table()?;
-        let _hhea = self.font.hhea_table()?;
-
         let mut writer = FontWriter::new();
 
-        for i in 0..glyph_map.len() {
-            let advance = hmtx.get_advance_width(i as u16);
-            let lsb = hmtx.get_lsb(i as u16);
-
-            writer.write_u16(advance);
-            writer.write_i16(lsb);
+        let mut retained = glyph_order.iter().peekable();
+        for new_id in 0..num_glyphs as u32 {
+            let old_id = match retained.next_if(|&&(_, id)| id == new_id) {
+                Some(&(old_id, _)) => old_id,
+                None => new_id,
+            };
+            writer.write_u16(hmtx.get_advance_width(old_id as u16));
+            writer.write_i16(hmtx.get_lsb(old_id as u16));
         }
 
-        // Update font data (simplified)
-        if let Some(record) = font.get_table_record(b"hmtx") {
-            let data = writer.into_inner();
-            let offset = record.offset as usize;
-            if offset + data.len() <= font.data.len() {
-                font.data[offset..offset + data.len()].copy_from_slice(&data);
-                if let Some(record) = font.table_records.iter_mut().find(|r| r.table_tag == *b"hmtx") {
-                    record.length = data.len() as u32;
-                }
-            }
+        if !font.has_table(b"hmtx") {
+            return Ok(());
         }
 
+        // Every glyph gets a full metric, which can outgrow an hmtx whose
+        // trailing glyphs only stored left side bearings
+        font.replace_table_data(b"hmtx", writer.into_inner());
+
+        // so hhea.numberOfHMetrics (offset 34) must match the glyph count
+        let mut hhea = font
+            .get_table_data(b"hhea")
+            .filter(|data| data.len() >= 36)
+            .ok_or_else(|| TtfError::MissingTable("hhea".to_string()))?;
+        hhea[34..36].copy_from_slice(&(num_glyphs as u16).to_be_bytes());
+        font.replace_table_data(b"hhea", hhea);
+
         Ok(())
     }
 
     /// Subset the cmap table
-    fn subset_cmap_table(&self, font: &mut Font, glyph_map: &HashMap<u32, u32>) -> Result<()> {
+    ///
+    /// Mappings of every Unicode subtable are merged and remapped to the new
+    /// glyph IDs, then written as a Format 4 subtable, plus a Format 12 one
+    /// when code points beyond the BMP remain.
+    fn subset_cmap_table(&self, font: &mut Font, glyph_order: &[(u32, u32)]) -> Result<()> {
         let cmap = self.font.cmap_table()?;
 
-        // Build character to new glyph mapping for all characters in the original cmap
         let mut char_to_new_glyph: Vec<(u32, u32)> = Vec::new();
-
-        // Iterate through all possible characters and find mappings
-        for subtable in &cmap.subtables {
-            match subtable {
-                CmapSubtable::Format4(format4) => {
-                    // For Format 4, collect all character mappings
-                    for seg in 0..format4.seg_count() as usize {
-                        let start_code = format4.start_codes[seg];
-                        let end_code = format4.end_codes[seg];
-                        let id_delta = format4.id_deltas[seg];
-
-                        for char_code in start_code..=end_code {
-                            let old_glyph = if format4.id_range_offsets[seg] == 0 {
-                                ((char_code as i32 + id_delta as i32) as u16)
-                            } else {
-                                // For complex cases, use the lookup
-                                if let Some(g) = format4.get_glyph(char_code) {
-                                    g
-                                } else {
-                                    continue;
-                                }
-                            };
-
-                            // Check if this glyph is in our subset
-                            if let Some(&new_glyph) = glyph_map.get(&(old_glyph as u32)) {
-                                char_to_new_glyph.push((char_code as u32, new_glyph));
-                            }
-                        }
-                    }
-                }
-                CmapSubtable::Format6(format6) => {
-                    for (i, &old_glyph) in format6.glyph_id_array.iter().enumerate() {
-                        let char_code = format6.first_code as u32 + i as u32;
-                        if let Some(&new_glyph) = glyph_map.get(&(old_glyph as u32)) {
-                            char_to_new_glyph.push((char_code, new_glyph));
-                        }
-                    }
-                }
-                CmapSubtable::Format12(format12) => {
-                    for group in &format12.groups {
-                        for char_code in group.start_char_code..=group.end_char_code {
-                            let old_glyph = group.start_glyph_code + (char_code - group.start_char_code);
-                            if let Some(&new_glyph) = glyph_map.get(&old_glyph) {
-                                char_to_new_glyph.push((char_code, new_glyph));
-                            }
-                        }
-                    }
-                }
-                CmapSubtable::Format13(format13) => {
-                    for group in &format13.groups {
-                        for char_code in group.start_char_code..=group.end_char_code {
-                            if let Some(&new_glyph) = glyph_map.get(&group.glyph_code) {
-                                char_to_new_glyph.push((char_code, new_glyph));
-                            }
-                        }
-                    }
-                }
-                _ => {
-                    // Format 0 and Format 14 are less common, skip for now
-                }
+        for (record, subtable) in cmap.encoding_records.iter().zip(&cmap.subtables) {
+            let is_unicode = record.platform_id == 0
+                || (record.platform_id == 3 && matches!(record.encoding_id, 1 | 10));
+            if !is_unicode {
+                continue;
             }
-        }
-
-        // Remove duplicates and sort
-        char_to_new_glyph.sort();
-        char_to_new_glyph.dedup();
-
-        // Build a new Format 4 subtable with the subset
-        if !char_to_new_glyph.is_empty() {
-            let new_format4 = self.build_format4_subset(&char_to_new_glyph)?;
-            self.write_cmap_subset(font, &new_format4)?;
-        }
 
-        Ok(())
-    }
-
-    /// Build a Format 4 subtable from character mappings
-    fn build_format4_subset(&self, mappings: &[(u32, u32)]) -> Result<Format4> {
-        if mappings.is_empty() {
-            return Err(TtfError::ParseError("No mappings for subset".to_string()));
-        }
-
-        // Build segments from continuous character ranges with sequential glyphs
-        let mut segments: Vec<(u16, u16, i16)> = Vec::new(); // (start, end, id_delta)
-
-        let mut current_start = mappings[0].0 as u16;
-        let mut current_end = current_start;
-        let mut current_glyph_delta = (mappings[0].1 as i32 - mappings[0].0 as i32) as i16;
-
-        for &(char_code, new_glyph) in mappings.iter().skip(1) {
-            let char_code = char_code as u16;
-            let expected_glyph = (char_code as i32 + current_glyph_delta as i32) as u32;
-
-            if char_code == current_end + 1 && new_glyph == expected_glyph {
-                // Continue current segment
-                current_end = char_code;
-            } else {
-                // Start new segment
-                segments.push((current_start, current_end, current_glyph_delta));
-                current_start = char_code;
-                current_end = char_code;
-                current_glyph_delta = (new_glyph as i32 - char_code as i32) as i16;
+            for (char_code, old_glyph) in subtable.mappings() {
+                if let Some(new_glyph) = new_glyph_id(glyph_order, old_glyph) {
+                    char_to_new_glyph.push((char_code, new_glyph));
+                }
             }
         }
 
-        // Add last segment
-        segments.push((current_start, current_end, current_glyph_delta));
-
-        // Add the final sentinel segment (0xFFFF, 0xFFFF, 1)
-        segments.push((0xFFFF, 0xFFFF, 1));
-
-        // Extract arrays from segments
-        let seg_count = segments.len() as u16;
-        let seg_count_x2 = seg_count * 2;
-        let end_codes: Vec<u16> = segments.iter().map(|(_, end, _)| *end).collect();
-        let start_codes: Vec<u16> = segments.iter().map(|(start, _, _)| *start).collect();
-        let id_deltas: Vec<i16> = segments.iter().map(|(_, _, delta)| *delta).collect();
-
-        // Calculate search range, entry selector, range_shift
-        let mut search_range = 1u16;
-        let mut entry_selector = 0u16;
-        while search_range * 2 <= seg_count_x2 {
-            search_range *= 2;
-            entry_selector += 1;
-        }
-        let range_shift = seg_count_x2 - search_range;
-
-        // For this simplified implementation, id_range_offsets are all 0
-        let id_range_offsets = vec![0u16; seg_count as usize];
-        let glyph_id_array = Vec::new(); // Empty since we use id_delta
-
-        let length = 2 + 2 + 2 + 2 + 2 + 2 + 2 // header
-            + (seg_count as u16 * 2) * 4 // end_codes, start_codes, id_deltas, id_range_offsets
-            + 2 // reserved
-            + (glyph_id_array.len() as u16 * 2);
-
-        Ok(Format4 {
-            format: 4,
-            length,
-            language: 0,
-            seg_count_x2,
-            search_range,
-            entry_selector,
-            range_shift,
-            end_codes,
-            start_codes,
-            id_deltas,
-            id_range_offsets,
-            glyph_id_array,
-        })
-    }
-
-    /// Write the subset cmap back to the font
-    fn write_cmap_subset(&self, font: &mut Font, format4: &Format4) -> Result<()> {
         let mut writer = FontWriter::new();
-
-        // cmap header
-        writer.write_u16(0); // version
-        writer.write_u16(1); // num_tables (just one subtable)
-
-        // Encoding record (Unicode BMP)
-        writer.write_u16(3); // platform_id (Windows)
-        writer.write_u16(1); // encoding_id (Unicode BMP)
-        writer.write_u32(12); // offset (after header + encoding record)
-
-        // Format 4 subtable
-        writer.write_u16(format4.format);
-        writer.write_u16(format4.length);
-        writer.write_u16(format4.language);
-        writer.write_u16(format4.seg_count_x2);
-        writer.write_u16(format4.search_range);
-        writer.write_u16(format4.entry_selector);
-        writer.write_u16(format4.range_shift);
-
-        for &end_code in &format4.end_codes {
-            writer.write_u16(end_code);
-        }
-
-        writer.write_u16(0); // reservedPad
-
-        for &start_code in &format4.start_codes {
-            writer.write_u16(start_code);
-        }
-
-        for &id_delta in &format4.id_deltas {
-            writer.write_i16(id_delta);
-        }
-
-        for &id_range_offset in &format4.id_range_offsets {
-            writer.write_u16(id_range_offset);
-        }
-
-        for &glyph_id in &format4.glyph_id_array {
-            writer.write_u16(glyph_id);
-        }
-
-        // Update font data
-        if let Some(record) = font.get_table_record(b"cmap") {
-            let data = writer.into_inner();
-            let offset = record.offset as usize;
-            if offset + data.len() <= font.data.len() {
-                font.data[offset..offset + data.len()].copy_from_slice(&data);
-                if let Some(record) = font.table_records.iter_mut().find(|r| r.table_tag == *b"cmap") {
-                    record.length = data.len() as u32;
-                }
-            }
-        }
+        CmapTable::from_mappings(&char_to_new_glyph)?.write(&mut writer)?;
+        font.replace_table_data(b"cmap", writer.into_inner());
 
         Ok(())
     }
 
     /// Update the maxp table with new glyph count
-    fn update_maxp_table(&self, font: &mut Font) -> Result<()> {
-        // Update num_glyphs in maxp table
-        let new_num_glyphs = self.glyph_ids.len() as u16;
-
+    fn update_maxp_table(&self, font: &mut Font, new_num_glyphs: u16) -> Result<()> {
         // Update font data (simplified)
         if let Some(record) = font.get_table_record(b"maxp") {
             let offset = record.offset as usize;
@@ -499,6 +394,14 @@ impl FontSubset {
     }
 }
 
+/// Look up the new ID of a retained glyph in (old, new) pairs sorted by old ID
+fn new_glyph_id(glyph_order: &[(u32, u32)], old_id: u32) -> Option<u32> {
+    glyph_order
+        .binary_search_by_key(&old_id, |&(old, _)| old)
+        .ok()
+        .map(|index| glyph_order[index].1)
+}
+
 impl Font {
     /// Create a subset of this font
     pub fn subset(self) -> FontSubset {
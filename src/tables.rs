@@ -6,6 +6,8 @@ pub mod cmap;
 pub mod name;
 pub mod hhea;
 pub mod hmtx;
+pub mod vhea;
+pub mod vmtx;
 pub mod glyf;
 pub mod loca;
 pub mod post;
@@ -13,6 +15,7 @@ pub mod os2;
 pub mod kern;
 pub mod gpos;
 pub mod gsub;
+pub mod layout;
 pub mod base;
 pub mod jstf;
 pub mod fpgm;
@@ -27,7 +30,12 @@ pub mod colr;
 pub mod cpal;
 pub mod svg;
 pub mod cbdt;
+pub mod ebdt;
 pub mod sbix;
+pub mod vdmx;
+pub mod ltsh;
+pub mod meta;
+pub mod cff;
 
 use crate::error::Result;
 use crate::stream::{FontReader, FontWriter};
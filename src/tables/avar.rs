@@ -1,55 +1,101 @@
-use crate::error::Result;
+use crate::error::{Result, TtfError};
 use crate::stream::FontReader;
 use crate::tables::TtfTable;
 
 /// AVAR table - Axis variations
 #[derive(Debug, Clone)]
 pub struct AvarTable {
-    pub version: u16,
-    pub reserved: u16,
-    pub axis_segment_maps: Vec<AxisSegmentMap>,
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub axis_segment_maps: Vec<SegmentMap>,
 }
 
+/// Piecewise-linear mapping of normalized coordinates for one axis
 #[derive(Debug, Clone)]
-pub struct AxisSegmentMap {
-    pub from_axis_count: u16,
-    pub from_coordinate_array: Vec<f32>,
-    pub to_axis_count: u16,
-    pub to_coordinate_array: Vec<f32>,
+pub struct SegmentMap {
+    pub axis_value_maps: Vec<AxisValueMap>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisValueMap {
+    pub from_coordinate: f32,
+    pub to_coordinate: f32,
+}
+
+impl SegmentMap {
+    /// Remap a normalized coordinate through this segment map
+    pub fn map(&self, normalized: f32) -> f32 {
+        let maps = &self.axis_value_maps;
+        let (first, last) = match (maps.first(), maps.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return normalized,
+        };
+
+        if normalized <= first.from_coordinate {
+            return first.to_coordinate;
+        }
+        if normalized >= last.from_coordinate {
+            return last.to_coordinate;
+        }
+
+        for pair in maps.windows(2) {
+            let (low, high) = (pair[0], pair[1]);
+            if normalized <= high.from_coordinate {
+                if high.from_coordinate == low.from_coordinate {
+                    return low.to_coordinate;
+                }
+                let t = (normalized - low.from_coordinate) / (high.from_coordinate - low.from_coordinate);
+                return low.to_coordinate + t * (high.to_coordinate - low.to_coordinate);
+            }
+        }
+
+        normalized
+    }
+}
+
+impl AvarTable {
+    /// Remap a normalized coordinate of an axis
+    ///
+    /// Axes without a segment map are passed through unchanged.
+    pub fn map_coords(&self, axis: usize, normalized: f32) -> f32 {
+        match self.axis_segment_maps.get(axis) {
+            Some(segment_map) => segment_map.map(normalized),
+            None => normalized,
+        }
+    }
 }
 
 impl TtfTable for AvarTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"avar")
+    }
+
     fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
-        let version = reader.read_u16()?;
-        let reserved = reader.read_u16()?;
-        let axis_count = reader.read_u16()?;
+        let major_version = reader.read_u16()?;
+        let minor_version = reader.read_u16()?;
+        if major_version != 1 {
+            return Err(TtfError::UnsupportedVersion(major_version as u32));
+        }
 
-        let mut axis_segment_maps = Vec::new();
+        reader.skip(2)?; // reserved
+        let axis_count = reader.read_u16()?;
 
+        let mut axis_segment_maps = Vec::with_capacity(axis_count as usize);
         for _ in 0..axis_count {
-            let from_axis_count = reader.read_u16()?;
-            let mut from_coordinate_array = Vec::new();
-            for _ in 0..from_axis_count {
-                from_coordinate_array.push(reader.read_fixed()?);
+            let position_map_count = reader.read_u16()?;
+            let mut axis_value_maps = Vec::with_capacity(position_map_count as usize);
+            for _ in 0..position_map_count {
+                axis_value_maps.push(AxisValueMap {
+                    from_coordinate: reader.read_f2dot14()?,
+                    to_coordinate: reader.read_f2dot14()?,
+                });
             }
-
-            let to_axis_count = reader.read_u16()?;
-            let mut to_coordinate_array = Vec::new();
-            for _ in 0..to_axis_count {
-                to_coordinate_array.push(reader.read_fixed()?);
-            }
-
-            axis_segment_maps.push(AxisSegmentMap {
-                from_axis_count,
-                from_coordinate_array,
-                to_axis_count,
-                to_coordinate_array,
-            });
+            axis_segment_maps.push(SegmentMap { axis_value_maps });
         }
 
         Ok(AvarTable {
-            version,
-            reserved,
+            major_version,
+            minor_version,
             axis_segment_maps,
         })
     }
@@ -11,6 +11,10 @@ pub struct BaseTable {
 }
 
 impl TtfTable for BaseTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"BASE")
+    }
+
     fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
         let version = reader.read_u32()?;
         let horiz_axis_offset = reader.read_u32()?;
@@ -1,61 +1,340 @@
-use crate::error::Result;
+use crate::error::{Result, TtfError};
 use crate::stream::FontReader;
 use crate::tables::TtfTable;
 
 /// CBDT table - Color bitmap data
+///
+/// The table is kept as raw bytes; glyph images are located through CBLC.
 #[derive(Debug, Clone)]
-pub struct CbdTTable {
-    pub version: u32,
-    pub num_glyphs: u32,
+pub struct CbdtTable {
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub data: Vec<u8>,
+}
+
+/// Encoding of an embedded color bitmap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorBitmapFormat {
+    /// PNG image (CBDT formats 17, 18 and 19)
+    Png,
+    /// Any other image format, returned as raw glyph data
+    Other(u16),
+}
+
+/// An embedded color bitmap
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorBitmap {
+    pub format: ColorBitmapFormat,
+    pub data: Vec<u8>,
+}
+
+impl CbdtTable {
+    /// Extract the image stored at `offset..offset + length` in the given
+    /// CBDT image format
+    pub fn bitmap(&self, image_format: u16, offset: u32, length: u32) -> Result<ColorBitmap> {
+        let start = offset as usize;
+        let end = start + length as usize;
+        let glyph_data = self
+            .data
+            .get(start..end)
+            .ok_or(TtfError::InvalidOffset(end as u64))?;
+
+        // PNG formats prefix the image with metrics and a data length
+        let metrics_size = match image_format {
+            17 => 5, // small glyph metrics
+            18 => 8, // big glyph metrics
+            19 => 0, // metrics live in CBLC
+            _ => {
+                return Ok(ColorBitmap {
+                    format: ColorBitmapFormat::Other(image_format),
+                    data: glyph_data.to_vec(),
+                });
+            }
+        };
+
+        let mut reader = FontReader::from_slice(glyph_data);
+        reader.skip(metrics_size)?;
+        let data_len = reader.read_u32()?;
+
+        Ok(ColorBitmap {
+            format: ColorBitmapFormat::Png,
+            data: reader.read_bytes(data_len as usize)?,
+        })
+    }
+}
+
+impl TtfTable for CbdtTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"CBDT")
+    }
+
+    fn from_reader(reader: &mut FontReader, length: u32) -> Result<Self> {
+        let major_version = reader.read_u16()?;
+        let minor_version = reader.read_u16()?;
+
+        // Image offsets in CBLC are relative to the start of the table
+        reader.set_position(0)?;
+        let data = reader.read_bytes(length as usize)?;
+
+        Ok(CbdtTable {
+            major_version,
+            minor_version,
+            data,
+        })
+    }
+}
+
+/// CBLC table - Color bitmap location
+#[derive(Debug, Clone)]
+pub struct CblcTable {
+    pub major_version: u16,
+    pub minor_version: u16,
     pub bitmap_sizes: Vec<BitmapSize>,
 }
 
+/// A strike: the bitmaps available at one size
 #[derive(Debug, Clone)]
 pub struct BitmapSize {
-    pub index: u32,
-    pub ppem_x: u16,
-    pub ppem_y: u16,
+    pub start_glyph_index: u16,
+    pub end_glyph_index: u16,
+    pub ppem_x: u8,
+    pub ppem_y: u8,
     pub bit_depth: u8,
     pub flags: u8,
+    pub index_subtables: Vec<IndexSubtable>,
 }
 
-impl TtfTable for CbdTTable {
-    fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
-        let version = reader.read_u32()?;
-        let num_glyphs = reader.read_u32()?;
+/// Locations of the images of a glyph range within CBDT
+#[derive(Debug, Clone)]
+pub struct IndexSubtable {
+    pub first_glyph_index: u16,
+    pub last_glyph_index: u16,
+    pub index_format: u16,
+    pub image_format: u16,
+    /// Metrics shared by every glyph (index formats 2 and 5)
+    pub metrics: Option<BigGlyphMetrics>,
+    /// (glyph id, CBDT offset, length) of every glyph with an image
+    pub glyph_locations: Vec<(u16, u32, u32)>,
+}
 
-        let bitmap_sizes = Vec::new();
+/// Big glyph metrics, covering horizontal and vertical layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BigGlyphMetrics {
+    pub height: u8,
+    pub width: u8,
+    pub hori_bearing_x: i8,
+    pub hori_bearing_y: i8,
+    pub hori_advance: u8,
+    pub vert_bearing_x: i8,
+    pub vert_bearing_y: i8,
+    pub vert_advance: u8,
+}
 
-        Ok(CbdTTable {
-            version,
-            num_glyphs,
-            bitmap_sizes,
+impl BigGlyphMetrics {
+    pub(crate) fn from_reader(reader: &mut FontReader) -> Result<Self> {
+        Ok(BigGlyphMetrics {
+            height: reader.read_u8()?,
+            width: reader.read_u8()?,
+            hori_bearing_x: reader.read_i8()?,
+            hori_bearing_y: reader.read_i8()?,
+            hori_advance: reader.read_u8()?,
+            vert_bearing_x: reader.read_i8()?,
+            vert_bearing_y: reader.read_i8()?,
+            vert_advance: reader.read_u8()?,
         })
     }
 }
 
-/// CBLC table - Color bitmap location
-#[derive(Debug, Clone)]
-pub struct CblcTable {
-    pub version: u32,
-    pub num_sizes: u32,
-    pub bitmap_tables: Vec<u32>,
+impl BitmapSize {
+    /// Find the image format and CBDT location of a glyph
+    pub fn glyph_location(&self, glyph_id: u16) -> Option<(u16, u32, u32)> {
+        self.glyph_subtable(glyph_id)
+            .map(|(subtable, offset, length)| (subtable.image_format, offset, length))
+    }
+
+    /// Find the index subtable holding a glyph, with the glyph's offset and
+    /// length in the data table
+    pub fn glyph_subtable(&self, glyph_id: u16) -> Option<(&IndexSubtable, u32, u32)> {
+        self.index_subtables
+            .iter()
+            .filter(|subtable| (subtable.first_glyph_index..=subtable.last_glyph_index).contains(&glyph_id))
+            .find_map(|subtable| {
+                subtable
+                    .glyph_locations
+                    .iter()
+                    .find(|(id, _, _)| *id == glyph_id)
+                    .map(|&(_, offset, length)| (subtable, offset, length))
+            })
+    }
+}
+
+impl CblcTable {
+    /// Pick the strike to use for a size: an exact match, else the smallest
+    /// larger strike (to scale down), else the largest available
+    pub fn best_size(&self, ppem: u16) -> Option<&BitmapSize> {
+        self.bitmap_sizes
+            .iter()
+            .filter(|size| size.ppem_y as u16 >= ppem)
+            .min_by_key(|size| size.ppem_y)
+            .or_else(|| self.bitmap_sizes.iter().max_by_key(|size| size.ppem_y))
+    }
 }
 
 impl TtfTable for CblcTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"CBLC")
+    }
+
     fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
-        let version = reader.read_u32()?;
+        let major_version = reader.read_u16()?;
+        let minor_version = reader.read_u16()?;
         let num_sizes = reader.read_u32()?;
 
-        let mut bitmap_tables = Vec::new();
+        // Each BitmapSize record takes 48 bytes, so the count can't outrun the data
+        let mut headers = Vec::with_capacity((num_sizes as usize).min(reader.remaining() / 48));
         for _ in 0..num_sizes {
-            bitmap_tables.push(reader.read_u32()?);
+            let index_subtable_array_offset = reader.read_u32()? as usize;
+            let _index_tables_size = reader.read_u32()?;
+            let number_of_index_subtables = reader.read_u32()?;
+            let _color_ref = reader.read_u32()?;
+            reader.skip(24)?; // horizontal and vertical line metrics
+            let start_glyph_index = reader.read_u16()?;
+            let end_glyph_index = reader.read_u16()?;
+            let ppem_x = reader.read_u8()?;
+            let ppem_y = reader.read_u8()?;
+            let bit_depth = reader.read_u8()?;
+            let flags = reader.read_u8()?;
+
+            headers.push((
+                index_subtable_array_offset,
+                number_of_index_subtables,
+                BitmapSize {
+                    start_glyph_index,
+                    end_glyph_index,
+                    ppem_x,
+                    ppem_y,
+                    bit_depth,
+                    flags,
+                    index_subtables: Vec::new(),
+                },
+            ));
+        }
+
+        let mut bitmap_sizes = Vec::with_capacity(headers.len());
+        for (array_offset, count, mut size) in headers {
+            for i in 0..count as usize {
+                reader.set_position(array_offset + i * 8)?;
+                let first_glyph_index = reader.read_u16()?;
+                let last_glyph_index = reader.read_u16()?;
+                let additional_offset = reader.read_u32()? as usize;
+
+                reader.set_position(array_offset + additional_offset)?;
+                size.index_subtables.push(read_index_subtable(reader, first_glyph_index, last_glyph_index)?);
+            }
+            bitmap_sizes.push(size);
         }
 
         Ok(CblcTable {
-            version,
-            num_sizes,
-            bitmap_tables,
+            major_version,
+            minor_version,
+            bitmap_sizes,
         })
     }
 }
+
+/// Start and end of the `index`th image in a run of `image_size` byte
+/// images (index formats 2 and 5), or `TtfError::InvalidOffset` if they
+/// don't fit in 32 bits
+fn fixed_size_image(index: u32, image_size: u32) -> Result<(u32, u32)> {
+    let end = (index + 1)
+        .checked_mul(image_size)
+        .ok_or(TtfError::InvalidOffset((index as u64 + 1) * image_size as u64))?;
+    Ok((end - image_size, end))
+}
+
+/// Read an index subtable at the reader's position
+fn read_index_subtable(reader: &mut FontReader, first_glyph_index: u16, last_glyph_index: u16) -> Result<IndexSubtable> {
+    let index_format = reader.read_u16()?;
+    let image_format = reader.read_u16()?;
+    let image_data_offset = reader.read_u32()?;
+
+    if last_glyph_index < first_glyph_index {
+        return Err(TtfError::ParseError(format!(
+            "Invalid CBLC glyph range {}..={}", first_glyph_index, last_glyph_index
+        )));
+    }
+    let glyph_count = (last_glyph_index - first_glyph_index) as usize + 1;
+
+    // Turn per-format offsets into (glyph id, start, end) relative to the image data
+    let mut ranges = Vec::with_capacity(glyph_count);
+    let mut metrics = None;
+    match index_format {
+        1 | 3 => {
+            let mut offsets = Vec::with_capacity(glyph_count + 1);
+            for _ in 0..=glyph_count {
+                offsets.push(if index_format == 1 {
+                    reader.read_u32()?
+                } else {
+                    reader.read_u16()? as u32
+                });
+            }
+            for (i, window) in offsets.windows(2).enumerate() {
+                ranges.push((first_glyph_index + i as u16, window[0], window[1]));
+            }
+        }
+        2 => {
+            let image_size = reader.read_u32()?;
+            metrics = Some(BigGlyphMetrics::from_reader(reader)?);
+            for i in 0..glyph_count as u32 {
+                let (start, end) = fixed_size_image(i, image_size)?;
+                ranges.push((first_glyph_index + i as u16, start, end));
+            }
+        }
+        4 => {
+            let num_glyphs = reader.read_u32()?;
+            let mut pairs = Vec::with_capacity((num_glyphs as usize + 1).min(reader.remaining() / 4));
+            for _ in 0..=num_glyphs {
+                pairs.push((reader.read_u16()?, reader.read_u16()? as u32));
+            }
+            for window in pairs.windows(2) {
+                ranges.push((window[0].0, window[0].1, window[1].1));
+            }
+        }
+        5 => {
+            let image_size = reader.read_u32()?;
+            metrics = Some(BigGlyphMetrics::from_reader(reader)?);
+            let num_glyphs = reader.read_u32()?;
+            for i in 0..num_glyphs {
+                let glyph_id = reader.read_u16()?;
+                let (start, end) = fixed_size_image(i, image_size)?;
+                ranges.push((glyph_id, start, end));
+            }
+        }
+        _ => {
+            return Err(TtfError::ParseError(format!(
+                "Unsupported CBLC index format {}", index_format
+            )));
+        }
+    }
+
+    // Empty ranges mean the glyph has no image
+    let glyph_locations = ranges
+        .into_iter()
+        .filter(|&(_, start, end)| end > start)
+        .map(|(glyph_id, start, end)| {
+            let offset = image_data_offset
+                .checked_add(start)
+                .ok_or(TtfError::InvalidOffset(image_data_offset as u64 + start as u64))?;
+            Ok((glyph_id, offset, end - start))
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(IndexSubtable {
+        first_glyph_index,
+        last_glyph_index,
+        index_format,
+        image_format,
+        metrics,
+        glyph_locations,
+    })
+}
@@ -0,0 +1,543 @@
+use crate::error::{Result, TtfError};
+use crate::stream::FontReader;
+use crate::tables::TtfTable;
+use crate::tables::glyf::Point;
+
+/// Deepest subroutine nesting allowed by the Type 2 charstring format
+const MAX_SUBR_DEPTH: usize = 10;
+
+/// Largest argument stack allowed by the Type 2 charstring format
+const MAX_STACK: usize = 48;
+
+/// CFF table - Compact Font Format (PostScript) outlines
+///
+/// Only name-keyed fonts with Type 2 charstrings are supported; CID-keyed
+/// fonts are rejected when parsing.
+#[derive(Debug, Clone)]
+pub struct CffTable {
+    pub major: u8,
+    pub minor: u8,
+    /// Font names from the Name INDEX
+    pub names: Vec<String>,
+    /// Custom strings from the String INDEX (string ids 391 and up)
+    pub strings: Vec<String>,
+    pub global_subrs: Vec<Vec<u8>>,
+    /// Subroutines from the Private DICT's Subrs INDEX
+    pub local_subrs: Vec<Vec<u8>>,
+    /// One Type 2 charstring per glyph
+    pub char_strings: Vec<Vec<u8>>,
+    pub default_width_x: f32,
+    pub nominal_width_x: f32,
+}
+
+/// A drawing command of a glyph outline, in absolute font units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutlineCommand {
+    MoveTo(Point),
+    LineTo(Point),
+    /// Cubic Bézier curve through two control points to an end point
+    CurveTo(Point, Point, Point),
+    Close,
+}
+
+/// Outline of a CFF glyph, decoded from its charstring
+#[derive(Debug, Clone, PartialEq)]
+pub struct CffOutline {
+    pub commands: Vec<OutlineCommand>,
+    pub advance_width: f32,
+}
+
+impl CffTable {
+    /// Number of glyphs (charstrings) in the font
+    pub fn num_glyphs(&self) -> usize {
+        self.char_strings.len()
+    }
+
+    /// Decode the outline of a glyph by interpreting its charstring.
+    ///
+    /// Path operators and subroutine calls are followed; hints are parsed
+    /// only to skip them. Flex and the deprecated seac form of endchar are
+    /// not supported.
+    pub fn outline(&self, glyph_id: u16) -> Result<CffOutline> {
+        let char_string = self
+            .char_strings
+            .get(glyph_id as usize)
+            .ok_or(TtfError::InvalidGlyphIndex(glyph_id))?;
+
+        let mut interpreter = Interpreter::new(self);
+        interpreter.run(char_string, 0)?;
+        interpreter.close_path();
+
+        Ok(CffOutline {
+            commands: interpreter.commands,
+            advance_width: interpreter.width.unwrap_or(self.default_width_x),
+        })
+    }
+}
+
+impl TtfTable for CffTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"CFF ")
+    }
+
+    fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
+        let major = reader.read_u8()?;
+        let minor = reader.read_u8()?;
+        let header_size = reader.read_u8()?;
+        let _offset_size = reader.read_u8()?;
+        if major != 1 {
+            return Err(TtfError::UnsupportedVersion(major as u32));
+        }
+
+        reader.set_position(header_size as usize)?;
+        let names = read_index(reader)?
+            .iter()
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .collect();
+        let top_dicts = read_index(reader)?;
+        let strings = read_index(reader)?
+            .iter()
+            .map(|string| String::from_utf8_lossy(string).into_owned())
+            .collect();
+        let global_subrs = read_index(reader)?;
+
+        // Only the first font of a FontSet is read
+        let top_dict = parse_dict(top_dicts.first().map(Vec::as_slice).unwrap_or_default())?;
+        if dict_operands(&top_dict, ROS).is_some() {
+            return Err(TtfError::ParseError("CID-keyed CFF fonts are not supported".to_string()));
+        }
+        if let Some(&[charstring_type]) = dict_operands(&top_dict, CHARSTRING_TYPE)
+            && charstring_type != 2.0
+        {
+            return Err(TtfError::ParseError(format!(
+                "Unsupported charstring type {}",
+                charstring_type
+            )));
+        }
+
+        let char_strings_offset = match dict_operands(&top_dict, CHAR_STRINGS) {
+            Some(&[offset]) => offset as usize,
+            _ => return Err(TtfError::ParseError("CFF Top DICT has no CharStrings".to_string())),
+        };
+        reader.set_position(char_strings_offset)?;
+        let char_strings = read_index(reader)?;
+
+        let mut local_subrs = Vec::new();
+        let mut default_width_x = 0.0;
+        let mut nominal_width_x = 0.0;
+        if let Some(&[size, offset]) = dict_operands(&top_dict, PRIVATE) {
+            let (size, offset) = (size as usize, offset as usize);
+            reader.set_position(offset)?;
+            let private_dict = parse_dict(&reader.read_bytes(size)?)?;
+
+            if let Some(&[width]) = dict_operands(&private_dict, DEFAULT_WIDTH_X) {
+                default_width_x = width;
+            }
+            if let Some(&[width]) = dict_operands(&private_dict, NOMINAL_WIDTH_X) {
+                nominal_width_x = width;
+            }
+            // Local subroutines are relative to the Private DICT
+            if let Some(&[subrs_offset]) = dict_operands(&private_dict, SUBRS) {
+                reader.set_position(offset + subrs_offset as usize)?;
+                local_subrs = read_index(reader)?;
+            }
+        }
+
+        Ok(CffTable {
+            major,
+            minor,
+            names,
+            strings,
+            global_subrs,
+            local_subrs,
+            char_strings,
+            default_width_x,
+            nominal_width_x,
+        })
+    }
+}
+
+/// Read an INDEX: a count, an offset size and 1-based offsets into the
+/// object data that follows
+fn read_index(reader: &mut FontReader) -> Result<Vec<Vec<u8>>> {
+    let count = reader.read_u16()? as usize;
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let offset_size = reader.read_u8()?;
+    if !(1..=4).contains(&offset_size) {
+        return Err(TtfError::ParseError(format!("Invalid CFF INDEX offset size {}", offset_size)));
+    }
+
+    let mut offsets = Vec::with_capacity(count + 1);
+    for _ in 0..=count {
+        let mut offset = 0usize;
+        for _ in 0..offset_size {
+            offset = (offset << 8) | reader.read_u8()? as usize;
+        }
+        offsets.push(offset);
+    }
+
+    let data_start = reader.position() - 1;
+    let mut objects = Vec::with_capacity(count);
+    for pair in offsets.windows(2) {
+        if pair[1] < pair[0] || pair[0] == 0 {
+            return Err(TtfError::ParseError("CFF INDEX offsets are out of order".to_string()));
+        }
+        reader.set_position(data_start + pair[0])?;
+        objects.push(reader.read_bytes(pair[1] - pair[0])?);
+    }
+    reader.set_position(data_start + offsets[count])?;
+
+    Ok(objects)
+}
+
+// DICT operators; two-byte operators are 1200 plus their second byte
+const CHAR_STRINGS: u16 = 17;
+const PRIVATE: u16 = 18;
+const SUBRS: u16 = 19;
+const DEFAULT_WIDTH_X: u16 = 20;
+const NOMINAL_WIDTH_X: u16 = 21;
+const CHARSTRING_TYPE: u16 = 1206;
+const ROS: u16 = 1230;
+
+/// Parse a DICT into (operator, operands) entries
+fn parse_dict(data: &[u8]) -> Result<Vec<(u16, Vec<f32>)>> {
+    let mut reader = FontReader::from_slice(data);
+    let mut entries = Vec::new();
+    let mut operands = Vec::new();
+
+    while !reader.is_empty() {
+        let b0 = reader.read_u8()?;
+        match b0 {
+            0..=11 | 13..=21 => entries.push((b0 as u16, std::mem::take(&mut operands))),
+            12 => {
+                let b1 = reader.read_u8()?;
+                entries.push((1200 + b1 as u16, std::mem::take(&mut operands)));
+            }
+            28 => operands.push(reader.read_i16()? as f32),
+            29 => operands.push(reader.read_i32()? as f32),
+            30 => operands.push(read_real(&mut reader)?),
+            32..=246 => operands.push(b0 as f32 - 139.0),
+            247..=250 => {
+                let b1 = reader.read_u8()?;
+                operands.push((b0 as f32 - 247.0) * 256.0 + b1 as f32 + 108.0);
+            }
+            251..=254 => {
+                let b1 = reader.read_u8()?;
+                operands.push(-(b0 as f32 - 251.0) * 256.0 - b1 as f32 - 108.0);
+            }
+            _ => return Err(TtfError::ParseError(format!("Invalid CFF DICT byte {}", b0))),
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Read a real number operand: packed BCD nibbles ending with 0xF
+fn read_real(reader: &mut FontReader) -> Result<f32> {
+    let mut text = String::new();
+    loop {
+        let byte = reader.read_u8()?;
+        for nibble in [byte >> 4, byte & 0x0F] {
+            match nibble {
+                0..=9 => text.push((b'0' + nibble) as char),
+                0xA => text.push('.'),
+                0xB => text.push('E'),
+                0xC => text.push_str("E-"),
+                0xE => text.push('-'),
+                0xF => {
+                    return text
+                        .parse()
+                        .map_err(|_| TtfError::ParseError(format!("Invalid CFF real number {}", text)));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn dict_operands(dict: &[(u16, Vec<f32>)], operator: u16) -> Option<&[f32]> {
+    dict.iter()
+        .find(|(op, _)| *op == operator)
+        .map(|(_, operands)| operands.as_slice())
+}
+
+/// Bias added to subroutine numbers, which depends on the INDEX size
+fn subr_bias(count: usize) -> i32 {
+    if count < 1240 {
+        107
+    } else if count < 33900 {
+        1131
+    } else {
+        32768
+    }
+}
+
+/// Type 2 charstring interpreter state
+struct Interpreter<'a> {
+    table: &'a CffTable,
+    stack: Vec<f32>,
+    commands: Vec<OutlineCommand>,
+    current: Point,
+    path_open: bool,
+    stem_count: usize,
+    width: Option<f32>,
+    width_parsed: bool,
+    finished: bool,
+}
+
+impl<'a> Interpreter<'a> {
+    fn new(table: &'a CffTable) -> Self {
+        Self {
+            table,
+            stack: Vec::new(),
+            commands: Vec::new(),
+            current: Point::new(0.0, 0.0),
+            path_open: false,
+            stem_count: 0,
+            width: None,
+            width_parsed: false,
+            finished: false,
+        }
+    }
+
+    fn run(&mut self, code: &[u8], depth: usize) -> Result<()> {
+        if depth > MAX_SUBR_DEPTH {
+            return Err(TtfError::ParseError("CFF subroutines nest too deeply".to_string()));
+        }
+
+        let mut reader = FontReader::from_slice(code);
+        while !reader.is_empty() && !self.finished {
+            let b0 = reader.read_u8()?;
+            match b0 {
+                28 => self.push(reader.read_i16()? as f32)?,
+                32..=246 => self.push(b0 as f32 - 139.0)?,
+                247..=250 => {
+                    let b1 = reader.read_u8()?;
+                    self.push((b0 as f32 - 247.0) * 256.0 + b1 as f32 + 108.0)?;
+                }
+                251..=254 => {
+                    let b1 = reader.read_u8()?;
+                    self.push(-(b0 as f32 - 251.0) * 256.0 - b1 as f32 - 108.0)?;
+                }
+                255 => self.push(reader.read_i32()? as f32 / 65536.0)?,
+
+                // hstem, vstem, hstemhm, vstemhm
+                1 | 3 | 18 | 23 => {
+                    self.parse_width(self.stack.len() % 2 == 1);
+                    self.stem_count += self.stack.len() / 2;
+                    self.stack.clear();
+                }
+                // hintmask, cntrmask: leftover arguments are an implied vstem
+                19 | 20 => {
+                    self.parse_width(self.stack.len() % 2 == 1);
+                    self.stem_count += self.stack.len() / 2;
+                    self.stack.clear();
+                    reader.skip(self.stem_count.div_ceil(8))?;
+                }
+
+                // rmoveto
+                21 => {
+                    self.parse_width(self.stack.len() > 2);
+                    let [dx, dy] = self.args::<2>()?;
+                    self.move_to(dx, dy);
+                }
+                // hmoveto
+                22 => {
+                    self.parse_width(self.stack.len() > 1);
+                    let [dx] = self.args::<1>()?;
+                    self.move_to(dx, 0.0);
+                }
+                // vmoveto
+                4 => {
+                    self.parse_width(self.stack.len() > 1);
+                    let [dy] = self.args::<1>()?;
+                    self.move_to(0.0, dy);
+                }
+
+                // rlineto
+                5 => {
+                    for pair in self.take_stack().chunks_exact(2) {
+                        self.line_to(pair[0], pair[1]);
+                    }
+                }
+                // hlineto, vlineto: alternating horizontal and vertical lines
+                6 | 7 => {
+                    let mut horizontal = b0 == 6;
+                    for delta in self.take_stack() {
+                        if horizontal {
+                            self.line_to(delta, 0.0);
+                        } else {
+                            self.line_to(0.0, delta);
+                        }
+                        horizontal = !horizontal;
+                    }
+                }
+                // rrcurveto
+                8 => {
+                    for args in self.take_stack().chunks_exact(6) {
+                        self.curve_to(args[0], args[1], args[2], args[3], args[4], args[5]);
+                    }
+                }
+                // rcurveline: curves followed by one line
+                24 => {
+                    let args = self.take_stack();
+                    let (curves, line) = args.split_at(args.len().saturating_sub(2));
+                    for c in curves.chunks_exact(6) {
+                        self.curve_to(c[0], c[1], c[2], c[3], c[4], c[5]);
+                    }
+                    if let [dx, dy] = line {
+                        self.line_to(*dx, *dy);
+                    }
+                }
+                // rlinecurve: lines followed by one curve
+                25 => {
+                    let args = self.take_stack();
+                    let (lines, curve) = args.split_at(args.len().saturating_sub(6));
+                    for pair in lines.chunks_exact(2) {
+                        self.line_to(pair[0], pair[1]);
+                    }
+                    if let [a, b, c, d, e, f] = curve {
+                        self.curve_to(*a, *b, *c, *d, *e, *f);
+                    }
+                }
+                // vvcurveto: vertical tangents, with an optional leading dx1
+                26 => {
+                    let args = self.take_stack();
+                    let (mut dx1, rest) = match args.len() % 4 {
+                        1 => (args[0], &args[1..]),
+                        _ => (0.0, &args[..]),
+                    };
+                    for c in rest.chunks_exact(4) {
+                        self.curve_to(dx1, c[0], c[1], c[2], 0.0, c[3]);
+                        dx1 = 0.0;
+                    }
+                }
+                // hhcurveto: horizontal tangents, with an optional leading dy1
+                27 => {
+                    let args = self.take_stack();
+                    let (mut dy1, rest) = match args.len() % 4 {
+                        1 => (args[0], &args[1..]),
+                        _ => (0.0, &args[..]),
+                    };
+                    for c in rest.chunks_exact(4) {
+                        self.curve_to(c[0], dy1, c[1], c[2], c[3], 0.0);
+                        dy1 = 0.0;
+                    }
+                }
+                // vhcurveto, hvcurveto: tangents alternate between vertical
+                // and horizontal; the last curve may take a fifth argument
+                30 | 31 => {
+                    let args = self.take_stack();
+                    let mut horizontal = b0 == 31;
+                    let mut rest = &args[..];
+                    while rest.len() >= 4 {
+                        let last = if rest.len() == 5 { rest[4] } else { 0.0 };
+                        if horizontal {
+                            self.curve_to(rest[0], 0.0, rest[1], rest[2], last, rest[3]);
+                        } else {
+                            self.curve_to(0.0, rest[0], rest[1], rest[2], rest[3], last);
+                        }
+                        rest = &rest[if rest.len() == 5 { 5 } else { 4 }..];
+                        horizontal = !horizontal;
+                    }
+                }
+
+                // callsubr, callgsubr
+                10 | 29 => {
+                    let table = self.table;
+                    let subrs = if b0 == 10 { &table.local_subrs } else { &table.global_subrs };
+                    let index = self
+                        .stack
+                        .pop()
+                        .ok_or_else(|| TtfError::ParseError("CFF subroutine call without an index".to_string()))?
+                        as i32
+                        + subr_bias(subrs.len());
+                    let subr = usize::try_from(index)
+                        .ok()
+                        .and_then(|index| subrs.get(index))
+                        .ok_or_else(|| TtfError::ParseError(format!("CFF subroutine {} does not exist", index)))?;
+                    self.run(subr, depth + 1)?;
+                }
+                // return
+                11 => return Ok(()),
+                // endchar
+                14 => {
+                    if self.stack.len() >= 4 {
+                        return Err(TtfError::ParseError("CFF seac accented glyphs are not supported".to_string()));
+                    }
+                    self.parse_width(!self.stack.is_empty());
+                    self.stack.clear();
+                    self.finished = true;
+                }
+                12 => {
+                    let b1 = reader.read_u8()?;
+                    return Err(TtfError::ParseError(format!("Unsupported CFF charstring operator 12 {}", b1)));
+                }
+                _ => {
+                    return Err(TtfError::ParseError(format!("Unsupported CFF charstring operator {}", b0)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn push(&mut self, value: f32) -> Result<()> {
+        if self.stack.len() >= MAX_STACK {
+            return Err(TtfError::ParseError("CFF charstring stack overflow".to_string()));
+        }
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// Take the advance width from the bottom of the stack if the first
+    /// stack-clearing operator has one argument too many
+    fn parse_width(&mut self, has_width: bool) {
+        if self.width_parsed {
+            return;
+        }
+        self.width_parsed = true;
+        if has_width {
+            self.width = Some(self.table.nominal_width_x + self.stack.remove(0));
+        }
+    }
+
+    fn take_stack(&mut self) -> Vec<f32> {
+        std::mem::take(&mut self.stack)
+    }
+
+    fn args<const N: usize>(&mut self) -> Result<[f32; N]> {
+        let args = self.take_stack();
+        args.try_into()
+            .map_err(|_| TtfError::ParseError("Wrong number of CFF charstring arguments".to_string()))
+    }
+
+    fn close_path(&mut self) {
+        if self.path_open {
+            self.commands.push(OutlineCommand::Close);
+            self.path_open = false;
+        }
+    }
+
+    fn move_to(&mut self, dx: f32, dy: f32) {
+        self.close_path();
+        self.current = self.current + Point::new(dx, dy);
+        self.commands.push(OutlineCommand::MoveTo(self.current));
+        self.path_open = true;
+    }
+
+    fn line_to(&mut self, dx: f32, dy: f32) {
+        self.current = self.current + Point::new(dx, dy);
+        self.commands.push(OutlineCommand::LineTo(self.current));
+    }
+
+    fn curve_to(&mut self, dxa: f32, dya: f32, dxb: f32, dyb: f32, dxc: f32, dyc: f32) {
+        let control1 = self.current + Point::new(dxa, dya);
+        let control2 = control1 + Point::new(dxb, dyb);
+        self.current = control2 + Point::new(dxc, dyc);
+        self.commands.push(OutlineCommand::CurveTo(control1, control2, self.current));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::error::{Result, TtfError};
-use crate::stream::FontReader;
-use crate::tables::TtfTable;
+use crate::stream::{FontReader, FontWriter};
+use crate::tables::{TtfTable, TtfTableWrite};
 
 /// CMAP table - Character to glyph mapping
 #[derive(Debug, Clone)]
@@ -20,6 +20,7 @@ pub struct EncodingRecord {
 #[derive(Debug, Clone)]
 pub enum CmapSubtable {
     Format0(Format0),
+    Format2(Format2),
     Format4(Format4),
     Format6(Format6),
     Format12(Format12),
@@ -35,6 +36,28 @@ pub struct Format0 {
     pub glyph_id_array: Vec<u8>,
 }
 
+/// Format 2 - High-byte mapping through subheaders, for legacy double-byte
+/// encodings such as Shift-JIS or Big5
+#[derive(Debug, Clone)]
+pub struct Format2 {
+    pub format: u16,
+    pub length: u16,
+    pub language: u16,
+    /// Subheader index times 8 for each high byte; 0 marks a single-byte code
+    pub sub_header_keys: Vec<u16>,
+    pub sub_headers: Vec<SubHeader>,
+    pub glyph_id_array: Vec<u16>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubHeader {
+    pub first_code: u16,
+    pub entry_count: u16,
+    pub id_delta: i16,
+    /// Bytes from this field to the subheader's first glyphIdArray entry
+    pub id_range_offset: u16,
+}
+
 #[derive(Debug, Clone)]
 pub struct Format4 {
     pub format: u16,
@@ -118,60 +141,156 @@ impl Format0 {
     }
 }
 
+impl Format2 {
+    /// Map a one- or two-byte character code to a glyph ID
+    ///
+    /// A code up to 0xFF is a single byte only if its subheader key is 0;
+    /// otherwise it is the high byte of a two-byte code and maps nothing on
+    /// its own.
+    pub fn get_glyph(&self, char_code: u16) -> Option<u16> {
+        let [high, low] = char_code.to_be_bytes();
+        let lead = if high == 0 { low } else { high };
+        let key = match (high, *self.sub_header_keys.get(lead as usize)?) {
+            // Single bytes use subheader 0
+            (0, 0) => 0,
+            (0, _) | (_, 0) => return None,
+            (_, key) => key,
+        };
+
+        let sub_header_index = key as usize / 8;
+        let sub_header = self.sub_headers.get(sub_header_index)?;
+        let offset = (low as u16).checked_sub(sub_header.first_code)?;
+        if offset >= sub_header.entry_count {
+            return None;
+        }
+
+        // idRangeOffset counts bytes from its own field, the last of the
+        // subheader, which sits this many entries before glyphIdArray
+        let entries_before_array = (self.sub_headers.len() - sub_header_index) * 4 - 3;
+        let index = (sub_header.id_range_offset as usize / 2)
+            .checked_add(offset as usize)?
+            .checked_sub(entries_before_array)?;
+        match *self.glyph_id_array.get(index)? {
+            0 => None,
+            glyph_id => Some((glyph_id as i32 + sub_header.id_delta as i32) as u16).filter(|&g| g != 0),
+        }
+    }
+
+    /// Every code the subtable can address: single bytes, then two-byte
+    /// codes for each high byte with a subheader
+    fn codes(&self) -> impl Iterator<Item = u32> + '_ {
+        let single = (0..=0xFFu32).filter(|&code| self.sub_header_keys.get(code as usize) == Some(&0));
+        let double = self
+            .sub_header_keys
+            .iter()
+            .enumerate()
+            .filter(|&(_, &key)| key != 0)
+            .flat_map(move |(high, &key)| {
+                let sub_header = self.sub_headers.get(key as usize / 8);
+                let range = sub_header.map_or(0..0, |sub| {
+                    sub.first_code as u32..(sub.first_code as u32 + sub.entry_count as u32).min(0x100)
+                });
+                range.map(move |low| ((high as u32) << 8) | low)
+            });
+        single.chain(double)
+    }
+}
+
+/// A 16-bit subtable length, or `TtfError::LimitExceeded` if the subtable
+/// is too large for its format
+fn subtable_length(format: u16, length: usize) -> Result<u16> {
+    u16::try_from(length).map_err(|_| {
+        TtfError::LimitExceeded(format!("Format {} subtable of {} bytes exceeds 65535 bytes", format, length))
+    })
+}
+
 impl Format4 {
+    /// Build a subtable from (character code, glyph ID) pairs sorted by code
+    ///
+    /// Runs of consecutive codes mapping to consecutive glyphs share a
+    /// segment, addressed by idDelta alone. Code 0xFFFF is reserved for the
+    /// final segment and is left out.
+    ///
+    /// Returns `TtfError::LimitExceeded` if the segments don't fit in the
+    /// 16-bit subtable length.
+    pub fn from_mappings(mappings: &[(u16, u16)]) -> Result<Self> {
+        // (start, end, id_delta)
+        let mut segments: Vec<(u16, u16, i16)> = Vec::new();
+        for &(char_code, glyph_id) in mappings.iter().filter(|&&(code, _)| code != 0xFFFF) {
+            let id_delta = glyph_id.wrapping_sub(char_code) as i16;
+            match segments.last_mut() {
+                Some((_, end, delta)) if char_code == *end + 1 && id_delta == *delta => *end = char_code,
+                _ => segments.push((char_code, char_code, id_delta)),
+            }
+        }
+        segments.push((0xFFFF, 0xFFFF, 1));
+
+        let length = subtable_length(4, 16 + segments.len() * 8)?;
+        let seg_count = segments.len() as u16;
+        let (search_range, entry_selector, range_shift) = Self::search_params(seg_count);
+
+        Ok(Format4 {
+            format: 4,
+            length,
+            language: 0,
+            seg_count_x2: seg_count * 2,
+            search_range,
+            entry_selector,
+            range_shift,
+            end_codes: segments.iter().map(|&(_, end, _)| end).collect(),
+            start_codes: segments.iter().map(|&(start, _, _)| start).collect(),
+            id_deltas: segments.iter().map(|&(_, _, delta)| delta).collect(),
+            id_range_offsets: vec![0; seg_count as usize],
+            glyph_id_array: Vec::new(),
+        })
+    }
+
+    /// searchRange, entrySelector and rangeShift for a segment count
+    fn search_params(seg_count: u16) -> (u16, u16, u16) {
+        let seg_count_x2 = seg_count as u32 * 2;
+        let mut search_range = 2u32;
+        let mut entry_selector = 0u16;
+        while search_range * 2 <= seg_count_x2 {
+            search_range *= 2;
+            entry_selector += 1;
+        }
+        (search_range as u16, entry_selector, (seg_count_x2 - search_range) as u16)
+    }
+
     pub fn seg_count(&self) -> u16 {
         self.seg_count_x2 / 2
     }
 
     pub fn get_glyph(&self, char_code: u16) -> Option<u16> {
-        let seg_count = self.seg_count();
-
-        // Binary search for the segment
-        let mut min = 0;
-        let mut max = (seg_count - 1) as usize;
-
-        loop {
-            let mid = (min + max) / 2;
+        let seg_count = (self.seg_count() as usize).min(self.end_codes.len());
 
-            if char_code > self.end_codes[mid] {
-                if min == max {
-                    return None;
-                }
-                min = mid + 1;
-            } else if char_code < self.start_codes[mid] {
-                if min == max {
-                    return None;
-                }
-                max = mid - 1;
-            } else {
-                // Found the segment
-                let start_code = self.start_codes[mid];
-                let id_delta = self.id_deltas[mid] as i32;
-                let id_range_offset = self.id_range_offsets[mid];
-
-                if id_range_offset == 0 {
-                    // Simple case
-                    return Some((char_code as i32 + id_delta) as u16);
-                } else {
-                    // Complex case with id_range_offset
-                    let offset_index =
-                        (id_range_offset as usize / 2 + (char_code as usize - start_code as usize))
-                            as usize;
-
-                    if offset_index >= self.glyph_id_array.len() {
-                        return None;
-                    }
-
-                    let glyph_id = self.glyph_id_array[offset_index];
+        // The segment is the first one whose end code is at or above the character
+        let segment = self.end_codes[..seg_count].partition_point(|&end| end < char_code);
+        let start_code = *self.start_codes.get(segment)?;
+        if segment >= seg_count || char_code < start_code {
+            return None;
+        }
 
-                    if glyph_id == 0 {
-                        return None;
-                    }
+        let id_delta = *self.id_deltas.get(segment)? as i32;
+        let id_range_offset = *self.id_range_offsets.get(segment)? as usize;
 
-                    return Some(glyph_id);
-                }
+        let glyph_id = if id_range_offset == 0 {
+            (char_code as i32 + id_delta) as u16
+        } else {
+            // idRangeOffset counts bytes from its own slot in the idRangeOffset
+            // array, which sits (seg_count - segment) entries before glyphIdArray.
+            // Malformed offsets may point anywhere, so every step is checked
+            let index = (id_range_offset / 2)
+                .checked_add(char_code.checked_sub(start_code)? as usize)?
+                .checked_sub(seg_count.checked_sub(segment)?)?;
+            match *self.glyph_id_array.get(index)? {
+                0 => return None,
+                glyph_id => (glyph_id as i32 + id_delta) as u16,
             }
-        }
+        };
+
+        // Glyph 0 means the character is not mapped
+        (glyph_id != 0).then_some(glyph_id)
     }
 }
 
@@ -188,6 +307,34 @@ impl Format6 {
 }
 
 impl Format12 {
+    /// Build a subtable from (character code, glyph ID) pairs sorted by code,
+    /// grouping runs of consecutive codes that map to consecutive glyphs
+    pub fn from_mappings(mappings: &[(u32, u32)]) -> Self {
+        let mut groups: Vec<SequentialMapGroup> = Vec::new();
+        for &(char_code, glyph_id) in mappings {
+            match groups.last_mut() {
+                Some(group)
+                    if char_code == group.end_char_code + 1
+                        && glyph_id == group.start_glyph_code + (char_code - group.start_char_code) =>
+                {
+                    group.end_char_code = char_code;
+                }
+                _ => groups.push(SequentialMapGroup {
+                    start_char_code: char_code,
+                    end_char_code: char_code,
+                    start_glyph_code: glyph_id,
+                }),
+            }
+        }
+
+        Format12 {
+            format: 12,
+            length: 16 + groups.len() as u32 * 12,
+            language: 0,
+            groups,
+        }
+    }
+
     pub fn get_glyph(&self, char_code: u32) -> Option<u32> {
         // Binary search through groups
         let mut min = 0;
@@ -242,48 +389,255 @@ impl Format13 {
 }
 
 impl CmapTable {
+    /// Build a Unicode cmap from (code point, glyph ID) pairs.
+    ///
+    /// BMP code points go in a Windows Unicode BMP (3,1) Format 4 subtable.
+    /// If any code point lies beyond the BMP, a Windows Unicode full
+    /// repertoire (3,10) Format 12 subtable holding every mapping is added.
+    /// When the BMP mappings need more segments than Format 4 can hold,
+    /// only the Format 12 subtable is written.
+    ///
+    /// Returns `TtfError::LimitExceeded` for glyph IDs above 65535.
+    pub fn from_mappings(mappings: &[(u32, u32)]) -> Result<Self> {
+        let mut mappings = mappings.to_vec();
+        mappings.sort_unstable();
+        mappings.dedup_by_key(|&mut (code, _)| code);
+
+        if let Some(&(_, glyph_id)) = mappings.iter().find(|&&(_, glyph_id)| glyph_id > u16::MAX as u32) {
+            return Err(TtfError::LimitExceeded(format!("Glyph ID {} exceeds 65535", glyph_id)));
+        }
+
+        let bmp: Vec<(u16, u16)> = mappings
+            .iter()
+            .filter(|&&(code, _)| code <= 0xFFFF)
+            .map(|&(code, glyph_id)| (code as u16, glyph_id as u16))
+            .collect();
+
+        let mut encoding_records = Vec::new();
+        let mut subtables = Vec::new();
+        if let Ok(format4) = Format4::from_mappings(&bmp) {
+            encoding_records.push(EncodingRecord {
+                platform_id: 3,
+                encoding_id: 1,
+                offset: 0,
+            });
+            subtables.push(CmapSubtable::Format4(format4));
+        }
+
+        if subtables.is_empty() || bmp.len() < mappings.len() {
+            encoding_records.push(EncodingRecord {
+                platform_id: 3,
+                encoding_id: 10,
+                offset: 0,
+            });
+            subtables.push(CmapSubtable::Format12(Format12::from_mappings(&mappings)));
+        }
+
+        Ok(CmapTable {
+            version: 0,
+            encoding_records,
+            subtables,
+        })
+    }
+
     pub fn get_best_subtable(&self) -> Option<&CmapSubtable> {
-        // Priority: Unicode BMP (3,1), Unicode (0,3), Windows Symbol (3,0), Mac Roman (1,0)
+        // Priority: full Unicode repertoire (3,10), (0,4), then Unicode BMP
+        // (3,1), (0,3), Windows Symbol (3,0), Mac Roman (1,0)
         let preferred = [
+            (3, 10), // Unicode full repertoire
+            (0, 4), // Unicode 2.0+ full repertoire
             (3, 1), // Unicode BMP
-            (0, 4), // Unicode 2.0+
-            (0, 3), // Unicode 1.1
+            (0, 3), // Unicode 2.0+ BMP
             (3, 0), // Symbol
             (1, 0), // Roman
         ];
 
-        for (platform_id, encoding_id) in &preferred {
-            for (i, record) in self.encoding_records.iter().enumerate() {
-                if record.platform_id == *platform_id && record.encoding_id == *encoding_id {
-                    return self.subtables.get(i);
-                }
-            }
-        }
+        preferred
+            .iter()
+            .find_map(|&(platform_id, encoding_id)| self.subtable_for(platform_id, encoding_id))
+            .or_else(|| self.subtables.first())
+    }
 
-        self.subtables.first()
+    /// Get the subtable of a specific encoding record
+    pub fn subtable_for(&self, platform_id: u16, encoding_id: u16) -> Option<&CmapSubtable> {
+        self.encoding_records
+            .iter()
+            .position(|record| record.platform_id == platform_id && record.encoding_id == encoding_id)
+            .and_then(|i| self.subtables.get(i))
     }
 
+    /// Map a character through the preferred subtable
+    ///
+    /// Symbol fonts place their glyphs at 0xF000-0xF0FF in a (3,0) subtable,
+    /// so codes below 0x100 that don't map directly are retried there.
     pub fn map_char(&self, c: char) -> Option<u32> {
         let code = c as u32;
+        self.get_best_subtable()
+            .and_then(|subtable| subtable.lookup(code))
+            .or_else(|| {
+                if code >= 0x100 {
+                    return None;
+                }
+                self.subtable_for(3, 0)?.lookup(code | 0xF000)
+            })
+    }
 
-        if let Some(subtable) = self.get_best_subtable() {
-            match subtable {
-                CmapSubtable::Format0(f) if code <= 0xFF => {
-                    f.get_glyph(code as u8).map(|g| g as u32)
+    /// Map a character through the subtable of a specific encoding record
+    /// instead of the preferred one
+    pub fn map_char_with(&self, c: char, platform_id: u16, encoding_id: u16) -> Option<u32> {
+        self.subtable_for(platform_id, encoding_id)
+            .and_then(|subtable| subtable.lookup(c as u32))
+    }
+}
+
+impl CmapSubtable {
+    /// Map a character code to a glyph ID, whatever the subtable format
+    ///
+    /// Codes beyond the range a format can address (0xFF for Format 0,
+    /// 0xFFFF for Formats 2, 4 and 6) give `None` rather than being truncated.
+    /// Format 14 only holds variation sequences and never maps a code.
+    pub fn lookup(&self, code: u32) -> Option<u32> {
+        match self {
+            CmapSubtable::Format0(f) if code <= 0xFF => {
+                f.get_glyph(code as u8).map(|g| g as u32)
+            }
+            CmapSubtable::Format2(f) if code <= 0xFFFF => f.get_glyph(code as u16).map(|g| g as u32),
+            CmapSubtable::Format4(f) if code <= 0xFFFF => f.get_glyph(code as u16).map(|g| g as u32),
+            CmapSubtable::Format6(f) if code <= 0xFFFF => f.get_glyph(code as u16).map(|g| g as u32),
+            CmapSubtable::Format12(f) => f.get_glyph(code),
+            CmapSubtable::Format13(f) => f.get_glyph(code),
+            _ => None,
+        }
+    }
+
+    /// Serialize the subtable. Lengths are computed from the data rather
+    /// than taken from the length fields.
+    ///
+    /// Format 14 subtables only keep their selector records, so they can't
+    /// be written back and return an error.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut writer = FontWriter::new();
+        match self {
+            CmapSubtable::Format0(f) => {
+                writer.write_u16(0);
+                writer.write_u16(6 + f.glyph_id_array.len() as u16);
+                writer.write_u16(f.language);
+                writer.write_bytes(&f.glyph_id_array);
+            }
+            CmapSubtable::Format2(f) => {
+                writer.write_u16(2);
+                writer.write_u16(subtable_length(
+                    2,
+                    6 + f.sub_header_keys.len() * 2 + f.sub_headers.len() * 8 + f.glyph_id_array.len() * 2,
+                )?);
+                writer.write_u16(f.language);
+                f.sub_header_keys.iter().for_each(|&key| writer.write_u16(key));
+                for sub_header in &f.sub_headers {
+                    writer.write_u16(sub_header.first_code);
+                    writer.write_u16(sub_header.entry_count);
+                    writer.write_i16(sub_header.id_delta);
+                    writer.write_u16(sub_header.id_range_offset);
                 }
-                CmapSubtable::Format4(f) if code <= 0xFFFF => f.get_glyph(code as u16).map(|g| g as u32),
-                CmapSubtable::Format6(f) if code <= 0xFFFF => f.get_glyph(code as u16).map(|g| g as u32),
-                CmapSubtable::Format12(f) => f.get_glyph(code),
-                CmapSubtable::Format13(f) => f.get_glyph(code),
-                _ => None,
+                f.glyph_id_array.iter().for_each(|&glyph_id| writer.write_u16(glyph_id));
+            }
+            CmapSubtable::Format4(f) => {
+                let length = subtable_length(4, 16 + f.end_codes.len() * 8 + f.glyph_id_array.len() * 2)?;
+                let seg_count = f.end_codes.len() as u16;
+                let (search_range, entry_selector, range_shift) = Format4::search_params(seg_count);
+                writer.write_u16(4);
+                writer.write_u16(length);
+                writer.write_u16(f.language);
+                writer.write_u16(seg_count * 2);
+                writer.write_u16(search_range);
+                writer.write_u16(entry_selector);
+                writer.write_u16(range_shift);
+                f.end_codes.iter().for_each(|&code| writer.write_u16(code));
+                writer.write_u16(0); // reservedPad
+                f.start_codes.iter().for_each(|&code| writer.write_u16(code));
+                f.id_deltas.iter().for_each(|&delta| writer.write_i16(delta));
+                f.id_range_offsets.iter().for_each(|&offset| writer.write_u16(offset));
+                f.glyph_id_array.iter().for_each(|&glyph_id| writer.write_u16(glyph_id));
+            }
+            CmapSubtable::Format6(f) => {
+                writer.write_u16(6);
+                writer.write_u16(subtable_length(6, 10 + f.glyph_id_array.len() * 2)?);
+                writer.write_u16(f.language);
+                writer.write_u16(f.first_code);
+                writer.write_u16(f.glyph_id_array.len() as u16);
+                f.glyph_id_array.iter().for_each(|&glyph_id| writer.write_u16(glyph_id));
+            }
+            CmapSubtable::Format12(f) => {
+                writer.write_u16(12);
+                writer.write_u16(0); // reserved
+                writer.write_u32(16 + f.groups.len() as u32 * 12);
+                writer.write_u32(f.language);
+                writer.write_u32(f.groups.len() as u32);
+                for group in &f.groups {
+                    writer.write_u32(group.start_char_code);
+                    writer.write_u32(group.end_char_code);
+                    writer.write_u32(group.start_glyph_code);
+                }
+            }
+            CmapSubtable::Format13(f) => {
+                writer.write_u16(13);
+                writer.write_u16(0); // reserved
+                writer.write_u32(16 + f.groups.len() as u32 * 12);
+                writer.write_u32(f.language);
+                writer.write_u32(f.groups.len() as u32);
+                for group in &f.groups {
+                    writer.write_u32(group.start_char_code);
+                    writer.write_u32(group.end_char_code);
+                    writer.write_u32(group.glyph_code);
+                }
+            }
+            CmapSubtable::Format14(_) => {
+                return Err(TtfError::ParseError(
+                    "Cannot serialize cmap format 14 subtable".to_string(),
+                ));
             }
-        } else {
-            None
         }
+        Ok(writer.into_inner())
+    }
+
+    /// Collect every (character code, glyph ID) pair the subtable maps,
+    /// in ascending code order. Codes mapped to glyph 0 are left out.
+    pub fn mappings(&self) -> Vec<(u32, u32)> {
+        let codes: Box<dyn Iterator<Item = u32> + '_> = match self {
+            CmapSubtable::Format0(_) => Box::new(0..=0xFF),
+            CmapSubtable::Format2(f) => Box::new(f.codes()),
+            CmapSubtable::Format4(f) => Box::new(
+                f.start_codes
+                    .iter()
+                    .zip(&f.end_codes)
+                    .flat_map(|(&start, &end)| start as u32..=end as u32),
+            ),
+            CmapSubtable::Format6(f) => {
+                let first_code = f.first_code as u32;
+                Box::new(first_code..first_code + f.glyph_id_array.len() as u32)
+            }
+            CmapSubtable::Format12(f) => Box::new(
+                f.groups.iter().flat_map(|g| g.start_char_code..=g.end_char_code),
+            ),
+            CmapSubtable::Format13(f) => Box::new(
+                f.groups.iter().flat_map(|g| g.start_char_code..=g.end_char_code),
+            ),
+            CmapSubtable::Format14(_) => Box::new(std::iter::empty()),
+        };
+
+        let mut mappings: Vec<(u32, u32)> = codes
+            .filter_map(|code| self.lookup(code).filter(|&glyph| glyph != 0).map(|glyph| (code, glyph)))
+            .collect();
+        mappings.sort_unstable();
+        mappings.dedup();
+        mappings
     }
 }
 
 impl TtfTable for CmapTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"cmap")
+    }
+
     fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
         let version = reader.read_u16()?;
         let num_tables = reader.read_u16()?;
@@ -303,9 +657,9 @@ impl TtfTable for CmapTable {
             let current_pos = reader.position();
             reader.set_position(record.offset as usize)?;
 
-            let format = reader.read_u16()?;
-            let subtable = match format {
+            let subtable = match reader.peek_u16()? {
                 0 => {
+                    let format = reader.read_u16()?;
                     let length = reader.read_u16()?;
                     let language = reader.read_u16()?;
                     let glyph_id_array = reader.read_bytes(256)?;
@@ -316,7 +670,38 @@ impl TtfTable for CmapTable {
                         glyph_id_array,
                     })
                 }
+                2 => {
+                    let format = reader.read_u16()?;
+                    let length = reader.read_u16()?;
+                    let language = reader.read_u16()?;
+                    let sub_header_keys = reader.read_u16_array(256)?;
+
+                    // Subheader 0 always exists; the keys address the rest
+                    let num_sub_headers = sub_header_keys.iter().max().map_or(0, |&key| key as usize / 8) + 1;
+                    let mut sub_headers = Vec::with_capacity(num_sub_headers);
+                    for _ in 0..num_sub_headers {
+                        sub_headers.push(SubHeader {
+                            first_code: reader.read_u16()?,
+                            entry_count: reader.read_u16()?,
+                            id_delta: reader.read_i16()?,
+                            id_range_offset: reader.read_u16()?,
+                        });
+                    }
+
+                    let array_len = (length as usize).saturating_sub(6 + 512 + num_sub_headers * 8) / 2;
+                    let glyph_id_array = reader.read_u16_array(array_len)?;
+
+                    CmapSubtable::Format2(Format2 {
+                        format,
+                        length,
+                        language,
+                        sub_header_keys,
+                        sub_headers,
+                        glyph_id_array,
+                    })
+                }
                 4 => {
+                    let format = reader.read_u16()?;
                     let length = reader.read_u16()?;
                     let language = reader.read_u16()?;
                     let seg_count_x2 = reader.read_u16()?;
@@ -325,27 +710,13 @@ impl TtfTable for CmapTable {
                     let entry_selector = reader.read_u16()?;
                     let range_shift = reader.read_u16()?;
 
-                    let mut end_codes = Vec::with_capacity(seg_count as usize);
-                    for _ in 0..seg_count {
-                        end_codes.push(reader.read_u16()?);
-                    }
+                    let end_codes = reader.read_u16_array(seg_count as usize)?;
 
                     let _reserved_pad = reader.read_u16()?;
 
-                    let mut start_codes = Vec::with_capacity(seg_count as usize);
-                    for _ in 0..seg_count {
-                        start_codes.push(reader.read_u16()?);
-                    }
-
-                    let mut id_deltas = Vec::with_capacity(seg_count as usize);
-                    for _ in 0..seg_count {
-                        id_deltas.push(reader.read_i16()?);
-                    }
-
-                    let mut id_range_offsets = Vec::with_capacity(seg_count as usize);
-                    for _ in 0..seg_count {
-                        id_range_offsets.push(reader.read_u16()?);
-                    }
+                    let start_codes = reader.read_u16_array(seg_count as usize)?;
+                    let id_deltas = reader.read_i16_array(seg_count as usize)?;
+                    let id_range_offsets = reader.read_u16_array(seg_count as usize)?;
 
                     let remaining = (length as usize)
                         .saturating_sub(2 + 2 + 2 + 2 + 2 + 2 + 2)
@@ -355,10 +726,7 @@ impl TtfTable for CmapTable {
                         .saturating_sub(seg_count as usize * 2)
                         .saturating_sub(seg_count as usize * 2);
                     let glyph_id_array_size = remaining / 2;
-                    let mut glyph_id_array = Vec::with_capacity(glyph_id_array_size);
-                    for _ in 0..glyph_id_array_size {
-                        glyph_id_array.push(reader.read_u16()?);
-                    }
+                    let glyph_id_array = reader.read_u16_array(glyph_id_array_size)?;
 
                     CmapSubtable::Format4(Format4 {
                         format,
@@ -377,6 +745,7 @@ impl TtfTable for CmapTable {
                 }
                 6 => {
                     // Format 6 - Trimmed table mapping
+                    let format = reader.read_u16()?;
                     let length = reader.read_u16()?;
                     let language = reader.read_u16()?;
                     let first_code = reader.read_u16()?;
@@ -398,12 +767,14 @@ impl TtfTable for CmapTable {
                 }
                 12 => {
                     // Format 12 - Segmented coverage (full Unicode)
-                    reader.skip(2)?; // format is already read, but it's u16 in the stream for formats < 8
+                    let format = reader.read_u16()?;
+                    let _reserved = reader.read_u16()?;
                     let length = reader.read_u32()?;
                     let language = reader.read_u32()?;
                     let num_groups = reader.read_u32()?;
 
-                    let mut groups = Vec::with_capacity(num_groups as usize);
+                    // Each group takes 12 bytes, so the count can't outrun the data
+                    let mut groups = Vec::with_capacity((num_groups as usize).min(reader.remaining() / 12));
                     for _ in 0..num_groups {
                         groups.push(SequentialMapGroup {
                             start_char_code: reader.read_u32()?,
@@ -413,7 +784,7 @@ impl TtfTable for CmapTable {
                     }
 
                     CmapSubtable::Format12(Format12 {
-                        format: 12,
+                        format: format as u32,
                         length,
                         language,
                         groups,
@@ -421,12 +792,13 @@ impl TtfTable for CmapTable {
                 }
                 13 => {
                     // Format 13 - Many-to-one range mappings
-                    reader.skip(2)?;
+                    let format = reader.read_u16()?;
+                    let _reserved = reader.read_u16()?;
                     let length = reader.read_u32()?;
                     let language = reader.read_u32()?;
                     let num_groups = reader.read_u32()?;
 
-                    let mut groups = Vec::with_capacity(num_groups as usize);
+                    let mut groups = Vec::with_capacity((num_groups as usize).min(reader.remaining() / 12));
                     for _ in 0..num_groups {
                         groups.push(ConstantMapGroup {
                             start_char_code: reader.read_u32()?,
@@ -436,7 +808,7 @@ impl TtfTable for CmapTable {
                     }
 
                     CmapSubtable::Format13(Format13 {
-                        format: 13,
+                        format: format as u32,
                         length,
                         language,
                         groups,
@@ -444,11 +816,12 @@ impl TtfTable for CmapTable {
                 }
                 14 => {
                     // Format 14 - Unicode variation sequences
-                    reader.skip(2)?;
+                    let format = reader.read_u16()?;
                     let length = reader.read_u32()?;
                     let num_var_selector_records = reader.read_u32()?;
 
-                    let mut var_selector_records = Vec::with_capacity(num_var_selector_records as usize);
+                    let mut var_selector_records =
+                        Vec::with_capacity((num_var_selector_records as usize).min(reader.remaining() / 11));
                     for _ in 0..num_var_selector_records {
                         var_selector_records.push(VarSelectorRecord {
                             var_selector: U24(reader.read_u24()?),
@@ -458,17 +831,18 @@ impl TtfTable for CmapTable {
                     }
 
                     CmapSubtable::Format14(Format14 {
-                        format: 14,
+                        format: format as u32,
                         length,
                         num_var_selector_records,
                         var_selector_records,
                     })
                 }
-                _ => {
-                    return Err(TtfError::ParseError(format!(
-                        "Unsupported cmap subtable format: {}",
-                        format
-                    )));
+                format => {
+                    return Err(TtfError::TableParseError {
+                        table: "cmap".to_string(),
+                        offset: record.offset as u64,
+                        message: format!("unsupported subtable format {}", format),
+                    });
                 }
             };
 
@@ -483,3 +857,36 @@ impl TtfTable for CmapTable {
         })
     }
 }
+
+impl TtfTableWrite for CmapTable {
+    fn table_tag() -> &'static [u8; 4] {
+        b"cmap"
+    }
+
+    /// Write the table with the subtables laid out after the encoding
+    /// records, in record order. Record offsets are recomputed.
+    fn write(&self, writer: &mut FontWriter) -> Result<()> {
+        let subtables = self
+            .subtables
+            .iter()
+            .map(CmapSubtable::to_bytes)
+            .collect::<Result<Vec<_>>>()?;
+
+        writer.write_u16(self.version);
+        writer.write_u16(self.encoding_records.len() as u16);
+
+        let mut offset = 4 + self.encoding_records.len() as u32 * 8;
+        for (record, data) in self.encoding_records.iter().zip(&subtables) {
+            writer.write_u16(record.platform_id);
+            writer.write_u16(record.encoding_id);
+            writer.write_u32(offset);
+            offset += data.len() as u32;
+        }
+
+        for data in &subtables {
+            writer.write_bytes(data);
+        }
+
+        Ok(())
+    }
+}
@@ -1,17 +1,16 @@
-use crate::error::Result;
+use crate::error::{Result, TtfError};
 use crate::stream::FontReader;
 use crate::tables::TtfTable;
 
 /// COLR table - Color table
+///
+/// Only the version 0 layer records are read; version 1 paint graphs are
+/// not supported, but the version 0 data of a version 1 table still is.
 #[derive(Debug, Clone)]
 pub struct ColrTable {
     pub version: u16,
-    pub num_base_glyph_records: u32,
-    pub num_layer_records: u32,
-    pub num_clip_boxes: u32,
     pub base_glyph_records: Vec<BaseGlyphRecord>,
-    pub layer_records: Vec<LayerRecord>,
-    pub clip_boxes: Vec<ClipBox>,
+    pub layer_records: Vec<ColorLayer>,
 }
 
 #[derive(Debug, Clone)]
@@ -21,38 +20,80 @@ pub struct BaseGlyphRecord {
     pub num_layers: u16,
 }
 
-#[derive(Debug, Clone)]
-pub struct LayerRecord {
+/// One layer of a color glyph, drawn bottom to top
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorLayer {
     pub glyph_id: u16,
-    pub palette_entry_index: u16,
+    /// Index into the CPAL palette; 0xFFFF means the text foreground color
+    pub palette_index: u16,
 }
 
-#[derive(Debug, Clone)]
-pub struct ClipBox {
-    pub format: u8,
-    pub clip_box: [i32; 4], // xMin, yMin, xMax, yMax
+impl ColorLayer {
+    pub const FOREGROUND: u16 = 0xFFFF;
+
+    pub fn is_foreground(&self) -> bool {
+        self.palette_index == Self::FOREGROUND
+    }
+}
+
+impl ColrTable {
+    /// Get the layers of a base glyph, or `None` if it isn't a color glyph
+    pub fn layers(&self, glyph_id: u16) -> Option<&[ColorLayer]> {
+        // Base glyph records are sorted by glyph id
+        let index = self
+            .base_glyph_records
+            .binary_search_by_key(&glyph_id, |record| record.glyph_id)
+            .ok()?;
+        let record = &self.base_glyph_records[index];
+
+        let start = record.first_layer_index as usize;
+        self.layer_records.get(start..start + record.num_layers as usize)
+    }
 }
 
 impl TtfTable for ColrTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"COLR")
+    }
+
     fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
         let version = reader.read_u16()?;
-        let num_base_glyph_records = reader.read_u32()?;
-        let num_layer_records = reader.read_u32()?;
-        let num_clip_boxes = reader.read_u32()?;
+        if version > 1 {
+            return Err(TtfError::UnsupportedVersion(version as u32));
+        }
+
+        let num_base_glyph_records = reader.read_u16()?;
+        let base_glyph_records_offset = reader.read_u32()?;
+        let layer_records_offset = reader.read_u32()?;
+        let num_layer_records = reader.read_u16()?;
+
+        let mut base_glyph_records = Vec::with_capacity(num_base_glyph_records as usize);
+        if num_base_glyph_records > 0 {
+            reader.set_position(base_glyph_records_offset as usize)?;
+            for _ in 0..num_base_glyph_records {
+                base_glyph_records.push(BaseGlyphRecord {
+                    glyph_id: reader.read_u16()?,
+                    first_layer_index: reader.read_u16()?,
+                    num_layers: reader.read_u16()?,
+                });
+            }
+        }
 
-        let mut base_glyph_records = Vec::new();
-        let mut layer_records = Vec::new();
-        let mut clip_boxes = Vec::new();
+        let mut layer_records = Vec::with_capacity(num_layer_records as usize);
+        if num_layer_records > 0 {
+            reader.set_position(layer_records_offset as usize)?;
+            for _ in 0..num_layer_records {
+                layer_records.push(ColorLayer {
+                    glyph_id: reader.read_u16()?,
+                    palette_index: reader.read_u16()?,
+                });
+            }
+        }
 
-        // Simplified - full implementation would parse all records
         Ok(ColrTable {
             version,
-            num_base_glyph_records,
-            num_layer_records,
-            num_clip_boxes,
             base_glyph_records,
             layer_records,
-            clip_boxes,
         })
     }
 }
@@ -1,4 +1,4 @@
-use crate::error::Result;
+use crate::error::{Result, TtfError};
 use crate::stream::FontReader;
 use crate::tables::TtfTable;
 
@@ -7,8 +7,6 @@ use crate::tables::TtfTable;
 pub struct CpalTable {
     pub version: u16,
     pub num_palette_entries: u16,
-    pub num_palettes: u16,
-    pub num_color_records: u32,
     pub palettes: Vec<Palette>,
 }
 
@@ -17,7 +15,7 @@ pub struct Palette {
     pub colors: Vec<ColorRecord>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ColorRecord {
     pub blue: u8,
     pub green: u8,
@@ -25,37 +23,55 @@ pub struct ColorRecord {
     pub alpha: u8,
 }
 
+impl ColorRecord {
+    /// Get the color as `[red, green, blue, alpha]`
+    pub fn to_rgba(&self) -> [u8; 4] {
+        [self.red, self.green, self.blue, self.alpha]
+    }
+}
+
 impl TtfTable for CpalTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"CPAL")
+    }
+
     fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
         let version = reader.read_u16()?;
         let num_palette_entries = reader.read_u16()?;
         let num_palettes = reader.read_u16()?;
-        let num_color_records = reader.read_u32()?;
-
-        let mut palettes = Vec::new();
+        let num_color_records = reader.read_u16()?;
+        let color_records_array_offset = reader.read_u32()?;
 
+        let mut color_record_indices = Vec::with_capacity(num_palettes as usize);
         for _ in 0..num_palettes {
-            let mut colors = Vec::new();
-            for _ in 0..num_palette_entries {
-                let blue = reader.read_u8()?;
-                let green = reader.read_u8()?;
-                let red = reader.read_u8()?;
-                let alpha = reader.read_u8()?;
-                colors.push(ColorRecord {
-                    blue,
-                    green,
-                    red,
-                    alpha,
-                });
-            }
+            color_record_indices.push(reader.read_u16()?);
+        }
+
+        // Palettes are windows into one shared array of BGRA color records
+        reader.set_position(color_records_array_offset as usize)?;
+        let mut color_records = Vec::with_capacity(num_color_records as usize);
+        for _ in 0..num_color_records {
+            color_records.push(ColorRecord {
+                blue: reader.read_u8()?,
+                green: reader.read_u8()?,
+                red: reader.read_u8()?,
+                alpha: reader.read_u8()?,
+            });
+        }
+
+        let mut palettes = Vec::with_capacity(num_palettes as usize);
+        for first in color_record_indices {
+            let start = first as usize;
+            let colors = color_records
+                .get(start..start + num_palette_entries as usize)
+                .ok_or(TtfError::InvalidOffset(first as u64))?
+                .to_vec();
             palettes.push(Palette { colors });
         }
 
         Ok(CpalTable {
             version,
             num_palette_entries,
-            num_palettes,
-            num_color_records,
             palettes,
         })
     }
@@ -9,6 +9,10 @@ pub struct CvtTable {
 }
 
 impl TtfTable for CvtTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"cvt ")
+    }
+
     fn from_reader(reader: &mut FontReader, length: u32) -> Result<Self> {
         let num_values = (length as usize) / 2;
         let mut values = Vec::with_capacity(num_values);
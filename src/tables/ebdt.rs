@@ -0,0 +1,177 @@
+use crate::error::{Result, TtfError};
+use crate::stream::FontReader;
+use crate::tables::cbdt::BigGlyphMetrics;
+use crate::tables::TtfTable;
+
+/// EBDT table - Embedded bitmap data
+///
+/// The table is kept as raw bytes; glyph images are located through EBLC,
+/// which shares its layout with CBLC.
+#[derive(Debug, Clone)]
+pub struct EbdtTable {
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub data: Vec<u8>,
+}
+
+/// An embedded monochrome or grayscale bitmap
+///
+/// Rows run top to bottom and are padded to whole bytes; within a byte the
+/// most significant bits come first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonoBitmap {
+    pub width: usize,
+    pub height: usize,
+    /// Bits per pixel (1 for monochrome strikes)
+    pub bit_depth: u8,
+    pub bearing_x: i8,
+    pub bearing_y: i8,
+    pub advance: u8,
+    pub data: Vec<u8>,
+}
+
+impl MonoBitmap {
+    /// Number of bytes in each row of `data`
+    pub fn stride(&self) -> usize {
+        (self.width * self.bit_depth as usize).div_ceil(8)
+    }
+
+    /// Value of the pixel at `(x, y)`, or 0 outside the bitmap
+    pub fn pixel(&self, x: usize, y: usize) -> u8 {
+        if x >= self.width || y >= self.height {
+            return 0;
+        }
+        let depth = self.bit_depth as usize;
+        let bit = x * depth;
+        let byte = self.data[y * self.stride() + bit / 8];
+        let shift = 8 - depth - bit % 8;
+        (byte >> shift) & ((1u16 << depth) - 1) as u8
+    }
+}
+
+impl EbdtTable {
+    /// Decode the image stored at `offset..offset + length` in the given EBDT
+    /// image format.
+    ///
+    /// Formats 5 and later without embedded metrics take them from the EBLC
+    /// index subtable, passed as `shared_metrics`.
+    pub fn bitmap(
+        &self,
+        image_format: u16,
+        offset: u32,
+        length: u32,
+        bit_depth: u8,
+        shared_metrics: Option<&BigGlyphMetrics>,
+    ) -> Result<MonoBitmap> {
+        let start = offset as usize;
+        let end = start + length as usize;
+        let glyph_data = self
+            .data
+            .get(start..end)
+            .ok_or(TtfError::InvalidOffset(end as u64))?;
+        let mut reader = FontReader::from_slice(glyph_data);
+
+        // (height, width, bearing x, bearing y, advance) and bit alignment
+        let (metrics, bit_aligned) = match image_format {
+            1 | 2 => {
+                let metrics = (
+                    reader.read_u8()?,
+                    reader.read_u8()?,
+                    reader.read_i8()?,
+                    reader.read_i8()?,
+                    reader.read_u8()?,
+                );
+                (metrics, image_format == 2)
+            }
+            5 => {
+                let big = shared_metrics.ok_or_else(|| TtfError::TableParseError {
+                    table: "EBDT".to_string(),
+                    offset: offset as u64,
+                    message: "format 5 glyph without EBLC metrics".to_string(),
+                })?;
+                (
+                    (big.height, big.width, big.hori_bearing_x, big.hori_bearing_y, big.hori_advance),
+                    true,
+                )
+            }
+            6 | 7 => {
+                let big = BigGlyphMetrics::from_reader(&mut reader)?;
+                (
+                    (big.height, big.width, big.hori_bearing_x, big.hori_bearing_y, big.hori_advance),
+                    image_format == 7,
+                )
+            }
+            _ => {
+                return Err(TtfError::TableParseError {
+                    table: "EBDT".to_string(),
+                    offset: offset as u64,
+                    message: format!("unsupported image format {}", image_format),
+                });
+            }
+        };
+
+        let (height, width, bearing_x, bearing_y, advance) = metrics;
+        let mut bitmap = MonoBitmap {
+            width: width as usize,
+            height: height as usize,
+            bit_depth,
+            bearing_x,
+            bearing_y,
+            advance,
+            data: Vec::new(),
+        };
+
+        let stride = bitmap.stride();
+        let image = &glyph_data[reader.position()..];
+        bitmap.data = if bit_aligned {
+            unpack_bit_aligned(image, bitmap.width * bit_depth as usize, bitmap.height, stride)?
+        } else {
+            image
+                .get(..stride * bitmap.height)
+                .ok_or(TtfError::InvalidOffset(end as u64))?
+                .to_vec()
+        };
+
+        Ok(bitmap)
+    }
+}
+
+/// Spread rows of `row_bits` packed back to back onto rows padded to `stride`
+/// bytes
+fn unpack_bit_aligned(image: &[u8], row_bits: usize, height: usize, stride: usize) -> Result<Vec<u8>> {
+    if image.len() * 8 < row_bits * height {
+        return Err(TtfError::ParseError("EBDT bitmap data is truncated".to_string()));
+    }
+
+    let mut data = vec![0u8; stride * height];
+    for y in 0..height {
+        for x in 0..row_bits {
+            let src = y * row_bits + x;
+            if image[src / 8] & (0x80 >> (src % 8)) != 0 {
+                data[y * stride + x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+    }
+    Ok(data)
+}
+
+impl TtfTable for EbdtTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"EBDT")
+    }
+
+    fn from_reader(reader: &mut FontReader, length: u32) -> Result<Self> {
+        let major_version = reader.read_u16()?;
+        let minor_version = reader.read_u16()?;
+
+        // Image offsets in EBLC are relative to the start of the table
+        reader.set_position(0)?;
+        let data = reader.read_bytes(length as usize)?;
+
+        Ok(EbdtTable {
+            major_version,
+            minor_version,
+            data,
+        })
+    }
+}
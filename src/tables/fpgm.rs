@@ -9,6 +9,10 @@ pub struct FpgmTable {
 }
 
 impl TtfTable for FpgmTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"fpgm")
+    }
+
     fn from_reader(reader: &mut FontReader, length: u32) -> Result<Self> {
         let instructions = reader.read_bytes(length as usize)?;
         Ok(FpgmTable { instructions })
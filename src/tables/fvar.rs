@@ -1,86 +1,143 @@
-use crate::error::Result;
+use crate::error::{Result, TtfError};
 use crate::stream::FontReader;
 use crate::tables::TtfTable;
 
 /// FVAR table - Font variations
 #[derive(Debug, Clone)]
 pub struct FvarTable {
-    pub version: u16,
-    pub offset_to_axes: u16,
-    pub count_size_pairs: u16,
-    pub axes: Vec<Axis>,
-    pub instance_size: u16,
-    pub count_instances: u16,
-    pub instances: Vec<Instance>,
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub axes: Vec<VariationAxis>,
+    pub instances: Vec<NamedInstance>,
 }
 
+/// A variation axis such as weight (`wght`) or width (`wdth`)
 #[derive(Debug, Clone)]
-pub struct Axis {
-    pub axis_tag: [u8; 4],
-    pub axis_name_id: u16,
-    pub min_value: f32,
-    pub default_value: f32,
-    pub max_value: f32,
+pub struct VariationAxis {
+    pub tag: [u8; 4],
+    pub min: f32,
+    pub default: f32,
+    pub max: f32,
     pub flags: u16,
-    pub axis_name_id_short: u16,
+    pub name_id: u16,
 }
 
+/// A named instance, i.e. a predefined position in the design space
 #[derive(Debug, Clone)]
-pub struct Instance {
+pub struct NamedInstance {
     pub subfamily_name_id: u16,
     pub flags: u16,
     pub coordinates: Vec<f32>,
     pub post_script_name_id: Option<u16>,
 }
 
+impl VariationAxis {
+    /// Get the axis tag as a string
+    pub fn tag_to_string(&self) -> String {
+        String::from_utf8_lossy(&self.tag).to_string()
+    }
+
+    /// Normalize a user-space value to the range -1.0..=1.0
+    ///
+    /// The default maps to 0, the minimum to -1 and the maximum to 1;
+    /// values outside the axis range are clamped.
+    pub fn normalize(&self, value: f32) -> f32 {
+        let value = value.clamp(self.min, self.max);
+        if value < self.default {
+            (value - self.default) / (self.default - self.min)
+        } else if value > self.default {
+            (value - self.default) / (self.max - self.default)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl FvarTable {
+    /// Find an axis by its tag
+    pub fn axis(&self, tag: &[u8; 4]) -> Option<&VariationAxis> {
+        self.axes.iter().find(|axis| axis.tag == *tag)
+    }
+
+    /// Normalize user-space coordinates, one per axis
+    ///
+    /// Missing coordinates are taken to be the axis default.
+    pub fn normalize(&self, coords: &[f32]) -> Vec<f32> {
+        self.axes
+            .iter()
+            .enumerate()
+            .map(|(i, axis)| coords.get(i).map_or(0.0, |&value| axis.normalize(value)))
+            .collect()
+    }
+}
+
 impl TtfTable for FvarTable {
-    fn from_reader(reader: &mut FontReader, length: u32) -> Result<Self> {
-        let version = reader.read_u16()?;
-        let offset_to_axes = reader.read_u16()?;
-        let count_size_pairs = reader.read_u16()?;
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"fvar")
+    }
 
-        // Skip reserved
-        reader.skip(2)?;
+    fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
+        let major_version = reader.read_u16()?;
+        let minor_version = reader.read_u16()?;
+        if major_version != 1 {
+            return Err(TtfError::UnsupportedVersion(major_version as u32));
+        }
+
+        let axes_array_offset = reader.read_u16()?;
+        reader.skip(2)?; // reserved
+        let axis_count = reader.read_u16()?;
+        let axis_size = reader.read_u16()?;
+        let instance_count = reader.read_u16()?;
+        let instance_size = reader.read_u16()?;
 
-        let mut axes = Vec::new();
-        let current_offset = reader.position();
+        let mut axes = Vec::with_capacity(axis_count as usize);
+        for i in 0..axis_count as usize {
+            reader.set_position(axes_array_offset as usize + i * axis_size as usize)?;
+
+            axes.push(VariationAxis {
+                tag: reader.read_tag()?,
+                min: reader.read_fixed()?,
+                default: reader.read_fixed()?,
+                max: reader.read_fixed()?,
+                flags: reader.read_u16()?,
+                name_id: reader.read_u16()?,
+            });
+        }
 
-        for _ in 0..count_size_pairs {
-            reader.set_position(current_offset + offset_to_axes as usize)?;
+        // Instances follow the axes; the PostScript name ID is present only
+        // when the record is large enough to hold it
+        let instances_offset = axes_array_offset as usize + axis_count as usize * axis_size as usize;
+        let has_post_script_name_id = instance_size as usize >= axis_count as usize * 4 + 6;
 
-            let axis_tag = reader.read_tag()?;
-            let axis_name_id = reader.read_u16()?;
-            let min_value = reader.read_fixed()?;
-            let default_value = reader.read_fixed()?;
-            let max_value = reader.read_fixed()?;
+        let mut instances = Vec::with_capacity(instance_count as usize);
+        for i in 0..instance_count as usize {
+            reader.set_position(instances_offset + i * instance_size as usize)?;
+
+            let subfamily_name_id = reader.read_u16()?;
             let flags = reader.read_u16()?;
-            let axis_name_id_short = reader.read_u16()?;
-
-            axes.push(Axis {
-                axis_tag,
-                axis_name_id,
-                min_value,
-                default_value,
-                max_value,
+            let mut coordinates = Vec::with_capacity(axis_count as usize);
+            for _ in 0..axis_count {
+                coordinates.push(reader.read_fixed()?);
+            }
+            let post_script_name_id = if has_post_script_name_id {
+                Some(reader.read_u16()?)
+            } else {
+                None
+            };
+
+            instances.push(NamedInstance {
+                subfamily_name_id,
                 flags,
-                axis_name_id_short,
+                coordinates,
+                post_script_name_id,
             });
-
-            break; // Simplified - full implementation would iterate all axes
         }
 
-        // Read instance data (simplified)
-        let instance_size = reader.read_u16()?;
-        let count_instances = reader.read_u16()?;
-
         Ok(FvarTable {
-            version,
-            offset_to_axes,
-            count_size_pairs,
+            major_version,
+            minor_version,
             axes,
-            instance_size,
-            count_instances,
-            instances: Vec::new(),
+            instances,
         })
     }
 }
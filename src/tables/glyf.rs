@@ -1,6 +1,8 @@
-use crate::error::Result;
-use crate::stream::FontReader;
+use crate::error::{Result, TtfError};
+use crate::stream::{FontReader, FontWriter};
+use crate::tables::TtfTableWrite;
 use super::loca::LocaTable;
+use std::ops::{Add, Mul, Sub};
 
 /// 2D Point for glyph coordinates
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -21,6 +23,41 @@ impl Point {
             y: transform.yx * self.x + transform.yy * self.y + transform.dy,
         }
     }
+
+    /// Euclidean distance to another point
+    pub fn distance(&self, other: &Point) -> f32 {
+        let delta = *other - *self;
+        delta.x.hypot(delta.y)
+    }
+
+    /// Linear interpolation towards `other`: `self` at t = 0, `other` at t = 1
+    pub fn lerp(&self, other: &Point, t: f32) -> Point {
+        *self + (*other - *self) * t
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<f32> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: f32) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
 }
 
 /// Bounding box for glyphs
@@ -73,6 +110,9 @@ impl BoundingBox {
     }
 }
 
+/// Maximum nesting of composite glyph components followed when resolving
+const MAX_COMPONENT_DEPTH: usize = 16;
+
 /// GLYF table - Glyph data
 #[derive(Debug, Clone)]
 pub struct GlyfTable {
@@ -106,6 +146,50 @@ pub struct SimpleGlyph {
     pub y_coordinates: Vec<i16>,
 }
 
+/// A point of a glyph contour
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContourPoint {
+    pub x: i16,
+    pub y: i16,
+    pub on_curve: bool,
+}
+
+/// Orientation queries on a contour, as returned by `SimpleGlyph::contours`
+///
+/// TrueType fills clockwise outer contours, so holes run counter-clockwise.
+pub trait Contour {
+    /// Area enclosed by the on-curve points (shoelace formula), positive
+    /// for counter-clockwise contours with y pointing up
+    fn signed_area(&self) -> f32;
+
+    /// Whether the contour runs clockwise, like a TrueType outer contour
+    fn is_clockwise(&self) -> bool {
+        self.signed_area() < 0.0
+    }
+}
+
+impl Contour for [ContourPoint] {
+    fn signed_area(&self) -> f32 {
+        let points: Vec<&ContourPoint> = self.iter().filter(|point| point.on_curve).collect();
+        let twice_area: i64 = points
+            .iter()
+            .zip(points.iter().cycle().skip(1))
+            .map(|(a, b)| a.x as i64 * b.y as i64 - b.x as i64 * a.y as i64)
+            .sum();
+        twice_area as f32 / 2.0
+    }
+}
+
+/// A drawing command of a glyph outline, in font units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand {
+    MoveTo(Point),
+    LineTo(Point),
+    /// Quadratic Bézier curve through a control point to an end point
+    QuadTo(Point, Point),
+    Close,
+}
+
 #[derive(Debug, Clone)]
 pub struct CompositeGlyph {
     pub components: Vec<GlyphComponent>,
@@ -120,7 +204,7 @@ pub struct GlyphComponent {
     pub transform: Transform,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Transform {
     pub xx: f32,
     pub xy: f32,
@@ -135,6 +219,18 @@ impl Glyph {
         matches!(self.data, GlyphData::Simple(_))
     }
 
+    /// A glyph with no outline, such as a space
+    pub fn empty() -> Self {
+        Glyph {
+            number_of_contours: 0,
+            x_min: 0,
+            y_min: 0,
+            x_max: 0,
+            y_max: 0,
+            data: GlyphData::Empty,
+        }
+    }
+
     pub fn is_composite(&self) -> bool {
         matches!(self.data, GlyphData::Composite(_))
     }
@@ -143,6 +239,27 @@ impl Glyph {
         matches!(self.data, GlyphData::Empty)
     }
 
+    /// Write the glyph record; empty glyphs produce no bytes
+    fn write(&self, writer: &mut FontWriter) {
+        let number_of_contours = match &self.data {
+            GlyphData::Simple(simple) => simple.end_pts_of_contours.len() as i16,
+            GlyphData::Composite(_) => -1,
+            GlyphData::Empty => return,
+        };
+
+        writer.write_i16(number_of_contours);
+        writer.write_i16(self.x_min);
+        writer.write_i16(self.y_min);
+        writer.write_i16(self.x_max);
+        writer.write_i16(self.y_max);
+
+        match &self.data {
+            GlyphData::Simple(simple) => simple.write(writer),
+            GlyphData::Composite(composite) => composite.write(writer),
+            GlyphData::Empty => {}
+        }
+    }
+
     /// Calculate the bounding box for this glyph
     pub fn calculate_bounding_box(&self) -> Option<BoundingBox> {
         if self.is_empty() {
@@ -207,7 +324,7 @@ impl Glyph {
             GlyphData::Composite(composite) => {
                 // Update the transform for each component
                 for component in &mut composite.components {
-                    component.transform = Self::combine_transforms(&component.transform, transform);
+                    component.transform = component.transform.then(transform);
                 }
 
                 // Update bounding box
@@ -264,82 +381,90 @@ impl Glyph {
         self.transform(&transform)
     }
 
-    /// Combine two transforms (applied right to left: second then first)
-    fn combine_transforms(first: &Transform, second: &Transform) -> Transform {
-        Transform {
-            xx: first.xx * second.xx + first.xy * second.yx,
-            xy: first.xx * second.xy + first.xy * second.yy,
-            yx: first.yx * second.xx + first.yy * second.yx,
-            yy: first.yx * second.xy + first.yy * second.yy,
-            dx: first.xx * second.dx + first.xy * second.dy + first.dx,
-            dy: first.yx * second.dx + first.yy * second.dy + first.dy,
-        }
-    }
-
     /// Simplify the glyph outline by removing redundant points
-    /// This is a basic implementation that removes collinear points
+    ///
+    /// An on-curve point is removed when both its neighbours are on-curve
+    /// and it lies within `tolerance` font units of the line between them.
+    /// Off-curve points, and the on-curve points next to them, are kept, so
+    /// curves are unchanged. Contours keep at least three points.
+    ///
+    /// Removing points renumbers them, so hinting instructions are dropped
+    /// whenever any point is removed.
     pub fn simplify(&mut self, tolerance: f32) -> Result<()> {
         if let GlyphData::Simple(simple) = &mut self.data {
-            if simple.x_coordinates.len() < 3 {
-                return Ok(());
-            }
+            let contours = simple.contours();
+            let num_points: usize = contours.iter().map(Vec::len).sum();
 
-            let mut to_remove = vec![false; simple.x_coordinates.len()];
-
-            // Check each point (except endpoints of contours)
-            let mut contour_start = 0;
-            for &end_pt in &simple.end_pts_of_contours {
-                let end = end_pt as usize;
-
-                // Check interior points of this contour
-                for i in (contour_start + 1)..end {
-                    let prev = if i > contour_start { i - 1 } else { end };
-                    let next = if i < end { i + 1 } else { contour_start };
-
-                    let x1 = simple.x_coordinates[prev] as f32;
-                    let y1 = simple.y_coordinates[prev] as f32;
-                    let x2 = simple.x_coordinates[i] as f32;
-                    let y2 = simple.y_coordinates[i] as f32;
-                    let x3 = simple.x_coordinates[next] as f32;
-                    let y3 = simple.y_coordinates[next] as f32;
-
-                    // Check if point i is collinear with prev and next
-                    let area = ((x2 - x1) * (y3 - y1) - (y2 - y1) * (x3 - x1)).abs();
-
-                    if area < tolerance {
-                        // Also check distance to ensure point isn't adding precision
-                        let dist = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
-                        if dist < tolerance || ((x3 - x2).powi(2) + (y3 - y2).powi(2)).sqrt() < tolerance {
-                            to_remove[i] = true;
-                        }
-                    }
-                }
+            let simplified: Vec<Vec<ContourPoint>> = contours
+                .iter()
+                .map(|contour| simplify_contour(contour, tolerance))
+                .collect();
 
-                contour_start = end + 1;
+            if simplified.iter().map(Vec::len).sum::<usize>() == num_points {
+                return Ok(());
             }
-
-            // Remove marked points (in reverse order to preserve indices)
-            for i in (0..to_remove.len()).rev() {
-                if to_remove[i] {
-                    simple.x_coordinates.remove(i);
-                    simple.y_coordinates.remove(i);
-                    simple.flags.remove(i);
-
-                    // Update contour end indices
-                    for end_pt in &mut simple.end_pts_of_contours {
-                        if *end_pt as usize > i {
-                            *end_pt = (*end_pt as usize - 1) as u16;
-                        }
-                    }
-                }
+            simple.set_contours(&simplified);
+            simple.instructions.clear();
+            simple.instruction_length = 0;
+
+            // Update bounding box
+            if let Some(bbox) = self.calculate_bounding_box() {
+                self.x_min = bbox.x_min.round() as i16;
+                self.y_min = bbox.y_min.round() as i16;
+                self.x_max = bbox.x_max.round() as i16;
+                self.y_max = bbox.y_max.round() as i16;
             }
         }
         Ok(())
     }
 }
 
-impl Default for Transform {
-    fn default() -> Self {
+/// Remove redundant on-curve points from a closed contour
+fn simplify_contour(contour: &[ContourPoint], tolerance: f32) -> Vec<ContourPoint> {
+    let mut points = contour.to_vec();
+
+    let mut i = 0;
+    while points.len() > 3 && i < points.len() {
+        let n = points.len();
+        let prev = points[(i + n - 1) % n];
+        let next = points[(i + 1) % n];
+
+        if points[i].on_curve
+            && prev.on_curve
+            && next.on_curve
+            && distance_to_segment(points[i], prev, next) < tolerance
+        {
+            points.remove(i);
+            // The previous point may now be removable too
+            i = i.saturating_sub(1);
+        } else {
+            i += 1;
+        }
+    }
+
+    points
+}
+
+/// Distance from `point` to the line segment from `start` to `end`
+fn distance_to_segment(point: ContourPoint, start: ContourPoint, end: ContourPoint) -> f32 {
+    let p = Point::new(point.x as f32, point.y as f32);
+    let a = Point::new(start.x as f32, start.y as f32);
+    let b = Point::new(end.x as f32, end.y as f32);
+
+    let ab = b - a;
+    let length_squared = ab.x * ab.x + ab.y * ab.y;
+    if length_squared == 0.0 {
+        return p.distance(&a);
+    }
+
+    let ap = p - a;
+    let t = ((ap.x * ab.x + ap.y * ab.y) / length_squared).clamp(0.0, 1.0);
+    p.distance(&a.lerp(&b, t))
+}
+
+impl Transform {
+    /// The transform that leaves points unchanged
+    pub fn identity() -> Self {
         Self {
             xx: 1.0,
             xy: 0.0,
@@ -349,6 +474,44 @@ impl Default for Transform {
             dy: 0.0,
         }
     }
+
+    /// Compose two transforms: the result applies `self` first, then `other`
+    pub fn then(&self, other: &Transform) -> Transform {
+        Transform {
+            xx: other.xx * self.xx + other.xy * self.yx,
+            xy: other.xx * self.xy + other.xy * self.yy,
+            yx: other.yx * self.xx + other.yy * self.yx,
+            yy: other.yx * self.xy + other.yy * self.yy,
+            dx: other.xx * self.dx + other.xy * self.dy + other.dx,
+            dy: other.yx * self.dx + other.yy * self.dy + other.dy,
+        }
+    }
+
+    /// The transform that undoes this one, or `None` if the 2x2 matrix is
+    /// singular (e.g. a zero scale)
+    pub fn inverse(&self) -> Option<Transform> {
+        let determinant = self.xx * self.yy - self.xy * self.yx;
+        if determinant == 0.0 {
+            return None;
+        }
+
+        let (xx, xy) = (self.yy / determinant, -self.xy / determinant);
+        let (yx, yy) = (-self.yx / determinant, self.xx / determinant);
+        Some(Transform {
+            xx,
+            xy,
+            yx,
+            yy,
+            dx: -(xx * self.dx + xy * self.dy),
+            dy: -(yx * self.dx + yy * self.dy),
+        })
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
 }
 
 impl GlyfTable {
@@ -361,14 +524,7 @@ impl GlyfTable {
 
             if offset == next_offset {
                 // Empty glyph
-                glyphs.push(Glyph {
-                    number_of_contours: 0,
-                    x_min: 0,
-                    y_min: 0,
-                    x_max: 0,
-                    y_max: 0,
-                    data: GlyphData::Empty,
-                });
+                glyphs.push(Glyph::empty());
                 continue;
             }
 
@@ -411,9 +567,83 @@ impl GlyfTable {
         self.glyphs.get_mut(index)
     }
 
+    /// Serialize the glyph data
+    ///
+    /// Returns the packed glyf bytes together with the loca offsets
+    /// (`glyphs.len() + 1` entries). Each glyph is padded to a 4-byte
+    /// boundary and empty glyphs take no space.
+    ///
+    /// Simple glyph outlines are written compactly: deltas that fit in a
+    /// byte use the short vector flags, zero deltas use the "same" flags,
+    /// and runs of identical flags are collapsed with the repeat flag.
+    pub fn to_bytes(&self) -> (Vec<u8>, Vec<u32>) {
+        let mut writer = FontWriter::new();
+        let mut offsets = Vec::with_capacity(self.glyphs.len() + 1);
+
+        for glyph in &self.glyphs {
+            offsets.push(writer.position() as u32);
+            glyph.write(&mut writer);
+            writer.write_padding(4);
+        }
+        offsets.push(writer.position() as u32);
+
+        (writer.into_inner(), offsets)
+    }
+
+    /// Iterate over every glyph in order, with composites flattened into
+    /// simple glyphs by `resolve_composite`
+    ///
+    /// Composites that resolve to no outline come out as empty glyphs.
+    pub fn iter_resolved(&self) -> impl Iterator<Item = Result<Glyph>> + '_ {
+        (0..self.glyphs.len()).map(|glyph_index| {
+            Ok(self.resolve_composite(glyph_index)?.unwrap_or_else(Glyph::empty))
+        })
+    }
+
+    /// Nesting depth of a glyph's component references
+    ///
+    /// Simple and empty glyphs have depth 0, and a composite made only of
+    /// simple glyphs has depth 1, matching maxp.maxComponentDepth.
+    pub fn component_depth(&self, glyph_index: usize) -> Result<u16> {
+        self.depth_below(glyph_index, 0)
+    }
+
+    fn depth_below(&self, glyph_index: usize, depth: usize) -> Result<u16> {
+        if depth > MAX_COMPONENT_DEPTH {
+            return Err(TtfError::ParseError(format!(
+                "Composite glyph nesting exceeds depth {} at glyph {}",
+                MAX_COMPONENT_DEPTH, glyph_index
+            )));
+        }
+
+        match self.get_glyph(glyph_index).map(|glyph| &glyph.data) {
+            Some(GlyphData::Composite(composite)) => {
+                let mut deepest = 0;
+                for component in &composite.components {
+                    deepest = deepest.max(self.depth_below(component.glyph_index as usize, depth + 1)?);
+                }
+                Ok(deepest + 1)
+            }
+            _ => Ok(0),
+        }
+    }
+
     /// Resolve a composite glyph by flattening it into a simple glyph
-    /// This resolves all component references and transforms
+    /// This resolves all component references and transforms, including
+    /// components that are themselves composite glyphs
     pub fn resolve_composite(&self, glyph_index: usize) -> Result<Option<Glyph>> {
+        self.resolve_glyph(glyph_index, 0)
+    }
+
+    fn resolve_glyph(&self, glyph_index: usize, depth: usize) -> Result<Option<Glyph>> {
+        // Guard against cyclic or absurdly deep component references
+        if depth > MAX_COMPONENT_DEPTH {
+            return Err(TtfError::ParseError(format!(
+                "Composite glyph nesting exceeds depth {} at glyph {}",
+                MAX_COMPONENT_DEPTH, glyph_index
+            )));
+        }
+
         let glyph = self.get_glyph(glyph_index);
         let glyph = match glyph {
             Some(g) => g,
@@ -430,10 +660,9 @@ impl GlyfTable {
                 let mut all_points: Vec<(i16, i16)> = Vec::new();
                 let mut all_flags: Vec<u8> = Vec::new();
                 let mut all_contours: Vec<u16> = Vec::new();
-                let mut total_contours = 0;
 
                 for component in &composite.components {
-                    let component_glyph = self.get_glyph(component.glyph_index as usize);
+                    let component_glyph = self.resolve_glyph(component.glyph_index as usize, depth + 1)?;
                     let component_glyph = match component_glyph {
                         Some(g) => g,
                         None => continue,
@@ -441,24 +670,19 @@ impl GlyfTable {
 
                     if let GlyphData::Simple(simple) = &component_glyph.data {
                         let transform = &component.transform;
+                        let first_point = all_points.len();
 
                         // Transform and collect all points from this component
-                        let point_count = simple.x_coordinates.len();
-                        for i in 0..point_count {
-                            let x = simple.x_coordinates[i] as f32;
-                            let y = simple.y_coordinates[i] as f32;
+                        for (i, (&x, &y)) in simple.x_coordinates.iter().zip(&simple.y_coordinates).enumerate() {
+                            let point = Point::new(x as f32, y as f32).transform(transform);
 
-                            let new_x = transform.xx * x + transform.xy * y + transform.dx;
-                            let new_y = transform.yx * x + transform.yy * y + transform.dy;
-
-                            all_points.push((new_x.round() as i16, new_y.round() as i16));
+                            all_points.push((point.x.round() as i16, point.y.round() as i16));
                             all_flags.push(simple.flags.get(i).copied().unwrap_or(0));
                         }
 
                         // Update contour endpoints
                         for end_pt in &simple.end_pts_of_contours {
-                            all_contours.push((*end_pt as usize + total_contours) as u16);
-                            total_contours += *end_pt as usize + 1;
+                            all_contours.push((*end_pt as usize + first_point) as u16);
                         }
                     }
                 }
@@ -502,6 +726,112 @@ impl GlyfTable {
 }
 
 impl SimpleGlyph {
+    /// Split the outline points into their contours
+    ///
+    /// Points with no flag are treated as on-curve; contours whose endpoint
+    /// lies past the last point are dropped.
+    pub fn contours(&self) -> Vec<Vec<ContourPoint>> {
+        let num_points = self.x_coordinates.len().min(self.y_coordinates.len());
+        let mut contours = Vec::with_capacity(self.end_pts_of_contours.len());
+
+        let mut start = 0;
+        for &end_pt in &self.end_pts_of_contours {
+            let end = end_pt as usize;
+            if end >= num_points || end < start {
+                break;
+            }
+
+            contours.push(
+                (start..=end)
+                    .map(|i| ContourPoint {
+                        x: self.x_coordinates[i],
+                        y: self.y_coordinates[i],
+                        on_curve: self.flags.get(i).is_none_or(|flag| flag & 0x1 != 0),
+                    })
+                    .collect(),
+            );
+            start = end + 1;
+        }
+
+        contours
+    }
+
+    /// Convert the outline into drawing commands
+    ///
+    /// Each contour becomes a `MoveTo` followed by lines and quadratic
+    /// curves, with the on-curve points implied between consecutive
+    /// off-curve points made explicit, and ends with `Close`. A contour made
+    /// only of off-curve points starts at the implied point between its last
+    /// and first points.
+    pub fn path(&self) -> Vec<PathCommand> {
+        let mut commands = Vec::new();
+
+        for contour in self.contours() {
+            let points: Vec<(Point, bool)> = contour
+                .iter()
+                .map(|point| (Point::new(point.x as f32, point.y as f32), point.on_curve))
+                .collect();
+
+            let (first, remaining, origin) = match points.iter().position(|&(_, on_curve)| on_curve) {
+                Some(first) => (first + 1, points.len() - 1, points[first].0),
+                None => (0, points.len(), points[points.len() - 1].0.lerp(&points[0].0, 0.5)),
+            };
+            commands.push(PathCommand::MoveTo(origin));
+
+            let mut control: Option<Point> = None;
+            for step in first..first + remaining {
+                let (point, on_curve) = points[step % points.len()];
+                match (control, on_curve) {
+                    (None, true) => commands.push(PathCommand::LineTo(point)),
+                    (None, false) => control = Some(point),
+                    (Some(ctrl), true) => {
+                        commands.push(PathCommand::QuadTo(ctrl, point));
+                        control = None;
+                    }
+                    (Some(ctrl), false) => {
+                        commands.push(PathCommand::QuadTo(ctrl, ctrl.lerp(&point, 0.5)));
+                        control = Some(point);
+                    }
+                }
+            }
+
+            // A pending curve ends at the origin; Close draws a straight edge
+            if let Some(ctrl) = control {
+                commands.push(PathCommand::QuadTo(ctrl, origin));
+            }
+            commands.push(PathCommand::Close);
+        }
+
+        commands
+    }
+
+    /// Replace the outline points with the given contours, rebuilding the
+    /// contour end points and flags.
+    ///
+    /// Flags keep only the on-curve bit, plus the overlap bit of the first
+    /// point; the other bits are recomputed when the glyph is written.
+    pub fn set_contours(&mut self, contours: &[Vec<ContourPoint>]) {
+        let overlap = self.flags.first().map_or(0, |flag| flag & 0x40);
+
+        self.end_pts_of_contours.clear();
+        self.flags.clear();
+        self.x_coordinates.clear();
+        self.y_coordinates.clear();
+
+        for contour in contours.iter().filter(|contour| !contour.is_empty()) {
+            for point in contour {
+                self.flags.push(point.on_curve as u8);
+                self.x_coordinates.push(point.x);
+                self.y_coordinates.push(point.y);
+            }
+            self.end_pts_of_contours.push((self.x_coordinates.len() - 1) as u16);
+        }
+
+        if let Some(first) = self.flags.first_mut() {
+            *first |= overlap;
+        }
+    }
+
     fn read(reader: &mut FontReader, num_contours: usize) -> Result<Self> {
         let mut end_pts_of_contours = Vec::with_capacity(num_contours);
         for _ in 0..num_contours {
@@ -542,7 +872,7 @@ impl SimpleGlyph {
         for &flag in &flags {
             if flag & 0x2 != 0 {
                 // 1-byte signed
-                let val = reader.read_i8()?;
+                let val = reader.read_u8()?;
                 x += if flag & 0x10 != 0 { val as i16 } else { -(val as i16) };
             } else if flag & 0x10 == 0 {
                 // 2-byte signed
@@ -557,7 +887,7 @@ impl SimpleGlyph {
         for &flag in &flags {
             if flag & 0x4 != 0 {
                 // 1-byte signed
-                let val = reader.read_i8()?;
+                let val = reader.read_u8()?;
                 y += if flag & 0x20 != 0 { val as i16 } else { -(val as i16) };
             } else if flag & 0x20 == 0 {
                 // 2-byte signed
@@ -576,6 +906,53 @@ impl SimpleGlyph {
             y_coordinates,
         })
     }
+
+    fn write(&self, writer: &mut FontWriter) {
+        for &end_pt in &self.end_pts_of_contours {
+            writer.write_u16(end_pt);
+        }
+
+        writer.write_u16(self.instructions.len() as u16);
+        writer.write_bytes(&self.instructions);
+
+        // Re-encode flags from the point deltas, keeping only the
+        // on-curve and overlap bits of the original flags
+        let mut flags = Vec::with_capacity(self.x_coordinates.len());
+        let mut x_data = FontWriter::new();
+        let mut y_data = FontWriter::new();
+        let mut prev_x = 0i16;
+        let mut prev_y = 0i16;
+
+        for (i, (&x, &y)) in self.x_coordinates.iter().zip(&self.y_coordinates).enumerate() {
+            let mut flag = self.flags.get(i).copied().unwrap_or(0x1) & 0x41;
+            flag |= encode_delta(x.wrapping_sub(prev_x), 0x2, 0x10, &mut x_data);
+            flag |= encode_delta(y.wrapping_sub(prev_y), 0x4, 0x20, &mut y_data);
+            flags.push(flag);
+            prev_x = x;
+            prev_y = y;
+        }
+
+        // Compress runs of identical flags with the repeat flag
+        let mut i = 0;
+        while i < flags.len() {
+            let flag = flags[i];
+            let mut run = 1;
+            while run <= 255 && i + run < flags.len() && flags[i + run] == flag {
+                run += 1;
+            }
+
+            if run > 1 {
+                writer.write_u8(flag | 0x8);
+                writer.write_u8((run - 1) as u8);
+            } else {
+                writer.write_u8(flag);
+            }
+            i += run;
+        }
+
+        writer.write_bytes(&x_data.into_inner());
+        writer.write_bytes(&y_data.into_inner());
+    }
 }
 
 impl CompositeGlyph {
@@ -598,40 +975,28 @@ impl CompositeGlyph {
                 reader.read_i8()? as i16
             };
 
-            let mut transform = Transform::default();
-
-            // Read transform based on flags
-            transform.xx = if flags & 0x80 != 0 {
-                reader.read_f2dot14()?
-            } else {
-                1.0
-            };
-
-            transform.xy = if flags & 0x40 != 0 {
-                reader.read_f2dot14()?
-            } else {
-                0.0
-            };
-
-            transform.yx = if flags & 0x20 != 0 {
-                reader.read_f2dot14()?
-            } else {
-                0.0
-            };
-
-            transform.yy = if flags & 0x10 != 0 {
-                reader.read_f2dot14()?
-            } else {
-                1.0
+            let mut transform = Transform {
+                dx: arg1 as f32,
+                dy: arg2 as f32,
+                ..Transform::default()
             };
 
+            // Read transform based on flags
             if flags & 0x8 != 0 {
-                transform.dx = reader.read_i16()? as f32;
-                transform.dy = reader.read_i16()? as f32;
-            } else {
-                // Use arg1/arg2 as offsets
-                transform.dx = arg1 as f32;
-                transform.dy = arg2 as f32;
+                // WE_HAVE_A_SCALE
+                let scale = reader.read_f2dot14()?;
+                transform.xx = scale;
+                transform.yy = scale;
+            } else if flags & 0x40 != 0 {
+                // WE_HAVE_AN_X_AND_Y_SCALE
+                transform.xx = reader.read_f2dot14()?;
+                transform.yy = reader.read_f2dot14()?;
+            } else if flags & 0x80 != 0 {
+                // WE_HAVE_A_TWO_BY_TWO
+                transform.xx = reader.read_f2dot14()?;
+                transform.xy = reader.read_f2dot14()?;
+                transform.yx = reader.read_f2dot14()?;
+                transform.yy = reader.read_f2dot14()?;
             }
 
             components.push(GlyphComponent {
@@ -650,4 +1015,84 @@ impl CompositeGlyph {
 
         Ok(CompositeGlyph { components })
     }
+
+    fn write(&self, writer: &mut FontWriter) {
+        for (i, component) in self.components.iter().enumerate() {
+            let transform = &component.transform;
+
+            // Keep ARGS_ARE_XY_VALUES, ROUND_XY_TO_GRID, USE_MY_METRICS,
+            // OVERLAP_COMPOUND and the offset scaling bits; everything
+            // else is derived from the component data. Instructions are
+            // not retained, so WE_HAVE_INSTRUCTIONS is dropped.
+            let mut flags = component.flags & 0x1E06;
+
+            let words = i8::try_from(component.arg1).is_err() || i8::try_from(component.arg2).is_err();
+            if words {
+                flags |= 0x1;
+            }
+
+            if i + 1 < self.components.len() {
+                flags |= 0x20;
+            }
+
+            if transform.xy != 0.0 || transform.yx != 0.0 {
+                flags |= 0x80;
+            } else if transform.xx != transform.yy {
+                flags |= 0x40;
+            } else if transform.xx != 1.0 {
+                flags |= 0x8;
+            }
+
+            writer.write_u16(flags);
+            writer.write_u16(component.glyph_index);
+
+            if words {
+                writer.write_i16(component.arg1);
+                writer.write_i16(component.arg2);
+            } else {
+                writer.write_i8(component.arg1 as i8);
+                writer.write_i8(component.arg2 as i8);
+            }
+
+            if flags & 0x8 != 0 {
+                writer.write_f2dot14(transform.xx);
+            } else if flags & 0x40 != 0 {
+                writer.write_f2dot14(transform.xx);
+                writer.write_f2dot14(transform.yy);
+            } else if flags & 0x80 != 0 {
+                writer.write_f2dot14(transform.xx);
+                writer.write_f2dot14(transform.xy);
+                writer.write_f2dot14(transform.yx);
+                writer.write_f2dot14(transform.yy);
+            }
+        }
+    }
+}
+
+/// Encode one coordinate delta, returning the flag bits that describe it
+///
+/// `short_bit` marks a 1-byte delta and `same_bit` doubles as the sign bit
+/// for short deltas or the "same as previous" bit for zero deltas.
+fn encode_delta(delta: i16, short_bit: u8, same_bit: u8, writer: &mut FontWriter) -> u8 {
+    if delta == 0 {
+        same_bit
+    } else if (-255..=255).contains(&delta) {
+        writer.write_u8(delta.unsigned_abs() as u8);
+        if delta > 0 { short_bit | same_bit } else { short_bit }
+    } else {
+        writer.write_i16(delta);
+        0
+    }
+}
+
+impl TtfTableWrite for GlyfTable {
+    fn table_tag() -> &'static [u8; 4] {
+        b"glyf"
+    }
+
+    fn write(&self, writer: &mut FontWriter) -> Result<()> {
+        let (data, _) = self.to_bytes();
+        writer.write_bytes(&data);
+        Ok(())
+    }
 }
@@ -1,8 +1,17 @@
 use crate::error::Result;
 use crate::stream::FontReader;
 use crate::tables::TtfTable;
+use crate::tables::layout::{ClassDef, Coverage, read_lookup_list};
+
+/// Lookup type of pair adjustment positioning
+const PAIR_ADJUSTMENT: u16 = 2;
+/// Lookup type that wraps subtables of another type behind 32-bit offsets
+const EXTENSION_POSITIONING: u16 = 9;
 
 /// GPOS table - Glyph positioning
+///
+/// Only pair adjustment lookups (kerning) are parsed, and of their value
+/// records only the first glyph's x advance is kept.
 #[derive(Debug, Clone)]
 pub struct GposTable {
     pub version: u32,
@@ -10,27 +19,179 @@ pub struct GposTable {
     pub feature_list_offset: u32,
     pub lookup_list_offset: u32,
     pub feature_variations_offset: Option<u32>,
+    /// Subtables of each pair adjustment lookup, in lookup list order
+    pub pair_lookups: Vec<Vec<PairPos>>,
+}
+
+/// Pair adjustment positioning subtable
+#[derive(Debug, Clone)]
+pub enum PairPos {
+    /// Format 1: adjustments for individual glyph pairs
+    Glyphs {
+        coverage: Coverage,
+        /// Per covered first glyph: (second glyph, x advance) sorted by glyph
+        pair_sets: Vec<Vec<(u16, i16)>>,
+    },
+    /// Format 2: adjustments between glyph classes
+    Classes {
+        coverage: Coverage,
+        class_def1: ClassDef,
+        class_def2: ClassDef,
+        /// x advance indexed by [first class][second class]
+        x_advances: Vec<Vec<i16>>,
+    },
+}
+
+impl PairPos {
+    /// Get the x advance adjustment of the first glyph of a pair, if this
+    /// subtable covers the pair
+    pub fn x_advance(&self, left: u16, right: u16) -> Option<i16> {
+        match self {
+            PairPos::Glyphs { coverage, pair_sets } => {
+                let pairs = pair_sets.get(coverage.index(left)?)?;
+                let index = pairs.binary_search_by_key(&right, |&(glyph, _)| glyph).ok()?;
+                Some(pairs[index].1)
+            }
+            PairPos::Classes { coverage, class_def1, class_def2, x_advances } => {
+                coverage.index(left)?;
+                x_advances
+                    .get(class_def1.class(left) as usize)?
+                    .get(class_def2.class(right) as usize)
+                    .copied()
+            }
+        }
+    }
+
+    fn read(reader: &mut FontReader, offset: usize) -> Result<Option<Self>> {
+        reader.set_position(offset)?;
+        let format = reader.read_u16()?;
+        let coverage_offset = offset + reader.read_u16()? as usize;
+        let value_format1 = reader.read_u16()?;
+        let value_format2 = reader.read_u16()?;
+
+        let pair_pos = match format {
+            1 => {
+                let pair_set_count = reader.read_u16()?;
+                let mut pair_set_offsets = Vec::with_capacity(pair_set_count as usize);
+                for _ in 0..pair_set_count {
+                    pair_set_offsets.push(offset + reader.read_u16()? as usize);
+                }
+
+                let mut pair_sets = Vec::with_capacity(pair_set_offsets.len());
+                for pair_set_offset in pair_set_offsets {
+                    reader.set_position(pair_set_offset)?;
+                    let pair_value_count = reader.read_u16()?;
+                    let mut pairs = Vec::with_capacity(pair_value_count as usize);
+                    for _ in 0..pair_value_count {
+                        let second_glyph = reader.read_u16()?;
+                        let x_advance = read_x_advance(reader, value_format1)?;
+                        read_x_advance(reader, value_format2)?;
+                        pairs.push((second_glyph, x_advance));
+                    }
+                    pair_sets.push(pairs);
+                }
+
+                PairPos::Glyphs {
+                    coverage: Coverage::read(reader, coverage_offset)?,
+                    pair_sets,
+                }
+            }
+            2 => {
+                let class_def1_offset = offset + reader.read_u16()? as usize;
+                let class_def2_offset = offset + reader.read_u16()? as usize;
+                let class1_count = reader.read_u16()?;
+                let class2_count = reader.read_u16()?;
+
+                let mut x_advances = Vec::with_capacity(class1_count as usize);
+                for _ in 0..class1_count {
+                    let mut row = Vec::with_capacity(class2_count as usize);
+                    for _ in 0..class2_count {
+                        row.push(read_x_advance(reader, value_format1)?);
+                        read_x_advance(reader, value_format2)?;
+                    }
+                    x_advances.push(row);
+                }
+
+                PairPos::Classes {
+                    coverage: Coverage::read(reader, coverage_offset)?,
+                    class_def1: ClassDef::read(reader, class_def1_offset)?,
+                    class_def2: ClassDef::read(reader, class_def2_offset)?,
+                    x_advances,
+                }
+            }
+            // Unknown formats are skipped rather than failing the table
+            _ => return Ok(None),
+        };
+
+        Ok(Some(pair_pos))
+    }
+}
+
+/// Read a value record, returning its x advance (0 when absent)
+fn read_x_advance(reader: &mut FontReader, value_format: u16) -> Result<i16> {
+    let mut x_advance = 0;
+    // One 16-bit field per set bit: x/y placement, x/y advance, then four device offsets
+    for bit in 0..8 {
+        if value_format & (1 << bit) != 0 {
+            let value = reader.read_i16()?;
+            if bit == 2 {
+                x_advance = value;
+            }
+        }
+    }
+    Ok(x_advance)
+}
+
+impl GposTable {
+    /// Get the horizontal advance adjustment between two glyphs.
+    ///
+    /// In each pair adjustment lookup the first subtable covering the pair
+    /// applies; adjustments from separate lookups add up. Returns `None` if
+    /// no lookup covers the pair.
+    pub fn pair_adjustment(&self, left: u16, right: u16) -> Option<i16> {
+        self.pair_lookups
+            .iter()
+            .filter_map(|subtables| subtables.iter().find_map(|subtable| subtable.x_advance(left, right)))
+            .reduce(|total, adjustment| total.saturating_add(adjustment))
+    }
 }
 
 impl TtfTable for GposTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"GPOS")
+    }
+
     fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
         let version = reader.read_u32()?;
-        let script_list_offset = reader.read_u32()?;
-        let feature_list_offset = reader.read_u32()?;
-        let lookup_list_offset = reader.read_u32()?;
+        let script_list_offset = reader.read_u16()? as u32;
+        let feature_list_offset = reader.read_u16()? as u32;
+        let lookup_list_offset = reader.read_u16()? as u32;
 
-        let feature_variations_offset = if version >= 0x00010000 {
+        let feature_variations_offset = if version >= 0x00010001 {
             Some(reader.read_u32()?)
         } else {
             None
         };
 
+        let mut pair_lookups = Vec::new();
+        if lookup_list_offset != 0 {
+            let lookups = read_lookup_list(reader, lookup_list_offset as usize, EXTENSION_POSITIONING)?;
+            for lookup in lookups.iter().filter(|lookup| lookup.lookup_type == PAIR_ADJUSTMENT) {
+                let mut subtables = Vec::with_capacity(lookup.subtable_offsets.len());
+                for &offset in &lookup.subtable_offsets {
+                    subtables.extend(PairPos::read(reader, offset)?);
+                }
+                pair_lookups.push(subtables);
+            }
+        }
+
         Ok(GposTable {
             version,
             script_list_offset,
             feature_list_offset,
             lookup_list_offset,
             feature_variations_offset,
+            pair_lookups,
         })
     }
 }
@@ -1,8 +1,20 @@
 use crate::error::Result;
 use crate::stream::FontReader;
 use crate::tables::TtfTable;
+use crate::tables::layout::{Coverage, default_feature_lookups, read_lookup_list};
+
+/// Lookup type of ligature substitution
+const LIGATURE_SUBSTITUTION: u16 = 4;
+/// Lookup type that wraps subtables of another type behind 32-bit offsets
+const EXTENSION_SUBSTITUTION: u16 = 7;
+
+/// Ligature features applied by default
+const LIGATURE_FEATURES: [[u8; 4]; 3] = [*b"rlig", *b"liga", *b"clig"];
 
 /// GSUB table - Glyph substitution
+///
+/// Only ligature substitution lookups are parsed, and only those enabled by
+/// the ligature features of the default script and language system.
 #[derive(Debug, Clone)]
 pub struct GsubTable {
     pub version: u32,
@@ -10,27 +22,144 @@ pub struct GsubTable {
     pub feature_list_offset: u32,
     pub lookup_list_offset: u32,
     pub feature_variations_offset: Option<u32>,
+    /// Subtables of each enabled ligature lookup, in lookup list order
+    pub ligature_lookups: Vec<Vec<LigatureSubst>>,
+}
+
+/// Ligature substitution subtable
+#[derive(Debug, Clone)]
+pub struct LigatureSubst {
+    /// Covers the first glyph of each ligature
+    pub coverage: Coverage,
+    /// Per covered first glyph, the ligatures in order of preference
+    pub ligature_sets: Vec<Vec<Ligature>>,
+}
+
+/// A ligature and the glyphs it replaces
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ligature {
+    pub ligature_glyph: u16,
+    /// Component glyphs after the first (which is given by the coverage)
+    pub components: Vec<u16>,
+}
+
+impl LigatureSubst {
+    /// Find the ligature formed at the start of `glyphs`, returning the
+    /// ligature glyph and the number of glyphs it replaces
+    pub fn lookup(&self, glyphs: &[u16]) -> Option<(u16, usize)> {
+        let (&first, rest) = glyphs.split_first()?;
+        self.ligature_sets
+            .get(self.coverage.index(first)?)?
+            .iter()
+            .find(|ligature| rest.starts_with(&ligature.components))
+            .map(|ligature| (ligature.ligature_glyph, ligature.components.len() + 1))
+    }
+
+    fn read(reader: &mut FontReader, offset: usize) -> Result<Option<Self>> {
+        reader.set_position(offset)?;
+        let format = reader.read_u16()?;
+        if format != 1 {
+            // Unknown formats are skipped rather than failing the table
+            return Ok(None);
+        }
+        let coverage_offset = offset + reader.read_u16()? as usize;
+        let ligature_set_count = reader.read_u16()?;
+        let mut ligature_set_offsets = Vec::with_capacity(ligature_set_count as usize);
+        for _ in 0..ligature_set_count {
+            ligature_set_offsets.push(offset + reader.read_u16()? as usize);
+        }
+
+        let mut ligature_sets = Vec::with_capacity(ligature_set_offsets.len());
+        for set_offset in ligature_set_offsets {
+            reader.set_position(set_offset)?;
+            let ligature_count = reader.read_u16()?;
+            let mut ligature_offsets = Vec::with_capacity(ligature_count as usize);
+            for _ in 0..ligature_count {
+                ligature_offsets.push(set_offset + reader.read_u16()? as usize);
+            }
+
+            let mut ligatures = Vec::with_capacity(ligature_offsets.len());
+            for ligature_offset in ligature_offsets {
+                reader.set_position(ligature_offset)?;
+                let ligature_glyph = reader.read_u16()?;
+                let component_count = reader.read_u16()?;
+                let mut components = Vec::with_capacity(component_count.saturating_sub(1) as usize);
+                for _ in 1..component_count {
+                    components.push(reader.read_u16()?);
+                }
+                ligatures.push(Ligature {
+                    ligature_glyph,
+                    components,
+                });
+            }
+            ligature_sets.push(ligatures);
+        }
+
+        Ok(Some(LigatureSubst {
+            coverage: Coverage::read(reader, coverage_offset)?,
+            ligature_sets,
+        }))
+    }
+}
+
+impl GsubTable {
+    /// Find the ligature formed at the start of `glyphs`.
+    ///
+    /// Returns the ligature glyph and how many input glyphs it consumes,
+    /// from the first lookup (in lookup list order) that forms one.
+    pub fn lookup_ligature(&self, glyphs: &[u16]) -> Option<(u16, usize)> {
+        self.ligature_lookups
+            .iter()
+            .flatten()
+            .find_map(|subtable| subtable.lookup(glyphs))
+    }
 }
 
 impl TtfTable for GsubTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"GSUB")
+    }
+
     fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
         let version = reader.read_u32()?;
-        let script_list_offset = reader.read_u32()?;
-        let feature_list_offset = reader.read_u32()?;
-        let lookup_list_offset = reader.read_u32()?;
+        let script_list_offset = reader.read_u16()? as u32;
+        let feature_list_offset = reader.read_u16()? as u32;
+        let lookup_list_offset = reader.read_u16()? as u32;
 
-        let feature_variations_offset = if version >= 0x00010000 {
+        let feature_variations_offset = if version >= 0x00010001 {
             Some(reader.read_u32()?)
         } else {
             None
         };
 
+        let mut ligature_lookups = Vec::new();
+        if lookup_list_offset != 0 {
+            let enabled = default_feature_lookups(
+                reader,
+                script_list_offset as usize,
+                feature_list_offset as usize,
+                &LIGATURE_FEATURES,
+            )?;
+            let lookups = read_lookup_list(reader, lookup_list_offset as usize, EXTENSION_SUBSTITUTION)?;
+            for (index, lookup) in lookups.iter().enumerate() {
+                if lookup.lookup_type != LIGATURE_SUBSTITUTION || !enabled.contains(&(index as u16)) {
+                    continue;
+                }
+                let mut subtables = Vec::with_capacity(lookup.subtable_offsets.len());
+                for &offset in &lookup.subtable_offsets {
+                    subtables.extend(LigatureSubst::read(reader, offset)?);
+                }
+                ligature_lookups.push(subtables);
+            }
+        }
+
         Ok(GsubTable {
             version,
             script_list_offset,
             feature_list_offset,
             lookup_list_offset,
             feature_variations_offset,
+            ligature_lookups,
         })
     }
 }
@@ -1,43 +1,362 @@
-use crate::error::Result;
+use crate::error::{Result, TtfError};
 use crate::stream::FontReader;
 use crate::tables::TtfTable;
+use crate::tables::glyf::SimpleGlyph;
 
 /// GVAR table - Glyph variations
+///
+/// Per-glyph variation data is kept in its serialized form and decoded on
+/// demand, since decoding needs the glyph's point count.
 #[derive(Debug, Clone)]
 pub struct GvarTable {
-    pub version: u32,
-    pub axes_array_offset: u16,
-    pub shared_tuples_count: u16,
-    pub shared_tuples_offset: u32,
-    pub glyph_variation_data: Vec<GlyphVariationData>,
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub axis_count: u16,
+    pub shared_tuples: Vec<Vec<f32>>,
+    pub glyph_variation_data: Vec<Vec<u8>>,
 }
 
+/// One tuple variation of a glyph: a region of the design space and the
+/// point deltas that apply at its peak
 #[derive(Debug, Clone)]
-pub struct GlyphVariationData {
-    pub glyph_id: u16,
-    pub variations: Vec<Variation>,
+pub struct TupleVariation {
+    pub peak_tuple: Vec<f32>,
+    pub intermediate_region: Option<(Vec<f32>, Vec<f32>)>,
+    /// Point numbers the deltas apply to; `None` means all points in order
+    pub point_numbers: Option<Vec<u16>>,
+    pub x_deltas: Vec<i16>,
+    pub y_deltas: Vec<i16>,
 }
 
-#[derive(Debug, Clone)]
-pub struct Variation {
-    pub tuple_index: u16,
-    pub variation_data: Vec<u8>,
+impl GvarTable {
+    /// Decode the tuple variations of a glyph
+    ///
+    /// `num_points` is the number of outline points plus the four phantom
+    /// points; it sizes the delta arrays of tuples that apply to all points.
+    pub fn glyph_variations(&self, glyph_id: u16, num_points: usize) -> Result<Vec<TupleVariation>> {
+        let data = self
+            .glyph_variation_data
+            .get(glyph_id as usize)
+            .ok_or(TtfError::InvalidGlyphIndex(glyph_id))?;
+        if data.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut reader = FontReader::from_slice(data);
+        let tuple_variation_count = reader.read_u16()?;
+        let data_offset = reader.read_u16()? as usize;
+        let has_shared_point_numbers = tuple_variation_count & 0x8000 != 0;
+
+        // Tuple variation headers
+        let mut headers = Vec::new();
+        for _ in 0..tuple_variation_count & 0x0FFF {
+            let variation_data_size = reader.read_u16()? as usize;
+            let tuple_index = reader.read_u16()?;
+
+            let peak_tuple = if tuple_index & 0x8000 != 0 {
+                self.read_tuple(&mut reader)?
+            } else {
+                self.shared_tuples
+                    .get((tuple_index & 0x0FFF) as usize)
+                    .cloned()
+                    .ok_or_else(|| TtfError::ParseError(format!(
+                        "Shared tuple index {} out of range", tuple_index & 0x0FFF
+                    )))?
+            };
+
+            let intermediate_region = if tuple_index & 0x4000 != 0 {
+                Some((self.read_tuple(&mut reader)?, self.read_tuple(&mut reader)?))
+            } else {
+                None
+            };
+
+            headers.push((variation_data_size, tuple_index & 0x2000 != 0, peak_tuple, intermediate_region));
+        }
+
+        // Serialized data: optional shared point numbers, then per-tuple data
+        reader.set_position(data_offset)?;
+        let shared_point_numbers = if has_shared_point_numbers {
+            read_packed_points(&mut reader)?
+        } else {
+            None
+        };
+
+        let mut variations = Vec::with_capacity(headers.len());
+        for (variation_data_size, has_private_points, peak_tuple, intermediate_region) in headers {
+            let start = reader.position();
+
+            let point_numbers = if has_private_points {
+                read_packed_points(&mut reader)?
+            } else {
+                shared_point_numbers.clone()
+            };
+
+            let count = point_numbers.as_ref().map_or(num_points, |points| points.len());
+            let x_deltas = read_packed_deltas(&mut reader, count)?;
+            let y_deltas = read_packed_deltas(&mut reader, count)?;
+
+            variations.push(TupleVariation {
+                peak_tuple,
+                intermediate_region,
+                point_numbers,
+                x_deltas,
+                y_deltas,
+            });
+
+            reader.set_position(start + variation_data_size)?;
+        }
+
+        Ok(variations)
+    }
+
+    fn read_tuple(&self, reader: &mut FontReader) -> Result<Vec<f32>> {
+        let mut tuple = Vec::with_capacity(self.axis_count as usize);
+        for _ in 0..self.axis_count {
+            tuple.push(reader.read_f2dot14()?);
+        }
+        Ok(tuple)
+    }
+}
+
+impl TupleVariation {
+    /// Scalar applied to this tuple's deltas at the given normalized coordinates
+    pub fn scalar(&self, coords: &[f32]) -> f32 {
+        let mut scalar = 1.0;
+
+        for (axis, &peak) in self.peak_tuple.iter().enumerate() {
+            let coord = coords.get(axis).copied().unwrap_or(0.0);
+            if peak == 0.0 || coord == peak {
+                continue;
+            }
+
+            let (start, end) = match &self.intermediate_region {
+                Some((start, end)) => (start[axis], end[axis]),
+                None => (peak.min(0.0), peak.max(0.0)),
+            };
+
+            // Malformed intermediate regions don't constrain the axis
+            if start > peak || peak > end || (start < 0.0 && end > 0.0) {
+                continue;
+            }
+
+            if coord < start || coord > end {
+                return 0.0;
+            }
+
+            scalar *= if coord < peak {
+                (coord - start) / (peak - start)
+            } else {
+                (end - coord) / (end - peak)
+            };
+        }
+
+        scalar
+    }
+
+    /// Unscaled deltas for every outline point of `glyph`
+    ///
+    /// Points not referenced by this tuple get deltas inferred from their
+    /// neighbours on the same contour (interpolation of untouched points).
+    pub fn point_deltas(&self, glyph: &SimpleGlyph) -> Vec<(f32, f32)> {
+        let num_points = glyph.x_coordinates.len();
+        let mut deltas = vec![(0.0, 0.0); num_points];
+        let mut touched = vec![false; num_points];
+
+        match &self.point_numbers {
+            None => {
+                for (i, delta) in deltas.iter_mut().enumerate() {
+                    *delta = (
+                        self.x_deltas.get(i).copied().unwrap_or(0) as f32,
+                        self.y_deltas.get(i).copied().unwrap_or(0) as f32,
+                    );
+                }
+                return deltas;
+            }
+            Some(points) => {
+                for (i, &point) in points.iter().enumerate() {
+                    // Phantom points and stray numbers are ignored
+                    if let Some(delta) = deltas.get_mut(point as usize) {
+                        delta.0 += self.x_deltas[i] as f32;
+                        delta.1 += self.y_deltas[i] as f32;
+                        touched[point as usize] = true;
+                    }
+                }
+            }
+        }
+
+        let mut start = 0;
+        for &end_pt in &glyph.end_pts_of_contours {
+            let end = (end_pt as usize).min(num_points.saturating_sub(1));
+            if start <= end {
+                infer_untouched(glyph, start, end, &touched, &mut deltas);
+            }
+            start = end_pt as usize + 1;
+        }
+
+        deltas
+    }
+}
+
+/// Interpolate deltas of untouched points on the contour `start..=end`
+fn infer_untouched(glyph: &SimpleGlyph, start: usize, end: usize, touched: &[bool], deltas: &mut [(f32, f32)]) {
+    let touched_points: Vec<usize> = (start..=end).filter(|&i| touched[i]).collect();
+    if touched_points.is_empty() || touched_points.len() == end - start + 1 {
+        return;
+    }
+
+    for i in start..=end {
+        if touched[i] {
+            continue;
+        }
+
+        // Nearest touched points before and after, wrapping around the contour
+        let prev = touched_points.iter().rev().find(|&&t| t < i).or(touched_points.last()).copied().unwrap();
+        let next = touched_points.iter().find(|&&t| t > i).or(touched_points.first()).copied().unwrap();
+
+        deltas[i].0 = infer_delta(
+            glyph.x_coordinates[i], glyph.x_coordinates[prev], glyph.x_coordinates[next],
+            deltas[prev].0, deltas[next].0,
+        );
+        deltas[i].1 = infer_delta(
+            glyph.y_coordinates[i], glyph.y_coordinates[prev], glyph.y_coordinates[next],
+            deltas[prev].1, deltas[next].1,
+        );
+    }
+}
+
+/// Infer the delta of one coordinate from two reference points
+fn infer_delta(coord: i16, prev_coord: i16, next_coord: i16, prev_delta: f32, next_delta: f32) -> f32 {
+    if prev_coord == next_coord {
+        return if prev_delta == next_delta { prev_delta } else { 0.0 };
+    }
+
+    let (low_coord, low_delta, high_coord, high_delta) = if prev_coord < next_coord {
+        (prev_coord, prev_delta, next_coord, next_delta)
+    } else {
+        (next_coord, next_delta, prev_coord, prev_delta)
+    };
+
+    if coord <= low_coord {
+        low_delta
+    } else if coord >= high_coord {
+        high_delta
+    } else {
+        let t = (coord - low_coord) as f32 / (high_coord - low_coord) as f32;
+        low_delta + t * (high_delta - low_delta)
+    }
+}
+
+/// Read packed point numbers; `None` means all points
+fn read_packed_points(reader: &mut FontReader) -> Result<Option<Vec<u16>>> {
+    let first = reader.read_u8()? as usize;
+    if first == 0 {
+        return Ok(None);
+    }
+
+    let count = if first & 0x80 != 0 {
+        ((first & 0x7F) << 8) | reader.read_u8()? as usize
+    } else {
+        first
+    };
+
+    let mut points = Vec::with_capacity(count);
+    let mut point = 0u16;
+    while points.len() < count {
+        let control = reader.read_u8()?;
+        let run_count = (control & 0x7F) as usize + 1;
+
+        for _ in 0..run_count.min(count - points.len()) {
+            let delta = if control & 0x80 != 0 {
+                reader.read_u16()?
+            } else {
+                reader.read_u8()? as u16
+            };
+            point = point.wrapping_add(delta);
+            points.push(point);
+        }
+    }
+
+    Ok(Some(points))
+}
+
+/// Read `count` packed deltas
+fn read_packed_deltas(reader: &mut FontReader, count: usize) -> Result<Vec<i16>> {
+    let mut deltas = Vec::with_capacity(count);
+    while deltas.len() < count {
+        let control = reader.read_u8()?;
+        let run_count = (control & 0x3F) as usize + 1;
+
+        for _ in 0..run_count.min(count - deltas.len()) {
+            let delta = if control & 0x80 != 0 {
+                0
+            } else if control & 0x40 != 0 {
+                reader.read_i16()?
+            } else {
+                reader.read_i8()? as i16
+            };
+            deltas.push(delta);
+        }
+    }
+
+    Ok(deltas)
 }
 
 impl TtfTable for GvarTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"gvar")
+    }
+
     fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
-        let version = reader.read_u32()?;
-        let axes_array_offset = reader.read_u16()?;
-        let shared_tuples_count = reader.read_u16()?;
+        let major_version = reader.read_u16()?;
+        let minor_version = reader.read_u16()?;
+        if major_version != 1 {
+            return Err(TtfError::UnsupportedVersion(major_version as u32));
+        }
+
+        let axis_count = reader.read_u16()?;
+        let shared_tuple_count = reader.read_u16()?;
         let shared_tuples_offset = reader.read_u32()?;
+        let glyph_count = reader.read_u16()?;
+        let flags = reader.read_u16()?;
+        let glyph_variation_data_array_offset = reader.read_u32()? as usize;
+
+        // Offsets are u16 (stored divided by 2) unless bit 0 of flags is set
+        let mut offsets = Vec::with_capacity(glyph_count as usize + 1);
+        for _ in 0..=glyph_count {
+            let offset = if flags & 0x1 != 0 {
+                reader.read_u32()?
+            } else {
+                reader.read_u16()? as u32 * 2
+            };
+            offsets.push(offset as usize);
+        }
+
+        let mut shared_tuples = Vec::with_capacity(shared_tuple_count as usize);
+        reader.set_position(shared_tuples_offset as usize)?;
+        for _ in 0..shared_tuple_count {
+            let mut tuple = Vec::with_capacity(axis_count as usize);
+            for _ in 0..axis_count {
+                tuple.push(reader.read_f2dot14()?);
+            }
+            shared_tuples.push(tuple);
+        }
+
+        let mut glyph_variation_data = Vec::with_capacity(glyph_count as usize);
+        for window in offsets.windows(2) {
+            let (start, end) = (window[0], window[1]);
+            if end < start {
+                return Err(TtfError::InvalidOffset(end as u64));
+            }
+            reader.set_position(glyph_variation_data_array_offset + start)?;
+            glyph_variation_data.push(reader.read_bytes(end - start)?);
+        }
 
-        // Simplified - full implementation would parse all variation data
         Ok(GvarTable {
-            version,
-            axes_array_offset,
-            shared_tuples_count,
-            shared_tuples_offset,
-            glyph_variation_data: Vec::new(),
+            major_version,
+            minor_version,
+            axis_count,
+            shared_tuples,
+            glyph_variation_data,
         })
     }
 }
@@ -1,6 +1,7 @@
 use crate::error::{Result, TtfError};
 use crate::stream::{FontReader, FontWriter};
 use crate::tables::{TtfTable, TtfTableWrite};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// HEAD table - Font header
 #[derive(Debug, Clone)]
@@ -27,6 +28,54 @@ pub struct HeadTable {
 impl HeadTable {
     pub const MAGIC_NUMBER: u32 = 0x5F0F3CF5;
 
+    /// Seconds from the head date epoch (1904-01-01 00:00 UTC) to the Unix epoch
+    pub const MAC_EPOCH_OFFSET: i64 = 2_082_844_800;
+
+    /// Creation time of the font
+    pub fn created_datetime(&self) -> SystemTime {
+        Self::to_system_time(self.created)
+    }
+
+    /// Last modification time of the font
+    pub fn modified_datetime(&self) -> SystemTime {
+        Self::to_system_time(self.modified)
+    }
+
+    /// Creation time of the font as a UTC date and time
+    #[cfg(feature = "chrono")]
+    pub fn created_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        chrono::DateTime::from_timestamp(Self::to_unix_seconds(self.created), 0)
+    }
+
+    /// Last modification time of the font as a UTC date and time
+    #[cfg(feature = "chrono")]
+    pub fn modified_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        chrono::DateTime::from_timestamp(Self::to_unix_seconds(self.modified), 0)
+    }
+
+    /// Convert a LONGDATETIME (signed seconds since 1904) to Unix seconds
+    fn to_unix_seconds(long_datetime: u64) -> i64 {
+        (long_datetime as i64).saturating_sub(Self::MAC_EPOCH_OFFSET)
+    }
+
+    /// Convert a time to a LONGDATETIME, as stored in `created`/`modified`
+    pub(crate) fn long_datetime(time: SystemTime) -> u64 {
+        let unix_seconds = match time.duration_since(UNIX_EPOCH) {
+            Ok(since) => since.as_secs() as i64,
+            Err(before) => -(before.duration().as_secs() as i64),
+        };
+        unix_seconds.saturating_add(Self::MAC_EPOCH_OFFSET) as u64
+    }
+
+    fn to_system_time(long_datetime: u64) -> SystemTime {
+        let seconds = Self::to_unix_seconds(long_datetime);
+        if seconds >= 0 {
+            UNIX_EPOCH + Duration::from_secs(seconds as u64)
+        } else {
+            UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs())
+        }
+    }
+
     pub fn is_short_loca_format(&self) -> bool {
         self.index_to_loc_format == 0
     }
@@ -37,6 +86,10 @@ impl HeadTable {
 }
 
 impl TtfTable for HeadTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"head")
+    }
+
     fn from_reader(reader: &mut FontReader, length: u32) -> Result<Self> {
         let start_pos = reader.position();
 
@@ -46,10 +99,14 @@ impl TtfTable for HeadTable {
         let magic_number = reader.read_u32()?;
 
         if magic_number != Self::MAGIC_NUMBER {
-            return Err(TtfError::ParseError(format!(
-                "Invalid magic number in head table: expected {:#x}, got {:#x}",
-                Self::MAGIC_NUMBER, magic_number
-            )));
+            return Err(TtfError::TableParseError {
+                table: "head".to_string(),
+                offset: start_pos as u64 + 12,
+                message: format!(
+                    "invalid magic number: expected {:#x}, got {:#x}",
+                    Self::MAGIC_NUMBER, magic_number
+                ),
+            });
         }
 
         let flags = reader.read_u16()?;
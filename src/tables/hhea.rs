@@ -20,7 +20,6 @@ pub struct HheaTable {
     pub reserved1: i16,
     pub reserved2: i16,
     pub reserved3: i16,
-    pub reserved4: i16,
     pub metric_data_format: i16,
     pub number_of_h_metrics: u16,
 }
@@ -42,6 +41,10 @@ impl HheaTable {
 }
 
 impl TtfTable for HheaTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"hhea")
+    }
+
     fn from_reader(reader: &mut FontReader, length: u32) -> Result<Self> {
         let table_version = reader.read_fixed()?;
         let ascent = reader.read_i16()?;
@@ -58,7 +61,6 @@ impl TtfTable for HheaTable {
         let reserved1 = reader.read_i16()?;
         let reserved2 = reader.read_i16()?;
         let reserved3 = reader.read_i16()?;
-        let reserved4 = reader.read_i16()?;
         let metric_data_format = reader.read_i16()?;
         let number_of_h_metrics = reader.read_u16()?;
 
@@ -84,7 +86,6 @@ impl TtfTable for HheaTable {
             reserved1,
             reserved2,
             reserved3,
-            reserved4,
             metric_data_format,
             number_of_h_metrics,
         })
@@ -112,7 +113,6 @@ impl TtfTableWrite for HheaTable {
         writer.write_i16(self.reserved1);
         writer.write_i16(self.reserved2);
         writer.write_i16(self.reserved3);
-        writer.write_i16(self.reserved4);
         writer.write_i16(self.metric_data_format);
         writer.write_u16(self.number_of_h_metrics);
         Ok(())
@@ -19,6 +19,10 @@ pub struct VvarTable {
 }
 
 impl TtfTable for HvarTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"HVAR")
+    }
+
     fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
         let version = reader.read_u32()?;
         let variation_region_list_offset = reader.read_u32()?;
@@ -33,6 +37,10 @@ impl TtfTable for HvarTable {
 }
 
 impl TtfTable for VvarTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"VVAR")
+    }
+
     fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
         let version = reader.read_u32()?;
         let variation_region_list_offset = reader.read_u32()?;
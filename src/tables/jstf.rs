@@ -13,6 +13,10 @@ pub struct JstfTable {
 }
 
 impl TtfTable for JstfTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"JSTF")
+    }
+
     fn from_reader(reader: &mut FontReader, length: u32) -> Result<Self> {
         let version = reader.read_u32()?;
         let gsub_lookup_count = reader.read_u16()?;
@@ -32,19 +32,53 @@ pub struct KernPair {
 
 impl KernFormat0 {
     pub fn is_horizontal(&self) -> bool {
-        self.coverage & 0x80 == 0
+        self.coverage & 0x1 != 0
     }
 
     pub fn is_minimum(&self) -> bool {
-        self.coverage & 0x40 == 0
+        self.coverage & 0x2 != 0
     }
 
     pub fn is_cross_stream(&self) -> bool {
-        self.coverage & 0x20 != 0
+        self.coverage & 0x4 != 0
+    }
+
+    /// Get the kerning value for a pair of glyphs
+    pub fn get_kerning(&self, left: u16, right: u16) -> Option<i16> {
+        // Pairs are sorted by the combined (left, right) key
+        self.pairs
+            .binary_search_by_key(&(left, right), |pair| (pair.left, pair.right))
+            .ok()
+            .map(|index| self.pairs[index].value)
+    }
+}
+
+impl KernTable {
+    /// Get the horizontal kerning adjustment between two glyphs
+    ///
+    /// Sums the values of all horizontal format 0 subtables; minimum and
+    /// cross-stream subtables don't adjust advances and are skipped.
+    pub fn get_kerning(&self, left: u16, right: u16) -> i16 {
+        self.subtables
+            .iter()
+            .map(|subtable| match subtable {
+                KernSubtable::Format0(format0) => {
+                    if format0.is_horizontal() && !format0.is_minimum() && !format0.is_cross_stream() {
+                        format0.get_kerning(left, right).unwrap_or(0)
+                    } else {
+                        0
+                    }
+                }
+            })
+            .fold(0i16, |total, value| total.saturating_add(value))
     }
 }
 
 impl TtfTable for KernTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"kern")
+    }
+
     fn from_reader(reader: &mut FontReader, length: u32) -> Result<Self> {
         let version = reader.read_u16()?;
         let num_tables = reader.read_u16()?;
@@ -56,7 +90,8 @@ impl TtfTable for KernTable {
             let subtable_length = reader.read_u16()?;
             let coverage = reader.read_u16()?;
 
-            let subtable = if (coverage & 0xFF) == 0 {
+            // The format lives in the high byte of coverage
+            let subtable = if coverage >> 8 == 0 {
                 // Format 0
                 let num_pairs = reader.read_u16()?;
                 let search_range = reader.read_u16()?;
@@ -80,7 +115,7 @@ impl TtfTable for KernTable {
                 })
             } else {
                 // Skip unsupported formats
-                let remaining = subtable_length.saturating_sub(8) as usize;
+                let remaining = subtable_length.saturating_sub(6) as usize;
                 reader.skip(remaining)?;
                 continue;
             };
@@ -0,0 +1,224 @@
+// Structures shared by the OpenType layout tables (GPOS, GSUB)
+
+use crate::error::{Result, TtfError};
+use crate::stream::FontReader;
+
+/// Coverage table - the glyphs a subtable applies to
+#[derive(Debug, Clone)]
+pub enum Coverage {
+    /// Sorted glyph list; the coverage index is the position in the list
+    Glyphs(Vec<u16>),
+    /// (start glyph, end glyph, coverage index of the start glyph)
+    Ranges(Vec<(u16, u16, u16)>),
+}
+
+impl Coverage {
+    /// Read a coverage table at `offset` (from the start of the table)
+    pub fn read(reader: &mut FontReader, offset: usize) -> Result<Self> {
+        reader.set_position(offset)?;
+        let format = reader.read_u16()?;
+        let count = reader.read_u16()?;
+
+        match format {
+            1 => {
+                let mut glyphs = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    glyphs.push(reader.read_u16()?);
+                }
+                Ok(Coverage::Glyphs(glyphs))
+            }
+            2 => {
+                let mut ranges = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    ranges.push((reader.read_u16()?, reader.read_u16()?, reader.read_u16()?));
+                }
+                Ok(Coverage::Ranges(ranges))
+            }
+            _ => Err(TtfError::ParseError(format!("Unsupported coverage format: {}", format))),
+        }
+    }
+
+    /// Get the coverage index of a glyph, or `None` if it isn't covered
+    pub fn index(&self, glyph_id: u16) -> Option<usize> {
+        match self {
+            Coverage::Glyphs(glyphs) => glyphs.binary_search(&glyph_id).ok(),
+            Coverage::Ranges(ranges) => ranges
+                .iter()
+                .find(|&&(start, end, _)| (start..=end).contains(&glyph_id))
+                .map(|&(start, _, start_index)| (start_index + (glyph_id - start)) as usize),
+        }
+    }
+}
+
+/// Class definition table - assigns glyphs to classes
+#[derive(Debug, Clone)]
+pub enum ClassDef {
+    /// Classes of the consecutive glyphs from a start glyph
+    Array { start_glyph_id: u16, classes: Vec<u16> },
+    /// (start glyph, end glyph, class)
+    Ranges(Vec<(u16, u16, u16)>),
+}
+
+impl ClassDef {
+    /// Read a class definition table at `offset` (from the start of the table)
+    pub fn read(reader: &mut FontReader, offset: usize) -> Result<Self> {
+        reader.set_position(offset)?;
+        let format = reader.read_u16()?;
+
+        match format {
+            1 => {
+                let start_glyph_id = reader.read_u16()?;
+                let glyph_count = reader.read_u16()?;
+                let mut classes = Vec::with_capacity(glyph_count as usize);
+                for _ in 0..glyph_count {
+                    classes.push(reader.read_u16()?);
+                }
+                Ok(ClassDef::Array { start_glyph_id, classes })
+            }
+            2 => {
+                let range_count = reader.read_u16()?;
+                let mut ranges = Vec::with_capacity(range_count as usize);
+                for _ in 0..range_count {
+                    ranges.push((reader.read_u16()?, reader.read_u16()?, reader.read_u16()?));
+                }
+                Ok(ClassDef::Ranges(ranges))
+            }
+            _ => Err(TtfError::ParseError(format!("Unsupported class definition format: {}", format))),
+        }
+    }
+
+    /// Get the class of a glyph; glyphs not listed are in class 0
+    pub fn class(&self, glyph_id: u16) -> u16 {
+        match self {
+            ClassDef::Array { start_glyph_id, classes } => glyph_id
+                .checked_sub(*start_glyph_id)
+                .and_then(|index| classes.get(index as usize))
+                .copied()
+                .unwrap_or(0),
+            ClassDef::Ranges(ranges) => ranges
+                .iter()
+                .find(|&&(start, end, _)| (start..=end).contains(&glyph_id))
+                .map_or(0, |&(_, _, class)| class),
+        }
+    }
+}
+
+/// A lookup of the lookup list, with extension subtables already resolved
+#[derive(Debug, Clone)]
+pub(crate) struct Lookup {
+    pub lookup_type: u16,
+    /// Subtable offsets from the start of the table
+    pub subtable_offsets: Vec<usize>,
+}
+
+/// Read the lookup list at `lookup_list_offset`.
+///
+/// Subtables of `extension_type` lookups (7 in GSUB, 9 in GPOS) are
+/// replaced by the subtables they point to, and take on their type.
+pub(crate) fn read_lookup_list(
+    reader: &mut FontReader,
+    lookup_list_offset: usize,
+    extension_type: u16,
+) -> Result<Vec<Lookup>> {
+    reader.set_position(lookup_list_offset)?;
+    let lookup_count = reader.read_u16()?;
+    let mut lookup_offsets = Vec::with_capacity(lookup_count as usize);
+    for _ in 0..lookup_count {
+        lookup_offsets.push(lookup_list_offset + reader.read_u16()? as usize);
+    }
+
+    let mut lookups = Vec::with_capacity(lookup_offsets.len());
+    for lookup_offset in lookup_offsets {
+        reader.set_position(lookup_offset)?;
+        let mut lookup_type = reader.read_u16()?;
+        let _lookup_flag = reader.read_u16()?;
+        let subtable_count = reader.read_u16()?;
+        let mut subtable_offsets = Vec::with_capacity(subtable_count as usize);
+        for _ in 0..subtable_count {
+            subtable_offsets.push(lookup_offset + reader.read_u16()? as usize);
+        }
+
+        if lookup_type == extension_type {
+            for offset in &mut subtable_offsets {
+                reader.set_position(*offset)?;
+                let _format = reader.read_u16()?;
+                lookup_type = reader.read_u16()?;
+                *offset += reader.read_u32()? as usize;
+            }
+        }
+
+        lookups.push(Lookup {
+            lookup_type,
+            subtable_offsets,
+        });
+    }
+
+    Ok(lookups)
+}
+
+/// Get the lookup indices, sorted, of the given features in the default
+/// language system of the default script ('DFLT', else the first script).
+///
+/// The language system's required feature is included whatever its tag.
+pub(crate) fn default_feature_lookups(
+    reader: &mut FontReader,
+    script_list_offset: usize,
+    feature_list_offset: usize,
+    feature_tags: &[[u8; 4]],
+) -> Result<Vec<u16>> {
+    if script_list_offset == 0 || feature_list_offset == 0 {
+        return Ok(Vec::new());
+    }
+
+    reader.set_position(script_list_offset)?;
+    let script_count = reader.read_u16()?;
+    let mut scripts = Vec::with_capacity(script_count as usize);
+    for _ in 0..script_count {
+        scripts.push((reader.read_tag()?, script_list_offset + reader.read_u16()? as usize));
+    }
+    let script_offset = match scripts.iter().find(|(tag, _)| tag == b"DFLT").or(scripts.first()) {
+        Some(&(_, offset)) => offset,
+        None => return Ok(Vec::new()),
+    };
+
+    reader.set_position(script_offset)?;
+    let default_lang_sys_offset = reader.read_u16()? as usize;
+    if default_lang_sys_offset == 0 {
+        return Ok(Vec::new());
+    }
+    reader.set_position(script_offset + default_lang_sys_offset)?;
+    let _lookup_order_offset = reader.read_u16()?;
+    let required_feature_index = reader.read_u16()?;
+    let feature_index_count = reader.read_u16()?;
+    let mut feature_indices = Vec::with_capacity(feature_index_count as usize);
+    for _ in 0..feature_index_count {
+        feature_indices.push(reader.read_u16()?);
+    }
+
+    reader.set_position(feature_list_offset)?;
+    let feature_count = reader.read_u16()?;
+    let mut features = Vec::with_capacity(feature_count as usize);
+    for _ in 0..feature_count {
+        features.push((reader.read_tag()?, feature_list_offset + reader.read_u16()? as usize));
+    }
+
+    let mut lookup_indices = Vec::new();
+    for (i, (tag, offset)) in features.into_iter().enumerate() {
+        let i = i as u16;
+        let enabled = feature_indices.contains(&i) && feature_tags.contains(&tag);
+        if !enabled && i != required_feature_index {
+            continue;
+        }
+
+        reader.set_position(offset)?;
+        let _feature_params_offset = reader.read_u16()?;
+        let lookup_index_count = reader.read_u16()?;
+        for _ in 0..lookup_index_count {
+            lookup_indices.push(reader.read_u16()?);
+        }
+    }
+
+    lookup_indices.sort_unstable();
+    lookup_indices.dedup();
+    Ok(lookup_indices)
+}
@@ -1,5 +1,5 @@
-use crate::error::Result;
-use crate::stream::FontReader;
+use crate::error::{Result, TtfError};
+use crate::stream::{FontReader, FontWriter};
 
 /// LOCA table - Index to location
 #[derive(Debug, Clone)]
@@ -29,15 +29,47 @@ impl LocaTable {
         match self {
             LocaTable::Short(offsets) => {
                 let offset = offsets.get(index).copied().ok_or_else(|| {
-                    crate::error::TtfError::InvalidGlyphIndex(index as u16)
+                    TtfError::InvalidGlyphIndex(index as u16)
                 })?;
                 Ok(offset as u32 * 2)
             }
             LocaTable::Long(offsets) => {
                 offsets.get(index).copied().ok_or_else(|| {
-                    crate::error::TtfError::InvalidGlyphIndex(index as u16)
+                    TtfError::InvalidGlyphIndex(index as u16)
                 })
             }
         }
     }
+
+    /// Byte offsets into the glyf table, one per glyph plus the end offset
+    pub fn offsets(&self) -> Vec<u32> {
+        match self {
+            LocaTable::Short(offsets) => offsets.iter().map(|&offset| offset as u32 * 2).collect(),
+            LocaTable::Long(offsets) => offsets.clone(),
+        }
+    }
+
+    /// Serialize in the short (offset / 2 as u16) or long (u32) format,
+    /// matching head.indexToLocFormat
+    pub fn to_bytes(&self, is_long: bool) -> Vec<u8> {
+        let mut writer = FontWriter::new();
+        for offset in self.offsets() {
+            if is_long {
+                writer.write_u32(offset);
+            } else {
+                writer.write_u16((offset / 2) as u16);
+            }
+        }
+        writer.into_inner()
+    }
+
+    /// Check that offsets never decrease; a decreasing offset gives a
+    /// glyph a negative length
+    pub fn validate(&self) -> Result<()> {
+        let offsets = self.offsets();
+        match offsets.windows(2).find(|pair| pair[1] < pair[0]) {
+            Some(pair) => Err(TtfError::InvalidLocaOffset(pair[1])),
+            None => Ok(()),
+        }
+    }
 }
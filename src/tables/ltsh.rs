@@ -0,0 +1,42 @@
+use crate::error::Result;
+use crate::stream::FontReader;
+use crate::tables::TtfTable;
+
+/// LTSH table - Linear threshold
+///
+/// For each glyph, the ppem at and above which its advance width scales
+/// linearly, so hinting no longer changes it.
+#[derive(Debug, Clone)]
+pub struct LtshTable {
+    pub version: u16,
+    pub num_glyphs: u16,
+    pub y_pels: Vec<u8>,
+}
+
+impl LtshTable {
+    /// Get the linear threshold of a glyph in ppem, or `None` for glyphs
+    /// past the end of the table
+    ///
+    /// A threshold of 1 means the glyph always scales linearly.
+    pub fn linear_threshold(&self, glyph_id: u16) -> Option<u8> {
+        self.y_pels.get(glyph_id as usize).copied()
+    }
+}
+
+impl TtfTable for LtshTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"LTSH")
+    }
+
+    fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
+        let version = reader.read_u16()?;
+        let num_glyphs = reader.read_u16()?;
+        let y_pels = reader.read_bytes(num_glyphs as usize)?;
+
+        Ok(LtshTable {
+            version,
+            num_glyphs,
+            y_pels,
+        })
+    }
+}
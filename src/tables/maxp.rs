@@ -1,6 +1,6 @@
 use crate::error::Result;
-use crate::stream::FontReader;
-use crate::tables::TtfTable;
+use crate::stream::{FontReader, FontWriter};
+use crate::tables::{TtfTable, TtfTableWrite};
 
 /// MAXP table - Maximum profile
 #[derive(Debug, Clone)]
@@ -37,8 +37,16 @@ impl MaxpTable {
 }
 
 impl TtfTable for MaxpTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"maxp")
+    }
+
     fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
-        let version = reader.read_fixed()?;
+        // Version 0.5 is stored as 0x00005000 rather than as a 16.16 value
+        let version = match reader.read_u32()? {
+            0x0000_5000 => Self::VERSION_0_5,
+            raw => raw as f32 / 65536.0,
+        };
         let num_glyphs = reader.read_u16()?;
 
         if version == Self::VERSION_0_5 {
@@ -96,3 +104,38 @@ impl TtfTable for MaxpTable {
         })
     }
 }
+
+impl TtfTableWrite for MaxpTable {
+    fn table_tag() -> &'static [u8; 4] {
+        b"maxp"
+    }
+
+    fn write(&self, writer: &mut FontWriter) -> Result<()> {
+        if self.is_version_0_5() {
+            writer.write_u32(0x0000_5000);
+            writer.write_u16(self.num_glyphs);
+            return Ok(());
+        }
+
+        writer.write_fixed(self.version);
+        writer.write_u16(self.num_glyphs);
+        for field in [
+            self.max_points,
+            self.max_contours,
+            self.max_composite_points,
+            self.max_composite_contours,
+            self.max_zones,
+            self.max_twilight_points,
+            self.max_storage,
+            self.max_function_defs,
+            self.max_instruction_defs,
+            self.max_stack_elements,
+            self.max_size_of_instructions,
+            self.max_component_elements,
+            self.max_component_depth,
+        ] {
+            writer.write_u16(field.unwrap_or(0));
+        }
+        Ok(())
+    }
+}
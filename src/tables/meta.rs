@@ -0,0 +1,95 @@
+use crate::error::{Result, TtfError};
+use crate::stream::FontReader;
+use crate::tables::TtfTable;
+
+/// META table - Metadata
+#[derive(Debug, Clone)]
+pub struct MetaTable {
+    pub version: u32,
+    pub flags: u32,
+    pub data_maps: Vec<MetaDataMap>,
+}
+
+/// A tagged metadata entry
+#[derive(Debug, Clone)]
+pub struct MetaDataMap {
+    pub tag: [u8; 4],
+    pub data: Vec<u8>,
+}
+
+impl MetaTable {
+    /// Get the data stored under a tag
+    pub fn get(&self, tag: &[u8; 4]) -> Option<&[u8]> {
+        self.data_maps
+            .iter()
+            .find(|map| &map.tag == tag)
+            .map(|map| map.data.as_slice())
+    }
+
+    /// Get the design languages ("dlng") as ScriptLangTags
+    pub fn design_languages(&self) -> Vec<String> {
+        self.language_tags(b"dlng")
+    }
+
+    /// Get the supported languages ("slng") as ScriptLangTags
+    pub fn supported_languages(&self) -> Vec<String> {
+        self.language_tags(b"slng")
+    }
+
+    fn language_tags(&self, tag: &[u8; 4]) -> Vec<String> {
+        self.get(tag)
+            .and_then(|data| std::str::from_utf8(data).ok())
+            .map(|text| {
+                text.split(',')
+                    .map(|tag| tag.trim().to_string())
+                    .filter(|tag| !tag.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl MetaDataMap {
+    pub fn tag_to_string(&self) -> String {
+        String::from_utf8_lossy(&self.tag).to_string()
+    }
+}
+
+impl TtfTable for MetaTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"meta")
+    }
+
+    fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
+        let version = reader.read_u32()?;
+        if version != 1 {
+            return Err(TtfError::UnsupportedVersion(version));
+        }
+
+        let flags = reader.read_u32()?;
+        let _reserved = reader.read_u32()?;
+        let data_maps_count = reader.read_u32()?;
+
+        // Each data map record takes 12 bytes, so the count can't outrun the data
+        let mut records = Vec::with_capacity((data_maps_count as usize).min(reader.remaining() / 12));
+        for _ in 0..data_maps_count {
+            records.push((reader.read_tag()?, reader.read_u32()? as usize, reader.read_u32()? as usize));
+        }
+
+        // Data offsets are relative to the start of the table
+        let mut data_maps = Vec::with_capacity(records.len());
+        for (tag, offset, length) in records {
+            reader.set_position(offset)?;
+            data_maps.push(MetaDataMap {
+                tag,
+                data: reader.read_bytes(length)?,
+            });
+        }
+
+        Ok(MetaTable {
+            version,
+            flags,
+            data_maps,
+        })
+    }
+}
@@ -11,6 +11,9 @@ pub struct NameTable {
     pub string_offset: u16,
     pub name_records: Vec<NameRecord>,
     pub string_data: HashMap<(u16, u16, u16, u16), Vec<u8>>, // (platform_id, encoding_id, language_id, name_id) -> string data
+    /// Format 1 language tags (e.g. "en-US"); language ID 0x8000 + i
+    /// refers to tag i. Written only when `format` is 1.
+    pub lang_tags: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -51,12 +54,87 @@ impl NameRecord {
     pub const DARK_BACKGROUND_PALETTE: u16 = 24;
 }
 
+/// Characters 0x80-0xFF of the Mac OS Roman encoding
+const MAC_ROMAN_HIGH: &str = "ÄÅÇÉÑÖÜáàâäãåçéèêëíìîïñóòôöõúùûü†°¢£§•¶ß®©™´¨≠ÆØ∞±≤≥¥µ∂∑∏π∫ªºΩæø¿¡¬√ƒ≈∆«»…\u{a0}ÀÃÕŒœ–—“”‘’÷◊ÿŸ⁄€‹›ﬁﬂ‡·‚„‰ÂÊÁËÈÍÎÏÌÓÔ\u{f8ff}ÒÚÛÙıˆ˜¯˘˙˚¸˝˛ˇ";
+
+/// Decode Mac OS Roman text
+pub(crate) fn decode_mac_roman(data: &[u8]) -> String {
+    data.iter()
+        .map(|&b| {
+            if b < 0x80 {
+                b as char
+            } else {
+                MAC_ROMAN_HIGH.chars().nth(b as usize - 0x80).unwrap_or('?')
+            }
+        })
+        .collect()
+}
+
+/// Decode name string data: Mac Roman for Macintosh records, UTF-16BE otherwise
+fn decode_name(data: &[u8], platform_id: u16) -> String {
+    if platform_id == 1 {
+        decode_mac_roman(data)
+    } else {
+        let units: Vec<u16> = data
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    }
+}
+
+/// Encode a name string for a platform; characters Mac Roman lacks become '?'
+fn encode_name(name: &str, platform_id: u16) -> Vec<u8> {
+    if platform_id == 1 {
+        name.chars()
+            .map(|c| {
+                if (c as u32) < 0x80 {
+                    c as u8
+                } else {
+                    MAC_ROMAN_HIGH
+                        .chars()
+                        .position(|m| m == c)
+                        .map_or(b'?', |index| 0x80 + index as u8)
+                }
+            })
+            .collect()
+    } else {
+        name.encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect()
+    }
+}
+
 impl NameTable {
     pub fn get_name(&self, name_id: u16) -> Option<(&NameRecord, String)> {
         self.name_records
             .iter()
             .find(|r| r.name_id == name_id)
-            .map(|record| (record, String::new())) // String would need to be extracted from string storage
+            .map(|record| (record, self.get_record_string(record).unwrap_or_default()))
+    }
+
+    /// Decode the string of a name record
+    pub fn get_record_string(&self, record: &NameRecord) -> Option<String> {
+        let key = (record.platform_id, record.encoding_id, record.language_id, record.name_id);
+        self.string_data
+            .get(&key)
+            .map(|data| decode_name(data, record.platform_id))
+    }
+
+    /// Get the decoded string for a name ID, preferring English Windows
+    /// (3, 1, 0x409), then English Mac Roman (1, 0, 0), then any record
+    pub fn get_string(&self, name_id: u16) -> Option<String> {
+        const PREFERRED: [(u16, u16, u16); 2] = [(3, 1, 0x409), (1, 0, 0)];
+
+        let records = || self.name_records.iter().filter(|r| r.name_id == name_id);
+        PREFERRED
+            .iter()
+            .find_map(|&(platform_id, encoding_id, language_id)| {
+                records().find(|r| {
+                    r.platform_id == platform_id && r.encoding_id == encoding_id && r.language_id == language_id
+                })
+            })
+            .into_iter()
+            .chain(records())
+            .find_map(|record| self.get_record_string(record))
     }
 
     pub fn get_font_name(&self) -> Option<&NameRecord> {
@@ -77,14 +155,24 @@ impl NameTable {
             .find(|r| r.name_id == NameRecord::POSTSCRIPT_NAME)
     }
 
+    /// Get the language tag a language ID refers to in a format 1 table
+    ///
+    /// Only IDs from 0x8000 up refer to language tags; lower IDs are
+    /// platform-specific language codes and give `None`, as do tags that
+    /// could not be read.
+    pub fn language_tag(&self, language_id: u16) -> Option<&str> {
+        if self.format != 1 || language_id < 0x8000 {
+            return None;
+        }
+        self.lang_tags
+            .get((language_id - 0x8000) as usize)
+            .filter(|tag| !tag.is_empty())
+            .map(String::as_str)
+    }
+
     /// Set or update a name record with the given value
     pub fn set_name(&mut self, name: &str, platform_id: u16, encoding_id: u16, language_id: u16, name_id: u16) {
-        // Encode the string
-        let name_bytes: Vec<u16> = name.encode_utf16().collect();
-        let mut name_data = Vec::new();
-        for code_unit in name_bytes {
-            name_data.extend_from_slice(&code_unit.to_be_bytes());
-        }
+        let name_data = encode_name(name, platform_id);
 
         let key = (platform_id, encoding_id, language_id, name_id);
         self.string_data.insert(key, name_data.clone());
@@ -112,7 +200,11 @@ impl NameTable {
 }
 
 impl TtfTable for NameTable {
-    fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"name")
+    }
+
+    fn from_reader(reader: &mut FontReader, length: u32) -> Result<Self> {
         let format = reader.read_u16()?;
         let count = reader.read_u16()?;
         let string_offset = reader.read_u16()?;
@@ -129,15 +221,57 @@ impl TtfTable for NameTable {
             });
         }
 
-        // Note: Actual string data would be read at string_offset
-        // For now, we just parse the records
+        // Format 1 follows the name records with (length, offset) records
+        // of the language tags
+        let mut lang_tag_records = Vec::new();
+        if format == 1 {
+            let lang_tag_count = reader.read_u16()?;
+            for _ in 0..lang_tag_count {
+                lang_tag_records.push((reader.read_u16()?, reader.read_u16()?));
+            }
+        }
+
+        // Strings live in the storage area at string_offset. A tag outside
+        // the table is left empty so later tags keep their language IDs
+        let mut lang_tags = Vec::with_capacity(lang_tag_records.len());
+        for &(tag_length, tag_offset) in &lang_tag_records {
+            let start = string_offset as usize + tag_offset as usize;
+            let end = start + tag_length as usize;
+            if end > length as usize {
+                lang_tags.push(String::new());
+                continue;
+            }
+
+            reader.set_position(start)?;
+            lang_tags.push(decode_name(&reader.read_bytes(tag_length as usize)?, 0));
+        }
+
+        // Records whose string lies outside the table are dropped
+        let mut string_data = HashMap::with_capacity(name_records.len());
+        name_records.retain(|record| {
+            let start = string_offset as usize + record.offset as usize;
+            let end = start + record.length as usize;
+            if end > length as usize || reader.set_position(start).is_err() {
+                return false;
+            }
+
+            match reader.read_bytes(record.length as usize) {
+                Ok(bytes) => {
+                    let key = (record.platform_id, record.encoding_id, record.language_id, record.name_id);
+                    string_data.insert(key, bytes);
+                    true
+                }
+                Err(_) => false,
+            }
+        });
 
         Ok(NameTable {
             format,
-            count,
+            count: name_records.len() as u16,
             string_offset,
             name_records,
-            string_data: HashMap::new(),
+            string_data,
+            lang_tags,
         })
     }
 }
@@ -149,12 +283,20 @@ impl TtfTableWrite for NameTable {
 
     fn write(&self, writer: &mut FontWriter) -> Result<()> {
         // Calculate string data size and offsets
-        let header_size = 6 + (self.name_records.len() * 12);
-        let mut current_offset = header_size as u16;
+        let lang_tags: &[String] = if self.format == 1 { &self.lang_tags } else { &[] };
+        let mut header_size = 6 + (self.name_records.len() * 12);
+        if self.format == 1 {
+            header_size += 2 + lang_tags.len() * 4;
+        }
+        // Record offsets are relative to the start of string storage
+        let mut current_offset = 0u16;
         let mut all_string_data = Vec::new();
 
-        // First, collect all string data and update offsets
+        // Records must be sorted by platform, encoding, language and name ID
         let mut updated_records = self.name_records.clone();
+        updated_records.sort_by_key(|r| (r.platform_id, r.encoding_id, r.language_id, r.name_id));
+
+        // Then collect all string data and update offsets
         for record in &mut updated_records {
             let key = (record.platform_id, record.encoding_id, record.language_id, record.name_id);
             if let Some(data) = self.string_data.get(&key) {
@@ -180,6 +322,18 @@ impl TtfTableWrite for NameTable {
             writer.write_u16(record.offset);
         }
 
+        // Language tags are UTF-16BE strings stored after the names
+        if self.format == 1 {
+            writer.write_u16(lang_tags.len() as u16);
+            for tag in lang_tags {
+                let data = encode_name(tag, 0);
+                writer.write_u16(data.len() as u16);
+                writer.write_u16(current_offset);
+                all_string_data.extend_from_slice(&data);
+                current_offset += data.len() as u16;
+            }
+        }
+
         // Write string data
         writer.write_bytes(&all_string_data);
 
@@ -35,13 +35,68 @@ pub struct Os2Table {
     pub s_typo_line_gap: i16,
     pub us_win_ascent: u16,
     pub us_win_descent: u16,
+    /// Version 1 and above; 0 in older tables
     pub ul_code_page_range1: u32,
     pub ul_code_page_range2: u32,
+    /// Version 2 and above; 0 in older tables
     pub sx_height: i16,
     pub s_cap_height: i16,
     pub us_default_char: u16,
     pub us_break_char: u16,
     pub us_max_context: u16,
+    /// Version 5 only, in TWIPs (1/20 point); 0 in older tables
+    pub us_lower_optical_point_size: u16,
+    pub us_upper_optical_point_size: u16,
+}
+
+/// Font embedding licensing rights, decoded from OS/2 fsType
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmbeddingPermission(pub u16);
+
+impl EmbeddingPermission {
+    /// Bits 0-3 hold the usage permission; the rest are flags
+    const USAGE_MASK: u16 = 0x000F;
+    const RESTRICTED: u16 = 0x0002;
+    const PREVIEW_PRINT: u16 = 0x0004;
+    const EDITABLE: u16 = 0x0008;
+    const NO_SUBSETTING: u16 = 0x0100;
+    const BITMAP_ONLY: u16 = 0x0200;
+
+    /// No usage bits set: the font may be embedded and permanently installed
+    pub fn is_installable(&self) -> bool {
+        self.0 & Self::USAGE_MASK == 0
+    }
+
+    /// Bit 1: the font must not be embedded without the owner's permission
+    pub fn is_restricted(&self) -> bool {
+        self.0 & Self::RESTRICTED != 0
+    }
+
+    /// Bit 2: the font may be embedded for previewing and printing only
+    pub fn is_preview_print(&self) -> bool {
+        self.0 & Self::PREVIEW_PRINT != 0
+    }
+
+    /// Bit 3: the font may be embedded in documents that are edited
+    pub fn is_editable(&self) -> bool {
+        self.0 & Self::EDITABLE != 0
+    }
+
+    /// Bit 8: the font must not be subsetted before embedding
+    pub fn no_subsetting(&self) -> bool {
+        self.0 & Self::NO_SUBSETTING != 0
+    }
+
+    /// Bit 9: only bitmaps in the font may be embedded
+    pub fn bitmap_only(&self) -> bool {
+        self.0 & Self::BITMAP_ONLY != 0
+    }
+}
+
+impl From<u16> for EmbeddingPermission {
+    fn from(fs_type: u16) -> Self {
+        EmbeddingPermission(fs_type)
+    }
 }
 
 impl Os2Table {
@@ -52,6 +107,9 @@ impl Os2Table {
     pub const VERSION_4: u16 = 4;
     pub const VERSION_5: u16 = 5;
 
+    /// fsSelection bit 7: line metrics should come from sTypo values
+    pub const USE_TYPO_METRICS: u16 = 0x0080;
+
     pub fn is_bold(&self) -> bool {
         self.fs_selection & 0x20 != 0 || self.us_weight_class >= 700
     }
@@ -60,6 +118,11 @@ impl Os2Table {
         self.fs_selection & 0x01 != 0
     }
 
+    /// Decode the embedding permission from fsType
+    pub fn embedding_permission(&self) -> EmbeddingPermission {
+        EmbeddingPermission::from(self.fs_type)
+    }
+
     pub fn get_weight_string(&self) -> &'static str {
         match self.us_weight_class {
             100..=199 => "Thin",
@@ -77,6 +140,10 @@ impl Os2Table {
 }
 
 impl TtfTable for Os2Table {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"OS/2")
+    }
+
     fn from_reader(reader: &mut FontReader, length: u32) -> Result<Self> {
         let version = reader.read_u16()?;
         let x_avg_char_width = reader.read_i16()?;
@@ -118,12 +185,17 @@ impl TtfTable for Os2Table {
         let s_typo_line_gap = reader.read_i16()?;
         let us_win_ascent = reader.read_u16()?;
         let us_win_descent = reader.read_u16()?;
-        let ul_code_page_range1 = reader.read_u32()?;
-        let ul_code_page_range2 = reader.read_u32()?;
 
-        // Version 1 and above fields
+        // Version 1 added the code page ranges
+        let (ul_code_page_range1, ul_code_page_range2) = if version >= Self::VERSION_1 {
+            (reader.read_u32()?, reader.read_u32()?)
+        } else {
+            (0, 0)
+        };
+
+        // Version 2 and above fields
         let (sx_height, s_cap_height, us_default_char, us_break_char, us_max_context) =
-            if version >= Self::VERSION_1 {
+            if version >= Self::VERSION_2 {
                 (
                     reader.read_i16()?,
                     reader.read_i16()?,
@@ -135,6 +207,13 @@ impl TtfTable for Os2Table {
                 (0, 0, 0, 0, 0)
             };
 
+        // Version 5 added the optical size range
+        let (us_lower_optical_point_size, us_upper_optical_point_size) = if version >= Self::VERSION_5 {
+            (reader.read_u16()?, reader.read_u16()?)
+        } else {
+            (0, 0)
+        };
+
         // Skip any remaining bytes for version-specific fields
         let bytes_read = reader.position();
         if bytes_read < length as usize {
@@ -179,6 +258,8 @@ impl TtfTable for Os2Table {
             us_default_char,
             us_break_char,
             us_max_context,
+            us_lower_optical_point_size,
+            us_upper_optical_point_size,
         })
     }
 }
@@ -223,17 +304,23 @@ impl TtfTableWrite for Os2Table {
         writer.write_i16(self.s_typo_line_gap);
         writer.write_u16(self.us_win_ascent);
         writer.write_u16(self.us_win_descent);
-        writer.write_u32(self.ul_code_page_range1);
-        writer.write_u32(self.ul_code_page_range2);
 
-        // Write version 1+ fields if applicable
+        // Only the fields of this table's version are written
         if self.version >= Self::VERSION_1 {
+            writer.write_u32(self.ul_code_page_range1);
+            writer.write_u32(self.ul_code_page_range2);
+        }
+        if self.version >= Self::VERSION_2 {
             writer.write_i16(self.sx_height);
             writer.write_i16(self.s_cap_height);
             writer.write_u16(self.us_default_char);
             writer.write_u16(self.us_break_char);
             writer.write_u16(self.us_max_context);
         }
+        if self.version >= Self::VERSION_5 {
+            writer.write_u16(self.us_lower_optical_point_size);
+            writer.write_u16(self.us_upper_optical_point_size);
+        }
 
         Ok(())
     }
@@ -1,11 +1,45 @@
-use crate::error::Result;
-use crate::stream::FontReader;
-use crate::tables::TtfTable;
+use crate::error::{Result, TtfError};
+use crate::stream::{FontReader, FontWriter};
+use crate::tables::{TtfTable, TtfTableWrite};
+
+/// The 258 glyph names of the standard Macintosh glyph order
+const STANDARD_MAC_NAMES: [&str; 258] = [
+    ".notdef", ".null", "nonmarkingreturn", "space", "exclam", "quotedbl", "numbersign", "dollar",
+    "percent", "ampersand", "quotesingle", "parenleft", "parenright", "asterisk", "plus", "comma",
+    "hyphen", "period", "slash", "zero", "one", "two", "three", "four", "five", "six", "seven",
+    "eight", "nine", "colon", "semicolon", "less", "equal", "greater", "question", "at", "A", "B",
+    "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S", "T", "U",
+    "V", "W", "X", "Y", "Z", "bracketleft", "backslash", "bracketright", "asciicircum",
+    "underscore", "grave", "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n",
+    "o", "p", "q", "r", "s", "t", "u", "v", "w", "x", "y", "z", "braceleft", "bar", "braceright",
+    "asciitilde", "Adieresis", "Aring", "Ccedilla", "Eacute", "Ntilde", "Odieresis", "Udieresis",
+    "aacute", "agrave", "acircumflex", "adieresis", "atilde", "aring", "ccedilla", "eacute",
+    "egrave", "ecircumflex", "edieresis", "iacute", "igrave", "icircumflex", "idieresis", "ntilde",
+    "oacute", "ograve", "ocircumflex", "odieresis", "otilde", "uacute", "ugrave", "ucircumflex",
+    "udieresis", "dagger", "degree", "cent", "sterling", "section", "bullet", "paragraph",
+    "germandbls", "registered", "copyright", "trademark", "acute", "dieresis", "notequal", "AE",
+    "Oslash", "infinity", "plusminus", "lessequal", "greaterequal", "yen", "mu", "partialdiff",
+    "summation", "product", "pi", "integral", "ordfeminine", "ordmasculine", "Omega", "ae",
+    "oslash", "questiondown", "exclamdown", "logicalnot", "radical", "florin", "approxequal",
+    "Delta", "guillemotleft", "guillemotright", "ellipsis", "nonbreakingspace", "Agrave", "Atilde",
+    "Otilde", "OE", "oe", "endash", "emdash", "quotedblleft", "quotedblright", "quoteleft",
+    "quoteright", "divide", "lozenge", "ydieresis", "Ydieresis", "fraction", "currency",
+    "guilsinglleft", "guilsinglright", "fi", "fl", "daggerdbl", "periodcentered", "quotesinglbase",
+    "quotedblbase", "perthousand", "Acircumflex", "Ecircumflex", "Aacute", "Edieresis", "Egrave",
+    "Iacute", "Icircumflex", "Idieresis", "Igrave", "Oacute", "Ocircumflex", "apple", "Ograve",
+    "Uacute", "Ucircumflex", "Ugrave", "dotlessi", "circumflex", "tilde", "macron", "breve",
+    "dotaccent", "ring", "cedilla", "hungarumlaut", "ogonek", "caron", "Lslash", "lslash", "Scaron",
+    "scaron", "Zcaron", "zcaron", "brokenbar", "Eth", "eth", "Yacute", "yacute", "Thorn", "thorn",
+    "minus", "multiply", "onesuperior", "twosuperior", "threesuperior", "onehalf", "onequarter",
+    "threequarters", "franc", "Gbreve", "gbreve", "Idotaccent", "Scedilla", "scedilla", "Cacute",
+    "cacute", "Ccaron", "ccaron", "dcroat",
+];
 
 /// POST table - PostScript information
 #[derive(Debug, Clone)]
 pub struct PostTable {
     pub format: f32,
+    /// Italic angle in degrees, decoded from its 16.16 fixed-point form
     pub italic_angle: f32,
     pub underline_position: i16,
     pub underline_thickness: i16,
@@ -14,6 +48,19 @@ pub struct PostTable {
     pub max_mem_type42: u32,
     pub min_mem_type1: u32,
     pub max_mem_type1: u32,
+    /// Where the glyph names come from, as set by the format
+    pub glyph_names: GlyphNames,
+}
+
+/// Glyph name source of a post table
+#[derive(Debug, Clone, PartialEq)]
+pub enum GlyphNames {
+    /// Format 1: glyphs follow the standard Macintosh order
+    Format1Standard,
+    /// Format 2: a name for every glyph, indexed by glyph ID
+    Format2Custom(Vec<String>),
+    /// Format 3 (and any other format): no glyph names
+    Format3None,
 }
 
 impl PostTable {
@@ -21,9 +68,66 @@ impl PostTable {
     pub const VERSION_2_0: f32 = 2.0;
     pub const VERSION_3_0: f32 = 3.0;
     pub const VERSION_4_0: f32 = 4.0;
+
+    /// Italic angle in degrees counter-clockwise from the vertical, so
+    /// forward-leaning italics are negative
+    pub fn italic_angle_degrees(&self) -> f32 {
+        self.italic_angle
+    }
+
+    /// Get the PostScript name of a glyph, or `None` if the table doesn't
+    /// name it
+    pub fn glyph_name(&self, glyph_id: u16) -> Option<&str> {
+        match &self.glyph_names {
+            GlyphNames::Format1Standard => STANDARD_MAC_NAMES.get(glyph_id as usize).copied(),
+            GlyphNames::Format2Custom(names) => names.get(glyph_id as usize).map(String::as_str),
+            GlyphNames::Format3None => None,
+        }
+    }
+
+    /// Find the glyph with the given PostScript name; the first one wins if
+    /// several share it
+    pub fn glyph_index(&self, name: &str) -> Option<u16> {
+        let index = match &self.glyph_names {
+            GlyphNames::Format1Standard => STANDARD_MAC_NAMES.iter().position(|&n| n == name),
+            GlyphNames::Format2Custom(names) => names.iter().position(|n| n == name),
+            GlyphNames::Format3None => None,
+        };
+        index.map(|index| index as u16)
+    }
+
+    /// Read the format 2 glyph name index and the names it refers to
+    fn read_format2_names(reader: &mut FontReader, length: u32) -> Result<Vec<String>> {
+        let num_glyphs = reader.read_u16()?;
+        let mut name_indices = Vec::with_capacity(num_glyphs as usize);
+        for _ in 0..num_glyphs {
+            name_indices.push(reader.read_u16()?);
+        }
+
+        // Pascal strings fill the rest of the table
+        let mut custom_names = Vec::new();
+        while reader.position() < length as usize {
+            custom_names.push(reader.read_pascal_string()?);
+        }
+
+        name_indices
+            .into_iter()
+            .map(|index| match STANDARD_MAC_NAMES.get(index as usize) {
+                Some(name) => Ok(name.to_string()),
+                None => custom_names
+                    .get(index as usize - STANDARD_MAC_NAMES.len())
+                    .cloned()
+                    .ok_or_else(|| TtfError::ParseError(format!("Invalid glyph name index: {}", index))),
+            })
+            .collect()
+    }
 }
 
 impl TtfTable for PostTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"post")
+    }
+
     fn from_reader(reader: &mut FontReader, length: u32) -> Result<Self> {
         let format = reader.read_fixed()?;
         let italic_angle = reader.read_fixed()?;
@@ -35,8 +139,13 @@ impl TtfTable for PostTable {
         let min_mem_type1 = reader.read_u32()?;
         let max_mem_type1 = reader.read_u32()?;
 
-        // Additional fields for format 2.0 and 3.0 would be read here
-        // For simplicity, we skip them in this basic implementation
+        let glyph_names = if format == Self::VERSION_1_0 {
+            GlyphNames::Format1Standard
+        } else if format == Self::VERSION_2_0 {
+            GlyphNames::Format2Custom(Self::read_format2_names(reader, length)?)
+        } else {
+            GlyphNames::Format3None
+        };
 
         let bytes_read = reader.position();
         if bytes_read < length as usize {
@@ -53,6 +162,55 @@ impl TtfTable for PostTable {
             max_mem_type42,
             min_mem_type1,
             max_mem_type1,
+            glyph_names,
         })
     }
 }
+
+impl TtfTableWrite for PostTable {
+    fn table_tag() -> &'static [u8; 4] {
+        b"post"
+    }
+
+    fn write(&self, writer: &mut FontWriter) -> Result<()> {
+        writer.write_fixed(self.format);
+        writer.write_fixed(self.italic_angle);
+        writer.write_i16(self.underline_position);
+        writer.write_i16(self.underline_thickness);
+        writer.write_u32(self.is_fixed_pitch);
+        writer.write_u32(self.min_mem_type42);
+        writer.write_u32(self.max_mem_type42);
+        writer.write_u32(self.min_mem_type1);
+        writer.write_u32(self.max_mem_type1);
+
+        if let GlyphNames::Format2Custom(names) = &self.glyph_names {
+            // Standard Macintosh names are referenced by index, others are
+            // stored once each as Pascal strings
+            let mut custom_names: Vec<&str> = Vec::new();
+            writer.write_u16(names.len() as u16);
+            for name in names {
+                let index = match STANDARD_MAC_NAMES.iter().position(|&n| n == name) {
+                    Some(index) => index,
+                    None => match custom_names.iter().position(|&n| n == name) {
+                        Some(index) => STANDARD_MAC_NAMES.len() + index,
+                        None => {
+                            custom_names.push(name);
+                            STANDARD_MAC_NAMES.len() + custom_names.len() - 1
+                        }
+                    },
+                };
+                writer.write_u16(index as u16);
+            }
+
+            for name in custom_names {
+                if name.len() > 255 {
+                    return Err(TtfError::ParseError(format!("Glyph name too long: {}", name)));
+                }
+                writer.write_u8(name.len() as u8);
+                writer.write_bytes(name.as_bytes());
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -9,6 +9,10 @@ pub struct PrepTable {
 }
 
 impl TtfTable for PrepTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"prep")
+    }
+
     fn from_reader(reader: &mut FontReader, length: u32) -> Result<Self> {
         let instructions = reader.read_bytes(length as usize)?;
         Ok(PrepTable { instructions })
@@ -1,6 +1,5 @@
-use crate::error::Result;
+use crate::error::{Result, TtfError};
 use crate::stream::FontReader;
-use crate::tables::TtfTable;
 
 /// sbix table - Standard bitmap graphics
 #[derive(Debug, Clone)]
@@ -10,38 +9,78 @@ pub struct SbixTable {
     pub strikes: Vec<Strike>,
 }
 
+/// A set of bitmaps for one pixels-per-em size
 #[derive(Debug, Clone)]
 pub struct Strike {
     pub ppem: u16,
-    pub resolution: u16,
-    pub bitmaps: Vec<Bitmap>,
+    pub ppi: u16,
+    /// Bitmap per glyph id; `None` for glyphs without a bitmap in this strike
+    pub glyphs: Vec<Option<SbixGlyph>>,
 }
 
-#[derive(Debug, Clone)]
-pub struct Bitmap {
-    pub glyph_id: u16,
-    pub bitmap_type: u32,
-    pub offset: u32,
+/// An embedded glyph image
+#[derive(Debug, Clone, PartialEq)]
+pub struct SbixGlyph {
+    pub origin_offset_x: i16,
+    pub origin_offset_y: i16,
+    /// Image format tag, e.g. `b"png "`, `b"jpg "` or `b"tiff"`
+    pub graphic_type: [u8; 4],
     pub data: Vec<u8>,
 }
 
-impl TtfTable for SbixTable {
-    fn from_reader(reader: &mut FontReader, length: u32) -> Result<Self> {
+impl SbixGlyph {
+    /// Get the graphic type as a string
+    pub fn graphic_type_to_string(&self) -> String {
+        String::from_utf8_lossy(&self.graphic_type).to_string()
+    }
+}
+
+impl SbixTable {
+    /// Parse the sbix table; `num_glyphs` comes from maxp
+    pub fn from_reader(reader: &mut FontReader, _length: u32, num_glyphs: u16) -> Result<Self> {
         let version = reader.read_u16()?;
         let flags = reader.read_u16()?;
         let num_strikes = reader.read_u32()?;
 
-        let mut strikes = Vec::new();
-
-        // Simplified implementation
+        // Each strike offset takes 4 bytes, so the count can't outrun the data
+        let mut strike_offsets = Vec::with_capacity((num_strikes as usize).min(reader.remaining() / 4));
         for _ in 0..num_strikes {
+            strike_offsets.push(reader.read_u32()? as usize);
+        }
+
+        let mut strikes = Vec::with_capacity(strike_offsets.len());
+        for strike_offset in strike_offsets {
+            reader.set_position(strike_offset)?;
             let ppem = reader.read_u16()?;
-            let resolution = reader.read_u16()?;
-            strikes.push(Strike {
-                ppem,
-                resolution,
-                bitmaps: Vec::new(),
-            });
+            let ppi = reader.read_u16()?;
+
+            let mut glyph_offsets = Vec::with_capacity(num_glyphs as usize + 1);
+            for _ in 0..=num_glyphs {
+                glyph_offsets.push(reader.read_u32()? as usize);
+            }
+
+            let mut glyphs = Vec::with_capacity(num_glyphs as usize);
+            for window in glyph_offsets.windows(2) {
+                let (start, end) = (window[0], window[1]);
+                if end < start {
+                    return Err(TtfError::InvalidOffset(end as u64));
+                }
+                // Glyph data needs at least the origin and the graphic type
+                if end - start < 8 {
+                    glyphs.push(None);
+                    continue;
+                }
+
+                reader.set_position(strike_offset + start)?;
+                glyphs.push(Some(SbixGlyph {
+                    origin_offset_x: reader.read_i16()?,
+                    origin_offset_y: reader.read_i16()?,
+                    graphic_type: reader.read_tag()?,
+                    data: reader.read_bytes(end - start - 8)?,
+                }));
+            }
+
+            strikes.push(Strike { ppem, ppi, glyphs });
         }
 
         Ok(SbixTable {
@@ -50,4 +89,28 @@ impl TtfTable for SbixTable {
             strikes,
         })
     }
+
+    /// Pick the strike to use for a size: an exact match, else the smallest
+    /// larger strike (to scale down), else the largest available
+    pub fn best_strike(&self, ppem: u16) -> Option<&Strike> {
+        self.strikes
+            .iter()
+            .filter(|strike| strike.ppem >= ppem)
+            .min_by_key(|strike| strike.ppem)
+            .or_else(|| self.strikes.iter().max_by_key(|strike| strike.ppem))
+    }
+}
+
+impl Strike {
+    /// Get the bitmap of a glyph, following `dupe` references
+    pub fn glyph(&self, glyph_id: u16) -> Option<&SbixGlyph> {
+        let glyph = self.glyphs.get(glyph_id as usize)?.as_ref()?;
+        if &glyph.graphic_type == b"dupe" && glyph.data.len() >= 2 {
+            let target = u16::from_be_bytes([glyph.data[0], glyph.data[1]]);
+            let target = self.glyphs.get(target as usize)?.as_ref()?;
+            // A duplicate of a duplicate is invalid
+            return (&target.graphic_type != b"dupe").then_some(target);
+        }
+        Some(glyph)
+    }
 }
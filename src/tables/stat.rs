@@ -28,6 +28,10 @@ pub struct AxisValue {
 }
 
 impl TtfTable for StatTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"STAT")
+    }
+
     fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
         let version = reader.read_u32()?;
         let design_axis_count = reader.read_u16()?;
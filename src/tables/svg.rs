@@ -1,32 +1,118 @@
-use crate::error::Result;
+use crate::error::{Result, TtfError};
 use crate::stream::FontReader;
 use crate::tables::TtfTable;
 
-/// SVG table - SVG table
+/// SVG table - SVG glyph documents
 #[derive(Debug, Clone)]
 pub struct SvgTable {
     pub version: u16,
     pub svg_documents: Vec<SvgDocument>,
 }
 
+/// An SVG document and the glyph range it renders
 #[derive(Debug, Clone)]
 pub struct SvgDocument {
     pub start_glyph_id: u16,
     pub end_glyph_id: u16,
+    /// Document bytes as stored, possibly gzip-compressed
     pub svg_data: Vec<u8>,
 }
 
+impl SvgTable {
+    /// Find the document that covers a glyph
+    pub fn document(&self, glyph_id: u16) -> Option<&SvgDocument> {
+        self.svg_documents
+            .iter()
+            .find(|document| (document.start_glyph_id..=document.end_glyph_id).contains(&glyph_id))
+    }
+}
+
+impl SvgDocument {
+    /// Whether the document is stored gzip-compressed
+    pub fn is_compressed(&self) -> bool {
+        self.svg_data.starts_with(&[0x1F, 0x8B])
+    }
+
+    /// Get the SVG text, decompressing it if needed
+    ///
+    /// Compressed documents require the `compression` feature and may
+    /// expand to at most 16 MiB.
+    pub fn text(&self) -> Result<String> {
+        let data = if self.is_compressed() {
+            decompress(&self.svg_data)?
+        } else {
+            self.svg_data.clone()
+        };
+
+        String::from_utf8(data)
+            .map_err(|e| TtfError::InvalidEncoding(format!("SVG document is not UTF-8: {}", e)))
+    }
+}
+
+/// Largest decompressed SVG document accepted, so a small compressed
+/// document can't expand without bound
+#[cfg(feature = "compression")]
+const MAX_SVG_DOCUMENT_SIZE: usize = 16 * 1024 * 1024;
+
+/// Decompress a gzip member. The decoder checks the CRC32 and size in the
+/// trailer
+#[cfg(feature = "compression")]
+fn decompress(data: &[u8]) -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut text = Vec::new();
+    flate2::read::GzDecoder::new(data)
+        .take(MAX_SVG_DOCUMENT_SIZE as u64 + 1)
+        .read_to_end(&mut text)
+        .map_err(|e| TtfError::ParseError(format!("Invalid gzip SVG document: {}", e)))?;
+    if text.len() > MAX_SVG_DOCUMENT_SIZE {
+        return Err(TtfError::LimitExceeded(format!(
+            "SVG document decompresses to more than {} bytes",
+            MAX_SVG_DOCUMENT_SIZE
+        )));
+    }
+
+    Ok(text)
+}
+
+#[cfg(not(feature = "compression"))]
+fn decompress(_data: &[u8]) -> Result<Vec<u8>> {
+    Err(TtfError::ParseError(
+        "Compressed SVG documents require the `compression` feature".to_string(),
+    ))
+}
+
 impl TtfTable for SvgTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"SVG ")
+    }
+
     fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
         let version = reader.read_u16()?;
-        let num_svg_documents = reader.read_u32()?;
+        let document_list_offset = reader.read_u32()? as usize;
+        let _reserved = reader.read_u32()?;
 
-        let mut svg_documents = Vec::new();
+        reader.set_position(document_list_offset)?;
+        let num_entries = reader.read_u16()?;
+
+        let mut records = Vec::with_capacity(num_entries as usize);
+        for _ in 0..num_entries {
+            let start_glyph_id = reader.read_u16()?;
+            let end_glyph_id = reader.read_u16()?;
+            let svg_doc_offset = reader.read_u32()? as usize;
+            let svg_doc_length = reader.read_u32()? as usize;
+            records.push((start_glyph_id, end_glyph_id, svg_doc_offset, svg_doc_length));
+        }
 
-        // Read SVG document offsets (simplified)
-        let mut offsets = Vec::new();
-        for _ in 0..num_svg_documents {
-            offsets.push(reader.read_u32()?);
+        // Document offsets are relative to the document list
+        let mut svg_documents = Vec::with_capacity(records.len());
+        for (start_glyph_id, end_glyph_id, offset, length) in records {
+            reader.set_position(document_list_offset + offset)?;
+            svg_documents.push(SvgDocument {
+                start_glyph_id,
+                end_glyph_id,
+                svg_data: reader.read_bytes(length)?,
+            });
         }
 
         Ok(SvgTable {
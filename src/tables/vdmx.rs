@@ -0,0 +1,154 @@
+use crate::error::{Result, TtfError};
+use crate::stream::FontReader;
+use crate::tables::TtfTable;
+
+/// VDMX table - Vertical device metrics
+#[derive(Debug, Clone)]
+pub struct VdmxTable {
+    pub version: u16,
+    pub ratio_ranges: Vec<RatioRange>,
+    pub groups: Vec<VdmxGroup>,
+}
+
+/// An aspect ratio range and the group of metrics used for it
+#[derive(Debug, Clone)]
+pub struct RatioRange {
+    pub char_set: u8,
+    pub x_ratio: u8,
+    pub y_start_ratio: u8,
+    pub y_end_ratio: u8,
+    /// Index into `VdmxTable::groups`
+    pub group_index: usize,
+}
+
+/// Per-size yMax/yMin values for a range of sizes
+#[derive(Debug, Clone)]
+pub struct VdmxGroup {
+    pub start_size: u8,
+    pub end_size: u8,
+    pub records: Vec<VdmxRecord>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VdmxRecord {
+    pub y_pel_height: u16,
+    pub y_max: i16,
+    pub y_min: i16,
+}
+
+impl RatioRange {
+    /// Whether a device with the given x:y aspect ratio falls in this range;
+    /// an x ratio of 0 matches every device
+    pub fn matches(&self, x: u16, y: u16) -> bool {
+        if self.x_ratio == 0 {
+            return true;
+        }
+
+        // x / y must lie between x_ratio / y_end_ratio and x_ratio / y_start_ratio
+        let scaled = self.x_ratio as u32 * y as u32;
+        self.y_start_ratio as u32 * x as u32 <= scaled && scaled <= self.y_end_ratio as u32 * x as u32
+    }
+}
+
+impl VdmxGroup {
+    /// Get (yMax, yMin) for a pixel height
+    pub fn metrics(&self, y_pel_height: u16) -> Option<(i16, i16)> {
+        self.records
+            .binary_search_by_key(&y_pel_height, |record| record.y_pel_height)
+            .ok()
+            .map(|index| (self.records[index].y_max, self.records[index].y_min))
+    }
+}
+
+impl VdmxTable {
+    /// Find the group for a device aspect ratio (first matching range)
+    pub fn group_for_ratio(&self, x: u16, y: u16) -> Option<&VdmxGroup> {
+        self.ratio_ranges
+            .iter()
+            .find(|range| range.matches(x, y))
+            .and_then(|range| self.groups.get(range.group_index))
+    }
+
+    /// Get (yMax, yMin) at `ppem` for a square-pixel (1:1) device
+    pub fn metrics_for_ppem(&self, ppem: u16) -> Option<(i16, i16)> {
+        self.group_for_ratio(1, 1)?.metrics(ppem)
+    }
+}
+
+impl TtfTable for VdmxTable {
+    fn table_tag() -> Option<&'static [u8; 4]> {
+        Some(b"VDMX")
+    }
+
+    fn from_reader(reader: &mut FontReader, _length: u32) -> Result<Self> {
+        let version = reader.read_u16()?;
+        let _num_recs = reader.read_u16()?;
+        let num_ratios = reader.read_u16()?;
+
+        let mut ranges = Vec::with_capacity(num_ratios as usize);
+        for _ in 0..num_ratios {
+            ranges.push((reader.read_u8()?, reader.read_u8()?, reader.read_u8()?, reader.read_u8()?));
+        }
+
+        let mut offsets = Vec::with_capacity(num_ratios as usize);
+        for _ in 0..num_ratios {
+            offsets.push(reader.read_u16()? as usize);
+        }
+
+        // Several ratio ranges may share one group
+        let mut group_offsets: Vec<usize> = Vec::new();
+        let mut groups = Vec::new();
+        let mut ratio_ranges = Vec::with_capacity(ranges.len());
+        for ((char_set, x_ratio, y_start_ratio, y_end_ratio), offset) in ranges.into_iter().zip(offsets) {
+            let group_index = match group_offsets.iter().position(|&o| o == offset) {
+                Some(index) => index,
+                None => {
+                    reader.set_position(offset)?;
+                    groups.push(read_group(reader)?);
+                    group_offsets.push(offset);
+                    groups.len() - 1
+                }
+            };
+
+            ratio_ranges.push(RatioRange {
+                char_set,
+                x_ratio,
+                y_start_ratio,
+                y_end_ratio,
+                group_index,
+            });
+        }
+
+        Ok(VdmxTable {
+            version,
+            ratio_ranges,
+            groups,
+        })
+    }
+}
+
+fn read_group(reader: &mut FontReader) -> Result<VdmxGroup> {
+    let recs = reader.read_u16()?;
+    let start_size = reader.read_u8()?;
+    let end_size = reader.read_u8()?;
+
+    let mut records = Vec::with_capacity(recs as usize);
+    for _ in 0..recs {
+        records.push(VdmxRecord {
+            y_pel_height: reader.read_u16()?,
+            y_max: reader.read_i16()?,
+            y_min: reader.read_i16()?,
+        });
+    }
+
+    // Lookups binary search by pixel height
+    if records.windows(2).any(|pair| pair[0].y_pel_height >= pair[1].y_pel_height) {
+        return Err(TtfError::ParseError("VDMX records are not sorted by yPelHeight".to_string()));
+    }
+
+    Ok(VdmxGroup {
+        start_size,
+        end_size,
+        records,
+    })
+}
@@ -0,0 +1,75 @@
+use crate::error::Result;
+use crate::stream::{FontReader, FontWriter};
+use crate::tables::TtfTableWrite;
+
+/// VMTX table - Vertical metrics
+#[derive(Debug, Clone)]
+pub struct VmtxTable {
+    pub v_metrics: Vec<LongVerMetric>,
+    pub top_side_bearings: Vec<i16>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LongVerMetric {
+    pub advance_height: u16,
+    pub tsb: i16,
+}
+
+impl VmtxTable {
+    pub fn from_reader(reader: &mut FontReader, _length: u32, num_glyphs: u16, num_v_metrics: u16) -> Result<Self> {
+        let mut v_metrics = Vec::with_capacity(num_v_metrics as usize);
+        for _ in 0..num_v_metrics {
+            v_metrics.push(LongVerMetric {
+                advance_height: reader.read_u16()?,
+                tsb: reader.read_i16()?,
+            });
+        }
+
+        let num_tsb = num_glyphs.saturating_sub(num_v_metrics) as usize;
+        let mut top_side_bearings = Vec::with_capacity(num_tsb);
+        for _ in 0..num_tsb {
+            top_side_bearings.push(reader.read_i16()?);
+        }
+
+        Ok(VmtxTable {
+            v_metrics,
+            top_side_bearings,
+        })
+    }
+
+    /// Advance height of a glyph; glyphs past the long metrics share the
+    /// last advance
+    pub fn get_advance_height(&self, glyph_index: u16) -> u16 {
+        self.v_metrics
+            .get(glyph_index as usize)
+            .or(self.v_metrics.last())
+            .map_or(0, |metric| metric.advance_height)
+    }
+
+    pub fn get_tsb(&self, glyph_index: u16) -> i16 {
+        if let Some(metric) = self.v_metrics.get(glyph_index as usize) {
+            return metric.tsb;
+        }
+        let idx = glyph_index as usize - self.v_metrics.len();
+        self.top_side_bearings.get(idx).copied().unwrap_or(0)
+    }
+}
+
+impl TtfTableWrite for VmtxTable {
+    fn table_tag() -> &'static [u8; 4] {
+        b"vmtx"
+    }
+
+    fn write(&self, writer: &mut FontWriter) -> Result<()> {
+        for metric in &self.v_metrics {
+            writer.write_u16(metric.advance_height);
+            writer.write_i16(metric.tsb);
+        }
+
+        for &tsb in &self.top_side_bearings {
+            writer.write_i16(tsb);
+        }
+
+        Ok(())
+    }
+}
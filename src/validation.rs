@@ -1,6 +1,7 @@
 use crate::error::Result;
 use crate::font::Font;
 use crate::stream::calculate_checksum;
+use crate::tables::cmap::CmapSubtable;
 
 /// Validation report for a font
 #[derive(Debug, Clone)]
@@ -61,6 +62,20 @@ impl Font {
             report.is_valid = false;
         }
 
+        // The binary search fields are derived from the table count
+        let expected = Font::calculate_search_range(self.num_tables);
+        if (self.search_range, self.entry_selector, self.range_shift) != expected {
+            report.warnings.push(ValidationWarning {
+                warning_type: ValidationWarningType::PotentiallyProblematic,
+                message: format!(
+                    "Table directory search fields ({}, {}, {}) don't match {} tables; expected ({}, {}, {})",
+                    self.search_range, self.entry_selector, self.range_shift,
+                    self.num_tables, expected.0, expected.1, expected.2
+                ),
+                table: None,
+            });
+        }
+
         // Check for required tables
         let required_tables = [
             (b"cmap", "character to glyph mapping"),
@@ -74,7 +89,7 @@ impl Font {
         ];
 
         for (tag, description) in &required_tables {
-            if self.get_table_record(tag).is_none() {
+            if !self.has_table(tag) {
                 report.errors.push(ValidationError {
                     error_type: ValidationErrorType::MissingRequiredTable,
                     message: format!("Missing required table: {} ({})",
@@ -85,11 +100,18 @@ impl Font {
             }
         }
 
-        // TrueType fonts require glyf and loca tables
-        if self.sfnt_version == 0x00010000 {
+        // TrueType outlines live in glyf and loca, PostScript outlines in CFF or CFF2
+        if self.sfnt_version == 0x4F54544F && !self.is_cff() {
+            report.errors.push(ValidationError {
+                error_type: ValidationErrorType::MissingRequiredTable,
+                message: "Missing required table for OpenType CFF: CFF or CFF2 (PostScript outlines)".to_string(),
+                table: Some("CFF ".to_string()),
+            });
+            report.is_valid = false;
+        } else if self.sfnt_version == 0x00010000 {
             let ttf_required = [(b"glyf", "glyph data"), (b"loca", "index to location")];
             for (tag, description) in &ttf_required {
-                if self.get_table_record(tag).is_none() {
+                if !self.has_table(tag) {
                     report.errors.push(ValidationError {
                         error_type: ValidationErrorType::MissingRequiredTable,
                         message: format!("Missing required table for TrueType: {} ({})",
@@ -101,6 +123,15 @@ impl Font {
             }
         }
 
+        // Bitmap-only fonts may keep an empty glyf table
+        if self.sfnt_version == 0x00010000 && self.has_glyf() && !self.has_outlines().unwrap_or(true) {
+            report.warnings.push(ValidationWarning {
+                warning_type: ValidationWarningType::PotentiallyProblematic,
+                message: "Font has no outline data: every glyph is empty".to_string(),
+                table: Some("glyf".to_string()),
+            });
+        }
+
         // Validate table checksums
         for record in &self.table_records {
             if let Some(table_data) = self.get_table_data(&record.table_tag) {
@@ -156,6 +187,28 @@ impl Font {
                 });
                 report.is_valid = false;
             }
+
+            // loca should have an entry for every glyph maxp declares
+            if let Some(count) = self.actual_glyph_count().ok().filter(|&count| count != maxp.num_glyphs as usize) {
+                report.warnings.push(ValidationWarning {
+                    warning_type: ValidationWarningType::PotentiallyProblematic,
+                    message: format!(
+                        "loca has entries for {} glyphs but maxp.num_glyphs is {}",
+                        count, maxp.num_glyphs
+                    ),
+                    table: Some("loca".to_string()),
+                });
+            }
+
+            // Hinting programs need instruction space reserved in maxp
+            let has_hinting_programs = self.has_table(b"fpgm") || self.has_table(b"prep");
+            if has_hinting_programs && maxp.max_size_of_instructions == Some(0) {
+                report.warnings.push(ValidationWarning {
+                    warning_type: ValidationWarningType::PotentiallyProblematic,
+                    message: "maxp.max_size_of_instructions is 0 but fpgm/prep bytecode is present".to_string(),
+                    table: Some("maxp".to_string()),
+                });
+            }
         }
 
         // Validate cmap table
@@ -168,14 +221,61 @@ impl Font {
                 });
                 report.is_valid = false;
             }
+
+            // Format 4 segments must be consistent and end with the 0xFFFF sentinel
+            for (record, subtable) in cmap.encoding_records.iter().zip(&cmap.subtables) {
+                let CmapSubtable::Format4(format4) = subtable else {
+                    continue;
+                };
+                let encoding = format!("({}, {})", record.platform_id, record.encoding_id);
+
+                if format4.seg_count_x2 as usize != 2 * format4.end_codes.len() {
+                    report.errors.push(ValidationError {
+                        error_type: ValidationErrorType::InvalidCmapData,
+                        message: format!(
+                            "Format 4 subtable {} has seg_count_x2 {} for {} segments",
+                            encoding, format4.seg_count_x2, format4.end_codes.len()
+                        ),
+                        table: Some("cmap".to_string()),
+                    });
+                    report.is_valid = false;
+                }
+
+                if format4.end_codes.last() != Some(&0xFFFF) {
+                    report.errors.push(ValidationError {
+                        error_type: ValidationErrorType::InvalidCmapData,
+                        message: format!("Format 4 subtable {} is missing the 0xFFFF sentinel segment", encoding),
+                        table: Some("cmap".to_string()),
+                    });
+                    report.is_valid = false;
+                }
+            }
+        }
+
+        // Italic angles lean forward, which the post table expresses as negative
+        if let Ok(post) = self.post_table() {
+            let italic_name = [self.subfamily_name(), self.full_name()]
+                .into_iter()
+                .flatten()
+                .any(|name| name.contains("Italic"));
+            if italic_name && post.italic_angle_degrees() > 0.0 {
+                report.warnings.push(ValidationWarning {
+                    warning_type: ValidationWarningType::PotentiallyProblematic,
+                    message: format!(
+                        "Italic font has a positive italic angle ({}); forward slants are negative",
+                        post.italic_angle_degrees()
+                    ),
+                    table: Some("post".to_string()),
+                });
+            }
         }
 
         // Check for non-standard tables
         let standard_tables = [
             "cmap", "head", "hhea", "hmtx", "maxp", "name", "OS/2", "post",
-            "glyf", "loca", "kern", "GPOS", "GSUB", "BASE", "GDEF", "JSTF",
-            "vhea", "vmtx", "VORG", "CVT ", "fpgm", "prep", "gasp", "EBSC",
-            "trak", "ltsh", "PCLT", "VDMX", "hdmx", "CBDT", "CBLC", "COLR",
+            "glyf", "loca", "CFF ", "CFF2", "kern", "GPOS", "GSUB", "BASE", "GDEF", "JSTF",
+            "vhea", "vmtx", "VORG", "CVT ", "fpgm", "prep", "gasp", "EBDT", "EBLC", "EBSC",
+            "trak", "LTSH", "PCLT", "VDMX", "hdmx", "CBDT", "CBLC", "COLR",
             "CPAL", "sbix", "acnt", "avar", "bdat", "bloc", "bsln", "cvar",
             "fdsc", "feat", "fmtx", "fvar", "gvar", "gcid", "glyf", "hvar",
             "just", "lcar", "mort", "morx", "opbd", "prop", "trak", "Zapf",
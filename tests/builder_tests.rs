@@ -0,0 +1,163 @@
+// Tests for building fonts from scratch with FontBuilder
+
+mod common;
+
+use common::*;
+use ttf_rs::{
+    calculate_checksum, CmapTable, ContourPoint, Font, FontBuilder, GlyfTable, Glyph, GlyphData, GlyphNames,
+    HeadTable, HheaTable, HmtxTable, LongHorMetric, MaxpTable, PostTable, SimpleGlyph, TtfError,
+};
+
+/// A 100 x 700 bar
+fn bar_glyph() -> Glyph {
+    let mut simple = SimpleGlyph {
+        end_pts_of_contours: Vec::new(),
+        instruction_length: 0,
+        instructions: Vec::new(),
+        flags: Vec::new(),
+        x_coordinates: Vec::new(),
+        y_coordinates: Vec::new(),
+    };
+    let point = |x, y| ContourPoint { x, y, on_curve: true };
+    simple.set_contours(&[vec![point(50, 0), point(50, 700), point(150, 700), point(150, 0)]]);
+
+    Glyph {
+        number_of_contours: 1,
+        x_min: 50,
+        y_min: 0,
+        x_max: 150,
+        y_max: 700,
+        data: GlyphData::Simple(simple),
+    }
+}
+
+/// A builder holding a font with a single outline, the bar, mapped from 'I'
+/// after an empty .notdef
+fn one_glyph_builder() -> FontBuilder {
+    // name and OS/2 come from the shared test font
+    let source = Font::from_data(test_font()).unwrap();
+
+    let head = HeadTable {
+        table_version: 1.0,
+        font_revision: 1.0,
+        checksum_adjustment: 0,
+        magic_number: 0x5F0F3CF5,
+        flags: 0x000B,
+        units_per_em: 1000,
+        created: 0,
+        modified: 0,
+        x_min: 50,
+        y_min: 0,
+        x_max: 150,
+        y_max: 700,
+        mac_style: 0,
+        lowest_rec_ppem: 8,
+        font_direction_hint: 2,
+        // Set by the builder to match the loca it writes
+        index_to_loc_format: 1,
+        glyph_data_format: 0,
+    };
+    let maxp = MaxpTable {
+        version: MaxpTable::VERSION_1_0,
+        num_glyphs: 2,
+        max_points: Some(4),
+        max_contours: Some(1),
+        max_composite_points: Some(0),
+        max_composite_contours: Some(0),
+        max_zones: Some(2),
+        max_twilight_points: Some(0),
+        max_storage: Some(0),
+        max_function_defs: Some(0),
+        max_instruction_defs: Some(0),
+        max_stack_elements: Some(0),
+        max_size_of_instructions: Some(0),
+        max_component_elements: Some(0),
+        max_component_depth: Some(0),
+    };
+    let hhea = HheaTable {
+        table_version: 1.0,
+        ascent: 800,
+        descent: -200,
+        line_gap: 0,
+        advance_width_max: 200,
+        min_left_side_bearing: 50,
+        min_right_side_bearing: 50,
+        x_max_extent: 150,
+        caret_slope_rise: 1,
+        caret_slope_run: 0,
+        caret_offset: 0,
+        reserved0: 0,
+        reserved1: 0,
+        reserved2: 0,
+        reserved3: 0,
+        metric_data_format: 0,
+        number_of_h_metrics: 1,
+    };
+    let hmtx = HmtxTable {
+        h_metrics: vec![LongHorMetric { advance_width: 200, lsb: 0 }],
+        left_side_bearings: vec![50],
+    };
+    let post = PostTable {
+        format: PostTable::VERSION_2_0,
+        italic_angle: 0.0,
+        underline_position: -100,
+        underline_thickness: 50,
+        is_fixed_pitch: 0,
+        min_mem_type42: 0,
+        max_mem_type42: 0,
+        min_mem_type1: 0,
+        max_mem_type1: 0,
+        glyph_names: GlyphNames::Format2Custom(vec![".notdef".to_string(), "bar".to_string()]),
+    };
+
+    let mut builder = FontBuilder::new();
+    builder
+        .table(&head).unwrap()
+        .table(&maxp).unwrap()
+        .table(&hhea).unwrap()
+        .table(&hmtx).unwrap()
+        .table(&CmapTable::from_mappings(&[('I' as u32, 1)]).unwrap()).unwrap()
+        .table(&source.name_table().unwrap()).unwrap()
+        .table(&source.os2_table().unwrap()).unwrap()
+        .table(&post).unwrap()
+        .glyf(&GlyfTable { glyphs: vec![Glyph::empty(), bar_glyph()] });
+    builder
+}
+
+#[test]
+fn test_build_one_glyph_font() {
+    let built = one_glyph_builder().build().unwrap();
+    assert_eq!(calculate_checksum(&built.data), 0xB1B0AFBA);
+
+    let font = Font::from_data(built.to_bytes().unwrap()).unwrap();
+    assert_eq!(font.num_glyphs().unwrap(), 2);
+    assert_eq!(font.actual_glyph_count().unwrap(), 2);
+    assert_eq!(font.units_per_em().unwrap(), 1000);
+    assert_eq!(font.char_to_glyph('I').unwrap(), 1);
+    assert_eq!(font.advance_for_text("II", false).unwrap(), 400);
+    assert_eq!(font.name_for_glyph(1).unwrap().as_deref(), Some("bar"));
+
+    // Small glyph data gets a short loca
+    assert_eq!(font.head_table().unwrap().index_to_loc_format, 0);
+    let glyph = font.glyf_table().unwrap().get_glyph(1).unwrap().clone();
+    assert_eq!((glyph.x_min, glyph.y_min, glyph.x_max, glyph.y_max), (50, 0, 150, 700));
+    let GlyphData::Simple(simple) = &glyph.data else {
+        panic!("expected a simple glyph");
+    };
+    assert_eq!(simple.x_coordinates, vec![50, 50, 150, 150]);
+    assert_eq!(simple.y_coordinates, vec![0, 700, 700, 0]);
+
+    let report = font.validate().unwrap();
+    assert!(report.is_valid, "{}", report.summary());
+    assert!(report.warnings.is_empty(), "{}", report.summary());
+}
+
+#[test]
+fn test_build_requires_tables() {
+    let mut builder = FontBuilder::new();
+    builder.glyf(&GlyfTable { glyphs: vec![bar_glyph()] });
+    match builder.build() {
+        Err(TtfError::MissingTable(tag)) => assert_eq!(tag, "cmap"),
+        other => panic!("expected a missing table error, got {:?}", other.map(|_| ())),
+    }
+}
@@ -0,0 +1,205 @@
+// Tests for CFF table parsing and charstring interpretation
+
+mod common;
+
+use common::*;
+use ttf_rs::{CffTable, Font, FontReader, FontWriter, OutlineCommand, Point, TtfError, TtfTable};
+
+/// Encode an INDEX with 2-byte offsets
+fn cff_index(objects: &[Vec<u8>]) -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    writer.write_u16(objects.len() as u16);
+    if objects.is_empty() {
+        return writer.into_inner();
+    }
+    writer.write_u8(2);
+    let mut offset = 1u16;
+    writer.write_u16(offset);
+    for object in objects {
+        offset += object.len() as u16;
+        writer.write_u16(offset);
+    }
+    for object in objects {
+        writer.write_bytes(object);
+    }
+    writer.into_inner()
+}
+
+/// Encode a charstring integer operand
+fn num(value: i32) -> Vec<u8> {
+    if (-107..=107).contains(&value) {
+        vec![(value + 139) as u8]
+    } else {
+        let mut bytes = vec![28];
+        bytes.extend_from_slice(&(value as i16).to_be_bytes());
+        bytes
+    }
+}
+
+/// Concatenate operands and operators into a charstring
+fn charstring(parts: &[Vec<u8>]) -> Vec<u8> {
+    parts.concat()
+}
+
+/// Encode a DICT integer operand in its 5-byte form, so offsets have a
+/// fixed size
+fn dict_int(value: i32) -> Vec<u8> {
+    let mut bytes = vec![29];
+    bytes.extend_from_slice(&value.to_be_bytes());
+    bytes
+}
+
+/// Build a name-keyed CFF table from its charstrings and subroutines
+fn cff_table(char_strings: &[Vec<u8>], local_subrs: &[Vec<u8>], global_subrs: &[Vec<u8>]) -> Vec<u8> {
+    let header = vec![1, 0, 4, 1];
+    let names = cff_index(&[b"TestCFF".to_vec()]);
+    let strings = cff_index(&[]);
+    let global_subrs = cff_index(global_subrs);
+    let char_strings = cff_index(char_strings);
+    let local_subrs = cff_index(local_subrs);
+
+    // The Top DICT has a fixed size: CharStrings, then Private size and offset
+    let top_dict_size = 6 + 11;
+    let top_dicts_size = cff_index(&[vec![0; top_dict_size]]).len();
+    let char_strings_offset = header.len() + names.len() + top_dicts_size + strings.len() + global_subrs.len();
+
+    // Private DICT: Subrs (relative offset), defaultWidthX 250, nominalWidthX 500
+    let private_size = 6 + 6 + 6;
+    let private_offset = char_strings_offset + char_strings.len();
+    let private_dict = [
+        dict_int(private_size), vec![19],
+        dict_int(250), vec![20],
+        dict_int(500), vec![21],
+    ]
+    .concat();
+
+    let top_dict = [
+        dict_int(char_strings_offset as i32), vec![17],
+        dict_int(private_size), dict_int(private_offset as i32), vec![18],
+    ]
+    .concat();
+    assert_eq!(top_dict.len(), top_dict_size);
+
+    [header, names, cff_index(&[top_dict]), strings, global_subrs, char_strings, private_dict, local_subrs].concat()
+}
+
+fn parse(data: &[u8]) -> ttf_rs::Result<CffTable> {
+    let mut reader = FontReader::from_slice(data);
+    CffTable::from_reader(&mut reader, data.len() as u32)
+}
+
+fn p(x: f32, y: f32) -> Point {
+    Point::new(x, y)
+}
+
+/// .notdef, a glyph drawn through both kinds of subroutine, and a hinted glyph
+fn test_cff() -> Vec<u8> {
+    let notdef = vec![14];
+    let glyph = charstring(&[
+        num(100), num(100), num(0), vec![21], // width 600, rmoveto 100 0
+        num(0), num(700), vec![5],            // rlineto 0 700
+        num(-107), vec![10],                  // callsubr 0
+        num(-107), vec![29],                  // callgsubr 0
+        vec![14],                             // endchar
+    ]);
+    let hinted = charstring(&[
+        num(0), num(50), vec![1],   // hstem
+        num(10), num(20), vec![3],  // vstem
+        vec![19, 0xC0],             // hintmask, one byte for two stems
+        num(10), vec![4],           // vmoveto 10
+        num(20), num(30), vec![7],  // vlineto 20, then 30 across
+        vec![14],
+    ]);
+    let local_subr = charstring(&[
+        num(50), num(0), num(100), num(50), num(50), num(-50), vec![8], // rrcurveto
+        vec![11],
+    ]);
+    let global_subr = charstring(&[num(-700), vec![7], vec![11]]); // vlineto -700
+
+    cff_table(&[notdef, glyph, hinted], &[local_subr], &[global_subr])
+}
+
+#[test]
+fn test_cff_parse_indexes() {
+    let cff = parse(&test_cff()).unwrap();
+
+    assert_eq!((cff.major, cff.minor), (1, 0));
+    assert_eq!(cff.names, vec!["TestCFF"]);
+    assert!(cff.strings.is_empty());
+    assert_eq!(cff.num_glyphs(), 3);
+    assert_eq!(cff.local_subrs.len(), 1);
+    assert_eq!(cff.global_subrs.len(), 1);
+    assert_eq!((cff.default_width_x, cff.nominal_width_x), (250.0, 500.0));
+}
+
+#[test]
+fn test_cff_outline_move_line_curve_and_subrs() {
+    let cff = parse(&test_cff()).unwrap();
+    let outline = cff.outline(1).unwrap();
+
+    assert_eq!(outline.advance_width, 600.0);
+    assert_eq!(
+        outline.commands,
+        vec![
+            OutlineCommand::MoveTo(p(100.0, 0.0)),
+            OutlineCommand::LineTo(p(100.0, 700.0)),
+            OutlineCommand::CurveTo(p(150.0, 700.0), p(250.0, 750.0), p(300.0, 700.0)),
+            OutlineCommand::LineTo(p(300.0, 0.0)),
+            OutlineCommand::Close,
+        ]
+    );
+
+    // An empty charstring has no outline and the default width
+    let notdef = cff.outline(0).unwrap();
+    assert!(notdef.commands.is_empty());
+    assert_eq!(notdef.advance_width, 250.0);
+
+    assert!(matches!(cff.outline(3), Err(TtfError::InvalidGlyphIndex(3))));
+}
+
+#[test]
+fn test_cff_outline_skips_hints() {
+    let cff = parse(&test_cff()).unwrap();
+    let outline = cff.outline(2).unwrap();
+
+    assert_eq!(outline.advance_width, 250.0);
+    assert_eq!(
+        outline.commands,
+        vec![
+            OutlineCommand::MoveTo(p(0.0, 10.0)),
+            OutlineCommand::LineTo(p(0.0, 30.0)),
+            OutlineCommand::LineTo(p(30.0, 30.0)),
+            OutlineCommand::Close,
+        ]
+    );
+}
+
+#[test]
+fn test_cff_outline_rejects_unsupported_operators() {
+    // flex (12 35) is out of scope
+    let flex = charstring(&[vec![139; 13], vec![12, 35], vec![14]]);
+    let cff = parse(&cff_table(&[flex], &[], &[])).unwrap();
+    assert!(matches!(cff.outline(0), Err(TtfError::ParseError(_))));
+
+    // A subroutine that calls itself runs into the nesting limit
+    let recursive = [charstring(&[num(-107), vec![10]])];
+    let cff = parse(&cff_table(&recursive, &recursive, &[])).unwrap();
+    assert!(matches!(cff.outline(0), Err(TtfError::ParseError(_))));
+}
+
+#[test]
+fn test_font_cff_outline() {
+    let mut tables: Vec<([u8; 4], Vec<u8>)> = test_font_tables()
+        .into_iter()
+        .filter(|(tag, _)| tag != b"glyf" && tag != b"loca")
+        .collect();
+    tables.push((*b"CFF ", test_cff()));
+    let font = Font::from_data(build_font_with_version(0x4F54544F, &tables)).unwrap();
+
+    assert!(font.is_cff());
+    assert_eq!(font.cff_table().unwrap().num_glyphs(), 3);
+    assert_eq!(font.cff_outline(1).unwrap().commands.len(), 5);
+
+    let truetype = Font::from_data(test_font()).unwrap();
+    assert!(matches!(truetype.cff_table(), Err(TtfError::MissingTable(_))));
+}
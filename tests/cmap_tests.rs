@@ -0,0 +1,370 @@
+// Tests for cmap subtable selection
+
+mod common;
+
+use common::*;
+use ttf_rs::{
+    CmapSubtable, CmapTable, Font, FontReader, FontWriter, Format12, Format4, TtfError, TtfTable, TtfTableWrite, ValidationErrorType,
+};
+
+/// A Format 4 subtable of (platform, encoding, mappings)
+type EncodedSubtable<'a> = (u16, u16, &'a [(u16, u16)]);
+
+/// cmap with one Format 4 subtable per entry
+fn multi_cmap_table(subtables: &[EncodedSubtable]) -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    writer.write_u16(0); // version
+    writer.write_u16(subtables.len() as u16);
+
+    let encoded: Vec<Vec<u8>> = subtables.iter().map(|(_, _, mappings)| cmap_format4(mappings)).collect();
+    let mut offset = 4 + subtables.len() * 8;
+    for ((platform_id, encoding_id, _), data) in subtables.iter().zip(&encoded) {
+        writer.write_u16(*platform_id);
+        writer.write_u16(*encoding_id);
+        writer.write_u32(offset as u32);
+        offset += data.len();
+    }
+    for data in &encoded {
+        writer.write_bytes(data);
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn test_char_to_glyph_with_overrides_preferred_subtable() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"cmap", multi_cmap_table(&[
+        (3, 0, &[(0x41, GID_B)]),
+        (3, 1, &[(0x41, GID_A)]),
+    ]));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    // Windows Unicode BMP is preferred by default
+    assert_eq!(font.char_to_glyph('A').unwrap(), GID_A as u32);
+    assert_eq!(font.char_to_glyph_with('A', 3, 0).unwrap(), GID_B as u32);
+    assert_eq!(font.char_to_glyph_with('A', 3, 1).unwrap(), GID_A as u32);
+
+    assert!(font.char_to_glyph_with('A', 1, 0).is_err());
+    assert!(font.char_to_glyph_with('Z', 3, 0).is_err());
+
+    let cmap = font.cmap_table().unwrap();
+    assert!(cmap.subtable_for(3, 0).is_some());
+    assert!(cmap.subtable_for(0, 3).is_none());
+}
+
+#[test]
+fn test_symbol_cmap_private_use_remapping() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"cmap", cmap_table_with_encoding(3, 0, &[
+        (0xF020, GID_SPACE),
+        (0xF041, GID_A),
+    ]));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    assert_eq!(font.char_to_glyph('A').unwrap(), GID_A as u32);
+    assert_eq!(font.char_to_glyph(' ').unwrap(), GID_SPACE as u32);
+    assert_eq!(font.char_to_glyph('\u{F041}').unwrap(), GID_A as u32);
+    assert!(font.char_to_glyph('B').is_err());
+    // Only single-byte codes are remapped
+    assert!(font.char_to_glyph('\u{141}').is_err());
+}
+
+#[test]
+fn test_unicode_cmap_is_not_remapped() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"cmap", cmap_table(&[(0xF041, GID_A)]));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    assert!(font.char_to_glyph('A').is_err());
+}
+
+#[test]
+fn test_format4_glyph_id_array_lookup() {
+    // Segment 'a'..='c' maps through glyphIdArray [C, 0, E], then the sentinel
+    let mut subtable = FontWriter::new();
+    subtable.write_u16(4); // format
+    subtable.write_u16(16 + 2 * 8 + 3 * 2); // length
+    subtable.write_u16(0); // language
+    subtable.write_u16(4); // segCountX2
+    subtable.write_u16(4); // searchRange
+    subtable.write_u16(1); // entrySelector
+    subtable.write_u16(0); // rangeShift
+    subtable.write_u16(0x63);
+    subtable.write_u16(0xFFFF); // end codes
+    subtable.write_u16(0); // reserved pad
+    subtable.write_u16(0x61);
+    subtable.write_u16(0xFFFF); // start codes
+    subtable.write_i16(0);
+    subtable.write_i16(1); // id deltas
+    subtable.write_u16(4); // glyphIdArray[0] is 4 bytes past this slot
+    subtable.write_u16(0); // id range offsets
+    for glyph_id in [GID_C, 0, GID_E] {
+        subtable.write_u16(glyph_id);
+    }
+
+    let mut cmap = FontWriter::new();
+    cmap.write_u16(0);
+    cmap.write_u16(1);
+    cmap.write_u16(3);
+    cmap.write_u16(1);
+    cmap.write_u32(12);
+    cmap.write_bytes(&subtable.into_inner());
+
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"cmap", cmap.into_inner());
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    assert_eq!(font.char_to_glyph('a').unwrap(), GID_C as u32);
+    assert!(font.char_to_glyph('b').is_err());
+    assert_eq!(font.char_to_glyph('c').unwrap(), GID_E as u32);
+    assert!(font.char_to_glyph('d').is_err());
+    assert!(font.char_to_glyph('\u{0}').is_err());
+    assert!(font.char_to_glyph('\u{FFFF}').is_err());
+}
+
+#[test]
+fn test_format4_out_of_bounds_id_range_offset() {
+    // Segment 0 points far past glyphIdArray, segment 1 before its start
+    let format4 = Format4 {
+        format: 4,
+        length: 0,
+        language: 0,
+        seg_count_x2: 6,
+        search_range: 4,
+        entry_selector: 1,
+        range_shift: 2,
+        end_codes: vec![0x20FF, 0x30FF, 0xFFFF],
+        start_codes: vec![0x2000, 0x3000, 0xFFFF],
+        id_deltas: vec![0, 0, 1],
+        id_range_offsets: vec![0xFFFE, 2, 0],
+        glyph_id_array: vec![GID_A, GID_B],
+    };
+
+    assert_eq!(format4.get_glyph(0x2000), None);
+    assert_eq!(format4.get_glyph(0x20FF), None);
+    assert_eq!(format4.get_glyph(0x3000), None);
+    assert_eq!(format4.get_glyph(0x30FF), None);
+}
+
+#[test]
+fn test_unsupported_cmap_format_reports_offset() {
+    let mut cmap = FontWriter::new();
+    cmap.write_u16(0); // version
+    cmap.write_u16(1); // num tables
+    cmap.write_u16(3);
+    cmap.write_u16(1);
+    cmap.write_u32(12);
+    cmap.write_u16(8); // mixed 16/32-bit coverage, not supported
+    cmap.write_u16(6);
+    cmap.write_u16(0);
+
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"cmap", cmap.into_inner());
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let err = font.cmap_table().unwrap_err();
+    assert!(matches!(&err, TtfError::TableParseError { table, offset: 12, .. } if table == "cmap"));
+    assert_eq!(err.to_string(), "cmap at offset 0xc: unsupported subtable format 8");
+}
+
+#[test]
+fn test_subtable_mappings() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"cmap", cmap_table(&[(0x20, GID_SPACE), (0x41, GID_A), (0x42, GID_B)]));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let cmap = font.cmap_table().unwrap();
+    let mappings = cmap.get_best_subtable().unwrap().mappings();
+    assert_eq!(
+        mappings,
+        vec![(0x20, GID_SPACE as u32), (0x41, GID_A as u32), (0x42, GID_B as u32)]
+    );
+}
+
+#[test]
+fn test_format2_double_byte_lookup() {
+    // Subheader 0 maps the single bytes 0x20..=0x21, subheader 1 the low
+    // bytes 0x40..=0x42 after lead byte 0x81 (with idDelta 1)
+    let mut subtable = FontWriter::new();
+    subtable.write_u16(2); // format
+    subtable.write_u16(6 + 512 + 2 * 8 + 5 * 2); // length
+    subtable.write_u16(0); // language
+    for high in 0..256 {
+        subtable.write_u16(if high == 0x81 { 8 } else { 0 });
+    }
+    for (first_code, entry_count, id_delta, id_range_offset) in [(0x20, 2, 0, 10), (0x40, 3, 1, 6)] {
+        subtable.write_u16(first_code);
+        subtable.write_u16(entry_count);
+        subtable.write_i16(id_delta);
+        subtable.write_u16(id_range_offset); // bytes to glyphIdArray[0] and [2]
+    }
+    for glyph_id in [GID_SPACE, GID_A, GID_B - 1, 0, GID_D - 1] {
+        subtable.write_u16(glyph_id);
+    }
+    let subtable_data = subtable.into_inner();
+
+    let mut cmap = FontWriter::new();
+    cmap.write_u16(0);
+    cmap.write_u16(1);
+    cmap.write_u16(3);
+    cmap.write_u16(2); // Windows Shift-JIS
+    cmap.write_u32(12);
+    cmap.write_bytes(&subtable_data);
+    let cmap_data = cmap.into_inner();
+
+    let cmap = CmapTable::from_reader(&mut FontReader::from_slice(&cmap_data), cmap_data.len() as u32).unwrap();
+    let format2 = cmap.subtable_for(3, 2).unwrap();
+    assert!(matches!(format2, CmapSubtable::Format2(_)));
+
+    assert_eq!(format2.lookup(0x20), Some(GID_SPACE as u32));
+    assert_eq!(format2.lookup(0x21), Some(GID_A as u32));
+    assert_eq!(format2.lookup(0x8140), Some(GID_B as u32));
+    assert_eq!(format2.lookup(0x8141), None);
+    assert_eq!(format2.lookup(0x8142), Some(GID_D as u32));
+    assert_eq!(format2.lookup(0x8143), None);
+    // A lead byte alone, and a two-byte code without a subheader
+    assert_eq!(format2.lookup(0x81), None);
+    assert_eq!(format2.lookup(0x8240), None);
+
+    assert_eq!(
+        format2.mappings(),
+        vec![
+            (0x20, GID_SPACE as u32),
+            (0x21, GID_A as u32),
+            (0x8140, GID_B as u32),
+            (0x8142, GID_D as u32),
+        ]
+    );
+    assert_eq!(format2.to_bytes().unwrap(), subtable_data);
+}
+
+#[test]
+fn test_subtable_lookup_range_checks_code() {
+    let font = Font::from_data(test_font()).unwrap();
+    let cmap = font.cmap_table().unwrap();
+    let format4 = cmap.get_best_subtable().unwrap();
+    assert!(matches!(format4, CmapSubtable::Format4(_)));
+
+    assert_eq!(format4.lookup(0x41), Some(GID_A as u32));
+    // 0x10041 would truncate to 0x0041
+    assert_eq!(format4.lookup(0x1_0041), None);
+
+    let format12 = CmapSubtable::Format12(Format12::from_mappings(&[(0x1_0041, GID_B as u32)]));
+    assert_eq!(format12.lookup(0x1_0041), Some(GID_B as u32));
+    assert_eq!(format12.lookup(0x41), None);
+}
+
+#[test]
+fn test_glyph_ids_for_text() {
+    let font = Font::from_data(test_font()).unwrap();
+
+    // The euro sign isn't mapped and falls back to .notdef
+    let (glyph_ids, clusters) = font.glyph_ids_for_text("AB\u{20AC}").unwrap();
+    assert_eq!(glyph_ids, vec![GID_A, GID_B, GID_NOTDEF]);
+    assert_eq!(clusters, vec![0, 1, 2]);
+
+    // Indices count characters, not bytes
+    let (glyph_ids, clusters) = font.glyph_ids_for_text("\u{C1}O").unwrap();
+    assert_eq!(glyph_ids, vec![GID_AACUTE, GID_O]);
+    assert_eq!(clusters, vec![0, 1]);
+
+    assert_eq!(font.glyph_ids_for_text("").unwrap(), (vec![], vec![]));
+}
+
+#[test]
+fn test_covers_fully_mapped_text() {
+    let font = Font::from_data(test_font()).unwrap();
+
+    assert!(font.covers("BAD FACE").unwrap());
+    assert_eq!(font.coverage_ratio("BAD FACE").unwrap(), 1.0);
+    assert!(font.covers("").unwrap());
+    assert_eq!(font.coverage_ratio("").unwrap(), 1.0);
+}
+
+#[test]
+fn test_covers_text_with_missing_glyph() {
+    let font = Font::from_data(test_font()).unwrap();
+
+    assert!(!font.covers("ABZ").unwrap());
+    assert!((font.coverage_ratio("ABZ").unwrap() - 2.0 / 3.0).abs() < 1e-6);
+    assert_eq!(font.coverage_ratio("xyz").unwrap(), 0.0);
+}
+
+#[test]
+fn test_covers_treats_notdef_as_missing() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"cmap", cmap_table(&[(0x41, GID_A), (0x58, GID_NOTDEF)]));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    assert!(!font.covers("AX").unwrap());
+    assert_eq!(font.coverage_ratio("AX").unwrap(), 0.5);
+}
+
+#[test]
+fn test_cmap_from_mappings_round_trip() {
+    let mappings = vec![(0x41, 1), (0x42, 2), (0x43, 3), (0x61, 7), (0x1F600, 9), (0x1F601, 10)];
+    let cmap = CmapTable::from_mappings(&mappings).unwrap();
+
+    let mut writer = FontWriter::new();
+    cmap.write(&mut writer).unwrap();
+    let data = writer.into_inner();
+    let reparsed = CmapTable::from_reader(&mut FontReader::from_slice(&data), data.len() as u32).unwrap();
+
+    match reparsed.subtable_for(3, 1) {
+        // A-C share a segment; then 'a' and the sentinel
+        Some(CmapSubtable::Format4(format4)) => assert_eq!(format4.seg_count(), 3),
+        other => panic!("expected a Format 4 subtable, got {:?}", other),
+    }
+    match reparsed.subtable_for(3, 10) {
+        Some(CmapSubtable::Format12(format12)) => assert_eq!(format12.groups.len(), 3),
+        other => panic!("expected a Format 12 subtable, got {:?}", other),
+    }
+    assert_eq!(reparsed.subtable_for(3, 10).unwrap().mappings(), mappings);
+    assert_eq!(reparsed.subtable_for(3, 1).unwrap().mappings(), mappings[..4].to_vec());
+
+    let bmp_only = CmapTable::from_mappings(&mappings[..4]).unwrap();
+    assert_eq!(bmp_only.subtables.len(), 1);
+}
+
+#[test]
+fn test_cmap_from_mappings_too_many_segments() {
+    // Every mapping needs its own segment, more than a Format 4 length allows
+    let mappings: Vec<(u32, u32)> = (0..9000).map(|i| (i * 2, i + 1)).collect();
+    let cmap = CmapTable::from_mappings(&mappings).unwrap();
+    assert_eq!(cmap.subtables.len(), 1);
+    assert!(cmap.subtable_for(3, 1).is_none());
+
+    let mut writer = FontWriter::new();
+    cmap.write(&mut writer).unwrap();
+    let data = writer.into_inner();
+    let reparsed = CmapTable::from_reader(&mut FontReader::from_slice(&data), data.len() as u32).unwrap();
+    assert_eq!(reparsed.subtable_for(3, 10).unwrap().mappings(), mappings);
+
+    let bmp: Vec<(u16, u16)> = mappings.iter().map(|&(code, glyph_id)| (code as u16, glyph_id as u16)).collect();
+    assert!(matches!(Format4::from_mappings(&bmp), Err(TtfError::LimitExceeded(_))));
+
+    // Glyph IDs are 16-bit
+    assert!(matches!(CmapTable::from_mappings(&[(0x41, 0x1_0000)]), Err(TtfError::LimitExceeded(_))));
+}
+
+#[test]
+fn test_validate_reports_format4_missing_sentinel() {
+    let font = Font::from_data(test_font()).unwrap();
+    let report = font.validate().unwrap();
+    assert!(!report.errors.iter().any(|e| e.error_type == ValidationErrorType::InvalidCmapData));
+
+    // One real segment plus the sentinel; overwrite the sentinel's endCode
+    let mut cmap = cmap_table(&[(0x41, GID_A)]);
+    let sentinel_end_code = 12 + 14 + 2;
+    cmap[sentinel_end_code..sentinel_end_code + 2].copy_from_slice(&0x0042u16.to_be_bytes());
+
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"cmap", cmap);
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let report = font.validate().unwrap();
+    assert!(!report.is_valid);
+    assert!(report.errors.iter().any(|e| {
+        e.error_type == ValidationErrorType::InvalidCmapData && e.message.contains("sentinel")
+    }));
+}
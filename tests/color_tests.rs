@@ -0,0 +1,515 @@
+// Tests for color font tables (COLR/CPAL, sbix, CBDT/CBLC, SVG) and
+// monochrome EBLC/EBDT strikes
+
+mod common;
+
+use common::*;
+use ttf_rs::{
+    CblcTable, ColorBitmapFormat, ColorLayer, Font, FontReader, FontWriter, SbixTable, TtfError, TtfTable,
+};
+
+/// COLR v0 with base glyphs of (glyph id, [(layer glyph id, palette index)])
+fn colr_table(base_glyphs: &[(u16, &[(u16, u16)])]) -> Vec<u8> {
+    let num_layers: usize = base_glyphs.iter().map(|(_, layers)| layers.len()).sum();
+    let base_offset = 14u32;
+    let layer_offset = base_offset + base_glyphs.len() as u32 * 6;
+
+    let mut writer = FontWriter::new();
+    writer.write_u16(0); // version
+    writer.write_u16(base_glyphs.len() as u16);
+    writer.write_u32(base_offset);
+    writer.write_u32(layer_offset);
+    writer.write_u16(num_layers as u16);
+
+    let mut first_layer = 0;
+    for &(glyph_id, layers) in base_glyphs {
+        writer.write_u16(glyph_id);
+        writer.write_u16(first_layer);
+        writer.write_u16(layers.len() as u16);
+        first_layer += layers.len() as u16;
+    }
+    for &(_, layers) in base_glyphs {
+        for &(glyph_id, palette_index) in layers {
+            writer.write_u16(glyph_id);
+            writer.write_u16(palette_index);
+        }
+    }
+    writer.into_inner()
+}
+
+/// CPAL v0 with palettes of RGBA colors, all the same size
+fn cpal_table(palettes: &[&[[u8; 4]]]) -> Vec<u8> {
+    let entries = palettes[0].len() as u16;
+    let mut writer = FontWriter::new();
+    writer.write_u16(0); // version
+    writer.write_u16(entries);
+    writer.write_u16(palettes.len() as u16);
+    writer.write_u16(entries * palettes.len() as u16);
+    writer.write_u32(12 + palettes.len() as u32 * 2); // color records offset
+    for i in 0..palettes.len() as u16 {
+        writer.write_u16(i * entries);
+    }
+    for palette in palettes {
+        for &[r, g, b, a] in *palette {
+            writer.write_bytes(&[b, g, r, a]);
+        }
+    }
+    writer.into_inner()
+}
+
+fn color_font() -> Font {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"COLR", colr_table(&[
+        (GID_O, &[(GID_B, 1), (GID_A, 0xFFFF)]),
+        (GID_AACUTE, &[(GID_A, 0), (GID_ACUTE, 1)]),
+    ]));
+    replace_table(&mut tables, b"CPAL", cpal_table(&[
+        &[[255, 0, 0, 255], [0, 0, 255, 128]],
+        &[[0, 255, 0, 255], [20, 30, 40, 255]],
+    ]));
+    Font::from_data(build_font(&tables)).unwrap()
+}
+
+#[test]
+fn test_color_layers() {
+    let font = color_font();
+
+    let layers = font.color_layers(GID_O).unwrap().unwrap();
+    assert_eq!(layers, vec![
+        ColorLayer { glyph_id: GID_B, palette_index: 1 },
+        ColorLayer { glyph_id: GID_A, palette_index: 0xFFFF },
+    ]);
+    assert!(!layers[0].is_foreground());
+    assert!(layers[1].is_foreground());
+
+    let layers = font.color_layers(GID_AACUTE).unwrap().unwrap();
+    assert_eq!(layers[1].glyph_id, GID_ACUTE);
+
+    assert!(font.color_layers(GID_B).unwrap().is_none());
+}
+
+#[test]
+fn test_palettes() {
+    let font = color_font();
+
+    assert_eq!(font.palette(0).unwrap(), vec![[255, 0, 0, 255], [0, 0, 255, 128]]);
+    assert_eq!(font.palette(1).unwrap(), vec![[0, 255, 0, 255], [20, 30, 40, 255]]);
+    assert!(font.palette(2).is_err());
+}
+
+#[test]
+fn test_monochrome_font_has_no_color_layers() {
+    let font = Font::from_data(test_font()).unwrap();
+
+    assert!(font.color_layers(GID_A).unwrap().is_none());
+    assert!(matches!(font.palette(0), Err(TtfError::MissingTable(_))));
+}
+
+const PNG_BLOB: &[u8] = b"\x89PNG\r\n\x1a\nfake image data";
+
+/// An sbix strike of (ppem, [(glyph id, graphic type, data)])
+type SbixStrike<'a> = (u16, &'a [(u16, [u8; 4], &'a [u8])]);
+
+/// sbix with the given strikes for 11 glyphs
+fn sbix_table(strikes: &[SbixStrike]) -> Vec<u8> {
+    let num_glyphs = 11;
+    let mut strike_data = Vec::new();
+    for &(ppem, glyphs) in strikes {
+        let mut header = FontWriter::new();
+        let mut body = FontWriter::new();
+        header.write_u16(ppem);
+        header.write_u16(72); // ppi
+        let body_start = 4 + (num_glyphs + 1) * 4;
+        for glyph_id in 0..num_glyphs as u16 {
+            header.write_u32((body_start + body.position()) as u32);
+            if let Some(&(_, graphic_type, data)) = glyphs.iter().find(|(id, _, _)| *id == glyph_id) {
+                body.write_i16(0); // origin x
+                body.write_i16(-20); // origin y
+                body.write_tag(&graphic_type);
+                body.write_bytes(data);
+            }
+        }
+        header.write_u32((body_start + body.position()) as u32);
+        header.write_bytes(&body.into_inner());
+        strike_data.push(header.into_inner());
+    }
+
+    let mut writer = FontWriter::new();
+    writer.write_u16(1); // version
+    writer.write_u16(1); // flags
+    writer.write_u32(strikes.len() as u32);
+    let mut offset = 8 + strikes.len() * 4;
+    for strike in &strike_data {
+        writer.write_u32(offset as u32);
+        offset += strike.len();
+    }
+    for strike in &strike_data {
+        writer.write_bytes(strike);
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn test_sbix_glyph() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"sbix", sbix_table(&[
+        (20, &[(GID_A, *b"png ", b"small")]),
+        (64, &[(GID_A, *b"png ", PNG_BLOB), (GID_B, *b"dupe", &[0, GID_A as u8])]),
+    ]));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let glyph = font.sbix_glyph(GID_A, 64).unwrap().unwrap();
+    assert_eq!(glyph.graphic_type_to_string(), "png ");
+    assert_eq!(glyph.data, PNG_BLOB);
+    assert_eq!((glyph.origin_offset_x, glyph.origin_offset_y), (0, -20));
+
+    // Sizes between strikes use the next larger one, larger sizes the largest
+    assert_eq!(font.sbix_glyph(GID_A, 32).unwrap().unwrap().data, PNG_BLOB);
+    assert_eq!(font.sbix_glyph(GID_A, 128).unwrap().unwrap().data, PNG_BLOB);
+    assert_eq!(font.sbix_glyph(GID_A, 12).unwrap().unwrap().data, b"small");
+
+    // Duplicates resolve to the referenced glyph
+    assert_eq!(font.sbix_glyph(GID_B, 64).unwrap().unwrap().data, PNG_BLOB);
+    assert!(font.sbix_glyph(GID_O, 64).unwrap().is_none());
+}
+
+#[test]
+fn test_sbix_record_past_end_of_data() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"sbix", sbix_table(&[(20, &[(GID_A, *b"png ", b"small")])]));
+    let mut font = Font::from_data(build_font(&tables)).unwrap();
+
+    let record = font.table_records.iter_mut().find(|r| r.table_tag == *b"sbix").unwrap();
+    record.length = u32::MAX / 2;
+    assert!(matches!(font.sbix_table(), Err(TtfError::InvalidOffset(_))));
+}
+
+#[test]
+fn test_sbix_strike_count_is_not_trusted() {
+    // Version, flags and u32::MAX strikes with a single offset present
+    let data = [0, 1, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 12, 0, 0];
+    let result = SbixTable::from_reader(&mut FontReader::from_slice(&data), data.len() as u32, 11);
+    assert!(matches!(result, Err(TtfError::UnexpectedEof { .. })));
+}
+
+#[test]
+fn test_sbix_glyph_without_table() {
+    let font = Font::from_data(test_font()).unwrap();
+    assert!(font.sbix_glyph(GID_A, 64).unwrap().is_none());
+}
+
+/// CBLC with one 109 ppem strike whose single index subtable (format 1,
+/// image format 17) covers `glyph_id` alone, plus the matching CBDT
+fn cblc_cbdt_tables(glyph_id: u16, png: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let mut cbdt = FontWriter::new();
+    cbdt.write_u16(3); // major version
+    cbdt.write_u16(0); // minor version
+    let image_offset = cbdt.position() as u32;
+    cbdt.write_bytes(&[109, 109, 0, 100, 109]); // small glyph metrics
+    cbdt.write_u32(png.len() as u32);
+    cbdt.write_bytes(png);
+    let image_length = cbdt.position() as u32 - image_offset;
+
+    let mut cblc = FontWriter::new();
+    cblc.write_u16(3); // major version
+    cblc.write_u16(0); // minor version
+    cblc.write_u32(1); // num sizes
+    let array_offset = 8 + 48;
+    cblc.write_u32(array_offset); // index subtable array offset
+    cblc.write_u32(8 + 8 + 8); // index tables size
+    cblc.write_u32(1); // number of index subtables
+    cblc.write_u32(0); // color ref
+    cblc.write_bytes(&[0; 24]); // line metrics
+    cblc.write_u16(glyph_id);
+    cblc.write_u16(glyph_id);
+    cblc.write_bytes(&[109, 109, 32, 1]); // ppem x/y, bit depth, flags
+
+    // Index subtable array, then the format 1 subtable
+    cblc.write_u16(glyph_id);
+    cblc.write_u16(glyph_id);
+    cblc.write_u32(8);
+    cblc.write_u16(1); // index format
+    cblc.write_u16(17); // image format
+    cblc.write_u32(image_offset);
+    cblc.write_u32(0);
+    cblc.write_u32(image_length);
+
+    (cblc.into_inner(), cbdt.into_inner())
+}
+
+#[test]
+fn test_color_bitmap() {
+    let mut tables = test_font_tables();
+    let (cblc, cbdt) = cblc_cbdt_tables(GID_O, PNG_BLOB);
+    replace_table(&mut tables, b"CBLC", cblc);
+    replace_table(&mut tables, b"CBDT", cbdt);
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let bitmap = font.color_bitmap(GID_O, 109).unwrap().unwrap();
+    assert_eq!(bitmap.format, ColorBitmapFormat::Png);
+    assert_eq!(bitmap.data, PNG_BLOB);
+
+    // The only strike serves other sizes too
+    assert_eq!(font.color_bitmap(GID_O, 32).unwrap().unwrap().data, PNG_BLOB);
+    assert!(font.color_bitmap(GID_A, 109).unwrap().is_none());
+
+    let plain = Font::from_data(test_font()).unwrap();
+    assert!(plain.color_bitmap(GID_O, 109).unwrap().is_none());
+}
+
+/// CBLC with one strike covering glyphs 1 to 3 through a single index
+/// subtable of `index_format` whose body (after the image data offset) is
+/// `subtable`
+fn cblc_with_index_subtable(index_format: u16, subtable: &[u8]) -> Vec<u8> {
+    let mut cblc = FontWriter::new();
+    cblc.write_u16(3); // major version
+    cblc.write_u16(0); // minor version
+    cblc.write_u32(1); // num sizes
+    cblc.write_u32(8 + 48); // index subtable array offset
+    cblc.write_u32(8 + 8 + subtable.len() as u32); // index tables size
+    cblc.write_u32(1); // number of index subtables
+    cblc.write_u32(0); // color ref
+    cblc.write_bytes(&[0; 24]); // line metrics
+    cblc.write_u16(1);
+    cblc.write_u16(3);
+    cblc.write_bytes(&[109, 109, 32, 1]); // ppem x/y, bit depth, flags
+
+    cblc.write_u16(1);
+    cblc.write_u16(3);
+    cblc.write_u32(8);
+    cblc.write_u16(index_format);
+    cblc.write_u16(17); // image format
+    cblc.write_u32(4); // image data offset
+    cblc.write_bytes(subtable);
+    cblc.into_inner()
+}
+
+fn parse_cblc(data: &[u8]) -> ttf_rs::Result<CblcTable> {
+    CblcTable::from_reader(&mut FontReader::from_slice(data), data.len() as u32)
+}
+
+#[test]
+fn test_cblc_size_count_is_not_trusted() {
+    // Eight bytes claiming u32::MAX strikes
+    let data = [0, 3, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF];
+    assert!(matches!(parse_cblc(&data), Err(TtfError::UnexpectedEof { .. })));
+}
+
+#[test]
+fn test_cblc_index_format4_glyph_count_is_not_trusted() {
+    let mut subtable = u32::MAX.to_be_bytes().to_vec(); // num glyphs
+    subtable.extend_from_slice(&[0, 1, 0, 0]);
+    let data = cblc_with_index_subtable(4, &subtable);
+    assert!(matches!(parse_cblc(&data), Err(TtfError::UnexpectedEof { .. })));
+}
+
+#[test]
+fn test_cblc_index_format2_image_size_overflow() {
+    let mut subtable = 0x8000_0000u32.to_be_bytes().to_vec(); // image size
+    subtable.extend_from_slice(&[0; 8]); // big glyph metrics
+    let data = cblc_with_index_subtable(2, &subtable);
+    assert!(matches!(parse_cblc(&data), Err(TtfError::InvalidOffset(_))));
+}
+
+#[test]
+fn test_cblc_index_format5_image_size_overflow() {
+    let mut subtable = 0x8000_0000u32.to_be_bytes().to_vec(); // image size
+    subtable.extend_from_slice(&[0; 8]); // big glyph metrics
+    subtable.extend_from_slice(&3u32.to_be_bytes()); // num glyphs
+    subtable.extend_from_slice(&[0, 1, 0, 2, 0, 3]);
+    let data = cblc_with_index_subtable(5, &subtable);
+    assert!(matches!(parse_cblc(&data), Err(TtfError::InvalidOffset(_))));
+
+    // A size that fits still parses
+    let mut subtable = 10u32.to_be_bytes().to_vec();
+    subtable.extend_from_slice(&[0; 8]);
+    subtable.extend_from_slice(&3u32.to_be_bytes());
+    subtable.extend_from_slice(&[0, 1, 0, 2, 0, 3]);
+    let cblc = parse_cblc(&cblc_with_index_subtable(5, &subtable)).unwrap();
+    assert_eq!(cblc.bitmap_sizes[0].index_subtables[0].glyph_locations, vec![(1, 4, 10), (2, 14, 10), (3, 24, 10)]);
+}
+
+/// EBLC with one 12 ppem, 1-bit strike whose single index subtable (format 3,
+/// image format 1) covers `glyph_id` alone, plus the matching EBDT
+fn eblc_ebdt_tables(glyph_id: u16, metrics: [u8; 5], image: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let mut ebdt = FontWriter::new();
+    ebdt.write_u16(2); // major version
+    ebdt.write_u16(0); // minor version
+    let image_offset = ebdt.position() as u32;
+    ebdt.write_bytes(&metrics); // small glyph metrics
+    ebdt.write_bytes(image);
+    let image_length = ebdt.position() as u32 - image_offset;
+
+    let mut eblc = FontWriter::new();
+    eblc.write_u16(2); // major version
+    eblc.write_u16(0); // minor version
+    eblc.write_u32(1); // num sizes
+    let array_offset = 8 + 48;
+    eblc.write_u32(array_offset); // index subtable array offset
+    eblc.write_u32(8 + 8 + 4); // index tables size
+    eblc.write_u32(1); // number of index subtables
+    eblc.write_u32(0); // color ref
+    eblc.write_bytes(&[0; 24]); // line metrics
+    eblc.write_u16(glyph_id);
+    eblc.write_u16(glyph_id);
+    eblc.write_bytes(&[12, 12, 1, 1]); // ppem x/y, bit depth, flags
+
+    // Index subtable array, then the format 3 subtable
+    eblc.write_u16(glyph_id);
+    eblc.write_u16(glyph_id);
+    eblc.write_u32(8);
+    eblc.write_u16(3); // index format
+    eblc.write_u16(1); // image format
+    eblc.write_u32(image_offset);
+    eblc.write_u16(0);
+    eblc.write_u16(image_length as u16);
+
+    (eblc.into_inner(), ebdt.into_inner())
+}
+
+#[test]
+fn test_embedded_bitmap() {
+    // A 10x3 box outline: full rows around a row with only its end pixels
+    let image = [0xFF, 0xC0, 0x80, 0x40, 0xFF, 0xC0];
+    let mut tables = test_font_tables();
+    let (eblc, ebdt) = eblc_ebdt_tables(GID_O, [3, 10, 1, 3, 11], &image);
+    replace_table(&mut tables, b"EBLC", eblc);
+    replace_table(&mut tables, b"EBDT", ebdt);
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let bitmap = font.embedded_bitmap(GID_O, 12).unwrap().unwrap();
+    assert_eq!((bitmap.width, bitmap.height, bitmap.bit_depth), (10, 3, 1));
+    assert_eq!((bitmap.bearing_x, bitmap.bearing_y, bitmap.advance), (1, 3, 11));
+    assert_eq!(bitmap.stride(), 2);
+    assert_eq!(bitmap.data, image);
+    assert_eq!(bitmap.pixel(0, 1), 1);
+    assert_eq!(bitmap.pixel(5, 1), 0);
+    assert_eq!(bitmap.pixel(9, 1), 1);
+
+    assert!(font.embedded_bitmap(GID_A, 12).unwrap().is_none());
+    assert!(!font.validate().unwrap().warnings.iter().any(|w| w.message.contains("EBLC")));
+
+    let plain = Font::from_data(test_font()).unwrap();
+    assert!(plain.embedded_bitmap(GID_O, 12).unwrap().is_none());
+}
+
+#[test]
+fn test_eblc_record_past_end_of_data() {
+    let mut tables = test_font_tables();
+    let (eblc, ebdt) = eblc_ebdt_tables(GID_O, [3, 10, 1, 3, 11], &[0xFF; 6]);
+    replace_table(&mut tables, b"EBLC", eblc);
+    replace_table(&mut tables, b"EBDT", ebdt);
+    let mut font = Font::from_data(build_font(&tables)).unwrap();
+
+    let record = font.table_records.iter_mut().find(|r| r.table_tag == *b"EBLC").unwrap();
+    record.length = u32::MAX / 2;
+    assert!(matches!(font.eblc_table(), Err(TtfError::InvalidOffset(_))));
+}
+
+const SVG_DOC: &str = r#"<svg xmlns="http://www.w3.org/2000/svg"><rect id="glyph7" width="10" height="10"/></svg>"#;
+
+/// SVG table with documents of (start glyph, end glyph, data)
+fn svg_table(documents: &[(u16, u16, &[u8])]) -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    writer.write_u16(0); // version
+    writer.write_u32(10); // document list offset
+    writer.write_u32(0); // reserved
+
+    writer.write_u16(documents.len() as u16);
+    let mut offset = 2 + documents.len() * 12;
+    for &(start, end, data) in documents {
+        writer.write_u16(start);
+        writer.write_u16(end);
+        writer.write_u32(offset as u32);
+        writer.write_u32(data.len() as u32);
+        offset += data.len();
+    }
+    for &(_, _, data) in documents {
+        writer.write_bytes(data);
+    }
+    writer.into_inner()
+}
+
+fn svg_font(documents: &[(u16, u16, &[u8])]) -> Font {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"SVG ", svg_table(documents));
+    Font::from_data(build_font(&tables)).unwrap()
+}
+
+#[test]
+fn test_svg_document_glyph_range() {
+    let font = svg_font(&[(GID_C, GID_F, SVG_DOC.as_bytes())]);
+
+    for glyph_id in GID_C..=GID_F {
+        assert_eq!(font.svg_document(glyph_id).unwrap().as_deref(), Some(SVG_DOC));
+    }
+    assert!(font.svg_document(GID_A).unwrap().is_none());
+
+    let svg = font.svg_table().unwrap();
+    assert_eq!(svg.svg_documents.len(), 1);
+    assert!(!svg.svg_documents[0].is_compressed());
+
+    // Fonts without an SVG table have no documents
+    let plain = Font::from_data(test_font()).unwrap();
+    assert!(plain.svg_document(GID_C).unwrap().is_none());
+}
+
+/// gzip of SVG_DOC (fixed Huffman block)
+const SVG_DOC_GZ: &str = "1f8b0800000000000203b3292e4b57a8c8cdc92bb655ca282929b0d2d72f2f2fd72b37d6cb2f4ad737323030d007aa50b2b3294a4d2e51c84cb1554acfa92cc830575228cf4c29c9b05532345052c848cd4ccf2801b3f5ed6c401aec002471812f58000000";
+
+/// gzip of a 1619-byte document with 40 paths (dynamic Huffman block)
+#[cfg(feature = "compression")]
+const SVG_PATHS_GZ: &str = "1f8b08000000000002036dd54b4ec3301485e1ad585e40731f8eeda0b62b800d3043424a900a443422b07b5a89d1b967940cfed1f962e778fd9ed3cffbe5e37acacbb6ad0fc3b0effb61f7c3e7d73c98880cb7229f8febcbb6a4b7d7539e2fbfeb2239dd5e9f24497a54b93f9ef3809142d45864106961954365caaa825567d508958facaa50156355c36a6255876aacac9aa0aa4e47c5e91bdf3e8c4fd7579cbfd3f915f79fe8fe8a001305501450a1048a06aa14415be82883a2831a85509450a71426e1dba516a6a1e347219c85916a186a68a51c5642473d2c7834ea61c1a3530f0b1e9d7a58f098a887a18709f57009b704f5700d1df570f430e39753b89d9c7a78b89f9c7a387a58a11e8e1e36520f6fa1a31e8e1e56a987078ff6ef71ff579cff0057340c8a53060000";
+
+#[cfg(feature = "compression")]
+#[test]
+fn test_svg_document_gzip() {
+    let small = hex::decode(SVG_DOC_GZ).unwrap();
+    let large = hex::decode(SVG_PATHS_GZ).unwrap();
+    let font = svg_font(&[(GID_A, GID_A, &small), (GID_B, GID_B, &large)]);
+
+    assert_eq!(font.svg_document(GID_A).unwrap().as_deref(), Some(SVG_DOC));
+
+    let text = font.svg_document(GID_B).unwrap().unwrap();
+    assert_eq!(text.len(), 1619);
+    assert!(text.starts_with("<svg"));
+    assert!(text.ends_with(r#"<path id="glyph39" d="M0 0 L100 273 Z"/></svg>"#));
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn test_svg_document_gzip_checks_trailer() {
+    let mut corrupt = hex::decode(SVG_DOC_GZ).unwrap();
+    let crc = corrupt.len() - 8;
+    corrupt[crc] ^= 0xFF;
+    let mut truncated = hex::decode(SVG_DOC_GZ).unwrap();
+    truncated.truncate(truncated.len() - 4);
+    let font = svg_font(&[(GID_A, GID_A, &corrupt), (GID_B, GID_B, &truncated)]);
+
+    assert!(matches!(font.svg_document(GID_A), Err(TtfError::ParseError(_))));
+    assert!(matches!(font.svg_document(GID_B), Err(TtfError::ParseError(_))));
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn test_svg_document_gzip_size_limit() {
+    use std::io::Write;
+
+    // 16 MiB of spaces plus one byte compresses to a few kilobytes
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+    encoder.write_all(&vec![b' '; 16 * 1024 * 1024 + 1]).unwrap();
+    let bomb = encoder.finish().unwrap();
+    let font = svg_font(&[(GID_A, GID_A, &bomb)]);
+
+    assert!(matches!(font.svg_document(GID_A), Err(TtfError::LimitExceeded(_))));
+}
+
+#[cfg(not(feature = "compression"))]
+#[test]
+fn test_svg_document_gzip_needs_feature() {
+    let small = hex::decode(SVG_DOC_GZ).unwrap();
+    let font = svg_font(&[(GID_A, GID_A, &small)]);
+
+    assert!(font.svg_table().unwrap().svg_documents[0].is_compressed());
+    assert!(matches!(font.svg_document(GID_A), Err(TtfError::ParseError(_))));
+}
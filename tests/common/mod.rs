@@ -0,0 +1,663 @@
+// Shared helpers for building synthetic fonts in integration tests
+//
+// Each helper produces the raw bytes of a single table so tests can assemble
+// exactly the font they need with `build_font`.
+
+#![allow(dead_code)]
+
+use ttf_rs::{calculate_checksum, FontWriter};
+
+/// Pack a list of tables into a complete SFNT file with a valid directory
+pub fn build_font(tables: &[([u8; 4], Vec<u8>)]) -> Vec<u8> {
+    build_font_with_version(0x00010000, tables)
+}
+
+/// Pack a list of tables into an SFNT file with the given sfnt version
+pub fn build_font_with_version(sfnt_version: u32, tables: &[([u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let num_tables = tables.len() as u16;
+    let mut max_power = 1u16;
+    let mut entry_selector = 0u16;
+    while max_power * 2 <= num_tables {
+        max_power *= 2;
+        entry_selector += 1;
+    }
+
+    let mut writer = FontWriter::new();
+    writer.write_u32(sfnt_version);
+    writer.write_u16(num_tables);
+    writer.write_u16(max_power * 16);
+    writer.write_u16(entry_selector);
+    writer.write_u16(num_tables.saturating_sub(max_power) * 16);
+
+    let mut offset = 12 + tables.len() * 16;
+    for (tag, data) in tables {
+        writer.write_tag(tag);
+        writer.write_u32(calculate_checksum(data));
+        writer.write_u32(offset as u32);
+        writer.write_u32(data.len() as u32);
+        offset += (data.len() + 3) & !3;
+    }
+
+    for (_, data) in tables {
+        writer.write_bytes(data);
+        writer.write_padding(4);
+    }
+
+    writer.into_inner()
+}
+
+pub fn head_table(units_per_em: u16) -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    writer.write_fixed(1.0); // version
+    writer.write_fixed(1.0); // font revision
+    writer.write_u32(0); // checksum adjustment
+    writer.write_u32(0x5F0F3CF5); // magic number
+    writer.write_u16(0); // flags
+    writer.write_u16(units_per_em);
+    writer.write_i64(0); // created
+    writer.write_i64(0); // modified
+    writer.write_i16(0); // xMin
+    writer.write_i16(0); // yMin
+    writer.write_i16(600); // xMax
+    writer.write_i16(850); // yMax
+    writer.write_u16(0); // mac style
+    writer.write_u16(8); // lowest rec ppem
+    writer.write_i16(2); // font direction hint
+    writer.write_i16(1); // index to loc format (long)
+    writer.write_i16(0); // glyph data format
+    writer.into_inner()
+}
+
+pub fn maxp_table(num_glyphs: u16) -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    writer.write_fixed(1.0); // version
+    writer.write_u16(num_glyphs);
+    writer.write_u16(16); // max points
+    writer.write_u16(2); // max contours
+    writer.write_u16(6); // max composite points
+    writer.write_u16(2); // max composite contours
+    writer.write_u16(2); // max zones
+    writer.write_u16(0); // max twilight points
+    writer.write_u16(0); // max storage
+    writer.write_u16(0); // max function defs
+    writer.write_u16(0); // max instruction defs
+    writer.write_u16(0); // max stack elements
+    writer.write_u16(0); // max size of instructions
+    writer.write_u16(2); // max component elements
+    writer.write_u16(1); // max component depth
+    writer.into_inner()
+}
+
+pub fn hhea_table(number_of_h_metrics: u16) -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    writer.write_fixed(1.0); // version
+    writer.write_i16(800); // ascent
+    writer.write_i16(-200); // descent
+    writer.write_i16(90); // line gap
+    writer.write_u16(600); // advance width max
+    writer.write_i16(0); // min left side bearing
+    writer.write_i16(0); // min right side bearing
+    writer.write_i16(600); // x max extent
+    writer.write_i16(1); // caret slope rise
+    writer.write_i16(0); // caret slope run
+    writer.write_i16(0); // caret offset
+    for _ in 0..4 {
+        writer.write_i16(0); // reserved
+    }
+    writer.write_i16(0); // metric data format
+    writer.write_u16(number_of_h_metrics);
+    writer.into_inner()
+}
+
+pub fn hmtx_table(metrics: &[(u16, i16)]) -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    for &(advance, lsb) in metrics {
+        writer.write_u16(advance);
+        writer.write_i16(lsb);
+    }
+    writer.into_inner()
+}
+
+/// Build a cmap with a single (3,1) Format 4 subtable, one segment per mapping
+pub fn cmap_table(mappings: &[(u16, u16)]) -> Vec<u8> {
+    cmap_table_with_encoding(3, 1, mappings)
+}
+
+/// Build a cmap with a single Format 4 subtable under the given encoding record
+pub fn cmap_table_with_encoding(platform_id: u16, encoding_id: u16, mappings: &[(u16, u16)]) -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    writer.write_u16(0); // version
+    writer.write_u16(1); // num tables
+    writer.write_u16(platform_id);
+    writer.write_u16(encoding_id);
+    writer.write_u32(12);
+    writer.write_bytes(&cmap_format4(mappings));
+    writer.into_inner()
+}
+
+/// Build a Format 4 subtable with one segment per mapping plus the 0xFFFF sentinel
+pub fn cmap_format4(mappings: &[(u16, u16)]) -> Vec<u8> {
+    let mut sorted = mappings.to_vec();
+    sorted.sort();
+
+    let seg_count = sorted.len() as u16 + 1;
+    let mut search_range = 2u16;
+    let mut entry_selector = 0u16;
+    while search_range * 2 <= seg_count * 2 {
+        search_range *= 2;
+        entry_selector += 1;
+    }
+
+    let mut writer = FontWriter::new();
+    writer.write_u16(4); // format
+    writer.write_u16(16 + seg_count * 8); // length
+    writer.write_u16(0); // language
+    writer.write_u16(seg_count * 2);
+    writer.write_u16(search_range);
+    writer.write_u16(entry_selector);
+    writer.write_u16(seg_count * 2 - search_range);
+    for &(code, _) in &sorted {
+        writer.write_u16(code);
+    }
+    writer.write_u16(0xFFFF);
+    writer.write_u16(0); // reserved pad
+    for &(code, _) in &sorted {
+        writer.write_u16(code);
+    }
+    writer.write_u16(0xFFFF);
+    for &(code, glyph) in &sorted {
+        writer.write_i16(glyph.wrapping_sub(code) as i16);
+    }
+    writer.write_i16(1);
+    for _ in 0..seg_count {
+        writer.write_u16(0); // id range offset
+    }
+    writer.into_inner()
+}
+
+/// Build a name table from (platform, encoding, language, name id, value) records
+///
+/// Windows and Unicode platform strings are encoded as UTF-16BE, Macintosh as
+/// single-byte text.
+pub fn name_table(records: &[(u16, u16, u16, u16, &str)]) -> Vec<u8> {
+    let mut storage = Vec::new();
+    let mut writer = FontWriter::new();
+    writer.write_u16(0); // format
+    writer.write_u16(records.len() as u16);
+    writer.write_u16(6 + records.len() as u16 * 12);
+
+    for &(platform_id, encoding_id, language_id, name_id, value) in records {
+        let bytes: Vec<u8> = if platform_id == 1 {
+            value.bytes().collect()
+        } else {
+            value.encode_utf16().flat_map(|u| u.to_be_bytes()).collect()
+        };
+        writer.write_u16(platform_id);
+        writer.write_u16(encoding_id);
+        writer.write_u16(language_id);
+        writer.write_u16(name_id);
+        writer.write_u16(bytes.len() as u16);
+        writer.write_u16(storage.len() as u16);
+        storage.extend_from_slice(&bytes);
+    }
+
+    writer.write_bytes(&storage);
+    writer.into_inner()
+}
+
+pub fn os2_table() -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    writer.write_u16(4); // version
+    writer.write_i16(500); // xAvgCharWidth
+    writer.write_u16(400); // usWeightClass
+    writer.write_u16(5); // usWidthClass
+    writer.write_u16(0); // fsType
+    for _ in 0..8 {
+        writer.write_i16(0); // sub/superscript metrics
+    }
+    writer.write_i16(50); // yStrikeoutSize
+    writer.write_i16(300); // yStrikeoutPosition
+    writer.write_i16(0); // sFamilyClass
+    writer.write_bytes(&[0u8; 10]); // PANOSE
+    for _ in 0..4 {
+        writer.write_u32(0); // ulUnicodeRange
+    }
+    writer.write_bytes(b"TEST"); // achVendID
+    writer.write_u16(0x0040); // fsSelection (REGULAR)
+    writer.write_u16(0x20); // usFirstCharIndex
+    writer.write_u16(0xC1); // usLastCharIndex
+    writer.write_i16(750); // sTypoAscender
+    writer.write_i16(-250); // sTypoDescender
+    writer.write_i16(0); // sTypoLineGap
+    writer.write_u16(900); // usWinAscent
+    writer.write_u16(300); // usWinDescent
+    writer.write_u32(1); // ulCodePageRange1
+    writer.write_u32(0); // ulCodePageRange2
+    writer.write_i16(500); // sxHeight
+    writer.write_i16(700); // sCapHeight
+    writer.write_u16(0); // usDefaultChar
+    writer.write_u16(32); // usBreakChar
+    writer.write_u16(1); // usMaxContext
+    writer.into_inner()
+}
+
+/// Build a format 3 post table (no glyph names)
+pub fn post_table() -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    writer.write_fixed(3.0); // format
+    writer.write_fixed(0.0); // italic angle
+    writer.write_i16(-100); // underline position
+    writer.write_i16(50); // underline thickness
+    writer.write_u32(0); // is fixed pitch
+    for _ in 0..4 {
+        writer.write_u32(0); // memory usage hints
+    }
+    writer.into_inner()
+}
+
+/// The standard test font with a (placeholder) DSIG table
+pub fn signed_test_font() -> Vec<u8> {
+    let mut tables = test_font_tables();
+    // version 1, no signatures, no flags
+    replace_table(&mut tables, b"DSIG", vec![0, 0, 0, 1, 0, 0, 0, 0]);
+    build_font(&tables)
+}
+
+/// Build a version 0 kern table with one horizontal format 0 subtable
+pub fn kern_table(pairs: &[(u16, u16, i16)]) -> Vec<u8> {
+    let mut pairs = pairs.to_vec();
+    pairs.sort_by_key(|&(left, right, _)| (left, right));
+
+    let mut writer = FontWriter::new();
+    writer.write_u16(0); // version
+    writer.write_u16(1); // number of subtables
+    writer.write_u16(0); // subtable version
+    writer.write_u16(14 + pairs.len() as u16 * 6); // subtable length
+    writer.write_u16(0x0001); // coverage: horizontal, format 0
+    writer.write_u16(pairs.len() as u16);
+    writer.write_u16(6); // search range (unused by the reader)
+    writer.write_u16(0); // entry selector
+    writer.write_u16(0); // range shift
+    for (left, right, value) in pairs {
+        writer.write_u16(left);
+        writer.write_u16(right);
+        writer.write_i16(value);
+    }
+    writer.into_inner()
+}
+
+/// Build a GPOS or GSUB table with empty script and feature lists and one
+/// single-subtable lookup per (lookup type, subtable bytes)
+pub fn layout_table(lookups: &[(u16, Vec<u8>)]) -> Vec<u8> {
+    layout_table_with_features(&[], lookups)
+}
+
+/// Build a GPOS or GSUB table whose 'DFLT' script enables every given
+/// (feature tag, lookup indices) in its default language system, with one
+/// single-subtable lookup per (lookup type, subtable bytes)
+pub fn layout_table_with_features(features: &[([u8; 4], &[u16])], lookups: &[(u16, Vec<u8>)]) -> Vec<u8> {
+    let mut script_list = FontWriter::new();
+    if features.is_empty() {
+        script_list.write_u16(0); // script count
+    } else {
+        script_list.write_u16(1);
+        script_list.write_tag(b"DFLT");
+        script_list.write_u16(8); // script offset
+        script_list.write_u16(4); // default language system offset
+        script_list.write_u16(0); // language system count
+        script_list.write_u16(0); // lookup order
+        script_list.write_u16(0xFFFF); // no required feature
+        script_list.write_u16(features.len() as u16);
+        for i in 0..features.len() {
+            script_list.write_u16(i as u16);
+        }
+    }
+    let script_list = script_list.into_inner();
+
+    let mut feature_list = FontWriter::new();
+    feature_list.write_u16(features.len() as u16);
+    let mut offset = 2 + features.len() * 6;
+    for (tag, lookup_indices) in features {
+        feature_list.write_tag(tag);
+        feature_list.write_u16(offset as u16);
+        offset += 4 + lookup_indices.len() * 2;
+    }
+    for (_, lookup_indices) in features {
+        feature_list.write_u16(0); // feature params
+        feature_list.write_u16(lookup_indices.len() as u16);
+        for &index in *lookup_indices {
+            feature_list.write_u16(index);
+        }
+    }
+    let feature_list = feature_list.into_inner();
+
+    let mut writer = FontWriter::new();
+    writer.write_u16(1); // major version
+    writer.write_u16(0); // minor version
+    writer.write_u16(10); // script list offset
+    writer.write_u16((10 + script_list.len()) as u16); // feature list offset
+    writer.write_u16((10 + script_list.len() + feature_list.len()) as u16); // lookup list offset
+    writer.write_bytes(&script_list);
+    writer.write_bytes(&feature_list);
+
+    // Lookup list, then each lookup (8 bytes) followed by its subtable
+    writer.write_u16(lookups.len() as u16);
+    let mut offset = 2 + lookups.len() * 2;
+    for (_, subtable) in lookups {
+        writer.write_u16(offset as u16);
+        offset += 8 + subtable.len();
+    }
+    for (lookup_type, subtable) in lookups {
+        writer.write_u16(*lookup_type);
+        writer.write_u16(0); // lookup flag
+        writer.write_u16(1); // subtable count
+        writer.write_u16(8); // subtable offset
+        writer.write_bytes(subtable);
+    }
+    writer.into_inner()
+}
+
+/// Encode a format 1 coverage table
+pub fn coverage_table(glyphs: &[u16]) -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    writer.write_u16(1); // format
+    writer.write_u16(glyphs.len() as u16);
+    for &glyph in glyphs {
+        writer.write_u16(glyph);
+    }
+    writer.into_inner()
+}
+
+/// Encode a PairPos format 1 subtable of (left, right, x advance) pairs
+pub fn pair_pos_format1(pairs: &[(u16, u16, i16)]) -> Vec<u8> {
+    let mut pairs = pairs.to_vec();
+    pairs.sort_by_key(|&(left, right, _)| (left, right));
+    let mut first_glyphs: Vec<u16> = pairs.iter().map(|&(left, _, _)| left).collect();
+    first_glyphs.dedup();
+
+    let mut pair_sets = FontWriter::new();
+    let mut pair_set_offsets = Vec::new();
+    let header_len = 10 + first_glyphs.len() * 2;
+    for &left in &first_glyphs {
+        pair_set_offsets.push((header_len + pair_sets.position()) as u16);
+        let set: Vec<_> = pairs.iter().filter(|&&(l, _, _)| l == left).collect();
+        pair_sets.write_u16(set.len() as u16);
+        for &&(_, right, x_advance) in &set {
+            pair_sets.write_u16(right);
+            pair_sets.write_i16(x_advance);
+        }
+    }
+    let pair_sets = pair_sets.into_inner();
+
+    let mut writer = FontWriter::new();
+    writer.write_u16(1); // format
+    writer.write_u16((header_len + pair_sets.len()) as u16); // coverage offset
+    writer.write_u16(0x0004); // value format 1: x advance
+    writer.write_u16(0); // value format 2
+    writer.write_u16(first_glyphs.len() as u16);
+    for offset in pair_set_offsets {
+        writer.write_u16(offset);
+    }
+    writer.write_bytes(&pair_sets);
+    writer.write_bytes(&coverage_table(&first_glyphs));
+    writer.into_inner()
+}
+
+/// Encode a LigatureSubst subtable of (component glyphs, ligature glyph)
+pub fn ligature_subst(ligatures: &[(&[u16], u16)]) -> Vec<u8> {
+    let mut first_glyphs: Vec<u16> = ligatures.iter().map(|(components, _)| components[0]).collect();
+    first_glyphs.sort_unstable();
+    first_glyphs.dedup();
+
+    let header_len = 6 + first_glyphs.len() * 2;
+    let mut sets = FontWriter::new();
+    let mut set_offsets = Vec::new();
+    for &first in &first_glyphs {
+        set_offsets.push((header_len + sets.position()) as u16);
+        let set: Vec<_> = ligatures.iter().filter(|(components, _)| components[0] == first).collect();
+        sets.write_u16(set.len() as u16);
+        let mut offset = 2 + set.len() * 2;
+        for (components, _) in &set {
+            sets.write_u16(offset as u16);
+            offset += 4 + (components.len() - 1) * 2;
+        }
+        for (components, ligature_glyph) in &set {
+            sets.write_u16(*ligature_glyph);
+            sets.write_u16(components.len() as u16);
+            for &component in &components[1..] {
+                sets.write_u16(component);
+            }
+        }
+    }
+    let sets = sets.into_inner();
+
+    let mut writer = FontWriter::new();
+    writer.write_u16(1); // format
+    writer.write_u16((header_len + sets.len()) as u16); // coverage offset
+    writer.write_u16(first_glyphs.len() as u16);
+    for offset in set_offsets {
+        writer.write_u16(offset);
+    }
+    writer.write_bytes(&sets);
+    writer.write_bytes(&coverage_table(&first_glyphs));
+    writer.into_inner()
+}
+
+/// Build an fvar table from (tag, min, default, max) axes and
+/// (subfamily name id, coordinates) instances, with PostScript name ids
+pub fn fvar_table(axes: &[([u8; 4], f32, f32, f32)], instances: &[(u16, &[f32])]) -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    writer.write_u16(1); // major version
+    writer.write_u16(0); // minor version
+    writer.write_u16(16); // axes array offset
+    writer.write_u16(2); // reserved
+    writer.write_u16(axes.len() as u16);
+    writer.write_u16(20); // axis size
+    writer.write_u16(instances.len() as u16);
+    writer.write_u16(axes.len() as u16 * 4 + 6); // instance size
+
+    for (i, &(tag, min, default, max)) in axes.iter().enumerate() {
+        writer.write_tag(&tag);
+        writer.write_fixed(min);
+        writer.write_fixed(default);
+        writer.write_fixed(max);
+        writer.write_u16(0); // flags
+        writer.write_u16(256 + i as u16); // axis name id
+    }
+
+    for (i, &(subfamily_name_id, coordinates)) in instances.iter().enumerate() {
+        writer.write_u16(subfamily_name_id);
+        writer.write_u16(0); // flags
+        for &coordinate in coordinates {
+            writer.write_fixed(coordinate);
+        }
+        writer.write_u16(300 + i as u16); // PostScript name id
+    }
+    writer.into_inner()
+}
+
+/// Encode a simple glyph from contours of (x, y, on_curve) points
+///
+/// Flags are written uncompressed with 2-byte coordinate deltas.
+pub fn simple_glyph(contours: &[&[(i16, i16, bool)]]) -> Vec<u8> {
+    let points: Vec<(i16, i16, bool)> = contours.iter().flat_map(|c| c.iter().copied()).collect();
+    let x_min = points.iter().map(|p| p.0).min().unwrap_or(0);
+    let y_min = points.iter().map(|p| p.1).min().unwrap_or(0);
+    let x_max = points.iter().map(|p| p.0).max().unwrap_or(0);
+    let y_max = points.iter().map(|p| p.1).max().unwrap_or(0);
+
+    let mut writer = FontWriter::new();
+    writer.write_i16(contours.len() as i16);
+    writer.write_i16(x_min);
+    writer.write_i16(y_min);
+    writer.write_i16(x_max);
+    writer.write_i16(y_max);
+
+    let mut end = 0u16;
+    for contour in contours {
+        end += contour.len() as u16;
+        writer.write_u16(end - 1);
+    }
+    writer.write_u16(0); // instruction length
+
+    for &(_, _, on_curve) in &points {
+        writer.write_u8(if on_curve { 0x01 } else { 0x00 });
+    }
+    let mut prev = 0i16;
+    for &(x, _, _) in &points {
+        writer.write_i16(x - prev);
+        prev = x;
+    }
+    prev = 0;
+    for &(_, y, _) in &points {
+        writer.write_i16(y - prev);
+        prev = y;
+    }
+    writer.into_inner()
+}
+
+/// Encode a composite glyph from (glyph index, dx, dy) components
+pub fn composite_glyph(bbox: (i16, i16, i16, i16), components: &[(u16, i16, i16)]) -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    writer.write_i16(-1);
+    writer.write_i16(bbox.0);
+    writer.write_i16(bbox.1);
+    writer.write_i16(bbox.2);
+    writer.write_i16(bbox.3);
+
+    for (i, &(glyph_index, dx, dy)) in components.iter().enumerate() {
+        // ARG_1_AND_2_ARE_WORDS | ARGS_ARE_XY_VALUES
+        let mut flags = 0x0001 | 0x0002;
+        if i + 1 < components.len() {
+            flags |= 0x0020; // MORE_COMPONENTS
+        }
+        writer.write_u16(flags);
+        writer.write_u16(glyph_index);
+        writer.write_i16(dx);
+        writer.write_i16(dy);
+    }
+    writer.into_inner()
+}
+
+/// Concatenate glyph records into a glyf table and a long-format loca table
+pub fn glyf_and_loca(glyphs: &[Vec<u8>]) -> (Vec<u8>, Vec<u8>) {
+    let mut glyf = FontWriter::new();
+    let mut loca = FontWriter::new();
+    for glyph in glyphs {
+        loca.write_u32(glyf.position() as u32);
+        glyf.write_bytes(glyph);
+        glyf.write_padding(4);
+    }
+    loca.write_u32(glyf.position() as u32);
+    (glyf.into_inner(), loca.into_inner())
+}
+
+/// Glyph ids of the standard test font
+pub const GID_NOTDEF: u16 = 0;
+pub const GID_A: u16 = 1;
+pub const GID_B: u16 = 2;
+pub const GID_O: u16 = 3;
+pub const GID_SPACE: u16 = 4;
+pub const GID_AACUTE: u16 = 5;
+pub const GID_ACUTE: u16 = 6;
+pub const GID_C: u16 = 7;
+pub const GID_D: u16 = 8;
+pub const GID_E: u16 = 9;
+pub const GID_F: u16 = 10;
+
+/// Encode an axis-aligned rectangle as a single-contour simple glyph
+pub fn rect_glyph(x_min: i16, y_min: i16, x_max: i16, y_max: i16) -> Vec<u8> {
+    simple_glyph(&[&[
+        (x_min, y_min, true),
+        (x_min, y_max, true),
+        (x_max, y_max, true),
+        (x_max, y_min, true),
+    ]])
+}
+
+/// Glyph records of the standard test font
+pub fn test_glyphs() -> Vec<Vec<u8>> {
+    vec![
+        // .notdef: rectangle
+        rect_glyph(0, 0, 500, 700),
+        // A: triangle
+        simple_glyph(&[&[(0, 0, true), (250, 700, true), (500, 0, true)]]),
+        // B: rectangle
+        rect_glyph(100, 0, 400, 700),
+        // O: two quadratic contours, outer and counter
+        simple_glyph(&[
+            &[
+                (300, 0, true), (0, 0, false), (0, 350, true), (0, 700, false),
+                (300, 700, true), (600, 700, false), (600, 350, true), (600, 0, false),
+            ],
+            &[
+                (300, 100, true), (500, 100, false), (500, 350, true), (500, 600, false),
+                (300, 600, true), (100, 600, false), (100, 350, true), (100, 100, false),
+            ],
+        ]),
+        // space: empty
+        Vec::new(),
+        // Aacute: A plus acute above
+        composite_glyph((0, 0, 500, 850), &[(GID_A, 0, 0), (GID_ACUTE, 200, 750)]),
+        // acute: small triangle
+        simple_glyph(&[&[(0, 0, true), (100, 100, true), (50, 0, true)]]),
+        // C, D, E, F: rectangles of increasing width
+        rect_glyph(50, 0, 350, 700),
+        rect_glyph(50, 0, 450, 700),
+        rect_glyph(50, 0, 550, 700),
+        rect_glyph(50, 0, 650, 700),
+    ]
+}
+
+/// Tables of the standard test font, in directory order
+pub fn test_font_tables() -> Vec<([u8; 4], Vec<u8>)> {
+    let glyphs = test_glyphs();
+    let (glyf, loca) = glyf_and_loca(&glyphs);
+    let metrics = [
+        (500, 0), (500, 0), (500, 100), (600, 0), (250, 0), (500, 0),
+        (100, 0), (400, 50), (500, 50), (600, 50), (700, 50),
+    ];
+
+    vec![
+        (*b"OS/2", os2_table()),
+        (*b"cmap", cmap_table(&[
+            (0x20, GID_SPACE),
+            (0x41, GID_A),
+            (0x42, GID_B),
+            (0x43, GID_C),
+            (0x44, GID_D),
+            (0x45, GID_E),
+            (0x46, GID_F),
+            (0x4F, GID_O),
+            (0xC1, GID_AACUTE),
+        ])),
+        (*b"glyf", glyf),
+        (*b"head", head_table(1000)),
+        (*b"hhea", hhea_table(metrics.len() as u16)),
+        (*b"hmtx", hmtx_table(&metrics)),
+        (*b"loca", loca),
+        (*b"maxp", maxp_table(glyphs.len() as u16)),
+        (*b"name", name_table(&[
+            (3, 1, 0x0409, 1, "Test Family"),
+            (3, 1, 0x0409, 2, "Regular"),
+            (3, 1, 0x0409, 4, "Test Family Regular"),
+            (3, 1, 0x0409, 6, "TestFamily-Regular"),
+        ])),
+        (*b"post", post_table()),
+    ]
+}
+
+/// A complete TrueType font with outlines, metrics, cmap and names
+pub fn test_font() -> Vec<u8> {
+    build_font(&test_font_tables())
+}
+
+/// Replace (or append) a table in a table list
+pub fn replace_table(tables: &mut Vec<([u8; 4], Vec<u8>)>, tag: &[u8; 4], data: Vec<u8>) {
+    if let Some(entry) = tables.iter_mut().find(|(t, _)| t == tag) {
+        entry.1 = data;
+    } else {
+        tables.push((*tag, data));
+        tables.sort_by_key(|(tag, _)| *tag);
+    }
+}
@@ -323,6 +323,8 @@ fn test_os2_table_helpers() {
         us_default_char: 0,
         us_break_char: 32,
         us_max_context: 0,
+        us_lower_optical_point_size: 0,
+        us_upper_optical_point_size: 0,
     };
 
     assert!(!os2.is_bold());
@@ -0,0 +1,98 @@
+// Tests for Point and Transform geometry helpers
+
+use ttf_rs::{Point, Transform};
+
+fn assert_close(a: &Transform, b: &Transform) {
+    let pairs = [(a.xx, b.xx), (a.xy, b.xy), (a.yx, b.yx), (a.yy, b.yy), (a.dx, b.dx), (a.dy, b.dy)];
+    for (x, y) in pairs {
+        assert!((x - y).abs() < 1e-4, "{:?} != {:?}", a, b);
+    }
+}
+
+#[test]
+fn test_point_add() {
+    assert_eq!(Point::new(1.0, 2.0) + Point::new(3.0, -5.0), Point::new(4.0, -3.0));
+}
+
+#[test]
+fn test_point_sub() {
+    assert_eq!(Point::new(1.0, 2.0) - Point::new(3.0, -5.0), Point::new(-2.0, 7.0));
+}
+
+#[test]
+fn test_point_mul() {
+    assert_eq!(Point::new(1.5, -2.0) * 2.0, Point::new(3.0, -4.0));
+    assert_eq!(Point::new(1.5, -2.0) * 0.0, Point::new(0.0, 0.0));
+}
+
+#[test]
+fn test_point_distance() {
+    let a = Point::new(1.0, 1.0);
+    let b = Point::new(4.0, 5.0);
+    assert_eq!(a.distance(&b), 5.0);
+    assert_eq!(b.distance(&a), 5.0);
+    assert_eq!(a.distance(&a), 0.0);
+}
+
+#[test]
+fn test_point_lerp() {
+    let a = Point::new(0.0, 10.0);
+    let b = Point::new(20.0, -10.0);
+    assert_eq!(a.lerp(&b, 0.0), a);
+    assert_eq!(a.lerp(&b, 0.5), Point::new(10.0, 0.0));
+    assert_eq!(a.lerp(&b, 1.0), b);
+}
+
+#[test]
+fn test_transform_identity() {
+    let point = Point::new(3.0, -7.0);
+    assert_eq!(point.transform(&Transform::identity()), point);
+    assert_eq!(Transform::identity(), Transform::default());
+}
+
+#[test]
+fn test_transform_then_scale_and_translate() {
+    let scale = Transform { xx: 2.0, xy: 0.0, yx: 0.0, yy: 3.0, dx: 0.0, dy: 0.0 };
+    let translate = Transform { dx: 10.0, dy: -5.0, ..Transform::identity() };
+
+    // Scale first: (x * 2 + 10, y * 3 - 5)
+    let composed = scale.then(&translate);
+    assert_eq!(composed, Transform { xx: 2.0, xy: 0.0, yx: 0.0, yy: 3.0, dx: 10.0, dy: -5.0 });
+    assert_eq!(Point::new(1.0, 1.0).transform(&composed), Point::new(12.0, -2.0));
+
+    // Translate first: ((x + 10) * 2, (y - 5) * 3)
+    let composed = translate.then(&scale);
+    assert_eq!(Point::new(1.0, 1.0).transform(&composed), Point::new(22.0, -12.0));
+}
+
+#[test]
+fn test_transform_then_matches_sequential_application() {
+    let a = Transform { xx: 0.8, xy: -0.6, yx: 0.6, yy: 0.8, dx: 4.0, dy: 1.0 };
+    let b = Transform { xx: 1.5, xy: 0.25, yx: 0.0, yy: -1.0, dx: -3.0, dy: 2.0 };
+    let point = Point::new(5.0, -2.0);
+
+    let expected = point.transform(&a).transform(&b);
+    let actual = point.transform(&a.then(&b));
+    assert!(expected.distance(&actual) < 1e-4);
+}
+
+#[test]
+fn test_transform_inverse() {
+    let transform = Transform { xx: 0.8, xy: -0.6, yx: 0.3, yy: 1.2, dx: 4.0, dy: -9.0 };
+    let inverse = transform.inverse().unwrap();
+
+    assert_close(&transform.then(&inverse), &Transform::identity());
+    assert_close(&inverse.then(&transform), &Transform::identity());
+
+    let point = Point::new(12.0, 7.0);
+    assert!(point.transform(&transform).transform(&inverse).distance(&point) < 1e-4);
+}
+
+#[test]
+fn test_transform_singular_has_no_inverse() {
+    let flatten = Transform { yy: 0.0, ..Transform::identity() };
+    assert!(flatten.inverse().is_none());
+
+    let collinear = Transform { xx: 1.0, xy: 2.0, yx: 2.0, yy: 4.0, dx: 0.0, dy: 0.0 };
+    assert!(collinear.inverse().is_none());
+}
@@ -0,0 +1,664 @@
+// Tests for glyf table parsing and serialization
+
+mod common;
+
+use common::*;
+use ttf_rs::{
+    Font, FontReader, FontWriter, GlyfTable, Glyph, GlyphComponent, GlyphData, GlyphExtents, LocaTable,
+    CompositeGlyph, Contour, ContourPoint, Transform, TtfError, TtfTableWrite,
+};
+
+fn reparse(glyf: &GlyfTable) -> GlyfTable {
+    let (data, offsets) = glyf.to_bytes();
+    assert_eq!(offsets.len(), glyf.glyphs.len() + 1);
+    assert_eq!(*offsets.last().unwrap() as usize, data.len());
+
+    let mut reader = FontReader::from_slice(&data);
+    let loca = LocaTable::Long(offsets);
+    GlyfTable::from_reader(&mut reader, data.len() as u32, &loca, glyf.glyphs.len() as u16).unwrap()
+}
+
+fn assert_same_glyph(a: &Glyph, b: &Glyph) {
+    assert_eq!(
+        (a.x_min, a.y_min, a.x_max, a.y_max),
+        (b.x_min, b.y_min, b.x_max, b.y_max)
+    );
+
+    match (&a.data, &b.data) {
+        (GlyphData::Simple(a), GlyphData::Simple(b)) => {
+            assert_eq!(a.end_pts_of_contours, b.end_pts_of_contours);
+            assert_eq!(a.x_coordinates, b.x_coordinates);
+            assert_eq!(a.y_coordinates, b.y_coordinates);
+            let on_curve = |flags: &[u8]| flags.iter().map(|f| f & 0x1).collect::<Vec<_>>();
+            assert_eq!(on_curve(&a.flags), on_curve(&b.flags));
+        }
+        (GlyphData::Composite(a), GlyphData::Composite(b)) => {
+            assert_eq!(a.components.len(), b.components.len());
+            for (a, b) in a.components.iter().zip(&b.components) {
+                assert_eq!(a.glyph_index, b.glyph_index);
+                assert_eq!((a.arg1, a.arg2), (b.arg1, b.arg2));
+                let (t, u) = (&a.transform, &b.transform);
+                assert_eq!((t.xx, t.xy, t.yx, t.yy), (u.xx, u.xy, u.yx, u.yy));
+                assert_eq!((t.dx, t.dy), (u.dx, u.dy));
+            }
+        }
+        (GlyphData::Empty, GlyphData::Empty) => {}
+        _ => panic!("glyph kind changed on round-trip"),
+    }
+}
+
+#[test]
+fn test_glyf_round_trip() {
+    let font = Font::from_data(test_font()).unwrap();
+    let glyf = font.glyf_table().unwrap();
+    let reparsed = reparse(&glyf);
+
+    assert_eq!(glyf.glyphs.len(), reparsed.glyphs.len());
+    for (a, b) in glyf.glyphs.iter().zip(&reparsed.glyphs) {
+        assert_same_glyph(a, b);
+    }
+}
+
+#[test]
+fn test_glyf_round_trip_short_deltas_and_transforms() {
+    // Deltas in the 1-byte range (including 128..=255), repeated flags and a long delta
+    let wave = simple_glyph(&[&[
+        (0, 0, true), (200, 0, true), (400, 0, true), (600, 0, true),
+        (600, -130, false), (300, 1000, true),
+    ]]);
+    let (glyf_data, loca_data) = glyf_and_loca(&[wave]);
+    let mut reader = FontReader::from_slice(&loca_data);
+    let loca = LocaTable::from_reader(&mut reader, loca_data.len() as u32, 1, true).unwrap();
+    let mut reader = FontReader::from_slice(&glyf_data);
+    let mut glyf = GlyfTable::from_reader(&mut reader, glyf_data.len() as u32, &loca, 1).unwrap();
+
+    let component = |glyph_index, arg1, arg2, xx, xy, yx, yy| GlyphComponent {
+        flags: 0x2,
+        glyph_index,
+        arg1,
+        arg2,
+        transform: Transform { xx, xy, yx, yy, dx: arg1 as f32, dy: arg2 as f32 },
+    };
+    glyf.glyphs.push(Glyph {
+        number_of_contours: -1,
+        x_min: -10,
+        y_min: -10,
+        x_max: 1200,
+        y_max: 1000,
+        data: GlyphData::Composite(CompositeGlyph {
+            components: vec![
+                component(0, 10, -10, 1.0, 0.0, 0.0, 1.0),
+                component(0, 300, 0, 0.5, 0.0, 0.0, 0.5),
+                component(0, 0, 0, 1.0, 0.0, 0.0, -1.0),
+                component(0, -5, 600, 0.0, 1.0, -1.0, 0.0),
+            ],
+        }),
+    });
+
+    let reparsed = reparse(&glyf);
+    for (a, b) in glyf.glyphs.iter().zip(&reparsed.glyphs) {
+        assert_same_glyph(a, b);
+    }
+}
+
+#[test]
+fn test_glyf_write_matches_to_bytes() {
+    let font = Font::from_data(test_font()).unwrap();
+    let glyf = font.glyf_table().unwrap();
+
+    let mut writer = FontWriter::new();
+    glyf.write(&mut writer).unwrap();
+    assert_eq!(writer.into_inner(), glyf.to_bytes().0);
+    assert_eq!(GlyfTable::table_tag(), b"glyf");
+}
+
+#[test]
+fn test_decompose_composites() {
+    let font = Font::from_data(test_font()).unwrap();
+    let original = font.glyf_table().unwrap();
+    let decomposed = font.decompose_composites().unwrap();
+    let glyf = decomposed.glyf_table().unwrap();
+
+    assert_eq!(glyf.glyphs.len(), original.glyphs.len());
+    assert!(glyf.glyphs.iter().all(|g| g.is_simple() || g.is_empty()));
+
+    let point_count = |glyph: &Glyph| match &glyph.data {
+        GlyphData::Simple(simple) => simple.x_coordinates.len(),
+        _ => 0,
+    };
+    let aacute = &glyf.glyphs[GID_AACUTE as usize];
+    assert_eq!(
+        point_count(aacute),
+        point_count(&original.glyphs[GID_A as usize]) + point_count(&original.glyphs[GID_ACUTE as usize])
+    );
+    assert_eq!((aacute.y_min, aacute.y_max), (0, 850));
+
+    let maxp = decomposed.maxp_table().unwrap();
+    assert_eq!(maxp.max_points, Some(16));
+    assert_eq!(maxp.max_contours, Some(2));
+    assert_eq!(maxp.max_composite_points, Some(0));
+    assert_eq!(maxp.max_component_depth, Some(0));
+}
+
+#[test]
+fn test_decompose_composites_updates_head_bbox() {
+    let mut tables = test_font_tables();
+    let mut glyphs = test_glyphs();
+    // Glyph 11's header claims Aacute's bounds, but it shifts Aacute to (600, 200)
+    glyphs.push(composite_glyph((0, 0, 500, 850), &[(GID_AACUTE, 600, 200)]));
+    let (glyf, loca) = glyf_and_loca(&glyphs);
+    replace_table(&mut tables, b"glyf", glyf);
+    replace_table(&mut tables, b"loca", loca);
+    replace_table(&mut tables, b"maxp", maxp_table(glyphs.len() as u16));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let decomposed = font.decompose_composites().unwrap();
+    let head = decomposed.head_table().unwrap();
+    assert_eq!((head.x_max, head.y_max), (1100, 1050));
+
+    let glyf = decomposed.glyf_table().unwrap();
+    let outlines = || glyf.glyphs.iter().filter(|glyph| glyph.is_simple());
+    assert_eq!(head.x_min, outlines().map(|glyph| glyph.x_min).min().unwrap());
+    assert_eq!(head.y_min, outlines().map(|glyph| glyph.y_min).min().unwrap());
+}
+
+#[test]
+fn test_resolve_nested_composite() {
+    let mut tables = test_font_tables();
+    let mut glyphs = test_glyphs();
+    // Glyph 11 places the Aacute composite twice, side by side
+    glyphs.push(composite_glyph((0, 0, 1100, 850), &[(GID_AACUTE, 0, 0), (GID_AACUTE, 600, 0)]));
+    let (glyf, loca) = glyf_and_loca(&glyphs);
+    replace_table(&mut tables, b"glyf", glyf);
+    replace_table(&mut tables, b"loca", loca);
+    replace_table(&mut tables, b"maxp", maxp_table(glyphs.len() as u16));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let resolved = font.glyf_table().unwrap().resolve_composite(11).unwrap().unwrap();
+    match &resolved.data {
+        GlyphData::Simple(simple) => {
+            assert_eq!(simple.x_coordinates.len(), 12);
+            assert_eq!(simple.end_pts_of_contours, vec![2, 5, 8, 11]);
+        }
+        _ => panic!("expected a simple glyph"),
+    }
+    assert_eq!((resolved.x_min, resolved.x_max), (0, 1100));
+}
+
+#[test]
+fn test_transform_nested_composite() {
+    let mut tables = test_font_tables();
+    let mut glyphs = test_glyphs();
+    // Glyph 11 places the Aacute composite at (600, 200)
+    glyphs.push(composite_glyph((600, 200, 1100, 1050), &[(GID_AACUTE, 600, 200)]));
+    let (glyf, loca) = glyf_and_loca(&glyphs);
+    replace_table(&mut tables, b"glyf", glyf);
+    replace_table(&mut tables, b"loca", loca);
+    replace_table(&mut tables, b"maxp", maxp_table(glyphs.len() as u16));
+    let mut glyf = Font::from_data(build_font(&tables)).unwrap().glyf_table().unwrap();
+
+    // A shear and scale that doesn't commute with the components' offsets:
+    // the component offset must be applied before it, not after
+    let shear = Transform { xx: 2.0, xy: 1.0, yx: 0.0, yy: 1.0, dx: 0.0, dy: 0.0 };
+    let mut expected = glyf.resolve_composite(11).unwrap().unwrap();
+    expected.transform(&shear).unwrap();
+
+    glyf.glyphs[11].transform(&shear).unwrap();
+    let resolved = glyf.resolve_composite(11).unwrap().unwrap();
+    assert_same_glyph(&resolved, &expected);
+    // A's bottom-left corner lands at (2 * 600 + 200, 200)
+    assert_eq!((resolved.x_min, resolved.y_min), (1400, 200));
+}
+
+#[test]
+fn test_simple_glyph_flag_compression() {
+    // 200 duplicate points after the first: zero deltas with identical flags
+    let mut points = vec![(100, 100, true); 201];
+    points[0] = (0, 0, true);
+    let uncompressed = simple_glyph(&[&points]);
+    let (glyf_data, loca_data) = glyf_and_loca(std::slice::from_ref(&uncompressed));
+
+    let mut reader = FontReader::from_slice(&loca_data);
+    let loca = LocaTable::from_reader(&mut reader, loca_data.len() as u32, 1, true).unwrap();
+    let mut reader = FontReader::from_slice(&glyf_data);
+    let glyf = GlyfTable::from_reader(&mut reader, glyf_data.len() as u32, &loca, 1).unwrap();
+
+    let (compressed, _) = glyf.to_bytes();
+    // header (10) + end point (2) + instruction length (2) + a handful of flag/coordinate bytes
+    assert!(compressed.len() <= 24, "compressed glyph is {} bytes", compressed.len());
+    assert!(compressed.len() * 10 < uncompressed.len());
+
+    let reparsed = reparse(&glyf);
+    assert_same_glyph(&glyf.glyphs[0], &reparsed.glyphs[0]);
+    match &reparsed.glyphs[0].data {
+        GlyphData::Simple(simple) => {
+            assert_eq!(simple.x_coordinates.len(), 201);
+            assert!(simple.x_coordinates[1..].iter().all(|&x| x == 100));
+            assert!(simple.y_coordinates[1..].iter().all(|&y| y == 100));
+        }
+        _ => panic!("expected a simple glyph"),
+    }
+}
+
+/// Parse a single glyph built by `simple_glyph`
+fn parse_glyph(data: Vec<u8>) -> GlyfTable {
+    let (glyf_data, loca_data) = glyf_and_loca(&[data]);
+    let mut reader = FontReader::from_slice(&loca_data);
+    let loca = LocaTable::from_reader(&mut reader, loca_data.len() as u32, 1, true).unwrap();
+    let mut reader = FontReader::from_slice(&glyf_data);
+    GlyfTable::from_reader(&mut reader, glyf_data.len() as u32, &loca, 1).unwrap()
+}
+
+fn glyph_contours(glyph: &Glyph) -> Vec<Vec<ttf_rs::ContourPoint>> {
+    match &glyph.data {
+        GlyphData::Simple(simple) => simple.contours(),
+        _ => panic!("expected a simple glyph"),
+    }
+}
+
+#[test]
+fn test_simplify_keeps_off_curve_points() {
+    let mut glyf = parse_glyph(simple_glyph(&[
+        &[
+            (0, 0, true),
+            (100, 0, true), // collinear between on-curve points
+            (200, 0, true),
+            (400, 0, false),
+            (400, 200, true),
+            (400, 301, true), // within tolerance of the line
+            (400, 400, true),
+            (200, 400, false),
+            (0, 400, true),
+            (0, 200, true), // collinear, next to the contour start
+        ],
+        &[(500, 0, true), (600, 0, false), (700, 0, true), (600, 100, false)],
+    ]));
+    let glyph = &mut glyf.glyphs[0];
+    let off_curve_before: Vec<_> = glyph_contours(glyph)
+        .into_iter()
+        .flatten()
+        .filter(|point| !point.on_curve)
+        .collect();
+
+    glyph.simplify(2.0).unwrap();
+
+    let contours = glyph_contours(glyph);
+    let points: Vec<_> = contours[0].iter().map(|p| (p.x, p.y, p.on_curve)).collect();
+    assert_eq!(points, vec![
+        (0, 0, true),
+        (200, 0, true),
+        (400, 0, false),
+        (400, 200, true),
+        (400, 400, true),
+        (200, 400, false),
+        (0, 400, true),
+    ]);
+    assert_eq!(contours[1].len(), 4);
+    let off_curve_after: Vec<_> = contours.into_iter().flatten().filter(|point| !point.on_curve).collect();
+    assert_eq!(off_curve_after, off_curve_before);
+
+    match &glyph.data {
+        GlyphData::Simple(simple) => {
+            assert_eq!(simple.end_pts_of_contours, vec![6, 10]);
+            assert_eq!(simple.flags.len(), 11);
+        }
+        _ => panic!("expected a simple glyph"),
+    }
+    assert_same_glyph(&glyf.glyphs[0], &reparse(&glyf).glyphs[0]);
+}
+
+#[test]
+fn test_simplify_updates_contour_end_points() {
+    let mut glyf = parse_glyph(simple_glyph(&[
+        &[
+            (0, 0, true),
+            (50, 0, true), // collinear
+            (100, 0, true),
+            (100, 100, true),
+            (0, 100, true),
+            (0, 50, true), // collinear, the contour's end point
+        ],
+        &[(200, 0, true), (300, 0, true), (250, 100, true)],
+        &[(400, 0, true), (450, 0, true), (500, 0, true), (450, 100, true)],
+    ]));
+
+    glyf.glyphs[0].simplify(1.0).unwrap();
+
+    match &glyf.glyphs[0].data {
+        GlyphData::Simple(simple) => assert_eq!(simple.end_pts_of_contours, vec![3, 6, 9]),
+        _ => panic!("expected a simple glyph"),
+    }
+    let contours = glyph_contours(&glyf.glyphs[0]);
+    let corners = |contour: &[ttf_rs::ContourPoint]| contour.iter().map(|p| (p.x, p.y)).collect::<Vec<_>>();
+    assert_eq!(corners(&contours[0]), vec![(0, 0), (100, 0), (100, 100), (0, 100)]);
+    assert_eq!(corners(&contours[1]), vec![(200, 0), (300, 0), (250, 100)]);
+    assert_eq!(corners(&contours[2]), vec![(400, 0), (500, 0), (450, 100)]);
+    assert_eq!(glyf.glyphs[0].number_of_contours, 3);
+}
+
+#[test]
+fn test_simplify_keeps_distinct_points() {
+    let square = [(0, 0, true), (100, 0, true), (100, 100, true), (0, 100, true)];
+    let mut glyf = parse_glyph(simple_glyph(&[&square]));
+
+    glyf.glyphs[0].simplify(2.0).unwrap();
+
+    assert_eq!(glyph_contours(&glyf.glyphs[0])[0].len(), 4);
+}
+
+#[test]
+fn test_font_glyph_out_of_range() {
+    let font = Font::from_data(test_font()).unwrap();
+
+    let glyph = font.glyph(GID_B).unwrap();
+    assert_eq!((glyph.x_min, glyph.x_max), (100, 400));
+
+    assert!(matches!(font.glyph(11), Err(TtfError::InvalidGlyphIndex(11))));
+    assert!(matches!(font.glyph(u16::MAX), Err(TtfError::InvalidGlyphIndex(u16::MAX))));
+    assert!(matches!(font.glyph_outline_at(11, &[]), Err(TtfError::InvalidGlyphIndex(11))));
+}
+
+#[test]
+fn test_loca_to_bytes_round_trips_both_formats() {
+    let loca = LocaTable::Long(vec![0, 20, 20, 64]);
+
+    let long = loca.to_bytes(true);
+    assert_eq!(long.len(), 16);
+    let mut reader = FontReader::from_slice(&long);
+    let reparsed = LocaTable::from_reader(&mut reader, long.len() as u32, 3, true).unwrap();
+    assert_eq!(reparsed.offsets(), vec![0, 20, 20, 64]);
+
+    let short = loca.to_bytes(false);
+    assert_eq!(short, vec![0, 0, 0, 10, 0, 10, 0, 32]);
+    let mut reader = FontReader::from_slice(&short);
+    let reparsed = LocaTable::from_reader(&mut reader, short.len() as u32, 3, false).unwrap();
+    assert_eq!(reparsed.offsets(), vec![0, 20, 20, 64]);
+    assert_eq!(reparsed.to_bytes(true), long);
+}
+
+#[test]
+fn test_loca_validate() {
+    assert!(LocaTable::Long(vec![0, 20, 20, 64]).validate().is_ok());
+    assert!(LocaTable::Short(vec![0, 10, 10, 32]).validate().is_ok());
+
+    match LocaTable::Long(vec![0, 40, 20, 64]).validate() {
+        Err(TtfError::InvalidLocaOffset(offset)) => assert_eq!(offset, 20),
+        other => panic!("expected InvalidLocaOffset, got {:?}", other),
+    }
+    assert!(LocaTable::Short(vec![0, 10, 5]).validate().is_err());
+}
+
+#[test]
+fn test_glyph_bounding_box_resolves_composite() {
+    let mut glyphs = test_glyphs();
+    // Stale header bbox: only covers the base component
+    glyphs[GID_AACUTE as usize] =
+        composite_glyph((0, 0, 500, 700), &[(GID_A, 0, 0), (GID_ACUTE, 200, 750)]);
+    let (glyf, loca) = glyf_and_loca(&glyphs);
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"glyf", glyf);
+    replace_table(&mut tables, b"loca", loca);
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let bbox = font.glyph_bounding_box(GID_AACUTE).unwrap().unwrap();
+    assert_eq!((bbox.x_min, bbox.y_min, bbox.x_max, bbox.y_max), (0.0, 0.0, 500.0, 850.0));
+
+    // The font-wide head bbox must contain it, and the accent reaches its top
+    let head = font.head_table().unwrap();
+    assert!(bbox.x_min >= head.x_min as f32 && bbox.y_min >= head.y_min as f32);
+    assert!(bbox.x_max <= head.x_max as f32);
+    assert_eq!(bbox.y_max, head.y_max as f32);
+
+    let bbox = font.glyph_bounding_box(GID_B).unwrap().unwrap();
+    assert_eq!((bbox.x_min, bbox.y_min, bbox.x_max, bbox.y_max), (100.0, 0.0, 400.0, 700.0));
+    assert!(font.glyph_bounding_box(GID_SPACE).unwrap().is_none());
+    assert!(matches!(
+        font.glyph_bounding_box(100),
+        Err(TtfError::InvalidGlyphIndex(100))
+    ));
+}
+
+#[test]
+fn test_glyph_extents_in_font_units() {
+    let font = Font::from_data(test_font()).unwrap();
+
+    let extents = font.glyph_extents(GID_B, None).unwrap();
+    assert_eq!(
+        extents,
+        GlyphExtents::FontUnits { x_bearing: 100, y_bearing: 700, width: 300, height: 700 }
+    );
+
+    // Composites cover every component
+    let extents = font.glyph_extents(GID_AACUTE, None).unwrap();
+    assert!(matches!(
+        extents,
+        GlyphExtents::FontUnits { y_bearing: 850, width: 500, height: 850, .. }
+    ));
+
+    let extents = font.glyph_extents(GID_SPACE, None).unwrap();
+    assert!(matches!(extents, GlyphExtents::FontUnits { width: 0, height: 0, .. }));
+    assert!(matches!(font.glyph_extents(100, None), Err(TtfError::InvalidGlyphIndex(100))));
+}
+
+#[test]
+fn test_glyph_extents_scaled() {
+    let font = Font::from_data(test_font()).unwrap();
+    let pixels = |glyph_id: u16, px_size: f32| match font.glyph_extents(glyph_id, Some(px_size)).unwrap() {
+        GlyphExtents::Pixels { x_bearing, y_bearing, width, height } => [x_bearing, y_bearing, width, height],
+        other => panic!("expected pixel extents, got {:?}", other),
+    };
+
+    // 20px at 1000 units per em is 0.02px per unit
+    for (value, expected) in pixels(GID_B, 20.0).into_iter().zip([2.0, 14.0, 6.0, 14.0]) {
+        assert!((value - expected).abs() < 1e-4);
+    }
+
+    // Scaled values keep their fractions
+    let [_, _, width, height] = pixels(GID_A, 15.0);
+    assert!((width - 7.5).abs() < 1e-4);
+    assert!((height - 10.5).abs() < 1e-4);
+}
+
+#[test]
+fn test_has_outlines_with_empty_loca() {
+    let font = Font::from_data(test_font()).unwrap();
+    assert!(font.has_outlines().unwrap());
+    let no_outline_warning = |font: &Font| {
+        font.validate().unwrap().warnings.iter().any(|w| w.message.contains("no outline data"))
+    };
+    assert!(!no_outline_warning(&font));
+
+    // Every loca entry points at the same offset, as in a bitmap-only font
+    let glyphs = vec![Vec::new(); test_glyphs().len()];
+    let (glyf, loca) = glyf_and_loca(&glyphs);
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"glyf", glyf);
+    replace_table(&mut tables, b"loca", loca);
+    let font = Font::from_data(build_font(&tables)).unwrap();
+    assert!(!font.has_outlines().unwrap());
+    assert!(no_outline_warning(&font));
+
+    // Without a glyf table there are no outlines either
+    let tables: Vec<_> = test_font_tables().into_iter().filter(|(tag, _)| tag != b"glyf").collect();
+    let font = Font::from_data(build_font(&tables)).unwrap();
+    assert!(!font.has_outlines().unwrap());
+}
+
+#[test]
+fn test_actual_glyph_count_mismatch_warns() {
+    let glyph_count_warning = |font: &Font| {
+        font.validate().unwrap().warnings.iter().any(|w| w.message.contains("loca has entries for"))
+    };
+
+    let font = Font::from_data(test_font()).unwrap();
+    assert_eq!(font.actual_glyph_count().unwrap(), font.num_glyphs().unwrap() as usize);
+    assert!(!glyph_count_warning(&font));
+
+    // loca only covers the first five glyphs
+    let (glyf, loca) = glyf_and_loca(&test_glyphs()[..5]);
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"glyf", glyf);
+    replace_table(&mut tables, b"loca", loca);
+    let font = Font::from_data(build_font(&tables)).unwrap();
+    assert_eq!(font.actual_glyph_count().unwrap(), 5);
+    assert_eq!(font.num_glyphs().unwrap(), 11);
+
+    let report = font.validate().unwrap();
+    let warning = report.warnings.iter().find(|w| w.message.contains("loca has entries for")).unwrap();
+    assert_eq!(warning.message, "loca has entries for 5 glyphs but maxp.num_glyphs is 11");
+    assert_eq!(warning.table.as_deref(), Some("loca"));
+}
+
+#[test]
+fn test_iter_resolved_flattens_composites() {
+    let font = Font::from_data(test_font()).unwrap();
+    let glyf = font.glyf_table().unwrap();
+
+    let resolved: Vec<Glyph> = glyf.iter_resolved().collect::<Result<_, _>>().unwrap();
+    assert_eq!(resolved.len(), glyf.glyphs.len());
+    assert!(resolved.iter().all(|glyph| !glyph.is_composite()));
+    assert!(resolved[GID_SPACE as usize].is_empty());
+
+    let contours = |glyph: &Glyph| match &glyph.data {
+        GlyphData::Simple(simple) => simple.end_pts_of_contours.len(),
+        _ => panic!("expected a simple glyph"),
+    };
+    let aacute = &resolved[GID_AACUTE as usize];
+    assert_eq!(
+        contours(aacute),
+        contours(&glyf.glyphs[GID_A as usize]) + contours(&glyf.glyphs[GID_ACUTE as usize])
+    );
+    assert_eq!(aacute.number_of_contours as usize, contours(aacute));
+    assert_same_glyph(&resolved[GID_B as usize], &glyf.glyphs[GID_B as usize]);
+}
+
+#[test]
+fn test_replace_glyph() {
+    let font = Font::from_data(test_font()).unwrap();
+    let replacement = parse_glyph(simple_glyph(&[
+        &[(50, 0, true), (50, 300, true), (450, 300, true), (450, 0, true)],
+        &[(50, 400, true), (50, 700, true), (450, 700, true), (450, 400, true)],
+        &[(200, 320, true), (250, 380, false), (300, 320, true)],
+    ]))
+    .glyphs
+    .remove(0);
+
+    let replaced = font.replace_glyph(GID_A, replacement.clone(), None).unwrap();
+    let glyf = replaced.glyf_table().unwrap();
+    assert_same_glyph(&glyf.glyphs[GID_A as usize], &replacement);
+
+    // The other glyphs are untouched
+    let original = font.glyf_table().unwrap();
+    assert_eq!(glyf.glyphs.len(), original.glyphs.len());
+    assert_same_glyph(&glyf.glyphs[GID_B as usize], &original.glyphs[GID_B as usize]);
+    assert_same_glyph(&glyf.glyphs[GID_AACUTE as usize], &original.glyphs[GID_AACUTE as usize]);
+
+    let hmtx = replaced.hmtx_table().unwrap();
+    assert_eq!(hmtx.get_lsb(GID_A), 50);
+    assert_eq!(hmtx.get_advance_width(GID_A), font.hmtx_table().unwrap().get_advance_width(GID_A));
+    assert_eq!(replaced.maxp_table().unwrap().max_contours, Some(3));
+
+    assert!(matches!(
+        font.replace_glyph(1000, replacement, None),
+        Err(TtfError::InvalidGlyphIndex(1000))
+    ));
+}
+
+#[test]
+fn test_replace_glyph_update_advance() {
+    let font = Font::from_data(test_font()).unwrap();
+    let replacement = font.glyph(GID_B).unwrap();
+
+    let replaced = font.replace_glyph(GID_A, replacement.clone(), Some(720)).unwrap();
+    let hmtx = replaced.hmtx_table().unwrap();
+    assert_eq!(hmtx.get_advance_width(GID_A), 720);
+    assert_eq!(hmtx.get_lsb(GID_A), replacement.x_min);
+    assert_eq!(hmtx.get_advance_width(GID_B), font.hmtx_table().unwrap().get_advance_width(GID_B));
+
+    // Only the first two glyphs have their own metrics; the rest share 600
+    let mut tables = test_font_tables();
+    let mut hmtx_data = hmtx_table(&[(500, 0), (600, 10)]);
+    for lsb in 2..test_glyphs().len() as i16 {
+        hmtx_data.extend_from_slice(&(lsb * 10).to_be_bytes());
+    }
+    replace_table(&mut tables, b"hhea", hhea_table(2));
+    replace_table(&mut tables, b"hmtx", hmtx_data);
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let replaced = font.replace_glyph(GID_SPACE, replacement.clone(), Some(720)).unwrap();
+    assert_eq!(replaced.hhea_table().unwrap().number_of_h_metrics, GID_SPACE + 2);
+    let hmtx = replaced.hmtx_table().unwrap();
+    assert_eq!(hmtx.get_advance_width(GID_SPACE), 720);
+    assert_eq!(hmtx.get_lsb(GID_SPACE), replacement.x_min);
+    assert_eq!((hmtx.get_advance_width(GID_O), hmtx.get_lsb(GID_O)), (600, 30));
+    assert_eq!((hmtx.get_advance_width(GID_AACUTE), hmtx.get_lsb(GID_AACUTE)), (600, 50));
+}
+
+#[test]
+fn test_contour_orientation() {
+    let point = |x, y| ContourPoint { x, y, on_curve: true };
+    let clockwise = vec![point(0, 0), point(0, 100), point(100, 100), point(100, 0)];
+    let counter_clockwise: Vec<ContourPoint> = clockwise.iter().rev().copied().collect();
+
+    assert_eq!(clockwise.signed_area(), -10000.0);
+    assert!(clockwise.is_clockwise());
+    assert_eq!(counter_clockwise.signed_area(), 10000.0);
+    assert!(!counter_clockwise.is_clockwise());
+
+    // Off-curve points are left out of the area
+    let mut curved = clockwise.clone();
+    curved.insert(2, ContourPoint { x: 50, y: 400, on_curve: false });
+    assert_eq!(curved.signed_area(), -10000.0);
+
+    // The test font's O has a clockwise outer contour around a hole
+    let font = Font::from_data(test_font()).unwrap();
+    let contours = glyph_contours(&font.glyph(GID_O).unwrap());
+    assert_eq!(contours.len(), 2);
+    assert!(contours[0].is_clockwise());
+    assert!(!contours[1].is_clockwise());
+}
+
+#[test]
+fn test_remove_overlaps_merges_rectangles() {
+    let mut glyph = parse_glyph(simple_glyph(&[
+        &[(0, 0, true), (0, 200, true), (200, 200, true), (200, 0, true)],
+        &[(100, 100, true), (100, 300, true), (300, 300, true), (300, 100, true)],
+    ]))
+    .glyphs
+    .remove(0);
+    glyph.remove_overlaps().unwrap();
+
+    let contours = glyph_contours(&glyph);
+    assert_eq!(contours.len(), 1);
+    assert_eq!(glyph.number_of_contours, 1);
+    assert_eq!(contours[0].len(), 8);
+    assert!(contours[0].is_clockwise());
+    assert_eq!(contours[0].signed_area(), -70000.0);
+    assert_eq!((glyph.x_min, glyph.y_min, glyph.x_max, glyph.y_max), (0, 0, 300, 300));
+
+    // The union survives a write and re-read
+    let glyf = reparse(&GlyfTable { glyphs: vec![glyph.clone()] });
+    assert_same_glyph(&glyf.glyphs[0], &glyph);
+}
+
+#[test]
+fn test_remove_overlaps_keeps_separate_contours() {
+    let font = Font::from_data(test_font()).unwrap();
+
+    // O's hole lies inside its outer contour without touching it
+    let mut glyph = font.glyph(GID_O).unwrap();
+    glyph.remove_overlaps().unwrap();
+    assert_same_glyph(&glyph, &font.glyph(GID_O).unwrap());
+
+    // A contour inside another of the same direction is absorbed
+    let mut nested = parse_glyph(simple_glyph(&[
+        &[(0, 0, true), (0, 300, true), (300, 300, true), (300, 0, true)],
+        &[(100, 100, true), (100, 200, true), (200, 200, true), (200, 100, true)],
+    ]))
+    .glyphs
+    .remove(0);
+    nested.remove_overlaps().unwrap();
+    let contours = glyph_contours(&nested);
+    assert_eq!(contours.len(), 1);
+    assert_eq!(contours[0].signed_area(), -90000.0);
+}
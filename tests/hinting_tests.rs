@@ -0,0 +1,123 @@
+// Tests for TrueType hinting tables (fpgm, prep, cvt)
+
+mod common;
+
+use common::*;
+use ttf_rs::{Font, FontWriter, GlyphData, LocaTable, TtfError};
+
+fn cvt_bytes(values: &[i16]) -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    for &value in values {
+        writer.write_i16(value);
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn test_cvt_table_values() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"cvt ", cvt_bytes(&[120, -40, 700]));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    assert_eq!(font.cvt_table().unwrap(), vec![120, -40, 700]);
+
+    let mut broken = font.clone();
+    let record = broken.table_records.iter_mut().find(|r| r.table_tag == *b"cvt ").unwrap();
+    record.length = u32::MAX / 2;
+    assert!(matches!(broken.cvt_table(), Err(TtfError::InvalidOffset(_))));
+}
+
+#[test]
+fn test_empty_fpgm_bytecode() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"fpgm", Vec::new());
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    assert!(font.fpgm_bytecode().unwrap().is_empty());
+}
+
+#[test]
+fn test_prep_bytecode() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"prep", vec![0xB0, 0x01, 0x2C]);
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    assert_eq!(font.prep_bytecode().unwrap(), vec![0xB0, 0x01, 0x2C]);
+}
+
+#[test]
+fn test_missing_hinting_tables() {
+    let font = Font::from_data(test_font()).unwrap();
+
+    assert!(matches!(font.cvt_table(), Err(TtfError::MissingTable(_))));
+    assert!(matches!(font.fpgm_bytecode(), Err(TtfError::MissingTable(_))));
+    assert!(matches!(font.prep_bytecode(), Err(TtfError::MissingTable(_))));
+}
+
+#[test]
+fn test_validation_warns_on_zero_instruction_size() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"fpgm", vec![0xB0, 0x00]);
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let report = font.validate().unwrap();
+    assert!(report.warnings.iter().any(|w| {
+        w.table.as_deref() == Some("maxp") && w.message.contains("max_size_of_instructions")
+    }));
+
+    let plain = Font::from_data(test_font()).unwrap();
+    let report = plain.validate().unwrap();
+    assert!(!report.warnings.iter().any(|w| w.message.contains("max_size_of_instructions")));
+}
+
+/// The test font with fpgm, prep, cvt and instructions on glyph B
+fn hinted_font() -> Font {
+    let plain = Font::from_data(test_font()).unwrap();
+    let mut glyf = plain.glyf_table().unwrap();
+    match &mut glyf.glyphs[GID_B as usize].data {
+        GlyphData::Simple(simple) => {
+            simple.instructions = vec![0xB0, 0x00, 0x2B];
+            simple.instruction_length = 3;
+        }
+        _ => panic!("expected a simple glyph"),
+    }
+    let (glyf_data, offsets) = glyf.to_bytes();
+    let is_long = plain.head_table().unwrap().is_long_loca_format();
+
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"glyf", glyf_data);
+    replace_table(&mut tables, b"loca", LocaTable::Long(offsets).to_bytes(is_long));
+    replace_table(&mut tables, b"fpgm", vec![0xB0, 0x00, 0x2C, 0x2D]);
+    replace_table(&mut tables, b"prep", vec![0xB0, 0x01]);
+    replace_table(&mut tables, b"cvt ", cvt_bytes(&[120, -40]));
+    Font::from_data(build_font(&tables)).unwrap()
+}
+
+#[test]
+fn test_strip_hinting() {
+    let font = hinted_font();
+    match &font.glyph(GID_B).unwrap().data {
+        GlyphData::Simple(simple) => assert_eq!(simple.instruction_length, 3),
+        _ => panic!("expected a simple glyph"),
+    }
+
+    let stripped = Font::from_data(font.strip_hinting().unwrap().to_bytes().unwrap()).unwrap();
+
+    for tag in [b"fpgm", b"prep", b"cvt "] {
+        assert!(stripped.get_table_record(tag).is_none());
+    }
+    let glyf = stripped.glyf_table().unwrap();
+    assert_eq!(glyf.glyphs.len(), font.glyf_table().unwrap().glyphs.len());
+    for glyph in &glyf.glyphs {
+        if let GlyphData::Simple(simple) = &glyph.data {
+            assert_eq!(simple.instruction_length, 0);
+            assert!(simple.instructions.is_empty());
+        }
+    }
+
+    let maxp = stripped.maxp_table().unwrap();
+    assert_eq!(maxp.max_zones, Some(1));
+    assert_eq!(maxp.max_function_defs, Some(0));
+    assert_eq!(maxp.max_size_of_instructions, Some(0));
+    assert_eq!(stripped.glyph_bounding_box(GID_B).unwrap(), font.glyph_bounding_box(GID_B).unwrap());
+}
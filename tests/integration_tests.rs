@@ -227,6 +227,8 @@ fn test_os2_table_helpers() {
         us_default_char: 0,
         us_break_char: 32,
         us_max_context: 0,
+        us_lower_optical_point_size: 0,
+        us_upper_optical_point_size: 0,
     };
     
     assert!(!os2_normal.is_bold());
@@ -265,7 +267,6 @@ fn test_hhea_table_helpers() {
         reserved1: 0,
         reserved2: 0,
         reserved3: 0,
-        reserved4: 0,
         metric_data_format: 0,
         number_of_h_metrics: 100,
     };
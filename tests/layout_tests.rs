@@ -0,0 +1,147 @@
+// Tests for the OpenType layout tables (GPOS, GSUB)
+
+mod common;
+
+use common::*;
+use ttf_rs::{Font, FontReader, FontWriter, GposTable, GsubTable, TtfTable};
+
+fn parse_gpos(data: &[u8]) -> GposTable {
+    let mut reader = FontReader::from_slice(data);
+    GposTable::from_reader(&mut reader, data.len() as u32).unwrap()
+}
+
+fn parse_gsub(data: &[u8]) -> GsubTable {
+    let mut reader = FontReader::from_slice(data);
+    GsubTable::from_reader(&mut reader, data.len() as u32).unwrap()
+}
+
+// Glyphs standing in for 'f', 'i' and the "fi" / "ffi" ligatures
+const GID_F_LOWER: u16 = GID_C;
+const GID_I_LOWER: u16 = GID_D;
+const GID_FI: u16 = GID_E;
+const GID_FFI: u16 = GID_F;
+
+#[test]
+fn test_gpos_pair_pos_format1() {
+    let gpos = parse_gpos(&layout_table(&[(2, pair_pos_format1(&[(GID_A, GID_B, -80)]))]));
+
+    assert_eq!(gpos.pair_lookups.len(), 1);
+    assert_eq!(gpos.pair_adjustment(GID_A, GID_B), Some(-80));
+    assert_eq!(gpos.pair_adjustment(GID_B, GID_A), None);
+    assert_eq!(gpos.pair_adjustment(GID_A, GID_O), None);
+}
+
+#[test]
+fn test_gpos_adjustments_add_across_lookups() {
+    let gpos = parse_gpos(&layout_table(&[
+        (2, pair_pos_format1(&[(GID_A, GID_B, -80), (GID_A, GID_O, 10), (GID_B, GID_A, 5)])),
+        (1, vec![0, 1]), // single adjustment lookups are ignored
+        (2, pair_pos_format1(&[(GID_A, GID_B, 30)])),
+    ]));
+
+    assert_eq!(gpos.pair_lookups.len(), 2);
+    assert_eq!(gpos.pair_adjustment(GID_A, GID_B), Some(-50));
+    assert_eq!(gpos.pair_adjustment(GID_A, GID_O), Some(10));
+    assert_eq!(gpos.pair_adjustment(GID_B, GID_A), Some(5));
+}
+
+#[test]
+fn test_gpos_pair_pos_format2() {
+    // Class 1 on the left: A and B; class 1 on the right: O
+    let mut writer = FontWriter::new();
+    writer.write_u16(2); // format
+    writer.write_u16(32); // coverage offset
+    writer.write_u16(0x0005); // value format 1: x placement and x advance
+    writer.write_u16(0); // value format 2
+    writer.write_u16(40); // class def 1 offset
+    writer.write_u16(50); // class def 2 offset
+    writer.write_u16(2); // class 1 count
+    writer.write_u16(2); // class 2 count
+    for (placement, advance) in [(0, 0), (0, 0), (0, 0), (7, -40)] {
+        writer.write_i16(placement);
+        writer.write_i16(advance);
+    }
+    writer.write_bytes(&coverage_table(&[GID_A, GID_B]));
+    // Class def 1, format 1: glyphs A and B in class 1
+    for value in [1, GID_A, 2, 1, 1] {
+        writer.write_u16(value);
+    }
+    // Class def 2, format 2: O in class 1
+    for value in [2, 1, GID_O, GID_O, 1] {
+        writer.write_u16(value);
+    }
+
+    let gpos = parse_gpos(&layout_table(&[(2, writer.into_inner())]));
+    assert_eq!(gpos.pair_adjustment(GID_A, GID_O), Some(-40));
+    assert_eq!(gpos.pair_adjustment(GID_B, GID_O), Some(-40));
+    assert_eq!(gpos.pair_adjustment(GID_A, GID_B), Some(0));
+    assert_eq!(gpos.pair_adjustment(GID_O, GID_A), None);
+}
+
+#[test]
+fn test_advance_for_text_uses_gpos() {
+    let mut tables = test_font_tables();
+    tables.push((*b"GPOS", layout_table(&[(2, pair_pos_format1(&[(GID_A, GID_B, -80)]))])));
+    tables.push((*b"kern", kern_table(&[(GID_A, GID_B, -50)])));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let unkerned = font.advance_for_text("AB", false).unwrap();
+    assert_eq!(font.advance_for_text("AB", true).unwrap(), unkerned - 80);
+}
+
+#[test]
+fn test_gsub_fi_ligature() {
+    let gsub = parse_gsub(&layout_table_with_features(
+        &[(*b"liga", &[0])],
+        &[(4, ligature_subst(&[(&[GID_F_LOWER, GID_I_LOWER], GID_FI)]))],
+    ));
+
+    assert_eq!(gsub.lookup_ligature(&[GID_F_LOWER, GID_I_LOWER]), Some((GID_FI, 2)));
+    assert_eq!(gsub.lookup_ligature(&[GID_F_LOWER, GID_I_LOWER, GID_A]), Some((GID_FI, 2)));
+    assert_eq!(gsub.lookup_ligature(&[GID_F_LOWER, GID_A]), None);
+    assert_eq!(gsub.lookup_ligature(&[GID_F_LOWER]), None);
+    assert_eq!(gsub.lookup_ligature(&[GID_I_LOWER, GID_F_LOWER]), None);
+    assert_eq!(gsub.lookup_ligature(&[]), None);
+}
+
+#[test]
+fn test_gsub_prefers_first_listed_ligature() {
+    let gsub = parse_gsub(&layout_table_with_features(
+        &[(*b"liga", &[0])],
+        &[(4, ligature_subst(&[
+            (&[GID_F_LOWER, GID_F_LOWER, GID_I_LOWER], GID_FFI),
+            (&[GID_F_LOWER, GID_I_LOWER], GID_FI),
+        ]))],
+    ));
+
+    assert_eq!(gsub.lookup_ligature(&[GID_F_LOWER, GID_F_LOWER, GID_I_LOWER]), Some((GID_FFI, 3)));
+    assert_eq!(gsub.lookup_ligature(&[GID_F_LOWER, GID_I_LOWER]), Some((GID_FI, 2)));
+}
+
+#[test]
+fn test_gsub_ignores_lookups_outside_ligature_features() {
+    let fi = ligature_subst(&[(&[GID_F_LOWER, GID_I_LOWER], GID_FI)]);
+
+    // Discretionary ligatures are off by default
+    let gsub = parse_gsub(&layout_table_with_features(&[(*b"dlig", &[0])], &[(4, fi.clone())]));
+    assert!(gsub.ligature_lookups.is_empty());
+    assert_eq!(gsub.lookup_ligature(&[GID_F_LOWER, GID_I_LOWER]), None);
+
+    // No script list at all
+    let gsub = parse_gsub(&layout_table(&[(4, fi)]));
+    assert_eq!(gsub.lookup_ligature(&[GID_F_LOWER, GID_I_LOWER]), None);
+}
+
+#[test]
+fn test_font_gsub_table() {
+    let mut tables = test_font_tables();
+    tables.push((*b"GSUB", layout_table_with_features(
+        &[(*b"liga", &[0])],
+        &[(4, ligature_subst(&[(&[GID_F_LOWER, GID_I_LOWER], GID_FI)]))],
+    )));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let gsub = font.gsub_table().unwrap();
+    assert_eq!(gsub.lookup_ligature(&[GID_F_LOWER, GID_I_LOWER]), Some((GID_FI, 2)));
+    assert!(Font::from_data(test_font()).unwrap().gsub_table().is_err());
+}
@@ -0,0 +1,180 @@
+// Tests for loading fonts from files
+
+mod common;
+
+use std::io::Write;
+
+use common::*;
+use ttf_rs::{Font, ParseLimits, TtfError};
+
+fn write_test_font() -> tempfile::NamedTempFile {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(&test_font()).unwrap();
+    file.flush().unwrap();
+    file
+}
+
+#[test]
+fn test_load_reads_into_memory() {
+    let file = write_test_font();
+    let font = Font::load(file.path()).unwrap();
+
+    assert!(!font.data.is_mapped());
+    assert_eq!(&font.data[..], &test_font()[..]);
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_load_mmap_matches_load() {
+    let file = write_test_font();
+    let loaded = Font::load(file.path()).unwrap();
+    let mapped = Font::load_mmap(file.path()).unwrap();
+    assert!(mapped.data.is_mapped());
+
+    assert_eq!(mapped.sfnt_version, loaded.sfnt_version);
+    assert_eq!(mapped.num_tables, loaded.num_tables);
+    assert_eq!(mapped.table_records.len(), loaded.table_records.len());
+    for (a, b) in mapped.table_records.iter().zip(&loaded.table_records) {
+        assert_eq!(
+            (a.table_tag, a.checksum, a.offset, a.length),
+            (b.table_tag, b.checksum, b.offset, b.length)
+        );
+        assert_eq!(mapped.get_table_data(&a.table_tag), loaded.get_table_data(&b.table_tag));
+    }
+
+    assert_eq!(mapped.num_glyphs().unwrap(), loaded.num_glyphs().unwrap());
+    assert_eq!(mapped.char_to_glyph('A').unwrap(), GID_A as u32);
+    assert_eq!(mapped.to_bytes().unwrap(), loaded.to_bytes().unwrap());
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_load_mmap_writes_do_not_touch_file() {
+    let file = write_test_font();
+    let mut subset = Font::load_mmap(file.path()).unwrap().subset();
+    subset.with_glyphs(&[GID_A as u32]);
+    let subset_font = subset.build().unwrap();
+
+    assert!(!subset_font.data.is_mapped());
+    assert_eq!(subset_font.num_glyphs().unwrap(), 2);
+    assert_eq!(std::fs::read(file.path()).unwrap(), test_font());
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_load_mmap_missing_file() {
+    assert!(matches!(
+        Font::load_mmap("/nonexistent/font.ttf"),
+        Err(ttf_rs::TtfError::Io(_))
+    ));
+}
+
+/// The test font's tables with the TrueType outlines swapped for a CFF table
+fn cff_font(cff: &[u8]) -> Font {
+    let mut tables: Vec<([u8; 4], Vec<u8>)> = test_font_tables()
+        .into_iter()
+        .filter(|(tag, _)| tag != b"glyf" && tag != b"loca")
+        .collect();
+    tables.push((*b"CFF ", cff.to_vec()));
+    Font::from_data(build_font_with_version(0x4F54544F, &tables)).unwrap()
+}
+
+#[test]
+fn test_cff_font_detection_and_passthrough() {
+    // CFF header: major 1, minor 0, header size 4, offset size 1
+    let cff = [1, 0, 4, 1, 0, 0, 0, 0];
+    let font = cff_font(&cff);
+
+    assert!(font.is_cff());
+    assert_eq!(font.cff_data().unwrap(), cff);
+    assert!(font.glyf_table().is_err());
+
+    let report = font.validate().unwrap();
+    assert!(report.is_valid, "{}", report.summary());
+    assert!(!report.errors.iter().any(|e| matches!(e.table.as_deref(), Some("glyf" | "loca"))));
+    assert!(!report.warnings.iter().any(|w| w.table.as_deref() == Some("CFF ")));
+
+    // TrueType fonts have no CFF data
+    let truetype = Font::from_data(test_font()).unwrap();
+    assert!(!truetype.is_cff());
+    assert!(matches!(truetype.cff_data(), Err(ttf_rs::TtfError::MissingTable(_))));
+
+    // An OTTO font without CFF outlines is invalid
+    let otto = Font::from_data(build_font_with_version(0x4F54544F, &test_font_tables())).unwrap();
+    assert!(!otto.is_cff());
+    assert!(!otto.validate().unwrap().is_valid);
+}
+
+#[test]
+fn test_tables_iterates_borrowed_table_data() {
+    let font = Font::from_data(test_font()).unwrap();
+
+    let tables: Vec<(&[u8; 4], &[u8])> = font.tables().collect();
+    assert_eq!(tables.len(), font.table_records.len());
+    for ((tag, data), record) in tables.iter().zip(&font.table_records) {
+        assert_eq!(**tag, record.table_tag);
+        assert_eq!(data.len(), record.length as usize);
+        assert_eq!(Some(data.to_vec()), font.get_table_data(tag));
+    }
+
+    // A record pointing past the end of the data is skipped
+    let mut broken = font.clone();
+    broken.table_records[0].offset = u32::MAX - 4;
+    assert_eq!(broken.tables().count(), font.table_records.len() - 1);
+}
+
+#[test]
+fn test_from_data_limited_rejects_absurd_glyph_count() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"maxp", maxp_table(60000));
+    let data = build_font(&tables);
+
+    let limits = ParseLimits { max_glyphs: 1000, ..ParseLimits::default() };
+    let result = Font::from_data_limited(data.clone(), limits);
+    assert!(matches!(result, Err(TtfError::LimitExceeded(_))));
+
+    // The default limits accept any 16-bit glyph count the data can hold,
+    // which 60000 glyphs in a few hundred bytes can't be
+    assert!(matches!(Font::from_data(data), Err(TtfError::LimitExceeded(_))));
+}
+
+#[test]
+fn test_from_data_rejects_oversized_table_record() {
+    let mut data = test_font();
+    // Claim a 4 GiB length for the first table
+    data[12 + 12..12 + 16].copy_from_slice(&0xFFFF_FFF0u32.to_be_bytes());
+    assert!(matches!(Font::from_data(data.clone()), Err(TtfError::LimitExceeded(_))));
+
+    // Without the length limit the record still runs past the data
+    let limits = ParseLimits { max_table_length: u32::MAX, ..ParseLimits::default() };
+    assert!(matches!(
+        Font::from_data_limited(data, limits),
+        Err(TtfError::TableParseError { .. })
+    ));
+}
+
+#[test]
+fn test_from_data_rejects_table_past_end() {
+    let mut data = test_font();
+    let len = data.len() as u32;
+    // Point the first table at the last four bytes with an eight byte length
+    data[12 + 8..12 + 16].copy_from_slice(&[(len - 4).to_be_bytes(), 8u32.to_be_bytes()].concat());
+    assert!(matches!(Font::from_data(data.clone()), Err(TtfError::TableParseError { .. })));
+
+    data[12 + 12..12 + 16].copy_from_slice(&4u32.to_be_bytes());
+    assert!(Font::from_data(data).is_ok());
+}
+
+#[test]
+fn test_cmap_group_count_is_not_trusted() {
+    let mut tables = test_font_tables();
+    // A Format 12 subtable claiming u32::MAX groups with only one present
+    let mut cmap = vec![0, 0, 0, 1, 0, 3, 0, 10, 0, 0, 0, 12];
+    cmap.extend_from_slice(&[0, 12, 0, 0, 0, 0, 0, 28, 0, 0, 0, 0]);
+    cmap.extend_from_slice(&u32::MAX.to_be_bytes());
+    cmap.extend_from_slice(&[0, 0, 0, 0x41, 0, 0, 0, 0x41, 0, 0, 0, 1]);
+    replace_table(&mut tables, b"cmap", cmap);
+
+    let font = Font::from_data(build_font(&tables)).unwrap();
+    assert!(font.cmap_table().is_err());
+}
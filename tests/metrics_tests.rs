@@ -0,0 +1,263 @@
+// Tests for text measurement and metrics helpers
+
+mod common;
+
+use common::*;
+use ttf_rs::{Font, FontWriter, LineMetrics, TtfError, TtfTableWrite};
+
+fn kerned_font() -> Font {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"kern", kern_table(&[
+        (GID_A, GID_B, -50),
+        (GID_B, GID_O, 20),
+        (GID_O, GID_A, -10),
+    ]));
+    Font::from_data(build_font(&tables)).unwrap()
+}
+
+#[test]
+fn test_advance_for_text_without_kerning() {
+    let font = kerned_font();
+
+    // A 500 + B 500 + O 600 + A 500
+    assert_eq!(font.advance_for_text("ABOA", false).unwrap(), 2100);
+    assert_eq!(font.advance_for_text("", false).unwrap(), 0);
+}
+
+#[test]
+fn test_advance_for_text_with_kerning() {
+    let font = kerned_font();
+
+    assert_eq!(font.advance_for_text("ABOA", true).unwrap(), 2100 - 50 + 20 - 10);
+    // Pairs only apply in order
+    assert_eq!(font.advance_for_text("BA", true).unwrap(), 1000);
+
+    let kern = font.kern_table().unwrap();
+    assert_eq!(kern.get_kerning(GID_A, GID_B), -50);
+    assert_eq!(kern.get_kerning(GID_B, GID_A), 0);
+}
+
+#[test]
+fn test_advance_for_text_unmapped_and_unkerned() {
+    // Unmapped characters use the .notdef advance (500)
+    let plain = Font::from_data(test_font()).unwrap();
+    assert_eq!(plain.advance_for_text("A\u{4E00}", true).unwrap(), 1000);
+    assert_eq!(plain.advance_for_text("AB", true).unwrap(), 1000);
+}
+
+const NUM_GLYPHS: u16 = GID_F + 1;
+
+/// Font with vhea and vmtx giving glyph `i` an advance height of 900 + 10 * i
+/// for the first `long_metrics` glyphs
+fn vertical_font(long_metrics: u16) -> Font {
+    let mut vhea = FontWriter::new();
+    vhea.write_fixed(1.1);
+    vhea.write_i16(500);
+    vhea.write_i16(-500);
+    vhea.write_i16(0);
+    vhea.write_u16(900 + 10 * (long_metrics - 1));
+    for _ in 0..11 {
+        vhea.write_i16(0);
+    }
+    vhea.write_u16(long_metrics);
+
+    let mut vmtx = FontWriter::new();
+    for gid in 0..long_metrics {
+        vmtx.write_u16(900 + 10 * gid);
+        vmtx.write_i16(100);
+    }
+    for _ in long_metrics..NUM_GLYPHS {
+        vmtx.write_i16(100);
+    }
+
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"vhea", vhea.into_inner());
+    replace_table(&mut tables, b"vmtx", vmtx.into_inner());
+    Font::from_data(build_font(&tables)).unwrap()
+}
+
+#[test]
+fn test_vertical_advance_for_text() {
+    let font = vertical_font(NUM_GLYPHS);
+    let vmtx = font.vmtx_table().unwrap();
+    assert_eq!(vmtx.get_advance_height(GID_B), 920);
+
+    let expected: i32 = [GID_A, GID_B, GID_O, GID_A]
+        .iter()
+        .map(|&gid| vmtx.get_advance_height(gid) as i32)
+        .sum();
+    assert_eq!(expected, 910 + 920 + 930 + 910);
+    assert_eq!(font.vertical_advance_for_text("ABOA").unwrap(), expected);
+    assert_eq!(font.vertical_advance_for_text("").unwrap(), 0);
+
+    // Glyphs past the long metrics share the last advance height
+    let short = vertical_font(2);
+    assert_eq!(short.vertical_advance_for_text("AO").unwrap(), 910 + 910);
+}
+
+#[test]
+fn test_vmtx_record_past_end_of_data() {
+    let mut font = vertical_font(NUM_GLYPHS);
+    let record = font.table_records.iter_mut().find(|r| r.table_tag == *b"vmtx").unwrap();
+    record.length = u32::MAX / 2;
+    assert!(matches!(font.vmtx_table(), Err(TtfError::InvalidOffset(_))));
+}
+
+#[test]
+fn test_vertical_advance_for_text_without_vmtx() {
+    // Every glyph advances by units_per_em
+    let font = Font::from_data(test_font()).unwrap();
+    assert_eq!(font.vertical_advance_for_text("AB\u{4E00}").unwrap(), 3000);
+}
+
+/// VDMX with a 1:1 range and a catch-all range sharing one group
+fn vdmx_table(records: &[(u16, i16, i16)]) -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    writer.write_u16(1); // version
+    writer.write_u16(1); // number of groups
+    writer.write_u16(2); // number of ratio ranges
+    for ratio in [[1u8, 1, 1, 1], [0, 0, 0, 0]] {
+        for value in ratio {
+            writer.write_u8(value);
+        }
+    }
+    let group_offset = 6 + 2 * 4 + 2 * 2;
+    writer.write_u16(group_offset);
+    writer.write_u16(group_offset);
+
+    writer.write_u16(records.len() as u16);
+    writer.write_u8(records.first().map_or(0, |r| r.0 as u8));
+    writer.write_u8(records.last().map_or(0, |r| r.0 as u8));
+    for &(y_pel_height, y_max, y_min) in records {
+        writer.write_u16(y_pel_height);
+        writer.write_i16(y_max);
+        writer.write_i16(y_min);
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn test_vdmx_metrics_for_ppem() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"VDMX", vdmx_table(&[(10, 9, -3), (11, 10, -3), (12, 11, -4)]));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let vdmx = font.vdmx_table().unwrap();
+    assert_eq!(vdmx.ratio_ranges.len(), 2);
+    assert_eq!(vdmx.groups.len(), 1);
+
+    assert_eq!(vdmx.metrics_for_ppem(10), Some((9, -3)));
+    assert_eq!(vdmx.metrics_for_ppem(11), Some((10, -3)));
+    assert_eq!(vdmx.metrics_for_ppem(12), Some((11, -4)));
+    assert_eq!(vdmx.metrics_for_ppem(13), None);
+
+    // Non-square devices fall through to the catch-all range
+    assert_eq!(vdmx.group_for_ratio(2, 1).unwrap().metrics(12), Some((11, -4)));
+}
+
+#[test]
+fn test_ltsh_linear_thresholds() {
+    let mut ltsh = FontWriter::new();
+    ltsh.write_u16(0);
+    ltsh.write_u16(3);
+    ltsh.write_bytes(&[1, 12, 40]);
+
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"LTSH", ltsh.into_inner());
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let ltsh = font.ltsh_table().unwrap();
+    assert_eq!(ltsh.version, 0);
+    assert_eq!(ltsh.num_glyphs, 3);
+    assert_eq!(ltsh.linear_threshold(GID_NOTDEF), Some(1));
+    assert_eq!(ltsh.linear_threshold(GID_A), Some(12));
+    assert_eq!(ltsh.linear_threshold(GID_B), Some(40));
+    assert_eq!(ltsh.linear_threshold(GID_O), None);
+
+    let report = font.validate().unwrap();
+    assert!(!report.warnings.iter().any(|w| w.message.contains("LTSH")));
+}
+
+fn font_with_os2(edit: impl FnOnce(&mut ttf_rs::Os2Table)) -> Font {
+    let plain = Font::from_data(test_font()).unwrap();
+    let mut os2 = plain.os2_table().unwrap();
+    edit(&mut os2);
+    let mut writer = FontWriter::new();
+    os2.write(&mut writer).unwrap();
+
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"OS/2", writer.into_inner());
+    Font::from_data(build_font(&tables)).unwrap()
+}
+
+#[test]
+fn test_line_metrics_use_typo_metrics() {
+    let font = font_with_os2(|os2| os2.fs_selection |= 0x80);
+
+    let typo = LineMetrics { ascent: 750, descent: -250, line_gap: 0 };
+    let hhea = LineMetrics { ascent: 800, descent: -200, line_gap: 90 };
+    assert_eq!(font.line_metrics(true).unwrap(), typo);
+    assert_eq!(font.line_metrics(false).unwrap(), hhea);
+    assert_eq!(typo.line_height(), 1000);
+}
+
+#[test]
+fn test_line_metrics_without_use_typo_metrics() {
+    let font = Font::from_data(test_font()).unwrap();
+
+    let hhea = LineMetrics { ascent: 800, descent: -200, line_gap: 90 };
+    assert_eq!(font.line_metrics(true).unwrap(), hhea);
+    assert_eq!(font.line_metrics(false).unwrap(), hhea);
+    assert_eq!(hhea.line_height(), 1090);
+}
+
+#[test]
+fn test_line_metrics_fallbacks() {
+    // Zeroed hhea metrics fall back to sTypo
+    let plain = Font::from_data(test_font()).unwrap();
+    let mut hhea = plain.hhea_table().unwrap();
+    hhea.ascent = 0;
+    hhea.descent = 0;
+    let mut writer = FontWriter::new();
+    hhea.write(&mut writer).unwrap();
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"hhea", writer.into_inner());
+    let font = Font::from_data(build_font(&tables)).unwrap();
+    assert_eq!(font.line_metrics(false).unwrap(), LineMetrics { ascent: 750, descent: -250, line_gap: 0 });
+
+    // ...and then to usWin when sTypo is zero too
+    let mut os2 = plain.os2_table().unwrap();
+    os2.s_typo_ascender = 0;
+    os2.s_typo_descender = 0;
+    let mut writer = FontWriter::new();
+    os2.write(&mut writer).unwrap();
+    replace_table(&mut tables, b"OS/2", writer.into_inner());
+    let font = Font::from_data(build_font(&tables)).unwrap();
+    assert_eq!(font.line_metrics(true).unwrap(), LineMetrics { ascent: 900, descent: -300, line_gap: 0 });
+}
+
+#[test]
+fn test_recompute_avg_char_width_weighted() {
+    // Lowercase letters are 400 wide except 'e' (700); space is 250
+    let mut mappings: Vec<(u16, u16)> = vec![(0x20, GID_SPACE)];
+    mappings.extend((b'a'..=b'z').map(|c| (u16::from(c), if c == b'e' { GID_F } else { GID_C })));
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"cmap", cmap_table(&mappings));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    // (734 * 400 + 100 * 700 + 166 * 250) / 1000 = 405.1
+    assert_eq!(font.recompute_avg_char_width().unwrap(), 405);
+}
+
+#[test]
+fn test_recompute_avg_char_width_falls_back_to_all_advances() {
+    let font = Font::from_data(test_font()).unwrap();
+    // No lowercase mappings: mean of the 11 non-zero advances, 5150 / 11
+    let width = font.recompute_avg_char_width().unwrap();
+    assert_eq!(width, 468);
+
+    let mut modifier = font.modify();
+    modifier.set_avg_char_width(width).unwrap();
+    let font = modifier.commit().unwrap();
+    assert_eq!(font.os2_table().unwrap().x_avg_char_width, 468);
+}
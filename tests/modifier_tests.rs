@@ -1,4 +1,7 @@
-use ttf_rs::{Font, FontWriter};
+mod common;
+
+use std::time::{Duration, UNIX_EPOCH};
+use ttf_rs::{Font, FontWriter, GlyphData, TtfError};
 
 #[test]
 fn test_font_modifier_chaining() {
@@ -60,6 +63,20 @@ fn test_set_font_metrics() {
     assert!(result.is_ok() || result.is_err()); // Either way is fine with current implementation
 }
 
+#[test]
+fn test_has_table() {
+    let font = Font::from_data(create_minimal_font()).unwrap();
+    assert!(font.has_table(b"head"));
+    assert!(font.has_table(b"OS/2"));
+    assert!(!font.has_table(b"GPOS"));
+    assert!(!font.has_table(b"OS2 "));
+    assert!(!font.has_glyf() && !font.has_cff() && !font.has_colr() && !font.has_gpos() && !font.has_kern());
+
+    let full = Font::from_data(common::test_font()).unwrap();
+    assert!(full.has_glyf());
+    assert!(!full.has_cff());
+}
+
 #[test]
 fn test_set_embedding_type() {
     let font_data = create_minimal_font();
@@ -73,6 +90,134 @@ fn test_set_embedding_type() {
     assert_eq!(os2_table.fs_type, 0x0004);
 }
 
+#[test]
+fn test_set_weight_and_width_class() {
+    let font = Font::from_data(create_minimal_font()).unwrap();
+    assert!(!font.is_bold().unwrap());
+
+    let mut modifier = font.modify();
+    modifier.set_weight_class(700).unwrap().set_width_class(3).unwrap();
+    let bold = Font::from_data(modifier.commit().unwrap().to_bytes().unwrap()).unwrap();
+
+    assert!(bold.is_bold().unwrap());
+    let os2 = bold.os2_table().unwrap();
+    assert_eq!((os2.us_weight_class, os2.us_width_class), (700, 3));
+    assert_eq!(os2.fs_selection & 0x60, 0x20);
+    assert_eq!(bold.head_table().unwrap().mac_style & 0x01, 0x01);
+
+    // Back to a regular weight clears the bold flags
+    let mut modifier = bold.modify();
+    modifier.set_weight_class(400).unwrap();
+    let regular = modifier.commit().unwrap();
+    assert!(!regular.is_bold().unwrap());
+    assert_eq!(regular.os2_table().unwrap().fs_selection & 0x60, 0x40);
+    assert_eq!(regular.head_table().unwrap().mac_style & 0x01, 0);
+}
+
+#[test]
+fn test_set_italic() {
+    let font = Font::from_data(common::test_font()).unwrap();
+    assert!(!font.is_italic().unwrap());
+
+    let mut modifier = font.modify();
+    modifier.set_italic(true, Some(-12.0)).unwrap();
+    let italic = Font::from_data(modifier.commit().unwrap().to_bytes().unwrap()).unwrap();
+
+    assert!(italic.is_italic().unwrap());
+    assert_eq!(italic.post_table().unwrap().italic_angle_degrees(), -12.0);
+    assert_eq!(italic.os2_table().unwrap().fs_selection & 0x41, 0x01);
+    assert_eq!(italic.head_table().unwrap().mac_style & 0x02, 0x02);
+
+    // Without an angle, an italic font keeps its slant
+    let mut modifier = italic.clone().modify();
+    modifier.set_italic(true, None).unwrap();
+    assert_eq!(modifier.commit().unwrap().post_table().unwrap().italic_angle_degrees(), -12.0);
+
+    let mut modifier = italic.modify();
+    modifier.set_italic(false, None).unwrap();
+    let upright = modifier.commit().unwrap();
+    assert!(!upright.is_italic().unwrap());
+    assert_eq!(upright.post_table().unwrap().italic_angle_degrees(), 0.0);
+    assert_eq!(upright.head_table().unwrap().mac_style & 0x02, 0);
+}
+
+#[test]
+fn test_set_created_and_modified() {
+    let font = Font::from_data(create_minimal_font()).unwrap();
+
+    let mut modifier = font.modify();
+    modifier
+        .set_created(UNIX_EPOCH)
+        .unwrap()
+        .set_modified(UNIX_EPOCH + Duration::from_secs(60))
+        .unwrap()
+        .set_font_revision(2, 5)
+        .unwrap();
+    let reloaded = Font::from_data(modifier.commit().unwrap().to_bytes().unwrap()).unwrap();
+
+    // Edits to the same table accumulate
+    let head = reloaded.head_table().unwrap();
+    assert_eq!(head.created, 2_082_844_800);
+    assert_eq!(head.modified, 2_082_844_860);
+    assert!((head.font_revision - 2.05).abs() < 0.001);
+    assert_eq!(head.created_datetime(), UNIX_EPOCH);
+}
+
+#[test]
+fn test_name_edits_accumulate() {
+    let font = Font::from_data(create_minimal_font()).unwrap();
+
+    let mut modifier = font.modify();
+    modifier.set_font_name("Accumulated").unwrap();
+    modifier.set_copyright("Copyright Test").unwrap();
+    let name = modifier.commit().unwrap().name_table().unwrap();
+
+    assert_eq!(name.get_string(1).as_deref(), Some("Accumulated"));
+    assert_eq!(name.get_string(0).as_deref(), Some("Copyright Test"));
+}
+
+fn embedding_permission_for(fs_type: u16) -> ttf_rs::EmbeddingPermission {
+    let font = Font::from_data(create_minimal_font()).unwrap();
+    let mut modifier = font.modify();
+    modifier.set_embedding_type(fs_type).unwrap();
+    modifier.commit().unwrap().embedding_permission().unwrap()
+}
+
+#[test]
+fn test_embedding_permission_installable() {
+    let permission = embedding_permission_for(0x0000);
+
+    assert!(permission.is_installable());
+    assert!(!permission.is_restricted());
+    assert!(!permission.is_preview_print());
+    assert!(!permission.is_editable());
+    assert!(!permission.no_subsetting());
+    assert!(!permission.bitmap_only());
+}
+
+#[test]
+fn test_embedding_permission_restricted() {
+    let permission = embedding_permission_for(0x0002);
+
+    assert!(!permission.is_installable());
+    assert!(permission.is_restricted());
+    assert!(!permission.is_preview_print());
+    assert!(!permission.is_editable());
+    assert!(!permission.no_subsetting());
+}
+
+#[test]
+fn test_embedding_permission_no_subsetting() {
+    let permission = embedding_permission_for(0x0100);
+
+    // Flags outside the usage bits don't affect installability
+    assert!(permission.is_installable());
+    assert!(!permission.is_restricted());
+    assert!(permission.no_subsetting());
+    assert!(!permission.bitmap_only());
+    assert_eq!(permission, ttf_rs::EmbeddingPermission(0x0100));
+}
+
 #[test]
 fn test_multiple_modifications() {
     let font_data = create_minimal_font();
@@ -95,6 +240,82 @@ fn test_multiple_modifications() {
     // Note: Full round-trip verification is a TODO item
 }
 
+#[test]
+fn test_remove_table_round_trip() {
+    let font = Font::from_data(common::test_font()).unwrap();
+    assert!(font.get_table_record(b"post").is_some());
+
+    let mut modifier = font.modify();
+    modifier.remove_table(b"post");
+    let modified = modifier.commit().unwrap();
+
+    let file = tempfile::NamedTempFile::new().unwrap();
+    modified.save(file.path()).unwrap();
+    let reloaded = Font::load(file.path()).unwrap();
+
+    assert!(reloaded.get_table_record(b"post").is_none());
+    assert_eq!(reloaded.num_tables, 9);
+    assert_eq!(reloaded.search_range, 128);
+    assert_eq!(reloaded.entry_selector, 3);
+    assert_eq!(reloaded.range_shift, 16);
+    assert_eq!(reloaded.units_per_em().unwrap(), 1000);
+    assert_eq!(reloaded.num_glyphs().unwrap(), 11);
+}
+
+#[test]
+fn test_add_table_inserts_and_replaces() {
+    let font = Font::from_data(common::test_font()).unwrap();
+
+    let mut modifier = font.modify();
+    modifier.add_table(b"TEST", vec![1, 2, 3, 4, 5]);
+    modifier.add_table(b"post", common::post_table());
+    let modified = modifier.commit().unwrap();
+
+    let reloaded = Font::from_data(modified.to_bytes().unwrap()).unwrap();
+    assert_eq!(reloaded.num_tables, 11);
+    assert_eq!(reloaded.get_table_data(b"TEST").unwrap(), vec![1, 2, 3, 4, 5]);
+    assert_eq!(reloaded.get_table_data(b"post").unwrap(), common::post_table());
+    assert!(reloaded.head_table().is_ok());
+}
+
+#[test]
+fn test_commit_drops_digital_signature() {
+    let font = Font::from_data(common::signed_test_font()).unwrap();
+    assert!(font.has_digital_signature());
+
+    let mut modifier = font.clone().modify();
+    modifier.set_version(2, 0).unwrap();
+    let modified = modifier.commit().unwrap();
+    assert!(!modified.has_digital_signature());
+    assert!(Font::from_data(modified.to_bytes().unwrap()).unwrap().get_table_record(b"DSIG").is_none());
+
+    let mut modifier = font.modify();
+    modifier.retain_digital_signature();
+    assert!(modifier.commit().unwrap().has_digital_signature());
+}
+
+#[test]
+fn test_normalize_header() {
+    // The minimal font stores the right values for its 5 tables, so
+    // overwrite them with arbitrary ones
+    let mut font_data = create_minimal_font();
+    font_data[6..12].copy_from_slice(&[0x00, 0x10, 0x00, 0x07, 0x01, 0x00]);
+    let mut font = Font::from_data(font_data).unwrap();
+    assert_eq!((font.search_range, font.entry_selector, font.range_shift), (16, 7, 256));
+
+    let has_warning = |font: &Font| {
+        font.validate().unwrap().warnings.iter().any(|w| w.message.contains("search fields"))
+    };
+    assert!(has_warning(&font));
+
+    font.normalize_header();
+    assert_eq!((font.search_range, font.entry_selector, font.range_shift), (64, 2, 16));
+    assert!(!has_warning(&font));
+
+    let reloaded = Font::from_data(font.to_bytes().unwrap()).unwrap();
+    assert_eq!((reloaded.search_range, reloaded.entry_selector, reloaded.range_shift), (64, 2, 16));
+}
+
 // Helper function to create a minimal valid TTF font for testing
 fn create_minimal_font() -> Vec<u8> {
     let mut writer = FontWriter::new();
@@ -291,3 +512,142 @@ fn create_minimal_hhea_table() -> Vec<u8> {
     writer.write_u16(10); // number of h metrics
     writer.into_inner()
 }
+
+#[test]
+fn test_verify_roundtrip() {
+    let font = Font::from_data(common::test_font()).unwrap();
+    font.verify_roundtrip().unwrap();
+
+    let mut modifier = font.clone().modify();
+    modifier.set_font_name("Round Trip").unwrap();
+    modifier.commit().unwrap().verify_roundtrip().unwrap();
+
+    // A record pointing past the end of the data can't be serialized
+    let mut broken = font;
+    let post = broken.table_records.iter_mut().find(|r| r.table_tag == *b"post").unwrap();
+    post.length += 1000;
+    match broken.verify_roundtrip() {
+        Err(TtfError::RoundTripMismatch(tables)) => assert!(tables.contains(&"post".to_string())),
+        other => panic!("expected a round trip mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_diff_flags_modified_name_table() {
+    let original = Font::from_data(common::test_font()).unwrap();
+    assert!(original.diff(&original.clone()).is_empty());
+
+    let mut modifier = original.clone().modify();
+    modifier.set_font_name("Renamed Family").unwrap();
+    let renamed = modifier.commit().unwrap();
+
+    let diff = original.diff(&renamed);
+    assert!(diff.only_in_self.is_empty());
+    assert!(diff.only_in_other.is_empty());
+    assert_eq!(diff.num_glyphs, None);
+    assert_eq!(diff.units_per_em, None);
+    let changed: Vec<&str> = diff.changed_tables.iter().map(|t| t.tag.as_str()).collect();
+    assert_eq!(changed, vec!["name"]);
+    assert!(diff.to_string().contains("~ name:"));
+}
+
+#[test]
+fn test_with_table_data_relocates_larger_table() {
+    let font = Font::from_data(common::test_font()).unwrap();
+    let old_length = font.get_table_record(b"name").unwrap().length as usize;
+
+    let long_family = "A Considerably Longer Family Name Than Before";
+    let name = common::name_table(&[
+        (3, 1, 0x409, 1, long_family),
+        (3, 1, 0x409, 2, "Regular"),
+        (3, 1, 0x409, 4, "A Considerably Longer Family Name Than Before Regular"),
+    ]);
+    assert!(name.len() > old_length);
+
+    let updated = font.with_table_data(b"name", name.clone()).unwrap();
+    assert_eq!(updated.get_table_data(b"name").unwrap(), name);
+    assert_eq!(updated.family_name().unwrap(), long_family);
+
+    // Neighbouring tables are untouched and the font survives a round trip
+    assert_eq!(updated.get_table_data(b"post"), font.get_table_data(b"post"));
+    let reloaded = Font::from_data(updated.to_bytes().unwrap()).unwrap();
+    assert_eq!(reloaded.get_table_data(b"name").unwrap(), name);
+    assert_eq!(reloaded.family_name().unwrap(), long_family);
+    assert!(font.diff(&reloaded).changed_tables.iter().all(|t| t.tag == "name"));
+
+    assert!(matches!(font.with_table_data(b"ZZZZ", Vec::new()), Err(TtfError::MissingTable(_))));
+}
+
+#[test]
+fn test_font_summary_of_minimal_font() {
+    let font = Font::from_data(create_minimal_font()).unwrap();
+    let summary = font.summary().unwrap();
+
+    assert_eq!(summary.sfnt_version, 0x00010000);
+    assert_eq!(summary.num_tables, 5);
+    assert_eq!(summary.num_glyphs, 10);
+    assert_eq!(summary.units_per_em, 1000);
+    assert_eq!(summary.family_name, "Test");
+    assert_eq!(summary.weight_class, 400);
+    assert!(!summary.is_bold);
+    assert!(!summary.is_italic);
+    assert_eq!(summary.tables, vec!["head", "hhea", "maxp", "name", "OS/2"]);
+
+    // The minimal name table has no subfamily record
+    assert_eq!(summary.subfamily_name, "Unknown");
+}
+
+#[test]
+fn test_font_summary_defaults_for_missing_tables() {
+    let head = common::head_table(2048);
+    let font = Font::from_data(common::build_font(&[(*b"head", head)])).unwrap();
+    let summary = font.summary().unwrap();
+
+    assert_eq!(summary.num_tables, 1);
+    assert_eq!(summary.num_glyphs, 0);
+    assert_eq!(summary.units_per_em, 2048);
+    assert_eq!(summary.family_name, "Unknown");
+    assert_eq!(summary.weight_class, 400);
+    assert!(!summary.is_bold);
+    assert_eq!(summary.tables, vec!["head"]);
+}
+
+#[test]
+fn test_rescale_to_upem() {
+    let font = Font::from_data(common::test_font()).unwrap();
+    assert_eq!(font.units_per_em().unwrap(), 1000);
+
+    let mut modifier = font.modify();
+    modifier.set_glyph_advance(common::GID_B as usize, 400).unwrap();
+    modifier.rescale_to_upem(2048).unwrap();
+    let rescaled = Font::from_data(modifier.commit().unwrap().to_bytes().unwrap()).unwrap();
+
+    assert_eq!(rescaled.units_per_em().unwrap(), 2048);
+
+    // A's apex (250, 700) scales by 2.048
+    let glyf = rescaled.glyf_table().unwrap();
+    let a = &glyf.glyphs[common::GID_A as usize];
+    match &a.data {
+        GlyphData::Simple(simple) => {
+            assert_eq!((simple.x_coordinates[1], simple.y_coordinates[1]), (512, 1434));
+        }
+        _ => panic!("A should be a simple glyph"),
+    }
+    assert_eq!((a.x_max, a.y_max), (1024, 1434));
+
+    // The acute is placed at (200, 750) in Aacute
+    match &glyf.glyphs[common::GID_AACUTE as usize].data {
+        GlyphData::Composite(composite) => {
+            let acute = &composite.components[1];
+            assert_eq!((acute.arg1, acute.arg2), (410, 1536));
+            assert_eq!(acute.transform.xx, 1.0);
+        }
+        _ => panic!("Aacute should be a composite glyph"),
+    }
+
+    // Advances scale too, including the one set before rescaling
+    let hmtx = rescaled.hmtx_table().unwrap();
+    assert_eq!(hmtx.get_advance_width(common::GID_A), 1024);
+    assert_eq!(hmtx.get_advance_width(common::GID_B), 819);
+    assert_eq!(rescaled.hhea_table().unwrap().ascent, 1638);
+}
@@ -0,0 +1,219 @@
+// Tests for naming and metadata (name, meta)
+
+mod common;
+
+use common::*;
+use ttf_rs::{Font, FontWriter, TtfError};
+
+/// meta table with (tag, data) maps
+fn meta_table(maps: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    writer.write_u32(1); // version
+    writer.write_u32(0); // flags
+    writer.write_u32(0); // reserved
+    writer.write_u32(maps.len() as u32);
+
+    let mut offset = 16 + maps.len() * 12;
+    for (tag, data) in maps {
+        writer.write_tag(tag);
+        writer.write_u32(offset as u32);
+        writer.write_u32(data.len() as u32);
+        offset += data.len();
+    }
+    for (_, data) in maps {
+        writer.write_bytes(data);
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn test_meta_tags() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"meta", meta_table(&[
+        (b"dlng", b"Latn, Cyrl"),
+        (b"slng", b"Latn,Cyrl,Grek"),
+        (b"bild", &[0xFF, 0xFE, 0x00]),
+    ]));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let tags = font.meta_tags().unwrap();
+    assert_eq!(tags.len(), 2);
+    assert_eq!(tags["dlng"], "Latn, Cyrl");
+    assert_eq!(tags["slng"], "Latn,Cyrl,Grek");
+
+    let meta = font.meta_table().unwrap();
+    assert_eq!(meta.design_languages(), vec!["Latn", "Cyrl"]);
+    assert_eq!(meta.supported_languages(), vec!["Latn", "Cyrl", "Grek"]);
+    assert_eq!(meta.get(b"bild"), Some(&[0xFF, 0xFE, 0x00][..]));
+
+    let plain = Font::from_data(test_font()).unwrap();
+    assert!(plain.meta_tags().unwrap().is_empty());
+}
+
+#[test]
+fn test_meta_truncated_table() {
+    // Claim u32::MAX data maps with only one record present
+    let mut meta = meta_table(&[(b"dlng", b"Latn")]);
+    meta[12..16].copy_from_slice(&u32::MAX.to_be_bytes());
+    meta.truncate(28);
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"meta", meta);
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    assert!(matches!(font.meta_table(), Err(TtfError::UnexpectedEof { .. })));
+}
+
+/// Decoded names of a font as (platform, name id) -> value
+fn names(font: &Font) -> std::collections::HashMap<(u16, u16), String> {
+    let table = font.name_table().unwrap();
+    table
+        .name_records
+        .iter()
+        .map(|r| ((r.platform_id, r.name_id), table.get_record_string(r).unwrap()))
+        .collect()
+}
+
+#[test]
+fn test_rename_updates_windows_and_mac_records() {
+    let mut tables = test_font_tables();
+    let mut records = Vec::new();
+    for (platform_id, encoding_id, language_id) in [(3, 1, 0x409), (1, 0, 0)] {
+        for (name_id, value) in [
+            (1, "Old Sans Light"),
+            (2, "Regular"),
+            (3, "1.000;VEND;OldSans-Light"),
+            (4, "Old Sans Light"),
+            (5, "Version 1.000"),
+            (6, "OldSans-Light"),
+            (16, "Old Sans"),
+            (17, "Light"),
+        ] {
+            records.push((platform_id, encoding_id, language_id, name_id, value));
+        }
+    }
+    replace_table(&mut tables, b"name", name_table(&records));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let renamed = font.rename("New Grotesk").unwrap();
+    let reloaded = Font::from_data(renamed.to_bytes().unwrap()).unwrap();
+    let names = names(&reloaded);
+
+    for platform_id in [3, 1] {
+        assert_eq!(names[&(platform_id, 1)], "New Grotesk Light");
+        assert_eq!(names[&(platform_id, 2)], "Regular");
+        assert_eq!(names[&(platform_id, 3)], "1.000;VEND;NewGrotesk-Light");
+        assert_eq!(names[&(platform_id, 4)], "New Grotesk Light");
+        assert_eq!(names[&(platform_id, 5)], "Version 1.000");
+        assert_eq!(names[&(platform_id, 6)], "NewGrotesk-Light");
+        assert_eq!(names[&(platform_id, 16)], "New Grotesk");
+        assert_eq!(names[&(platform_id, 17)], "Light");
+    }
+    assert_eq!(names.len(), 16);
+}
+
+#[test]
+fn test_rename_without_typographic_family() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"name", name_table(&[
+        (3, 1, 0x409, 1, "Test"),
+        (3, 1, 0x409, 2, "Bold"),
+        (3, 1, 0x409, 4, "Something Else"),
+        (3, 1, 0x409, 6, "Other"),
+    ]));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let names = names(&font.rename("Café (Mono)").unwrap());
+    assert_eq!(names[&(3, 1)], "Café (Mono)");
+    assert_eq!(names[&(3, 4)], "Café (Mono) Bold");
+    assert_eq!(names[&(3, 6)], "CafMono-Bold");
+    assert!(!names.contains_key(&(3, 16)));
+}
+
+#[test]
+fn test_mac_roman_names_round_trip() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"name", name_table(&[(1, 0, 0, 1, "Test")]));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let names = names(&font.rename("Café").unwrap());
+    assert_eq!(names[&(1, 1)], "Café");
+}
+
+#[test]
+fn test_get_string_prefers_windows_english() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"name", name_table(&[
+        (1, 0, 0, 1, "Mac Family"),
+        (3, 1, 0x407, 1, "German Family"),
+        (3, 1, 0x409, 1, "Windows Family"),
+        (1, 0, 0, 2, "Mac Subfamily"),
+        (0, 3, 0, 4, "Unicode Full Name"),
+    ]));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let name = font.name_table().unwrap();
+    assert_eq!(name.get_string(1).as_deref(), Some("Windows Family"));
+    assert_eq!(name.get_string(2).as_deref(), Some("Mac Subfamily"));
+    assert_eq!(name.get_string(4).as_deref(), Some("Unicode Full Name"));
+    assert_eq!(name.get_string(6), None);
+
+    assert_eq!(font.family_name().unwrap(), "Windows Family");
+    assert_eq!(font.subfamily_name().unwrap(), "Mac Subfamily");
+    assert_eq!(font.full_name().unwrap(), "Unicode Full Name");
+    assert_eq!(font.postscript_name().unwrap(), "Unknown");
+}
+
+#[test]
+fn test_font_name_accessors() {
+    let font = Font::from_data(test_font()).unwrap();
+
+    assert_eq!(font.font_name().unwrap(), "Test Family");
+    assert_eq!(font.family_name().unwrap(), "Test Family");
+    assert_eq!(font.subfamily_name().unwrap(), "Regular");
+    assert_eq!(font.full_name().unwrap(), "Test Family Regular");
+    assert_eq!(font.postscript_name().unwrap(), "TestFamily-Regular");
+}
+
+/// Format 2 post table naming the test font's glyphs; the acute accent gets
+/// a custom name
+fn post_format2_table() -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    writer.write_fixed(2.0); // format
+    writer.write_fixed(0.0); // italic angle
+    writer.write_i16(-100); // underline position
+    writer.write_i16(50); // underline thickness
+    for _ in 0..5 {
+        writer.write_u32(0); // fixed pitch, memory usage hints
+    }
+    // .notdef A B O space Aacute acute.cap C D E F
+    let name_indices = [0u16, 36, 37, 50, 3, 201, 258, 38, 39, 40, 41];
+    writer.write_u16(name_indices.len() as u16);
+    for index in name_indices {
+        writer.write_u16(index);
+    }
+    writer.write_u8(9);
+    writer.write_bytes(b"acute.cap");
+    writer.into_inner()
+}
+
+#[test]
+fn test_glyph_names_from_post_format2() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"post", post_format2_table());
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    assert_eq!(font.glyph_for_name("acute.cap").unwrap(), Some(GID_ACUTE));
+    assert_eq!(font.name_for_glyph(GID_ACUTE).unwrap().as_deref(), Some("acute.cap"));
+    assert_eq!(font.glyph_for_name("Aacute").unwrap(), Some(GID_AACUTE));
+    assert_eq!(font.name_for_glyph(GID_SPACE).unwrap().as_deref(), Some("space"));
+    assert_eq!(font.glyph_for_name("Z").unwrap(), None);
+    assert_eq!(font.name_for_glyph(100).unwrap(), None);
+}
+
+#[test]
+fn test_glyph_names_from_post_format3() {
+    let font = Font::from_data(test_font()).unwrap();
+
+    assert_eq!(font.glyph_for_name("A").unwrap(), None);
+    assert_eq!(font.name_for_glyph(GID_A).unwrap(), None);
+}
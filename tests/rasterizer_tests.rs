@@ -0,0 +1,224 @@
+// Tests for the glyph rasterizer
+
+mod common;
+
+use common::*;
+use ttf_rs::{Font, Rasterizer};
+
+#[test]
+fn test_outline_parsed_once_per_glyph() {
+    let font = Font::from_data(test_font()).unwrap();
+    let mut rasterizer = Rasterizer::new(font);
+
+    let first = rasterizer.rasterize_glyph(GID_B as u32, 100).unwrap();
+    rasterizer.clear_cache();
+    let second = rasterizer.rasterize_glyph(GID_B as u32, 100).unwrap();
+
+    assert_eq!(first.bitmap, second.bitmap);
+    assert_eq!(rasterizer.outline_parse_count(), 1);
+
+    rasterizer.rasterize_glyph(GID_O as u32, 100).unwrap();
+    assert_eq!(rasterizer.outline_parse_count(), 2);
+}
+
+#[test]
+fn test_glyf_table_parsed_once() {
+    let font = Font::from_data(test_font()).unwrap();
+    let mut rasterizer = Rasterizer::new(font);
+
+    rasterizer.rasterize_glyph(GID_B as u32, 100).unwrap();
+    rasterizer.rasterize_glyph(GID_O as u32, 100).unwrap();
+    rasterizer.rasterize_sdf(GID_AACUTE as u32, 32.0, 4.0).unwrap();
+
+    assert_eq!(rasterizer.outline_parse_count(), 3);
+    assert_eq!(rasterizer.glyf_parse_count(), 1);
+}
+
+#[test]
+fn test_rasterize_cache_is_keyed_by_size() {
+    let font = Font::from_data(test_font()).unwrap();
+    let mut rasterizer = Rasterizer::new(font);
+
+    let small = rasterizer.rasterize_glyph(GID_O as u32, 16).unwrap();
+    let large = rasterizer.rasterize_glyph(GID_O as u32, 32).unwrap();
+    assert_eq!((small.width, small.height), (10, 12));
+    assert_eq!((large.width, large.height), (20, 23));
+
+    // Both sizes stay cached
+    let small_again = rasterizer.rasterize_glyph(GID_O as u32, 16).unwrap();
+    assert_eq!((small_again.width, small_again.height), (10, 12));
+    assert_eq!(small_again.bitmap, small.bitmap);
+    assert_eq!(rasterizer.outline_parse_count(), 1);
+}
+
+#[test]
+fn test_glyph_outline_invalid_index() {
+    let font = Font::from_data(test_font()).unwrap();
+    let mut rasterizer = Rasterizer::new(font);
+
+    assert!(rasterizer.glyph_outline(100).is_err());
+    assert_eq!(rasterizer.outline_parse_count(), 0);
+}
+
+#[test]
+fn test_rasterize_sdf_inside_and_outside() {
+    let font = Font::from_data(test_font()).unwrap();
+    let mut rasterizer = Rasterizer::new(font);
+
+    // B is a 300x700 rectangle: 30x70 pixels at 100px, padded by 4 each side
+    let sdf = rasterizer.rasterize_sdf(GID_B as u32, 100.0, 4.0).unwrap();
+    assert_eq!((sdf.width, sdf.height), (38, 78));
+    assert_eq!(sdf.bitmap.len(), sdf.width * sdf.height);
+
+    let at = |col: usize, row: usize| sdf.bitmap[row * sdf.width + col];
+    // Far inside
+    assert!(at(19, 39) >= 250);
+    // Far outside, in the padding corner
+    assert!(at(0, 0) <= 5);
+    // Half a pixel inside and outside the left edge
+    assert!((at(4, 39) as i32 - 144).abs() <= 1);
+    assert!((at(3, 39) as i32 - 112).abs() <= 1);
+}
+
+#[test]
+fn test_rasterize_sdf_counter_is_outside() {
+    let font = Font::from_data(test_font()).unwrap();
+    let mut rasterizer = Rasterizer::new(font);
+
+    // The counter of O is outside the outline, its ring is inside
+    let sdf = rasterizer.rasterize_sdf(GID_O as u32, 100.0, 4.0).unwrap();
+    let centre = sdf.bitmap[(sdf.height / 2) * sdf.width + sdf.width / 2];
+    assert!(centre < 128);
+
+    let ring = sdf.bitmap[(sdf.height / 2) * sdf.width + 4 + 5];
+    assert!(ring > 128);
+
+    // Empty glyphs produce an empty field
+    let space = rasterizer.rasterize_sdf(GID_SPACE as u32, 100.0, 4.0).unwrap();
+    assert!(space.bitmap.is_empty());
+}
+
+#[test]
+fn test_rasterized_glyph_to_pgm() {
+    let font = Font::from_data(test_font()).unwrap();
+    let mut rasterizer = Rasterizer::new(font);
+    let glyph = rasterizer.rasterize_glyph(GID_B as u32, 100).unwrap();
+
+    let pgm = glyph.to_pgm();
+    let header = format!("P5\n{} {}\n255\n", glyph.width, glyph.height);
+    assert!(pgm.starts_with(b"P5"));
+    assert!(pgm.starts_with(header.as_bytes()));
+    assert_eq!(pgm.len(), header.len() + glyph.width * glyph.height);
+    assert_eq!(&pgm[header.len()..], &glyph.bitmap[..]);
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn test_rasterized_glyph_to_png() {
+    let font = Font::from_data(test_font()).unwrap();
+    let mut rasterizer = Rasterizer::new(font);
+    let glyph = rasterizer.rasterize_glyph(GID_B as u32, 100).unwrap();
+
+    let png = glyph.to_png().unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&png[12..16], b"IHDR");
+    assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()) as usize, glyph.width);
+    assert_eq!(u32::from_be_bytes(png[20..24].try_into().unwrap()) as usize, glyph.height);
+    assert!(png.ends_with(b"IEND\xae\x42\x60\x82"));
+
+    let space = rasterizer.rasterize_glyph(GID_SPACE as u32, 100).unwrap();
+    assert!(space.to_png().is_err());
+}
+
+#[test]
+fn test_rasterize_glyphs_matches_sequential() {
+    let font = Font::from_data(test_font()).unwrap();
+    let glyph_ids: Vec<u32> = [GID_O, GID_A, GID_SPACE, GID_B, GID_AACUTE, GID_A]
+        .iter()
+        .map(|&gid| gid as u32)
+        .collect();
+
+    let batch = font.rasterize_glyphs(&glyph_ids, 100.0).unwrap();
+    assert_eq!(batch.len(), glyph_ids.len());
+
+    for (glyph, &glyph_id) in batch.iter().zip(&glyph_ids) {
+        let expected = font.rasterizer().rasterize_glyph(glyph_id, 100).unwrap();
+        assert_eq!(glyph.glyph_id, glyph_id);
+        assert_eq!(glyph.bitmap, expected.bitmap);
+        assert_eq!((glyph.width, glyph.height), (expected.width, expected.height));
+        assert_eq!(glyph.advance_width, expected.advance_width);
+        assert_eq!(glyph.left_side_bearing, expected.left_side_bearing);
+    }
+}
+
+#[test]
+fn test_rasterize_glyphs_invalid_index() {
+    let font = Font::from_data(test_font()).unwrap();
+    assert!(font.rasterize_glyphs(&[GID_A as u32, 100], 100.0).is_err());
+    assert!(font.rasterize_glyphs(&[], 100.0).unwrap().is_empty());
+}
+
+#[test]
+fn test_snap_to_pixel_aligns_vertical_edges() {
+    let font = Font::from_data(test_font()).unwrap();
+    let mut rasterizer = Rasterizer::new(font);
+    assert!(!rasterizer.snap_to_pixel());
+
+    // At 13px B's left edge falls at x = 1.3 and its right edge at 5.2
+    let blurred = rasterizer.rasterize_sdf(GID_B as u32, 13.0, 4.0).unwrap();
+    assert_eq!(blurred.width, 13);
+
+    // Snapped, the edges move to x = 1 and x = 5
+    rasterizer.set_snap_to_pixel(true);
+    let sdf = rasterizer.rasterize_sdf(GID_B as u32, 13.0, 4.0).unwrap();
+    assert_eq!(sdf.width, 12);
+
+    // Column 3 spans x = 0..1 and column 4 spans x = 1..2, so the edge
+    // sits exactly between them: half a pixel outside and inside
+    let row = sdf.height / 2;
+    let at = |col: usize| sdf.bitmap[row * sdf.width + col] as i32;
+    assert_eq!(at(3), 112);
+    assert_eq!(at(4), 144);
+    assert_eq!(at(3) + at(4), 256);
+    let blurred_at = |col: usize| blurred.bitmap[row * blurred.width + col] as i32;
+    assert_ne!(blurred_at(3) + blurred_at(4), 256);
+
+    // The outline bitmap's width covers whole pixels only
+    let outline = rasterizer.rasterize_glyph(GID_B as u32, 13).unwrap();
+    assert_eq!(outline.width, 4);
+}
+
+#[test]
+fn test_rasterize_ignores_stale_glyph_bbox() {
+    // B's header claims 100..250 x 0..350, but its outline spans 100..400 x 0..700
+    let mut glyphs = test_glyphs();
+    glyphs[GID_B as usize][6..10].copy_from_slice(&[0, 250, 1, 94]);
+    let mut tables = test_font_tables();
+    let (glyf, loca) = glyf_and_loca(&glyphs);
+    replace_table(&mut tables, b"glyf", glyf);
+    replace_table(&mut tables, b"loca", loca);
+    let font = Font::from_data(build_font(&tables)).unwrap();
+    assert_eq!(font.glyph(GID_B).unwrap().y_max, 350);
+
+    let mut rasterizer = Rasterizer::new(font);
+    let glyph = rasterizer.rasterize_glyph(GID_B as u32, 100).unwrap();
+    assert_eq!((glyph.width, glyph.height), (30, 70));
+
+    // The right edge and the top edge are drawn in full
+    let lit = |x: usize, y: usize| glyph.bitmap[y * glyph.width + x] != 0;
+    assert!((0..glyph.height).all(|y| lit(glyph.width - 1, y)));
+    assert!((0..glyph.width).all(|x| lit(x, glyph.height - 1)));
+}
+
+#[test]
+fn test_rasterize_composite_glyph() {
+    // Á is A plus an acute offset to (200, 750), spanning 0..500 x 0..850
+    let font = Font::from_data(test_font()).unwrap();
+    let glyph = font.rasterizer().rasterize_glyph(GID_AACUTE as u32, 100).unwrap();
+    assert_eq!((glyph.width, glyph.height), (50, 85));
+    assert!(glyph.bitmap.iter().any(|&v| v != 0));
+
+    // The batch path resolves composites the same way
+    let batch = font.rasterize_glyphs(&[GID_AACUTE as u32], 100.0).unwrap();
+    assert_eq!(batch[0].bitmap, glyph.bitmap);
+}
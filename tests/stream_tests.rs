@@ -1,4 +1,5 @@
-use ttf_rs::{FontReader, FontWriter, calculate_checksum};
+use std::io::Write;
+use ttf_rs::{ChecksumWriter, FontReader, FontWriter, TtfError, calculate_checksum};
 
 #[test]
 fn test_font_reader_u8() {
@@ -29,6 +30,29 @@ fn test_font_reader_u16() {
     assert_eq!(reader.read_u16().unwrap(), 0x5678);
 }
 
+#[test]
+fn test_font_reader_u16_array() {
+    let data = vec![0x00, 0x01, 0x12, 0x34, 0xFF, 0xFF, 0xAB];
+    let mut reader = FontReader::from_slice(&data);
+
+    assert_eq!(reader.read_u16_array(3).unwrap(), vec![0x0001, 0x1234, 0xFFFF]);
+    assert_eq!(reader.position(), 6);
+
+    // Only one byte left: the read fails without consuming anything
+    assert!(reader.read_u16_array(1).is_err());
+    assert_eq!(reader.position(), 6);
+    assert_eq!(reader.read_u16_array(0).unwrap(), Vec::<u16>::new());
+}
+
+#[test]
+fn test_font_reader_i16_and_u32_arrays() {
+    let data = vec![0xFF, 0xFE, 0x00, 0x02, 0x12, 0x34, 0x56, 0x78];
+    let mut reader = FontReader::from_slice(&data);
+    assert_eq!(reader.read_i16_array(2).unwrap(), vec![-2, 2]);
+    assert_eq!(reader.read_u32_array(1).unwrap(), vec![0x12345678]);
+    assert!(reader.read_u32_array(usize::MAX).is_err());
+}
+
 #[test]
 fn test_font_reader_i16() {
     let data = vec![0x7F, 0xFF, 0x80, 0x00];
@@ -89,6 +113,18 @@ fn test_font_reader_set_position() {
     assert_eq!(reader.read_u8().unwrap(), 0x00);
 }
 
+#[test]
+fn test_font_reader_pascal_string() {
+    let data = [&[5u8][..], b"Hello", &[2, 0x8E, b'!'], &[4, b'a']].concat();
+    let mut reader = FontReader::from_slice(&data);
+
+    assert_eq!(reader.read_pascal_string().unwrap(), "Hello");
+    // High bytes decode as Mac Roman
+    assert_eq!(reader.read_pascal_string().unwrap(), "\u{E9}!");
+    // The length runs past the end of the data
+    assert!(reader.read_pascal_string().is_err());
+}
+
 #[test]
 fn test_font_reader_skip() {
     let data = vec![0x00, 0x11, 0x22, 0x33, 0x44];
@@ -188,6 +224,30 @@ fn test_font_writer_fixed() {
     assert_eq!(&data[4..8], &[0x00, 0x01, 0x80, 0x00]);
 }
 
+#[test]
+fn test_font_writer_f2dot14_round_trip() {
+    let values = [-2.0, -0.5, 0.0, 0.5, 1.0, 1.9999];
+    let mut writer = FontWriter::new();
+    for &value in &values {
+        writer.write_f2dot14(value);
+    }
+
+    let data = writer.into_inner();
+    let mut reader = FontReader::from_slice(&data);
+    for &value in &values {
+        let read = reader.read_f2dot14().unwrap();
+        assert!((read - value).abs() <= 1.0 / 16384.0, "{} read back as {}", value, read);
+    }
+
+    // Rounds to the nearest quantum rather than truncating toward zero
+    let mut writer = FontWriter::new();
+    writer.write_f2dot14(-0.99999);
+    writer.write_f2dot14(3.0);
+    let data = writer.into_inner();
+    assert_eq!(&data[0..2], &(-16384i16).to_be_bytes());
+    assert_eq!(&data[2..4], &i16::MAX.to_be_bytes());
+}
+
 #[test]
 fn test_font_writer_bytes() {
     let mut writer = FontWriter::new();
@@ -218,6 +278,25 @@ fn test_calculate_checksum_empty() {
     assert_eq!(checksum, 0);
 }
 
+#[test]
+fn test_checksum_writer_matches_calculate_checksum() {
+    let data: Vec<u8> = (0..37u32).map(|i| (i * 73 + 11) as u8).collect();
+
+    for len in 0..data.len() {
+        // Feed the bytes in uneven chunks so words straddle writes
+        let mut writer = ChecksumWriter::new();
+        for chunk in data[..len].chunks(3) {
+            writer.write_bytes(chunk);
+        }
+        assert_eq!(writer.len(), len);
+        assert_eq!(writer.checksum(), calculate_checksum(&data[..len]), "length {}", len);
+    }
+
+    let mut writer = ChecksumWriter::new();
+    writer.write_all(&[0x12, 0x34, 0x56]).unwrap();
+    assert_eq!(writer.checksum(), 0x12345600);
+}
+
 #[test]
 fn test_round_trip_u8() {
     let mut writer = FontWriter::new();
@@ -262,3 +341,123 @@ fn test_round_trip_fixed() {
     let value = reader.read_fixed().unwrap();
     assert!((value - 1.5).abs() < 0.0001);
 }
+
+#[test]
+fn test_round_trip_fixed_rounds_to_nearest() {
+    let quantum = 1.0 / 65536.0;
+    let cases = [
+        (2.5, 0x0002_8000),
+        (-1.5, -0x0001_8000),
+        (1.0 + 0.7 * quantum, 0x0001_0001),
+        (-(1.0 + 0.7 * quantum), -0x0001_0001),
+        (1.0 + 0.3 * quantum, 0x0001_0000),
+        (-0.3 * quantum, 0),
+        (40000.0, i32::MAX),
+    ];
+
+    let mut writer = FontWriter::new();
+    for &(value, _) in &cases {
+        writer.write_fixed(value);
+    }
+
+    let data = writer.into_inner();
+    let mut reader = FontReader::from_slice(&data);
+    for &(value, expected) in &cases {
+        assert_eq!(reader.read_i32().unwrap(), expected, "writing {}", value);
+    }
+}
+
+#[test]
+fn test_font_reader_peek_does_not_advance() {
+    let data = vec![0x12, 0x34, 0x56, 0x78, 0x9A];
+    let mut reader = FontReader::from_slice(&data);
+
+    assert_eq!(reader.peek_u8().unwrap(), 0x12);
+    assert_eq!(reader.peek_u16().unwrap(), 0x1234);
+    assert_eq!(reader.peek_u32().unwrap(), 0x12345678);
+    assert_eq!(reader.position(), 0);
+
+    reader.skip(1).unwrap();
+    assert_eq!(reader.peek_u32().unwrap(), 0x3456789A);
+    assert_eq!(reader.position(), 1);
+}
+
+#[test]
+fn test_font_reader_peek_matches_read() {
+    let data = vec![0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE];
+    let mut reader = FontReader::from_slice(&data);
+
+    let peeked = reader.peek_u8().unwrap();
+    assert_eq!(reader.read_u8().unwrap(), peeked);
+    let peeked = reader.peek_u16().unwrap();
+    assert_eq!(reader.read_u16().unwrap(), peeked);
+    let peeked = reader.peek_u32().unwrap();
+    assert_eq!(reader.read_u32().unwrap(), peeked);
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn test_font_reader_peek_eof() {
+    let data = vec![0x12, 0x34, 0x56];
+    let mut reader = FontReader::from_slice(&data);
+
+    assert!(reader.peek_u32().is_err());
+    reader.skip(2).unwrap();
+    let peek_err = reader.peek_u16().unwrap_err();
+    let read_err = reader.read_u16().unwrap_err();
+    assert!(matches!(peek_err, TtfError::UnexpectedEof { offset: 2, .. }));
+    assert_eq!(peek_err.to_string(), read_err.to_string());
+    assert_eq!(reader.position(), 2);
+    assert_eq!(reader.peek_u8().unwrap(), 0x56);
+}
+
+#[test]
+fn test_font_reader_eof_reports_offset() {
+    let data = vec![0x00, 0x01, 0x02, 0x03, 0x04];
+    let mut reader = FontReader::from_slice(&data);
+    reader.read_u16().unwrap();
+
+    let err = reader.read_u32().unwrap_err();
+    assert!(matches!(err, TtfError::UnexpectedEof { offset: 2, context: "reading u32" }));
+    assert_eq!(err.to_string(), "Unexpected EOF at offset 2 reading u32");
+    assert_eq!(reader.position(), 2);
+
+    let err = reader.read_u16_array(2).unwrap_err();
+    assert!(matches!(err, TtfError::UnexpectedEof { offset: 2, .. }));
+    assert!(matches!(reader.set_position(6), Err(TtfError::UnexpectedEof { offset: 6, .. })));
+}
+
+#[test]
+fn test_font_writer_overwrite_u32_at() {
+    let mut writer = FontWriter::new();
+    writer.write_bytes(&[0xAA; 12]);
+
+    writer.overwrite_u32_at(4, 0x12345678).unwrap();
+
+    let data = writer.into_inner();
+    assert_eq!(data.len(), 12);
+    assert_eq!(&data[..4], &[0xAA; 4]);
+    assert_eq!(&data[4..8], &[0x12, 0x34, 0x56, 0x78]);
+    assert_eq!(&data[8..], &[0xAA; 4]);
+}
+
+#[test]
+fn test_font_writer_overwrite_bytes_at() {
+    let mut writer = FontWriter::new();
+    writer.write_u32(0);
+    writer.write_u16(0xFFFF);
+
+    writer.overwrite_bytes_at(3, &[1, 2, 3]).unwrap();
+    assert_eq!(writer.as_slice(), &[0, 0, 0, 1, 2, 3]);
+}
+
+#[test]
+fn test_font_writer_overwrite_out_of_bounds() {
+    let mut writer = FontWriter::new();
+    writer.write_u32(0x01020304);
+
+    assert!(writer.overwrite_u32_at(1, 0xFFFFFFFF).is_err());
+    assert!(writer.overwrite_bytes_at(5, &[]).is_err());
+    assert!(writer.overwrite_bytes_at(usize::MAX, &[1]).is_err());
+    assert_eq!(writer.as_slice(), &[1, 2, 3, 4]);
+}
@@ -0,0 +1,390 @@
+// Tests for font subsetting
+
+mod common;
+
+use common::*;
+use ttf_rs::{CmapSubtable, Font, FontWriter};
+
+fn hello_font() -> Font {
+    // H and e map to simple glyphs, l to the composite Aacute, o shares e's glyph
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"cmap", cmap_table(&[
+        (u16::from(b'H'), GID_B),
+        (u16::from(b'e'), GID_O),
+        (u16::from(b'l'), GID_AACUTE),
+        (u16::from(b'o'), GID_O),
+    ]));
+    Font::from_data(build_font(&tables)).unwrap()
+}
+
+#[test]
+fn test_with_text_collects_glyphs_and_components() {
+    let mut subset = hello_font().subset();
+    subset.with_text("Hello").unwrap();
+
+    // Aacute pulls in its A and acute components
+    assert_eq!(
+        subset.glyph_ids(),
+        vec![GID_A as u32, GID_B as u32, GID_O as u32, GID_AACUTE as u32, GID_ACUTE as u32]
+    );
+
+    // .notdef is kept ahead of the text's glyphs
+    let subset_font = subset.build().unwrap();
+    assert_eq!(subset_font.num_glyphs().unwrap(), 6);
+    let notdef = subset_font.glyph(0).unwrap();
+    assert_eq!((notdef.x_min, notdef.y_min, notdef.x_max, notdef.y_max), (0, 0, 500, 700));
+    assert_eq!(subset_font.char_to_glyph('H').unwrap(), 2);
+}
+
+#[test]
+fn test_with_text_subset_keeps_distinct_letters() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"cmap", cmap_table(&[
+        (u16::from(b'h'), GID_B),
+        (u16::from(b'e'), GID_C),
+        (u16::from(b'l'), GID_D),
+        (u16::from(b'o'), GID_O),
+    ]));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    // Repeated letters and the trailing line break add nothing
+    let mut subset = font.subset();
+    subset.with_text("hello\r\n").unwrap();
+    assert_eq!(
+        subset.glyph_ids(),
+        vec![GID_B as u32, GID_O as u32, GID_C as u32, GID_D as u32]
+    );
+
+    let subsetted = subset.build().unwrap();
+    assert_eq!(subsetted.maxp_table().unwrap().num_glyphs, 5);
+    for c in ['h', 'e', 'l', 'o'] {
+        assert_ne!(subsetted.char_to_glyph(c).unwrap(), 0);
+    }
+}
+
+#[test]
+fn test_with_text_skips_unmapped_chars() {
+    let mut subset = hello_font().subset();
+    subset.with_text("zHxz").unwrap();
+
+    assert_eq!(subset.glyph_ids(), vec![GID_B as u32]);
+    assert_eq!(subset.unmapped_chars(), vec!['x', 'z']);
+    assert_eq!(subset.build().unwrap().num_glyphs().unwrap(), 2);
+}
+
+#[test]
+fn test_with_text_ligature_closure() {
+    // "f" + "i" form a ligature, and that ligature + "i" forms another
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"cmap", cmap_table(&[
+        (u16::from(b'f'), GID_C),
+        (u16::from(b'i'), GID_D),
+        (u16::from(b'o'), GID_O),
+    ]));
+    tables.push((*b"GSUB", layout_table_with_features(
+        &[(*b"liga", &[0])],
+        &[(4, ligature_subst(&[(&[GID_C, GID_D], GID_E), (&[GID_E, GID_D], GID_F), (&[GID_O, GID_A], GID_B)]))],
+    )));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    // Off by default
+    let mut subset = font.clone().subset();
+    subset.with_text("fi").unwrap();
+    assert_eq!(subset.glyph_ids(), vec![GID_C as u32, GID_D as u32]);
+
+    let mut subset = font.subset();
+    subset.ligature_closure(true).with_text("fio").unwrap();
+    // O + A isn't formed since A isn't in the text
+    assert_eq!(
+        subset.glyph_ids(),
+        vec![GID_O as u32, GID_C as u32, GID_D as u32, GID_E as u32, GID_F as u32]
+    );
+}
+
+#[test]
+fn test_subset_drops_digital_signature() {
+    let font = Font::from_data(signed_test_font()).unwrap();
+    assert!(font.has_digital_signature());
+
+    let mut subset = font.clone().subset();
+    subset.with_glyphs(&[GID_A as u32, GID_B as u32]);
+    let subset_font = subset.build().unwrap();
+    assert!(!subset_font.has_digital_signature());
+
+    let reloaded = Font::from_data(subset_font.to_bytes().unwrap()).unwrap();
+    assert!(reloaded.get_table_record(b"DSIG").is_none());
+
+    let mut subset = font.subset();
+    subset.with_glyphs(&[GID_A as u32]).retain_digital_signature();
+    assert!(subset.build().unwrap().has_digital_signature());
+}
+
+#[test]
+fn test_subset_out_of_range_glyph_errors() {
+    let mut subset = Font::from_data(test_font()).unwrap().subset();
+    subset.with_glyphs(&[GID_A as u32, 42]);
+
+    assert!(matches!(subset.build(), Err(ttf_rs::TtfError::InvalidGlyphIndex(42))));
+}
+
+#[test]
+fn test_subset_maps_notdef_first() {
+    let font = Font::from_data(test_font()).unwrap();
+    let mut subset = font.clone().subset();
+    subset.with_glyphs(&[GID_AACUTE as u32, GID_E as u32]);
+    let subset_font = subset.build().unwrap();
+
+    assert_eq!(subset_font.num_glyphs().unwrap(), 3);
+    for (new_id, old_id) in [(0, GID_NOTDEF), (1, GID_AACUTE), (2, GID_E)] {
+        let new_glyph = subset_font.glyph(new_id).unwrap();
+        let old_glyph = font.glyph(old_id).unwrap();
+        assert_eq!(
+            (new_glyph.x_min, new_glyph.y_min, new_glyph.x_max, new_glyph.y_max),
+            (old_glyph.x_min, old_glyph.y_min, old_glyph.x_max, old_glyph.y_max),
+            "glyph {} should be old glyph {}", new_id, old_id
+        );
+    }
+
+    let hmtx = subset_font.hmtx_table().unwrap();
+    assert_eq!(hmtx.get_advance_width(2), font.hmtx_table().unwrap().get_advance_width(GID_E));
+}
+
+#[test]
+fn test_subset_without_notdef() {
+    let font = Font::from_data(test_font()).unwrap();
+    let mut subset = font.clone().subset();
+    subset.with_glyphs(&[GID_AACUTE as u32, GID_E as u32]).include_notdef(false);
+    let subset_font = subset.build().unwrap();
+
+    assert_eq!(subset_font.num_glyphs().unwrap(), 2);
+    assert_eq!(subset_font.glyph(0).unwrap().y_max, font.glyph(GID_AACUTE).unwrap().y_max);
+    assert_eq!(subset_font.glyph(1).unwrap().x_max, font.glyph(GID_E).unwrap().x_max);
+}
+
+#[test]
+fn test_with_unicode_ranges() {
+    // A-Z cycle through glyphs 1-9; 'a' alone maps to F
+    let mut mappings: Vec<(u16, u16)> = (b'A'..=b'Z')
+        .map(|c| (u16::from(c), 1 + u16::from(c - b'A') % 9))
+        .collect();
+    mappings.push((u16::from(b'a'), GID_F));
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"cmap", cmap_table(&mappings));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let mut subset = font.subset();
+    subset.with_unicode_ranges(&[0x41..=0x5A]).unwrap();
+    assert!(!subset.glyph_ids().contains(&(GID_F as u32)));
+    let subset_font = subset.build().unwrap();
+
+    for c in 'A'..='Z' {
+        assert!(subset_font.char_to_glyph(c).is_ok(), "{} should be mapped", c);
+    }
+    assert!(subset_font.char_to_glyph('a').is_err());
+}
+
+/// cmap with a (3,1) Format 4 subtable for `bmp` and a (3,10) Format 12
+/// subtable with one group per mapping of `bmp` and `full`
+fn cmap_with_format12(bmp: &[(u16, u16)], full: &[(u32, u16)]) -> Vec<u8> {
+    let mut groups: Vec<(u32, u16)> = bmp.iter().map(|&(code, glyph)| (code as u32, glyph)).collect();
+    groups.extend_from_slice(full);
+    groups.sort();
+
+    let format4 = cmap_format4(bmp);
+    let mut writer = FontWriter::new();
+    writer.write_u16(0); // version
+    writer.write_u16(2); // num tables
+    writer.write_u16(3);
+    writer.write_u16(1);
+    writer.write_u32(20);
+    writer.write_u16(3);
+    writer.write_u16(10);
+    writer.write_u32(20 + format4.len() as u32);
+    writer.write_bytes(&format4);
+
+    writer.write_u16(12); // format
+    writer.write_u16(0); // reserved
+    writer.write_u32(16 + groups.len() as u32 * 12);
+    writer.write_u32(0); // language
+    writer.write_u32(groups.len() as u32);
+    for (code, glyph) in groups {
+        writer.write_u32(code);
+        writer.write_u32(code);
+        writer.write_u32(glyph as u32);
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn test_subset_keeps_supplementary_plane_mappings() {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"cmap", cmap_with_format12(
+        &[(u16::from(b'A'), GID_A), (u16::from(b'B'), GID_B)],
+        &[(0x1F600, GID_O)],
+    ));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+    assert_eq!(font.char_to_glyph('\u{1F600}').unwrap(), GID_O as u32);
+
+    let mut subset = font.subset();
+    subset.with_text("A\u{1F600}").unwrap();
+    let reloaded = Font::from_data(subset.build().unwrap().to_bytes().unwrap()).unwrap();
+
+    // .notdef, A and O in old glyph order
+    assert_eq!(reloaded.char_to_glyph('A').unwrap(), 1);
+    assert_eq!(reloaded.char_to_glyph('\u{1F600}').unwrap(), 2);
+    assert!(reloaded.char_to_glyph('B').is_err());
+
+    let cmap = reloaded.cmap_table().unwrap();
+    let records: Vec<_> = cmap.encoding_records.iter().map(|r| (r.platform_id, r.encoding_id)).collect();
+    assert_eq!(records, vec![(3, 1), (3, 10)]);
+    assert!(matches!(cmap.subtable_for(3, 1), Some(CmapSubtable::Format4(_))));
+    assert!(matches!(cmap.subtable_for(3, 10), Some(CmapSubtable::Format12(_))));
+    assert_eq!(cmap.map_char_with('A', 3, 1), Some(1));
+    assert_eq!(cmap.map_char_with('\u{1F600}', 3, 1), None);
+}
+
+#[test]
+fn test_subset_bmp_only_writes_format4() {
+    let mut subset = hello_font().subset();
+    subset.with_text("Hel").unwrap();
+    let reloaded = Font::from_data(subset.build().unwrap().to_bytes().unwrap()).unwrap();
+
+    let cmap = reloaded.cmap_table().unwrap();
+    assert_eq!(cmap.subtables.len(), 1);
+    assert!(matches!(cmap.subtable_for(3, 1), Some(CmapSubtable::Format4(_))));
+    for c in ['H', 'e', 'l', 'o'] {
+        assert!(reloaded.char_to_glyph(c).is_ok(), "{} should be mapped", c);
+    }
+}
+
+
+#[test]
+fn test_recompute_maxp_after_subset() {
+    let mut subset = Font::from_data(test_font()).unwrap().subset();
+    subset.with_glyphs(&[GID_A as u32, GID_B as u32]);
+    let subset_font = subset.build().unwrap();
+
+    // The subset still carries the full font's maxima (O has 16 points)
+    assert_eq!(subset_font.maxp_table().unwrap().max_points, Some(16));
+
+    let maxp = subset_font.recompute_maxp().unwrap().maxp_table().unwrap();
+    assert_eq!(maxp.num_glyphs, 3);
+    assert_eq!(maxp.max_points, Some(4));
+    assert_eq!(maxp.max_contours, Some(1));
+    assert_eq!(maxp.max_composite_points, Some(0));
+    assert_eq!(maxp.max_composite_contours, Some(0));
+    assert_eq!(maxp.max_component_elements, Some(0));
+    assert_eq!(maxp.max_component_depth, Some(0));
+
+    // With the composite retained, its flattened outline counts too
+    let mut subset = Font::from_data(test_font()).unwrap().subset();
+    subset.with_text("\u{C1}").unwrap();
+    let maxp = subset.build().unwrap().recompute_maxp().unwrap().maxp_table().unwrap();
+    assert_eq!(maxp.num_glyphs, 4);
+    assert_eq!(maxp.max_composite_points, Some(6));
+    assert_eq!(maxp.max_composite_contours, Some(2));
+    assert_eq!(maxp.max_component_elements, Some(2));
+    assert_eq!(maxp.max_component_depth, Some(1));
+}
+
+#[test]
+fn test_recompute_maxp_counts_loca_glyphs() {
+    // maxp only admits .notdef, A and B, but loca holds all eleven glyphs
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"maxp", maxp_table(3));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    let maxp = font.recompute_maxp().unwrap().maxp_table().unwrap();
+    assert_eq!(maxp.num_glyphs, 11);
+    assert_eq!(maxp.max_points, Some(16));
+    assert_eq!(maxp.max_component_elements, Some(2));
+}
+
+#[test]
+fn test_subset_preserving_glyph_ids() {
+    let font = Font::from_data(test_font()).unwrap();
+    let mut subset = font.clone().subset();
+    subset.with_glyphs(&[GID_NOTDEF as u32, GID_F as u32]).preserve_glyph_ids(true);
+    let subset_font = subset.build().unwrap();
+
+    assert_eq!(subset_font.num_glyphs().unwrap(), font.num_glyphs().unwrap());
+
+    let kept = subset_font.glyph(GID_F).unwrap();
+    let original = font.glyph(GID_F).unwrap();
+    assert_eq!(
+        (kept.x_min, kept.y_min, kept.x_max, kept.y_max),
+        (original.x_min, original.y_min, original.x_max, original.y_max)
+    );
+
+    // Dropped glyphs keep their slot but have no outline
+    let loca = subset_font.loca_table().unwrap();
+    for gid in [GID_A, GID_B, GID_AACUTE, GID_E] {
+        assert_eq!(loca.get_offset(gid as usize).unwrap(), loca.get_offset(gid as usize + 1).unwrap());
+    }
+    assert_eq!(subset_font.char_to_glyph('F').unwrap(), GID_F as u32);
+    assert_eq!(subset_font.char_to_glyph('A').unwrap_or(0), 0);
+}
+
+/// The test font with a single long metric, so hmtx stores only left side
+/// bearings for every glyph after .notdef
+fn monospace_font() -> Font {
+    let mut tables = test_font_tables();
+    let mut hmtx = hmtx_table(&[(500, 0)]);
+    for _ in 1..=GID_F {
+        hmtx.extend_from_slice(&0i16.to_be_bytes());
+    }
+    replace_table(&mut tables, b"hhea", hhea_table(1));
+    replace_table(&mut tables, b"hmtx", hmtx);
+    Font::from_data(build_font(&tables)).unwrap()
+}
+
+#[test]
+fn test_subset_preserving_glyph_ids_with_compressed_hmtx() {
+    let font = monospace_font();
+    let mut subset = font.clone().subset();
+    subset.with_glyphs(&[GID_NOTDEF as u32, GID_F as u32]).preserve_glyph_ids(true);
+    let subset_font = Font::from_data(subset.build().unwrap().to_bytes().unwrap()).unwrap();
+
+    // The full-metric hmtx must not spill into the glyf and loca tables
+    let kept = subset_font.glyph(GID_F).unwrap();
+    let original = font.glyph(GID_F).unwrap();
+    assert_eq!(
+        (kept.x_min, kept.y_min, kept.x_max, kept.y_max),
+        (original.x_min, original.y_min, original.x_max, original.y_max)
+    );
+    assert!(subset_font.loca_table().unwrap().validate().is_ok());
+
+    let hhea = subset_font.hhea_table().unwrap();
+    assert_eq!(hhea.number_of_h_metrics, GID_F + 1);
+    let hmtx = subset_font.hmtx_table().unwrap();
+    assert_eq!(hmtx.h_metrics.len(), GID_F as usize + 1);
+    assert!(hmtx.h_metrics.iter().all(|metric| metric.advance_width == 500));
+}
+
+#[test]
+fn test_subset_monospace_font() {
+    let mut subset = monospace_font().subset();
+    subset.with_glyphs(&(1..=GID_F as u32).collect::<Vec<_>>());
+    let subset_font = Font::from_data(subset.build().unwrap().to_bytes().unwrap()).unwrap();
+
+    assert_eq!(subset_font.num_glyphs().unwrap(), GID_F + 1);
+    assert_eq!(subset_font.hhea_table().unwrap().number_of_h_metrics, GID_F + 1);
+    assert_eq!(subset_font.advance_for_text("ABF", false).unwrap(), 1500);
+    for gid in 0..=GID_F {
+        subset_font.glyph(gid).unwrap();
+    }
+}
+
+#[test]
+fn test_subset_output_is_reproducible() {
+    let subset_bytes = || {
+        let mut subset = Font::from_data(test_font()).unwrap().subset();
+        subset.with_text("\u{C1}FOB").unwrap();
+        subset.build().unwrap().to_bytes().unwrap()
+    };
+
+    let first = subset_bytes();
+    for _ in 0..8 {
+        assert_eq!(subset_bytes(), first);
+    }
+}
@@ -0,0 +1,75 @@
+// Tests for SVG path export
+
+mod common;
+
+use common::*;
+use ttf_rs::{Font, PathCommand, Point};
+
+#[test]
+fn test_glyph_svg_path_two_contours() {
+    let font = Font::from_data(test_font()).unwrap();
+    let path = font.glyph_svg_path(GID_O, false).unwrap();
+
+    assert!(path.starts_with('M'));
+    assert_eq!(path.matches('Z').count(), 2);
+    assert_eq!(path.matches('M').count(), 2);
+    assert_eq!(path.matches('Q').count(), 8);
+    assert!(!path.contains('L'));
+    assert!(path.starts_with("M300 0 Q0 0 0 350 Q0 700 300 700"));
+}
+
+#[test]
+fn test_glyph_svg_path_lines_and_flip() {
+    let font = Font::from_data(test_font()).unwrap();
+
+    assert_eq!(font.glyph_svg_path(GID_B, false).unwrap(), "M100 0 L100 700 L400 700 L400 0 Z");
+    assert_eq!(font.glyph_svg_path(GID_B, true).unwrap(), "M100 0 L100 -700 L400 -700 L400 0 Z");
+}
+
+#[test]
+fn test_glyph_svg_path_implied_points() {
+    // A contour made only of off-curve points starts at an implied midpoint
+    let mut glyphs = test_glyphs();
+    glyphs[GID_C as usize] = simple_glyph(&[&[
+        (0, 0, false), (100, 0, false), (100, 100, false), (0, 100, false),
+    ]]);
+    let mut tables = test_font_tables();
+    let (glyf, loca) = glyf_and_loca(&glyphs);
+    replace_table(&mut tables, b"glyf", glyf);
+    replace_table(&mut tables, b"loca", loca);
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    assert_eq!(
+        font.glyph_svg_path(GID_C, false).unwrap(),
+        "M0 50 Q0 0 50 0 Q100 0 100 50 Q100 100 50 100 Q0 100 0 50 Z"
+    );
+}
+
+#[test]
+fn test_glyph_svg_path_composite_and_empty() {
+    let font = Font::from_data(test_font()).unwrap();
+
+    // Aacute resolves to the contours of A and the acute
+    let path = font.glyph_svg_path(GID_AACUTE, false).unwrap();
+    assert_eq!(path.matches('Z').count(), 2);
+    assert!(path.contains("M200 750"));
+
+    assert_eq!(font.glyph_svg_path(GID_SPACE, false).unwrap(), "");
+    assert!(font.glyph_svg_path(100, false).is_err());
+}
+
+#[test]
+fn test_glyph_path_two_contours() {
+    let font = Font::from_data(test_font()).unwrap();
+    let path = font.glyph_path(GID_O).unwrap();
+
+    let count = |wanted: fn(&PathCommand) -> bool| path.iter().filter(|c| wanted(c)).count();
+    assert_eq!(count(|c| matches!(c, PathCommand::MoveTo(_))), 2);
+    assert_eq!(count(|c| matches!(c, PathCommand::Close)), 2);
+    assert_eq!(count(|c| matches!(c, PathCommand::QuadTo(..))), 8);
+    assert_eq!(path[0], PathCommand::MoveTo(Point::new(300.0, 0.0)));
+    assert_eq!(path[1], PathCommand::QuadTo(Point::new(0.0, 0.0), Point::new(0.0, 350.0)));
+    assert_eq!(path.last(), Some(&PathCommand::Close));
+
+    assert!(font.glyph_path(GID_SPACE).unwrap().is_empty());
+}
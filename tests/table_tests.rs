@@ -1,4 +1,10 @@
-use ttf_rs::{FontReader, FontWriter};
+mod common;
+
+use std::time::{Duration, UNIX_EPOCH};
+use ttf_rs::{
+    Font, FontReader, FontWriter, GlyphNames, HeadTable, HheaTable, NameTable, Os2Table, PostTable, TtfError, TtfTable,
+    TtfTableWrite,
+};
 
 #[test]
 fn test_head_table_parse() {
@@ -113,6 +119,21 @@ fn test_hhea_table_parse() {
     assert_eq!(line_gap, 100);
 }
 
+#[test]
+fn test_hhea_table_is_36_bytes() {
+    // Four reserved fields sit between caretOffset and metricDataFormat
+    let data = common::hhea_table(7);
+    assert_eq!(data.len(), 36);
+
+    let hhea = HheaTable::from_reader(&mut FontReader::from_slice(&data), data.len() as u32).unwrap();
+    assert_eq!(hhea.metric_data_format, 0);
+    assert_eq!(hhea.number_of_h_metrics, 7);
+
+    let mut writer = FontWriter::new();
+    hhea.write(&mut writer).unwrap();
+    assert_eq!(writer.into_inner(), data);
+}
+
 #[test]
 fn test_cmap_format_0() {
     let mut writer = FontWriter::new();
@@ -208,6 +229,113 @@ fn test_name_record_parse() {
     assert_eq!(offset, 0);
 }
 
+#[test]
+fn test_name_table_write_sorts_records() {
+    let data = common::name_table(&[(3, 1, 0x0409, 4, "Test Regular")]);
+    let mut name = NameTable::from_reader(&mut FontReader::from_slice(&data), data.len() as u32).unwrap();
+    name.set_name("Test", 3, 1, 0x0409, 1);
+    name.set_name("Test Mac", 1, 0, 0, 4);
+    name.set_name("Test French", 3, 1, 0x040C, 4);
+    name.set_name("Regular", 3, 1, 0x0409, 2);
+
+    let mut writer = FontWriter::new();
+    name.write(&mut writer).unwrap();
+    let data = writer.into_inner();
+
+    let mut reader = FontReader::from_slice(&data);
+    reader.skip(2).unwrap();
+    let count = reader.read_u16().unwrap();
+    reader.skip(2).unwrap();
+    let mut keys = Vec::new();
+    for _ in 0..count {
+        keys.push((
+            reader.read_u16().unwrap(),
+            reader.read_u16().unwrap(),
+            reader.read_u16().unwrap(),
+            reader.read_u16().unwrap(),
+        ));
+        reader.skip(4).unwrap();
+    }
+    assert_eq!(
+        keys,
+        vec![(1, 0, 0, 4), (3, 1, 0x0409, 1), (3, 1, 0x0409, 2), (3, 1, 0x0409, 4), (3, 1, 0x040C, 4)]
+    );
+
+    // Strings still match their records after sorting
+    let parsed = NameTable::from_reader(&mut FontReader::from_slice(&data), data.len() as u32).unwrap();
+    assert_eq!(parsed.get_string(1).as_deref(), Some("Test"));
+    assert_eq!(parsed.get_string(2).as_deref(), Some("Regular"));
+    assert_eq!(parsed.get_string(4).as_deref(), Some("Test Regular"));
+    let french = parsed.name_records.iter().find(|r| r.language_id == 0x040C).unwrap();
+    assert_eq!(parsed.get_record_string(french).as_deref(), Some("Test French"));
+}
+
+#[test]
+fn test_name_table_format1_round_trip() {
+    let utf16 = |text: &str| -> Vec<u8> { text.encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect() };
+    let family = utf16("Test");
+    let tag = utf16("de-CH");
+
+    let mut writer = FontWriter::new();
+    writer.write_u16(1); // format
+    writer.write_u16(1); // count
+    writer.write_u16(6 + 12 + 2 + 4); // storage offset
+    for value in [3, 1, 0x8000, 1, family.len() as u16, 0] {
+        writer.write_u16(value);
+    }
+    writer.write_u16(1); // langTagCount
+    writer.write_u16(tag.len() as u16);
+    writer.write_u16(family.len() as u16);
+    writer.write_bytes(&family);
+    writer.write_bytes(&tag);
+    let data = writer.into_inner();
+
+    let name = NameTable::from_reader(&mut FontReader::from_slice(&data), data.len() as u32).unwrap();
+    assert_eq!(name.lang_tags, vec!["de-CH".to_string()]);
+    assert_eq!(name.language_tag(0x8000), Some("de-CH"));
+    assert_eq!(name.language_tag(0x8001), None);
+    assert_eq!(name.language_tag(0x0409), None);
+    assert_eq!(name.get_string(1).as_deref(), Some("Test"));
+
+    let mut writer = FontWriter::new();
+    name.write(&mut writer).unwrap();
+    assert_eq!(writer.into_inner(), data);
+}
+
+#[test]
+fn test_name_table_skips_records_outside_storage() {
+    let utf16 = |text: &str| -> Vec<u8> { text.encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect() };
+    let family = utf16("Test");
+    let tag = utf16("de-CH");
+
+    let mut writer = FontWriter::new();
+    writer.write_u16(1); // format
+    writer.write_u16(2); // count
+    writer.write_u16(6 + 24 + 2 + 8); // storage offset
+    for value in [3, 1, 0x8001, 1, family.len() as u16, 0] {
+        writer.write_u16(value);
+    }
+    // Subfamily name running past the end of the table
+    for value in [3, 1, 0x8001, 2, 100, family.len() as u16] {
+        writer.write_u16(value);
+    }
+    writer.write_u16(2); // langTagCount
+    writer.write_u16(100); // tag running past the end of the table
+    writer.write_u16(0);
+    writer.write_u16(tag.len() as u16);
+    writer.write_u16(family.len() as u16);
+    writer.write_bytes(&family);
+    writer.write_bytes(&tag);
+    let data = writer.into_inner();
+
+    let name = NameTable::from_reader(&mut FontReader::from_slice(&data), data.len() as u32).unwrap();
+    assert_eq!(name.name_records.len(), 1);
+    assert_eq!(name.get_string(1).as_deref(), Some("Test"));
+    assert_eq!(name.get_string(2), None);
+    assert_eq!(name.language_tag(0x8000), None);
+    assert_eq!(name.language_tag(0x8001), Some("de-CH"));
+}
+
 #[test]
 fn test_hmtx_long_metric() {
     let mut writer = FontWriter::new();
@@ -298,3 +426,234 @@ fn test_os2_table_parse() {
     let us_width_class = reader.read_u16().unwrap();
     assert_eq!(us_width_class, 5);
 }
+
+/// Parse an OS/2 table and write it back
+fn os2_round_trip(data: &[u8]) -> (Os2Table, Vec<u8>) {
+    let os2 = Os2Table::from_reader(&mut FontReader::from_slice(data), data.len() as u32).unwrap();
+    let mut writer = FontWriter::new();
+    os2.write(&mut writer).unwrap();
+    (os2, writer.into_inner())
+}
+
+#[test]
+fn test_os2_version_round_trips() {
+    // Version 0 ends after usWinDescent
+    let mut v0 = common::os2_table()[..78].to_vec();
+    v0[0..2].copy_from_slice(&0u16.to_be_bytes());
+    let (os2, written) = os2_round_trip(&v0);
+    assert_eq!((os2.ul_code_page_range1, os2.sx_height), (0, 0));
+    assert_eq!(written, v0);
+
+    let v4 = common::os2_table();
+    assert_eq!(v4.len(), 96);
+    let (os2, written) = os2_round_trip(&v4);
+    assert_eq!((os2.ul_code_page_range1, os2.sx_height, os2.us_max_context), (1, 500, 1));
+    assert_eq!(os2.us_lower_optical_point_size, 0);
+    assert_eq!(written, v4);
+
+    // Version 5 adds the optical size range, here 9-72pt in TWIPs
+    let mut v5 = common::os2_table();
+    v5[0..2].copy_from_slice(&5u16.to_be_bytes());
+    v5.extend_from_slice(&180u16.to_be_bytes());
+    v5.extend_from_slice(&1440u16.to_be_bytes());
+    let (os2, written) = os2_round_trip(&v5);
+    assert_eq!((os2.us_lower_optical_point_size, os2.us_upper_optical_point_size), (180, 1440));
+    assert_eq!(written, v5);
+}
+
+/// Build a post table header of the given format, followed by `names`
+fn post_table_with_names(format: f32, names: &[u8]) -> PostTable {
+    let mut writer = FontWriter::new();
+    writer.write_fixed(format);
+    writer.write_fixed(0.0);
+    writer.write_i16(-100);
+    writer.write_i16(50);
+    for _ in 0..5 {
+        writer.write_u32(0);
+    }
+    writer.write_bytes(names);
+
+    let data = writer.into_inner();
+    let mut reader = FontReader::from_slice(&data);
+    PostTable::from_reader(&mut reader, data.len() as u32).unwrap()
+}
+
+#[test]
+fn test_post_format1_standard_names() {
+    let post = post_table_with_names(1.0, &[]);
+
+    assert_eq!(post.glyph_names, GlyphNames::Format1Standard);
+    assert_eq!(post.glyph_name(0), Some(".notdef"));
+    assert_eq!(post.glyph_name(3), Some("space"));
+    assert_eq!(post.glyph_name(257), Some("dcroat"));
+    assert_eq!(post.glyph_name(258), None);
+}
+
+#[test]
+fn test_post_format2_custom_names() {
+    let mut writer = FontWriter::new();
+    writer.write_u16(5); // numGlyphs
+    for index in [0, 36, 258, 3, 259] {
+        writer.write_u16(index);
+    }
+    for name in ["uni00C1", "A.alt"] {
+        writer.write_u8(name.len() as u8);
+        writer.write_bytes(name.as_bytes());
+    }
+    let post = post_table_with_names(2.0, &writer.into_inner());
+
+    assert_eq!(post.glyph_name(0), Some(".notdef"));
+    assert_eq!(post.glyph_name(1), Some("A"));
+    assert_eq!(post.glyph_name(2), Some("uni00C1"));
+    assert_eq!(post.glyph_name(3), Some("space"));
+    assert_eq!(post.glyph_name(4), Some("A.alt"));
+    assert_eq!(post.glyph_name(5), None);
+}
+
+#[test]
+fn test_post_format2_invalid_name_index() {
+    let mut writer = FontWriter::new();
+    writer.write_u16(1);
+    writer.write_u16(258); // no custom names follow
+
+    let data = {
+        let mut header = FontWriter::new();
+        header.write_fixed(2.0);
+        header.write_bytes(&[0; 28]);
+        header.write_bytes(&writer.into_inner());
+        header.into_inner()
+    };
+    let mut reader = FontReader::from_slice(&data);
+    assert!(PostTable::from_reader(&mut reader, data.len() as u32).is_err());
+}
+
+#[test]
+fn test_post_format3_no_names() {
+    let post = post_table_with_names(3.0, &[]);
+
+    assert_eq!(post.glyph_names, GlyphNames::Format3None);
+    assert_eq!(post.glyph_name(3), None);
+}
+
+#[test]
+fn test_head_dates() {
+    let mut head = Font::from_data(common::test_font()).unwrap().head_table().unwrap();
+    // 2015-01-01 00:00:00 UTC
+    head.created = 3_502_915_200;
+    // The head epoch itself, 1904-01-01
+    head.modified = 0;
+
+    assert_eq!(head.created_datetime(), UNIX_EPOCH + Duration::from_secs(1_420_070_400));
+    assert_eq!(head.modified_datetime(), UNIX_EPOCH - Duration::from_secs(2_082_844_800));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_head_dates_utc() {
+    let mut head = Font::from_data(common::test_font()).unwrap().head_table().unwrap();
+    head.created = 3_502_915_200;
+    head.modified = 0;
+
+    assert_eq!(head.created_utc().unwrap().timestamp(), 1_420_070_400);
+    assert_eq!(head.modified_utc().unwrap().timestamp(), -2_082_844_800);
+}
+
+
+/// A format 3 post table with the given raw 16.16 italic angle
+fn post_table_with_angle(raw_angle: u32) -> Vec<u8> {
+    let mut data = common::post_table();
+    data[4..8].copy_from_slice(&raw_angle.to_be_bytes());
+    data
+}
+
+#[test]
+fn test_post_italic_angle_fixed_point() {
+    // -12.0 in 16.16 fixed point
+    let data = post_table_with_angle(0xFFF4_0000);
+    let mut reader = FontReader::from_slice(&data);
+    let post = PostTable::from_reader(&mut reader, data.len() as u32).unwrap();
+
+    assert_eq!(post.italic_angle, -12.0);
+    assert_eq!(post.italic_angle_degrees(), -12.0);
+
+    // -11.5 keeps its fraction
+    let data = post_table_with_angle(0xFFF4_8000);
+    let mut reader = FontReader::from_slice(&data);
+    let post = PostTable::from_reader(&mut reader, data.len() as u32).unwrap();
+    assert_eq!(post.italic_angle_degrees(), -11.5);
+}
+
+#[test]
+fn test_validate_warns_on_positive_italic_angle() {
+    let italic_font = |raw_angle: u32| {
+        let mut tables = common::test_font_tables();
+        common::replace_table(&mut tables, b"name", common::name_table(&[
+            (3, 1, 0x409, 1, "Test Family"),
+            (3, 1, 0x409, 2, "Italic"),
+        ]));
+        common::replace_table(&mut tables, b"post", post_table_with_angle(raw_angle));
+        Font::from_data(common::build_font(&tables)).unwrap()
+    };
+    let has_warning = |font: &Font| {
+        font.validate().unwrap().warnings.iter().any(|w| w.message.contains("positive italic angle"))
+    };
+
+    assert!(has_warning(&italic_font(0x000C_0000)));
+    assert!(!has_warning(&italic_font(0xFFF4_0000)));
+
+    // Upright fonts may use any angle
+    let mut tables = common::test_font_tables();
+    common::replace_table(&mut tables, b"post", post_table_with_angle(0x000C_0000));
+    assert!(!has_warning(&Font::from_data(common::build_font(&tables)).unwrap()));
+}
+
+#[test]
+fn test_generic_table_parse_and_set() {
+    let mut font = Font::from_data(common::test_font()).unwrap();
+
+    let mut head: HeadTable = font.parse_table().unwrap();
+    assert_eq!(head.units_per_em, font.head_table().unwrap().units_per_em);
+    head.font_revision = 2.5;
+    head.flags |= 0x8;
+    font.set_table(&head).unwrap();
+
+    let reparsed = Font::from_data(font.to_bytes().unwrap()).unwrap();
+    let head = reparsed.parse_table::<HeadTable>().unwrap();
+    assert_eq!(head.font_revision, 2.5);
+    assert_eq!(head.flags & 0x8, 0x8);
+    assert_eq!(reparsed.num_tables, font.num_tables);
+
+    // Types without a tag, and tables the font lacks, are errors
+    struct Untagged;
+    impl TtfTable for Untagged {
+        fn from_reader(_reader: &mut FontReader, _length: u32) -> ttf_rs::Result<Self> {
+            Ok(Untagged)
+        }
+    }
+    assert!(matches!(font.parse_table::<Untagged>(), Err(TtfError::ParseError(_))));
+    assert!(matches!(font.parse_table::<ttf_rs::KernTable>(), Err(TtfError::MissingTable(_))));
+}
+
+#[test]
+fn test_dump_table_labels_fields() {
+    let mut tables = common::test_font_tables();
+    common::replace_table(&mut tables, b"zzzz", (0u8..20).collect());
+    let font = Font::from_data(common::build_font(&tables)).unwrap();
+
+    let head = font.dump_table(b"head").unwrap();
+    assert!(head.starts_with("head (54 bytes)\n"));
+    assert!(head.contains("units_per_em: 1000 @ offset 18\n"));
+    assert!(head.contains("magic_number: 0x5f0f3cf5 @ offset 12\n"));
+    assert!(head.ends_with("glyph_data_format: 0 @ offset 52\n"));
+
+    // Unknown tables are hex dumped 16 bytes per line
+    let raw = font.dump_table(b"zzzz").unwrap();
+    assert_eq!(
+        raw,
+        "zzzz (20 bytes)\n\
+         00000000: 00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f\n\
+         00000010: 10 11 12 13\n"
+    );
+
+    assert!(matches!(font.dump_table(b"GSUB"), Err(TtfError::MissingTable(_))));
+}
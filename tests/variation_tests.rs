@@ -0,0 +1,201 @@
+// Tests for variable font tables
+
+mod common;
+
+use common::*;
+use ttf_rs::{Font, FontWriter, FontReader, GlyphData, AvarTable, TtfTable, TtfError};
+
+fn weight_font() -> Font {
+    Font::from_data(build_font(&weight_font_tables())).unwrap()
+}
+
+fn weight_font_tables() -> Vec<([u8; 4], Vec<u8>)> {
+    let mut tables = test_font_tables();
+    replace_table(&mut tables, b"fvar", fvar_table(
+        &[(*b"wght", 100.0, 400.0, 900.0)],
+        &[(258, &[400.0]), (259, &[700.0])],
+    ));
+    tables
+}
+
+/// Encode glyph variation data holding one tuple with an embedded peak.
+/// `points` of `None` applies the deltas to all points.
+fn glyph_variation(peak: &[f32], points: Option<&[u16]>, x_deltas: &[i16], y_deltas: &[i16]) -> Vec<u8> {
+    let mut data = FontWriter::new();
+    if let Some(points) = points {
+        data.write_u8(points.len() as u8);
+        data.write_u8(points.len() as u8 - 1); // one run of byte-sized deltas
+        let mut prev = 0;
+        for &point in points {
+            data.write_u8((point - prev) as u8);
+            prev = point;
+        }
+    }
+    for deltas in [x_deltas, y_deltas] {
+        data.write_u8(0x40 | (deltas.len() as u8 - 1)); // one run of word deltas
+        for &delta in deltas {
+            data.write_i16(delta);
+        }
+    }
+    let data = data.into_inner();
+
+    let mut writer = FontWriter::new();
+    writer.write_u16(1); // tuple variation count
+    writer.write_u16(4 + 4 + peak.len() as u16 * 2); // data offset
+    writer.write_u16(data.len() as u16);
+    writer.write_u16(if points.is_some() { 0xA000 } else { 0x8000 }); // embedded peak (+ private points)
+    for &coord in peak {
+        writer.write_f2dot14(coord);
+    }
+    writer.write_bytes(&data);
+    writer.into_inner()
+}
+
+/// Build a gvar table with long offsets and no shared tuples
+fn gvar_table(axis_count: u16, glyph_count: u16, variations: &[(u16, Vec<u8>)]) -> Vec<u8> {
+    let mut array = FontWriter::new();
+    let mut offsets = Vec::new();
+    for glyph_id in 0..glyph_count {
+        offsets.push(array.position() as u32);
+        if let Some((_, data)) = variations.iter().find(|(id, _)| *id == glyph_id) {
+            array.write_bytes(data);
+            array.write_padding(2);
+        }
+    }
+    offsets.push(array.position() as u32);
+
+    let mut writer = FontWriter::new();
+    writer.write_u16(1); // major version
+    writer.write_u16(0); // minor version
+    writer.write_u16(axis_count);
+    writer.write_u16(0); // shared tuple count
+    let header_size = 20 + offsets.len() as u32 * 4;
+    writer.write_u32(header_size); // shared tuples offset
+    writer.write_u16(glyph_count);
+    writer.write_u16(1); // long offsets
+    writer.write_u32(header_size); // glyph variation data array offset
+    for offset in offsets {
+        writer.write_u32(offset);
+    }
+    writer.write_bytes(&array.into_inner());
+    writer.into_inner()
+}
+
+fn simple_points(font: &Font, glyph_id: u16, coords: &[f32]) -> Vec<(i16, i16)> {
+    let glyph = font.glyph_outline_at(glyph_id, coords).unwrap();
+    match glyph.data {
+        GlyphData::Simple(simple) => simple.x_coordinates.into_iter().zip(simple.y_coordinates).collect(),
+        _ => panic!("expected a simple glyph"),
+    }
+}
+
+#[test]
+fn test_fvar_single_axis() {
+    let font = weight_font();
+    assert!(font.is_variable().unwrap());
+
+    let fvar = font.fvar_table().unwrap();
+    assert_eq!(fvar.axes.len(), 1);
+
+    let wght = fvar.axis(b"wght").unwrap();
+    assert_eq!(wght.tag_to_string(), "wght");
+    assert_eq!((wght.min, wght.default, wght.max), (100.0, 400.0, 900.0));
+    assert_eq!(wght.name_id, 256);
+
+    assert_eq!(fvar.instances.len(), 2);
+    assert_eq!(fvar.instances[1].subfamily_name_id, 259);
+    assert_eq!(fvar.instances[1].coordinates, vec![700.0]);
+    assert_eq!(fvar.instances[1].post_script_name_id, Some(301));
+}
+
+#[test]
+fn test_static_font_is_not_variable() {
+    let font = Font::from_data(test_font()).unwrap();
+    assert!(!font.is_variable().unwrap());
+    assert!(matches!(font.fvar_table(), Err(TtfError::MissingTable(_))));
+}
+
+#[test]
+fn test_glyph_outline_at_interpolates_all_points() {
+    // B is the rectangle (100,0) (100,700) (400,700) (400,0); at full weight
+    // the right edge moves 100 units right
+    let mut tables = weight_font_tables();
+    let variation = glyph_variation(&[1.0], None, &[0, 0, 100, 100, 0, 0, 0, 0], &[0; 8]);
+    replace_table(&mut tables, b"gvar", gvar_table(1, 11, &[(GID_B, variation)]));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    // Halfway between default (400) and max (900)
+    assert_eq!(
+        simple_points(&font, GID_B, &[650.0]),
+        vec![(100, 0), (100, 700), (450, 700), (450, 0)]
+    );
+    assert_eq!(font.glyph_outline_at(GID_B, &[650.0]).unwrap().x_max, 450);
+
+    // At the default and below it (the tuple only covers the positive side) nothing moves
+    assert_eq!(simple_points(&font, GID_B, &[400.0])[2], (400, 700));
+    assert_eq!(simple_points(&font, GID_B, &[100.0])[2], (400, 700));
+
+    // Glyphs without variation data are unchanged
+    assert_eq!(simple_points(&font, GID_C, &[900.0])[2], (350, 700));
+}
+
+#[test]
+fn test_glyph_outline_at_infers_untouched_points() {
+    // Only the top-right point of B has an explicit delta; the untouched
+    // points of the contour are interpolated from it
+    let mut tables = weight_font_tables();
+    let variation = glyph_variation(&[1.0], Some(&[1, 2]), &[0, 100], &[0, 0]);
+    replace_table(&mut tables, b"gvar", gvar_table(1, 11, &[(GID_B, variation)]));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    // Point 3 (400,0) lies at the same x as point 2 and beyond the 100..400
+    // range, so it takes point 2's delta; point 0 matches point 1's x
+    assert_eq!(
+        simple_points(&font, GID_B, &[900.0]),
+        vec![(100, 0), (100, 700), (500, 700), (500, 0)]
+    );
+}
+
+/// Build an avar table with one segment map per axis
+fn avar_table(maps: &[&[(f32, f32)]]) -> Vec<u8> {
+    let mut writer = FontWriter::new();
+    writer.write_u16(1); // major version
+    writer.write_u16(0); // minor version
+    writer.write_u16(0); // reserved
+    writer.write_u16(maps.len() as u16);
+    for map in maps {
+        writer.write_u16(map.len() as u16);
+        for &(from, to) in *map {
+            writer.write_f2dot14(from);
+            writer.write_f2dot14(to);
+        }
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn test_avar_map_coords() {
+    let data = avar_table(&[&[(-1.0, -1.0), (0.0, 0.0), (0.5, 0.25), (1.0, 1.0)]]);
+    let mut reader = FontReader::from_slice(&data);
+    let avar = AvarTable::from_reader(&mut reader, data.len() as u32).unwrap();
+
+    assert_eq!(avar.map_coords(0, 0.5), 0.25);
+    // Midpoint of the second positive segment
+    assert_eq!(avar.map_coords(0, 0.75), 0.625);
+    assert_eq!(avar.map_coords(0, -0.5), -0.5);
+    assert_eq!(avar.map_coords(0, 1.0), 1.0);
+    // Axes without a segment map pass through
+    assert_eq!(avar.map_coords(1, 0.3), 0.3);
+}
+
+#[test]
+fn test_glyph_outline_at_applies_avar() {
+    let mut tables = weight_font_tables();
+    let variation = glyph_variation(&[1.0], None, &[0, 0, 100, 100, 0, 0, 0, 0], &[0; 8]);
+    replace_table(&mut tables, b"gvar", gvar_table(1, 11, &[(GID_B, variation)]));
+    replace_table(&mut tables, b"avar", avar_table(&[&[(-1.0, -1.0), (0.0, 0.0), (0.5, 0.25), (1.0, 1.0)]]));
+    let font = Font::from_data(build_font(&tables)).unwrap();
+
+    // 650 normalizes to 0.5, which avar remaps to 0.25
+    assert_eq!(simple_points(&font, GID_B, &[650.0])[2], (425, 700));
+}